@@ -16,7 +16,7 @@ async fn main() -> anyhow::Result<()> {
     let log = Log::mock();
     let fs = Fs::spawn();
     let net = Net::spawn(config.clone(), log.clone()).await;
-    let lore = LoreApi::spawn(net);
+    let lore = LoreApi::spawn(net, log.clone());
     let feed_cache = FeedCache::spawn(lore, fs.clone(), config.clone(), log.clone()).await?;
 
     let cache_path = config.path(PathOpt::CachePath).await;
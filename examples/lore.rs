@@ -7,9 +7,9 @@ use ph::net::Net;
 async fn main() -> anyhow::Result<()> {
     let config = Config::mock(Default::default());
     let log = Log::mock();
-    let net = Net::spawn(config, log).await;
+    let net = Net::spawn(config, log.clone()).await;
 
-    let lore = LoreApi::spawn(net);
+    let lore = LoreApi::spawn(net, log);
 
     let lists = match lore.get_available_lists().await {
         Ok(lists) => lists,
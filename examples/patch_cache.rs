@@ -17,7 +17,7 @@ async fn main() -> anyhow::Result<()> {
     let log = Log::mock();
     let fs = Fs::spawn();
     let net = Net::spawn(config.clone(), log.clone()).await;
-    let lore = LoreApi::spawn(net);
+    let lore = LoreApi::spawn(net, log.clone());
     let ml = MailingListCache::spawn(lore.clone(), fs.clone(), config.clone(), log.clone()).await?;
     let pm = FeedCache::spawn(lore, fs.clone(), config.clone(), log.clone()).await?;
 
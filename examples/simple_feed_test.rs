@@ -1,7 +1,15 @@
 use ph::{
     app::{
-        cache::{feed::FeedCache, mailing_list::MailingListCache, patch::PatchCache},
+        apply::Apply,
+        mail::Mail,
+        maintainers::Maintainers,
+        reply::Reply,
+        cache::{
+            feed::FeedCache, mailing_list::MailingListCache, patch::PatchCache, review::ReviewCache,
+            seen::SeenCache,
+        },
         ui::Ui,
+        watch::Watch,
     },
     log::Log,
     render::Render,
@@ -27,6 +35,13 @@ async fn main() -> anyhow::Result<()> {
         feed_cache,
         patch_cache,
         render,
+        Apply::mock(),
+        ReviewCache::mock(Default::default()),
+        SeenCache::mock(Default::default()),
+        Reply::mock(),
+        Mail::mock(),
+        Maintainers::mock(Default::default()),
+        Watch::mock(Default::default()),
     );
 
     println!("✅ UI actor created successfully with patch cache dependency!");
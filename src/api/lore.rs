@@ -2,18 +2,31 @@ use anyhow::Context;
 use std::collections::HashMap;
 use tokio::sync::{mpsc::Sender, oneshot};
 
+use crate::log::Log;
 use crate::utils::ArcSlice;
 use crate::{ArcStr, net::Net};
 
 mod core;
 pub mod data;
+pub mod diff;
 mod message;
+pub mod mime;
 mod mock;
 pub mod parse;
+pub mod query;
 
 // Re-export public types for external use
-pub use data::{LoreMailingList, LorePage, LorePatchMetadata};
+pub use data::{
+    LoreFeedDiagnostic, LoreHeaders, LoreMailingList, LoreMessage, LorePage, LorePatchMessage,
+    LorePatchMetadata, LorePatchTitle, LoreThread, LoreThreadNode, LoreTrailer,
+};
+pub use diff::{Diff, DiffFile, DiffHunk, DiffLine, FileStatus, LineKind, parse_diff};
 pub use message::LoreApiMessage;
+pub use mime::parse_patch_message;
+pub use query::{ALL_LISTS, LoreQuery, LoreSearch};
+
+/// The archive used when no other domain is configured
+pub const DEFAULT_DOMAIN: &str = "https://lore.kernel.org";
 
 /// The Lore API actor that provides a high-level interface for interacting with the Lore Kernel API.
 ///
@@ -23,7 +36,7 @@ pub use message::LoreApiMessage;
 ///
 /// # Examples
 /// ```ignore
-/// let lore_api = LoreApi::spawn(net);
+/// let lore_api = LoreApi::spawn(net, log);
 /// let patch_feed = lore_api.get_patch_feed("amd-gfx", 0).await?;
 /// ```
 ///
@@ -43,11 +56,12 @@ impl LoreApi {
     ///
     /// # Arguments
     /// * `net` - The networking actor for making HTTP requests
+    /// * `log` - The logging actor
     ///
     /// # Returns
     /// A new Lore API actor configured for the Lore Kernel Archive.
-    pub fn spawn(net: Net) -> Self {
-        let (lore_api, _) = core::Core::new(net).spawn();
+    pub fn spawn(net: Net, log: Log) -> Self {
+        let (lore_api, _) = core::Core::new(net, log).spawn();
         lore_api
     }
 
@@ -56,11 +70,29 @@ impl LoreApi {
     /// # Arguments
     /// * `net` - The networking actor for making HTTP requests
     /// * `domain` - The base domain for API requests
+    /// * `log` - The logging actor
     ///
     /// # Returns
     /// A new Lore API actor configured with the specified domain.
-    pub fn spawn_with_domain(net: Net, domain: ArcStr) -> Self {
-        let (lore_api, _) = core::Core::with_domain(net, domain).spawn();
+    pub fn spawn_with_domain(net: Net, domain: ArcStr, log: Log) -> Self {
+        let (lore_api, _) = core::Core::with_domain(net, domain, log).spawn();
+        lore_api
+    }
+
+    /// Creates a new Lore API actor that browses several public-inbox archives.
+    ///
+    /// Lists are identified by name alone, so requests for a list are sent to the
+    /// archive it was listed by. Lists not seen yet go to the first archive.
+    ///
+    /// # Arguments
+    /// * `net` - The networking actor for making HTTP requests
+    /// * `archives` - The base URLs of the archives, the main one first
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new Lore API actor configured with the given archives.
+    pub fn spawn_with_archives(net: Net, archives: Vec<ArcStr>, log: Log) -> Self {
+        let (lore_api, _) = core::Core::with_archives(net, archives, log).spawn();
         lore_api
     }
 
@@ -120,16 +152,56 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_patch_feed_page(target_list, min_index).await
+            LoreApi::Mock(mock) => mock.get_patch_feed_page(target_list, min_index).await,
+        }
+    }
+
+    /// Fetches a page of the patches matching a search.
+    ///
+    /// Results come in the same form as a patch feed, newest first, and each one keeps
+    /// the list it was archived in, which matters when searching [`ALL_LISTS`].
+    ///
+    /// # Arguments
+    /// * `search` - The query and the list it runs against
+    /// * `min_index` - The offset for pagination (0-based)
+    ///
+    /// # Returns
+    /// The page of results, or `None` past the last one.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let query = LoreQuery::patches().author("jane@example.com");
+    /// let page = lore_api.search_page(LoreSearch::new(ArcStr::from(ALL_LISTS), query), 0).await?;
+    /// ```
+    pub async fn search_page(
+        &self,
+        search: LoreSearch,
+        min_index: usize,
+    ) -> anyhow::Result<Option<LorePage<LorePatchMetadata>>> {
+        match self {
+            LoreApi::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(LoreApiMessage::SearchPage {
+                        search,
+                        min_index,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to LoreApi actor")
+                    .expect("LoreApi actor died");
+                rx.await
+                    .context("Awaiting response from LoreApi actor")
+                    .expect("LoreApi actor died")
             }
+            LoreApi::Mock(mock) => mock.search_page(search, min_index).await,
         }
     }
 
     /// Fetches a single page of available mailing lists with pagination.
     ///
-    /// This method retrieves a paginated list of all available mailing lists
-    /// archived on the Lore Kernel Archive.
+    /// This method retrieves a paginated list of the mailing lists archived on the
+    /// main archive.
     ///
     /// # Arguments
     /// * `min_index` - The offset for pagination (0-based)
@@ -152,16 +224,15 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_available_lists_page(min_index).await
-            }
+            LoreApi::Mock(mock) => mock.get_available_lists_page(min_index).await,
         }
     }
 
     /// Fetches all available mailing lists, aggregating all paginated results.
     ///
-    /// This method retrieves all available mailing lists archived on the Lore Kernel Archive,
-    /// following pagination until all items are collected.
+    /// This method retrieves the mailing lists of every configured archive, following
+    /// pagination until all items are collected. A list also hosted by an earlier
+    /// archive is left out, since lists are identified by name.
     ///
     /// # Returns
     /// An `ArcSlice<LoreMailingList>` containing all available mailing lists.
@@ -178,9 +249,7 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_available_lists().await
-            }
+            LoreApi::Mock(mock) => mock.get_available_lists().await,
         }
     }
 
@@ -221,9 +290,7 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_patch_html(target_list, message_id).await
-            }
+            LoreApi::Mock(mock) => mock.get_patch_html(target_list, message_id).await,
         }
     }
 
@@ -264,9 +331,50 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_raw_patch(target_list, message_id).await
+            LoreApi::Mock(mock) => mock.get_raw_patch(target_list, message_id).await,
+        }
+    }
+
+    /// Fetches the whole thread a message belongs to as a reply tree.
+    ///
+    /// This method downloads the compressed thread mbox from the archive, splits it into
+    /// messages and links them by their `In-Reply-To` and `References` headers.
+    ///
+    /// # Arguments
+    /// * `target_list` - The mailing list name
+    /// * `message_id` - The message ID of any message in the thread
+    ///
+    /// # Returns
+    /// The thread as a [`LoreThread`], or an error if the request fails. Messages that
+    /// cannot be parsed are logged and left out, see [`LoreThread::skipped`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let thread = lore_api.get_thread("amd-gfx", "20231201.123456.1-1@amd.com").await?;
+    /// let root = thread.root();
+    /// ```
+    pub async fn get_thread(
+        &self,
+        target_list: ArcStr,
+        message_id: ArcStr,
+    ) -> anyhow::Result<LoreThread> {
+        match self {
+            LoreApi::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(LoreApiMessage::GetThread {
+                        target_list,
+                        message_id,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to LoreApi actor")
+                    .expect("LoreApi actor died");
+                rx.await
+                    .context("Awaiting response from LoreApi actor")
+                    .expect("LoreApi actor died")
             }
+            LoreApi::Mock(mock) => mock.get_thread(target_list, message_id).await,
         }
     }
 
@@ -307,9 +415,7 @@ impl LoreApi {
                     .context("Awaiting response from LoreApi actor")
                     .expect("LoreApi actor died")
             }
-            LoreApi::Mock(mock) => {
-                mock.get_patch_metadata(target_list, message_id).await
-            }
+            LoreApi::Mock(mock) => mock.get_patch_metadata(target_list, message_id).await,
         }
     }
 }
@@ -321,7 +427,7 @@ mod tests {
     #[tokio::test]
     async fn test_lore_api_creation() {
         let net = Net::mock_empty();
-        let lore_api = LoreApi::spawn(net);
+        let lore_api = LoreApi::spawn(net, Log::mock());
 
         // Test that we can create the actor successfully
         assert!(matches!(lore_api, LoreApi::Actual(_)));
@@ -331,7 +437,7 @@ mod tests {
     async fn test_lore_api_with_custom_domain() {
         let net = Net::mock_empty();
         let custom_domain = ArcStr::from("https://custom.lore.kernel.org");
-        let lore_api = LoreApi::spawn_with_domain(net, custom_domain);
+        let lore_api = LoreApi::spawn_with_domain(net, custom_domain, Log::mock());
 
         // Test that we can create the actor with custom domain successfully
         assert!(matches!(lore_api, LoreApi::Actual(_)));
@@ -340,7 +446,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_patch_feed_url_construction() {
         let net = Net::mock_empty();
-        let lore_api = LoreApi::spawn(net);
+        let lore_api = LoreApi::spawn(net, Log::mock());
 
         // This test verifies the URL construction logic
         // The actual request will fail with mock, but we can verify the structure
@@ -353,7 +459,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_available_lists_url_construction() {
         let net = Net::mock_empty();
-        let lore_api = LoreApi::spawn(net);
+        let lore_api = LoreApi::spawn(net, Log::mock());
 
         let result = lore_api.get_available_lists().await;
         assert!(result.is_err()); // Expected with mock
@@ -362,7 +468,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_patch_html_url_construction() {
         let net = Net::mock_empty();
-        let lore_api = LoreApi::spawn(net);
+        let lore_api = LoreApi::spawn(net, Log::mock());
 
         let result = lore_api
             .get_patch_html(ArcStr::from("test-list"), ArcStr::from("test-message-id"))
@@ -380,4 +486,162 @@ mod tests {
             .await;
         assert!(result.is_err()); // Expected with empty mock
     }
+
+    #[tokio::test]
+    async fn test_mock_get_thread() {
+        let mut responses = HashMap::new();
+        responses.insert(
+            "thread_test-list_child@x".to_string(),
+            ArcStr::from(
+                "From mboxrd@z Thu Jan  1 00:00:00 1970\nMessage-ID: <root@x>\n\n\
+                 From mboxrd@z Thu Jan  1 00:00:00 1970\nMessage-ID: <child@x>\n\
+                 In-Reply-To: <root@x>\n\nbody\n",
+            ),
+        );
+        let lore_api = LoreApi::mock(responses);
+
+        let thread = lore_api
+            .get_thread(ArcStr::from("test-list"), ArcStr::from("child@x"))
+            .await
+            .unwrap();
+        assert_eq!(thread.len(), 2);
+        assert_eq!(&*thread.root().unwrap().message.message_id, "root@x");
+    }
+
+    #[tokio::test]
+    async fn test_get_thread_rejects_non_gzip_body() {
+        use crate::net::message::MockRequestKey;
+
+        let mut responses = HashMap::new();
+        responses.insert(
+            MockRequestKey::get(ArcStr::from(
+                "https://lore.kernel.org/test-list/root@x/t.mbox.gz",
+            )),
+            ArcStr::from("<html>not an mbox</html>"),
+        );
+        let lore_api = LoreApi::spawn(Net::mock(responses), Log::mock());
+
+        let result = lore_api
+            .get_thread(ArcStr::from("test-list"), ArcStr::from("root@x"))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lists_are_fetched_from_their_archive() {
+        use crate::net::message::MockRequestKey;
+
+        let index = |name: &str| {
+            ArcStr::from(format!(
+                "<pre>\n* 2025-07-14 13:47 - <a\nhref=\"{name}/\">{name}</a>\n  The {name} list\n</pre>\nResults 1 of 1"
+            ))
+        };
+        let feed = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] Internal change</title><updated>2025-07-14T10:00:00Z</updated>
+<link href="https://inbox.example.com/internal/patch@example.com/"/><id>urn:uuid:0</id></entry>
+</feed>"#;
+
+        let mut responses = HashMap::new();
+        for (archive, name) in [
+            ("https://lore.kernel.org", "linux-arch"),
+            ("https://inbox.example.com", "internal"),
+        ] {
+            responses.insert(
+                MockRequestKey::get(ArcStr::from(format!("{archive}/?&o=0"))),
+                index(name),
+            );
+        }
+        responses.insert(
+            MockRequestKey::get(ArcStr::from(
+                "https://inbox.example.com/internal/?x=A&q=((s:patch+OR+s:rfc)+AND+NOT+s:re:)&o=0",
+            )),
+            ArcStr::from(feed),
+        );
+        let lore_api = LoreApi::spawn_with_archives(
+            Net::mock(responses),
+            vec![
+                ArcStr::from("https://lore.kernel.org"),
+                ArcStr::from("https://inbox.example.com/"),
+            ],
+            Log::mock(),
+        );
+
+        let page = lore_api
+            .get_patch_feed_page(ArcStr::from("internal"), 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].list, ArcStr::from("internal"));
+
+        let lists = lore_api.get_available_lists().await.unwrap();
+        let instances: Vec<_> = lists
+            .iter()
+            .map(|list| (list.name.to_string(), list.instance().to_string()))
+            .collect();
+        assert_eq!(
+            instances,
+            vec![
+                ("linux-arch".to_string(), "lore.kernel.org".to_string()),
+                ("internal".to_string(), "inbox.example.com".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_extra_archive_is_skipped() {
+        use crate::net::message::MockRequestKey;
+
+        let mut responses = HashMap::new();
+        responses.insert(
+            MockRequestKey::get(ArcStr::from("https://lore.kernel.org/?&o=0")),
+            ArcStr::from(
+                "<pre>\n* 2025-07-14 13:47 - <a\nhref=\"linux-mm/\">linux-mm</a>\n  The linux-mm list\n</pre>\nResults 1 of 1",
+            ),
+        );
+        let lore_api = LoreApi::spawn_with_archives(
+            Net::mock(responses),
+            vec![
+                ArcStr::from("https://lore.kernel.org"),
+                ArcStr::from("https://down.example.com"),
+            ],
+            Log::mock(),
+        );
+
+        let lists = lore_api.get_available_lists().await.unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].name, ArcStr::from("linux-mm"));
+    }
+
+    #[tokio::test]
+    async fn test_search_page_url_construction() {
+        use crate::net::message::MockRequestKey;
+
+        let feed = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] mm: fix the thing</title><updated>2025-07-14T10:00:00Z</updated>
+<link href="https://lore.kernel.org/linux-mm/patch@example.com/"/><id>urn:uuid:0</id></entry>
+</feed>"#;
+        let mut responses = HashMap::new();
+        responses.insert(
+            MockRequestKey::get(ArcStr::from(
+                "https://lore.kernel.org/all/?x=A&q=((s:patch+OR+s:rfc)+AND+NOT+s:re:+AND+f:jane+AND+dfn:mm/+AND+d:2025-07-01..)&o=0",
+            )),
+            ArcStr::from(feed),
+        );
+        let lore_api = LoreApi::spawn(Net::mock(responses), Log::mock());
+
+        let query = LoreQuery::patches()
+            .author("jane")
+            .touches("mm/")
+            .date(Some("2025-07-01"), None);
+        let page = lore_api
+            .search_page(LoreSearch::new(ArcStr::from(ALL_LISTS), query), 0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].list, ArcStr::from("linux-mm"));
+    }
 }
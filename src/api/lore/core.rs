@@ -2,11 +2,15 @@ use anyhow::Context;
 use std::collections::HashMap;
 use tokio::task::JoinHandle;
 
-use super::data::{LoreMailingList, LorePage, LorePatchMetadata};
+use super::data::{LoreMailingList, LorePage, LorePatchMetadata, LoreThread};
 use super::parse;
+use super::query::{LoreQuery, LoreSearch};
 use crate::ArcSlice;
+use crate::log::Log;
 use crate::{ArcStr, api::lore::message::LoreApiMessage, net::Net};
 
+const SCOPE: &str = "api.lore";
+
 /// The core of the Lore API system that handles Lore-specific HTTP requests.
 ///
 /// This struct provides thread-safe access to Lore API operations through an actor pattern.
@@ -21,7 +25,7 @@ use crate::{ArcStr, api::lore::message::LoreApiMessage, net::Net};
 ///
 /// # Examples
 /// ```ignore
-/// let core = Core::new(net);
+/// let core = Core::new(net, log);
 /// let (lore_api, _) = core.spawn();
 /// ```
 ///
@@ -32,8 +36,14 @@ use crate::{ArcStr, api::lore::message::LoreApiMessage, net::Net};
 pub struct Core {
     /// The networking actor for making HTTP requests
     net: Net,
-    /// The base domain for Lore API requests
-    domain: ArcStr,
+    /// The base URLs of the archives, the main one first
+    archives: Vec<ArcStr>,
+    /// The archive each known list was listed by
+    list_archives: HashMap<String, ArcStr>,
+    /// Whether the lists of every archive have been fetched
+    lists_fetched: bool,
+    /// The logging actor
+    log: Log,
 }
 
 impl Core {
@@ -41,14 +51,12 @@ impl Core {
     ///
     /// # Arguments
     /// * `net` - The networking actor for making HTTP requests
+    /// * `log` - The logging actor
     ///
     /// # Returns
     /// A new instance of `Core` configured for the Lore Kernel Archive.
-    pub fn new(net: Net) -> Self {
-        Self {
-            net,
-            domain: ArcStr::from("https://lore.kernel.org"),
-        }
+    pub fn new(net: Net, log: Log) -> Self {
+        Self::with_domain(net, ArcStr::from(super::DEFAULT_DOMAIN), log)
     }
 
     /// Creates a new Lore API core instance with a custom domain.
@@ -56,11 +64,38 @@ impl Core {
     /// # Arguments
     /// * `net` - The networking actor for making HTTP requests
     /// * `domain` - The base domain for API requests
+    /// * `log` - The logging actor
     ///
     /// # Returns
     /// A new instance of `Core` configured with the specified domain.
-    pub fn with_domain(net: Net, domain: ArcStr) -> Self {
-        Self { net, domain }
+    pub fn with_domain(net: Net, domain: ArcStr, log: Log) -> Self {
+        Self::with_archives(net, vec![domain], log)
+    }
+
+    /// Creates a new Lore API core instance that browses several archives.
+    ///
+    /// # Arguments
+    /// * `net` - The networking actor for making HTTP requests
+    /// * `archives` - The base URLs of the archives, the main one first
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new instance of `Core`, using the Lore Kernel Archive if `archives` is empty.
+    pub fn with_archives(net: Net, archives: Vec<ArcStr>, log: Log) -> Self {
+        let mut archives: Vec<ArcStr> = archives
+            .iter()
+            .map(|archive| ArcStr::from(archive.trim_end_matches('/')))
+            .collect();
+        if archives.is_empty() {
+            archives.push(ArcStr::from(super::DEFAULT_DOMAIN));
+        }
+        Self {
+            net,
+            archives,
+            list_archives: HashMap::new(),
+            lists_fetched: false,
+            log,
+        }
     }
 
     /// Transforms the Lore API core instance into an actor.
@@ -76,7 +111,7 @@ impl Core {
     ///
     /// # Panics
     /// This function will panic if the underlying task fails to spawn.
-    pub fn spawn(self) -> (crate::api::lore::LoreApi, JoinHandle<()>) {
+    pub fn spawn(mut self) -> (crate::api::lore::LoreApi, JoinHandle<()>) {
         let (tx, mut rx) = tokio::sync::mpsc::channel(100);
 
         let handle = tokio::spawn(async move {
@@ -95,6 +130,19 @@ impl Core {
                             });
                         let _ = tx.send(response);
                     }
+                    LoreApiMessage::SearchPage {
+                        search,
+                        min_index,
+                        tx,
+                    } => {
+                        let response = self
+                            .handle_search_page(&search, min_index)
+                            .await
+                            .with_context(|| {
+                                format!("GET search failed for query: {}", search.query)
+                            });
+                        let _ = tx.send(response);
+                    }
                     LoreApiMessage::GetAvailableLists { tx } => {
                         let response = self
                             .handle_get_available_lists()
@@ -141,6 +189,21 @@ impl Core {
                             });
                         let _ = tx.send(response);
                     }
+                    LoreApiMessage::GetThread {
+                        target_list,
+                        message_id,
+                        tx,
+                    } => {
+                        let response = self
+                            .handle_get_thread(&target_list, &message_id)
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "GET thread failed for list: {target_list}, message: {message_id}"
+                                )
+                            });
+                        let _ = tx.send(response);
+                    }
                     LoreApiMessage::GetPatchMetadata {
                         target_list,
                         message_id,
@@ -163,15 +226,47 @@ impl Core {
         (crate::api::lore::LoreApi::Actual(tx), handle)
     }
 
+    /// Returns the base URL of the archive hosting `list`.
+    ///
+    /// With several archives, the lists of all of them are fetched the first time an
+    /// unknown list is requested. Lists that are still unknown go to the main archive.
+    async fn domain_for(&mut self, list: &str) -> ArcStr {
+        if self.archives.len() > 1
+            && !self.lists_fetched
+            && !self.list_archives.contains_key(list)
+        {
+            // A failure leaves the list on the main archive, the request itself reports errors
+            let _ = self.handle_get_available_lists().await;
+        }
+        self.list_archives
+            .get(list)
+            .cloned()
+            .unwrap_or_else(|| self.archives[0].clone())
+    }
+
     /// Handles GET patch feed requests
     async fn handle_get_patch_feed_page(
-        &self,
+        &mut self,
         target_list: &str,
         min_index: usize,
     ) -> anyhow::Result<Option<LorePage<LorePatchMetadata>>> {
+        let search = LoreSearch::new(ArcStr::from(target_list), LoreQuery::patches());
+        self.handle_search_page(&search, min_index).await
+    }
+
+    /// Handles search requests, whose results come as an Atom feed
+    async fn handle_search_page(
+        &mut self,
+        search: &LoreSearch,
+        min_index: usize,
+    ) -> anyhow::Result<Option<LorePage<LorePatchMetadata>>> {
+        let domain = self.domain_for(&search.list).await;
         let url = format!(
-            "{}/{}/?x=A&q=((s:patch+OR+s:rfc)+AND+NOT+s:re:)&o={}",
-            self.domain, target_list, min_index
+            "{}/{}/?x=A&q={}&o={}",
+            domain,
+            search.list,
+            search.query.encode(),
+            min_index
         );
 
         let mut headers = HashMap::new();
@@ -188,22 +283,52 @@ impl Core {
             return Ok(None);
         }
 
-        let page = parse::parse_patch_feed_xml(&response, min_index)?;
+        let page = parse::parse_patch_feed_xml(&response, min_index, &domain)?;
 
         Ok(Some(page))
     }
 
-    /// Handles GET available lists requests
-    async fn handle_get_available_lists(&self) -> anyhow::Result<ArcSlice<LoreMailingList>> {
+    /// Handles GET available lists requests, going through every archive
+    ///
+    /// An extra archive that cannot be fetched is left out after logging why, so it
+    /// does not take the lists of the others down with it. The main archive failing
+    /// fails the whole request.
+    async fn handle_get_available_lists(&mut self) -> anyhow::Result<ArcSlice<LoreMailingList>> {
         let mut all_items = Vec::new();
+        for (i, archive) in self.archives.clone().into_iter().enumerate() {
+            match self.get_archive_lists(&archive).await {
+                Ok(items) => all_items.extend(items),
+                Err(e) if i > 0 => self.log.warn(
+                    SCOPE,
+                    format!("Skipping the lists of archive {archive}: {e:#}"),
+                ),
+                Err(e) => return Err(e),
+            }
+        }
+        self.lists_fetched = true;
+        Ok(ArcSlice::from(&all_items[..]))
+    }
+
+    /// Fetches every page of the lists of one archive, leaving out the lists recorded
+    /// for another archive.
+    async fn get_archive_lists(
+        &mut self,
+        archive: &ArcStr,
+    ) -> anyhow::Result<Vec<LoreMailingList>> {
+        let mut items = Vec::new();
         let mut min_index = 0;
         loop {
-            let page = self.handle_get_available_lists_page(min_index).await?;
+            let page = self.get_archive_lists_page(archive, min_index).await?;
             let Some(page) = page else {
                 break;
             };
 
-            all_items.extend(page.items);
+            // The first archive listing a name wins
+            items.extend(
+                page.items
+                    .into_iter()
+                    .filter(|list| &list.archive == archive),
+            );
 
             if let Some(next) = page.next_page_index {
                 min_index = next;
@@ -211,15 +336,28 @@ impl Core {
                 break;
             }
         }
-        Ok(ArcSlice::from(&all_items[..]))
+        Ok(items)
     }
 
-    /// Handles GET available lists requests
+    /// Handles GET available lists requests for the main archive
     async fn handle_get_available_lists_page(
-        &self,
+        &mut self,
+        min_index: usize,
+    ) -> anyhow::Result<Option<LorePage<LoreMailingList>>> {
+        let archive = self.archives[0].clone();
+        self.get_archive_lists_page(&archive, min_index).await
+    }
+
+    /// Fetches a page of the lists of one archive and records which archive hosts them.
+    ///
+    /// Lists already recorded for another archive keep it, and have their `archive` field
+    /// set to it so callers can tell them apart.
+    async fn get_archive_lists_page(
+        &mut self,
+        archive: &ArcStr,
         min_index: usize,
     ) -> anyhow::Result<Option<LorePage<LoreMailingList>>> {
-        let url = ArcStr::from(&format!("{}/?&o={}", self.domain, min_index));
+        let url = ArcStr::from(&format!("{}/?&o={}", archive, min_index));
 
         let mut headers = HashMap::new();
         headers.insert(
@@ -228,17 +366,26 @@ impl Core {
         );
 
         let html = self.net.get(url, Some(headers)).await?;
-        parse::parse_available_lists_html(&html, min_index)
-            .context("Failed to parse available lists page")
+        let mut page = parse::parse_available_lists_html(&html, min_index, archive)
+            .context("Failed to parse available lists page")?;
+        for list in page.iter_mut().flat_map(|page| page.items.iter_mut()) {
+            list.archive = self
+                .list_archives
+                .entry(list.name.to_string())
+                .or_insert_with(|| archive.clone())
+                .clone();
+        }
+        Ok(page)
     }
 
     /// Handles GET patch HTML requests
     async fn handle_get_patch_html(
-        &self,
+        &mut self,
         target_list: &str,
         message_id: &str,
     ) -> anyhow::Result<ArcStr> {
-        let url = format!("{}/{}/{}/", self.domain, target_list, message_id);
+        let domain = self.domain_for(target_list).await;
+        let url = format!("{}/{}/{}/", domain, target_list, message_id);
 
         let mut headers = HashMap::new();
         headers.insert(
@@ -251,11 +398,12 @@ impl Core {
 
     /// Handles GET raw patch requests
     async fn handle_get_raw_patch(
-        &self,
+        &mut self,
         target_list: &str,
         message_id: &str,
     ) -> anyhow::Result<ArcStr> {
-        let url = format!("{}/{}/{}/raw", self.domain, target_list, message_id);
+        let domain = self.domain_for(target_list).await;
+        let url = format!("{}/{}/{}/raw", domain, target_list, message_id);
 
         let mut headers = HashMap::new();
         headers.insert(ArcStr::from("Accept"), ArcStr::from("text/plain"));
@@ -263,13 +411,39 @@ impl Core {
         self.net.get(ArcStr::from(&url), Some(headers)).await
     }
 
+    /// Handles GET thread requests
+    async fn handle_get_thread(
+        &mut self,
+        target_list: &str,
+        message_id: &str,
+    ) -> anyhow::Result<LoreThread> {
+        let domain = self.domain_for(target_list).await;
+        let url = format!("{}/{}/{}/t.mbox.gz", domain, target_list, message_id);
+
+        let mut headers = HashMap::new();
+        headers.insert(ArcStr::from("Accept"), ArcStr::from("application/gzip"));
+
+        let bytes = self
+            .net
+            .get_bytes(ArcStr::from(&url), Some(headers))
+            .await?;
+        let mbox = parse::decompress_mbox_gz(&bytes)?;
+        let thread = parse::parse_thread_mbox(&mbox);
+        for reason in &thread.skipped {
+            self.log
+                .warn(SCOPE, format!("Skipping a message of {url}: {reason}"));
+        }
+        Ok(thread)
+    }
+
     /// Handles GET patch metadata requests
     async fn handle_get_patch_metadata(
-        &self,
+        &mut self,
         target_list: &str,
         message_id: &str,
     ) -> anyhow::Result<ArcStr> {
-        let url = format!("{}/{}/{}/json", self.domain, target_list, message_id);
+        let domain = self.domain_for(target_list).await;
+        let url = format!("{}/{}/{}/json", domain, target_list, message_id);
 
         let mut headers = HashMap::new();
         headers.insert(ArcStr::from("Accept"), ArcStr::from("application/json"));
@@ -1,3 +1,4 @@
+use super::diff::{Diff, parse_diff};
 use crate::{ArcStr, SequenceNumber};
 use chrono::{DateTime, Utc};
 
@@ -12,6 +13,19 @@ pub struct LorePage<T> {
     pub total_items: Option<usize>,
     /// The list of available mailing lists
     pub items: Vec<T>,
+    /// Entries of the page that could not be parsed and are missing from `items`
+    pub diagnostics: Vec<LoreFeedDiagnostic>,
+}
+
+/// Explains why an entry of a feed page was left out of the parsed items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoreFeedDiagnostic {
+    /// The position of the entry in the feed (the page start index plus its offset)
+    pub position: usize,
+    /// The title of the entry, as found in the feed
+    pub title: ArcStr,
+    /// Why the entry could not be parsed
+    pub reason: ArcStr,
 }
 
 /// Represents a single available mailing list item from the Lore Kernel Archive.
@@ -23,6 +37,21 @@ pub struct LoreMailingList {
     pub description: ArcStr,
     /// The last update date and time (UTC, e.g., 2025-07-14 13:47)
     pub last_update: DateTime<Utc>,
+    /// The base URL of the archive hosting the list (e.g., "https://lore.kernel.org")
+    #[serde(default = "default_archive")]
+    pub archive: ArcStr,
+}
+
+fn default_archive() -> ArcStr {
+    ArcStr::from(super::DEFAULT_DOMAIN)
+}
+
+impl LoreMailingList {
+    /// Returns the host name of the list's archive, as shown next to the list name.
+    pub fn instance(&self) -> &str {
+        let archive = self.archive.trim_end_matches('/');
+        archive.split_once("://").map_or(archive, |(_, host)| host)
+    }
 }
 
 /// Represents a patch that is obtained from the feed for a given patch list
@@ -46,4 +75,247 @@ pub struct LorePatchMetadata {
     pub list: ArcStr,
     /// The message ID of the patch
     pub message_id: ArcStr,
+    /// The message ID this patch replies to (e.g. the cover letter of its series), if any
+    #[serde(default)]
+    pub in_reply_to: Option<ArcStr>,
+    /// Whether the patch is marked as a request for comments
+    #[serde(default)]
+    pub rfc: bool,
+    /// Whether the patch is a resend of an earlier submission
+    #[serde(default)]
+    pub resend: bool,
+    /// Tree or subsystem tags of the title (e.g. `net-next`, `bpf` or a stable version like `6.1`)
+    #[serde(default)]
+    pub prefixes: Vec<ArcStr>,
+}
+
+/// The tags found in the bracketed prefix of a patch title, such as `[RFC PATCH net-next v3 02/10]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LorePatchTitle {
+    /// The patch version (1 if not specified)
+    pub version: usize,
+    /// The position of the patch in its series (None for a single patch)
+    pub sequence: Option<SequenceNumber>,
+    /// Whether the patch is marked as a request for comments
+    pub rfc: bool,
+    /// Whether the patch is a resend of an earlier submission
+    pub resend: bool,
+    /// Tree or subsystem tags, in the order they appear
+    pub prefixes: Vec<ArcStr>,
+    /// The title without the bracketed tags
+    pub subject: ArcStr,
+}
+
+/// Represents a single message of a mailing list thread, as extracted from a thread mbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoreMessage {
+    /// The message ID, without the surrounding angle brackets
+    pub message_id: ArcStr,
+    /// The message ID this message directly replies to, if any
+    pub in_reply_to: Option<ArcStr>,
+    /// The message IDs listed in the References header, oldest first
+    pub references: Vec<ArcStr>,
+    /// The subject of the message
+    pub subject: ArcStr,
+    /// The author name of the message
+    pub author: ArcStr,
+    /// The email of the author of the message
+    pub email: ArcStr,
+    /// The date the message was sent, if it could be parsed
+    pub date: Option<DateTime<Utc>>,
+    /// The full raw message, headers included
+    pub raw: ArcStr,
+}
+
+/// A message placed in a [`LoreThread`] reply tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoreThreadNode {
+    /// The message held by this node
+    pub message: LoreMessage,
+    /// Index of the node this message replies to, or None for a root
+    pub parent: Option<usize>,
+    /// Indices of the nodes replying to this message, ordered by date
+    pub children: Vec<usize>,
+}
+
+/// Represents a whole mailing list thread as a reply tree.
+///
+/// Nodes are stored in a flat vector and link to each other by index. A well-formed
+/// thread has a single root, but messages whose parent is missing from the archive
+/// become additional roots so that nothing is dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoreThread {
+    /// All the messages of the thread
+    pub nodes: Vec<LoreThreadNode>,
+    /// Indices of the nodes that have no parent in the thread, ordered by date
+    pub roots: Vec<usize>,
+    /// Why the messages of the archive that are missing from the thread were left out
+    pub skipped: Vec<ArcStr>,
+}
+
+impl LoreThread {
+    /// Returns the number of messages in the thread.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if the thread has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the first root of the thread, which is usually the message that started it.
+    pub fn root(&self) -> Option<&LoreThreadNode> {
+        self.roots.first().map(|&idx| &self.nodes[idx])
+    }
+
+    /// Finds the index of the node holding the message with the given ID.
+    pub fn find(&self, message_id: &str) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| &*node.message.message_id == message_id)
+    }
+
+    /// Returns the parent node of the node at `idx`, if any.
+    pub fn parent(&self, idx: usize) -> Option<&LoreThreadNode> {
+        self.nodes
+            .get(idx)?
+            .parent
+            .map(|parent| &self.nodes[parent])
+    }
+
+    /// Returns the child nodes of the node at `idx`.
+    pub fn children(&self, idx: usize) -> impl Iterator<Item = &LoreThreadNode> {
+        self.nodes
+            .get(idx)
+            .map(|node| node.children.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|&child| &self.nodes[child])
+    }
+
+    /// Flattens the tree in depth-first order, pairing each node index with its depth.
+    ///
+    /// This is the order in which a threaded view should display the messages.
+    pub fn walk(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<(usize, usize)> = self.roots.iter().rev().map(|&r| (r, 0)).collect();
+        while let Some((idx, depth)) = stack.pop() {
+            out.push((idx, depth));
+            stack.extend(
+                self.nodes[idx]
+                    .children
+                    .iter()
+                    .rev()
+                    .map(|&c| (c, depth + 1)),
+            );
+        }
+        out
+    }
+}
+
+/// The header fields of a message, in the order they appear.
+///
+/// Values are unfolded and their RFC 2047 encoded-words are decoded. Lookups are
+/// case insensitive, as header names are.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoreHeaders {
+    /// The `(name, value)` pairs of the header block
+    pub fields: Vec<(ArcStr, ArcStr)>,
+}
+
+impl LoreHeaders {
+    /// Returns the value of the first field with the given name.
+    pub fn get(&self, name: &str) -> Option<&ArcStr> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns the values of every field with the given name, in order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ArcStr> {
+        self.fields
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+}
+
+/// A `Key: value` trailer from the end of a commit message, such as `Signed-off-by`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoreTrailer {
+    /// The trailer key, as written (e.g. "Signed-off-by")
+    pub key: ArcStr,
+    /// The trailer value, with continuation lines joined
+    pub value: ArcStr,
+}
+
+/// A patch email split into its logical parts.
+///
+/// The layout follows what `git format-patch` produces: the commit message and its
+/// trailers, a `---` separator, optional notes and the diffstat, then the diff and an
+/// optional `-- ` signature. Messages that do not carry a diff (cover letters, replies)
+/// keep their whole text in `commit_message` and have an empty `diff`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LorePatchMessage {
+    /// The header fields of the message
+    pub headers: LoreHeaders,
+    /// The decoded subject
+    pub subject: ArcStr,
+    /// The decoded author name, falling back to the email
+    pub author: ArcStr,
+    /// The email of the author
+    pub email: ArcStr,
+    /// The date the message was sent, if it could be parsed
+    pub date: Option<DateTime<Utc>>,
+    /// The message ID, without the surrounding angle brackets
+    pub message_id: Option<ArcStr>,
+    /// The decoded plain text body, with every text part joined
+    pub body: ArcStr,
+    /// The commit message, without its trailers
+    pub commit_message: ArcStr,
+    /// The trailers closing the commit message
+    pub trailers: Vec<LoreTrailer>,
+    /// Free text between the `---` separator and the diffstat (changelogs, notes)
+    pub notes: ArcStr,
+    /// The diffstat, summary line included
+    pub diffstat: ArcStr,
+    /// The diff itself, without the signature
+    pub diff: ArcStr,
+}
+
+impl LorePatchMessage {
+    /// Returns true if the message carries a diff.
+    pub fn is_patch(&self) -> bool {
+        !self.diff.is_empty()
+    }
+
+    /// Parses the diff of the message into files, hunks and lines.
+    pub fn parse_diff(&self) -> Diff {
+        parse_diff(&self.diff)
+    }
+
+    /// Returns the values of every trailer with the given key (case insensitive).
+    pub fn trailer<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a ArcStr> {
+        self.trailers
+            .iter()
+            .filter(move |t| t.key.eq_ignore_ascii_case(key))
+            .map(|t| &t.value)
+    }
+
+    /// Formats the message for reading: the main headers followed by the decoded body.
+    ///
+    /// Unlike the raw message, the result has no transfer encoding or MIME structure left.
+    pub fn to_text(&self) -> String {
+        let mut text = String::with_capacity(self.body.len() + 256);
+        for name in ["From", "Date", "Subject", "To", "Cc"] {
+            if let Some(value) = self.headers.get(name) {
+                text.push_str(&format!("{}: {}\n", name, value));
+            }
+        }
+        text.push('\n');
+        text.push_str(&self.body);
+        text
+    }
 }
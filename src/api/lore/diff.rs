@@ -0,0 +1,602 @@
+//! A structured model of the diff carried by a patch.
+//!
+//! [`parse_diff`] understands `git diff` output (extended headers, renames, copies, mode
+//! changes, binary markers) as well as plain unified diffs, and is lenient about the
+//! damage mail clients do, such as stripping the space of empty context lines.
+
+use crate::ArcStr;
+
+/// How a file is changed by a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file is created
+    Added,
+    /// The file is removed
+    Deleted,
+    /// The file is changed in place
+    Modified,
+    /// The file is moved, possibly with changes
+    Renamed,
+    /// The file is copied, possibly with changes
+    Copied,
+}
+
+/// The kind of a line within a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// A line present on both sides
+    Context,
+    /// A line only present in the new file
+    Added,
+    /// A line only present in the old file
+    Removed,
+    /// The `\ No newline at end of file` marker for the preceding line
+    NoNewline,
+}
+
+/// A single line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    /// The kind of the line
+    pub kind: LineKind,
+    /// The text of the line, without its `+`/`-`/` ` prefix
+    pub content: ArcStr,
+    /// The line number in the old file, for context and removed lines
+    pub old_line: Option<usize>,
+    /// The line number in the new file, for context and added lines
+    pub new_line: Option<usize>,
+}
+
+/// A hunk of changes, introduced by an `@@ -a,b +c,d @@` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The `@@` line as written
+    pub header: ArcStr,
+    /// First line of the hunk in the old file
+    pub old_start: usize,
+    /// Number of old file lines covered by the hunk
+    pub old_count: usize,
+    /// First line of the hunk in the new file
+    pub new_start: usize,
+    /// Number of new file lines covered by the hunk
+    pub new_count: usize,
+    /// The text after the closing `@@`, usually the enclosing function
+    pub section: ArcStr,
+    /// The lines of the hunk
+    pub lines: Vec<DiffLine>,
+    /// Index of the `@@` line within the diff text, the hunk lines follow it
+    pub start_line: usize,
+}
+
+/// The changes made to a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffFile {
+    /// Path before the change, None for created files
+    pub old_path: Option<ArcStr>,
+    /// Path after the change, None for deleted files
+    pub new_path: Option<ArcStr>,
+    /// How the file is changed
+    pub status: FileStatus,
+    /// File mode before the change, when the diff states it
+    pub old_mode: Option<ArcStr>,
+    /// File mode after the change, when the diff states it
+    pub new_mode: Option<ArcStr>,
+    /// Similarity of a rename or copy, in percent
+    pub similarity: Option<u8>,
+    /// Whether the diff only says the file is binary, or carries a binary patch
+    pub binary: bool,
+    /// Lines between the start of the file and its first hunk (`diff --git`, `index`, ...)
+    pub header: Vec<ArcStr>,
+    /// The hunks, in order
+    pub hunks: Vec<DiffHunk>,
+    /// Index of the first line of this file within the diff text
+    pub start_line: usize,
+}
+
+impl DiffFile {
+    /// Returns the path that best names the file: the new path, or the old one if deleted.
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of added lines.
+    pub fn additions(&self) -> usize {
+        self.count(LineKind::Added)
+    }
+
+    /// Returns the number of removed lines.
+    pub fn deletions(&self) -> usize {
+        self.count(LineKind::Removed)
+    }
+
+    /// Returns true if the mode of the file changes.
+    pub fn mode_changed(&self) -> bool {
+        self.old_mode.is_some() && self.new_mode.is_some() && self.old_mode != self.new_mode
+    }
+
+    fn count(&self, kind: LineKind) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter(|line| line.kind == kind)
+            .count()
+    }
+}
+
+/// The whole diff of a patch, file by file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Diff {
+    /// The changed files, in the order they appear
+    pub files: Vec<DiffFile>,
+}
+
+impl Diff {
+    /// Returns true if the diff changes no file.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns every path touched by the diff, old paths of renames included.
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = Vec::new();
+        for file in &self.files {
+            for path in [&file.old_path, &file.new_path].into_iter().flatten() {
+                if !paths.contains(&path.as_ref()) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Returns true if the diff touches `path` or anything below it, if it is a directory.
+    pub fn touches(&self, path: &str) -> bool {
+        let dir = path.trim_end_matches('/');
+        self.paths().into_iter().any(|touched| {
+            touched == dir
+                || (touched.starts_with(dir) && touched[dir.len()..].starts_with('/'))
+                || dir.is_empty()
+        })
+    }
+
+    /// Returns the total number of added lines.
+    pub fn additions(&self) -> usize {
+        self.files.iter().map(DiffFile::additions).sum()
+    }
+
+    /// Returns the total number of removed lines.
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(DiffFile::deletions).sum()
+    }
+
+    /// Returns the index past the last line of the diff text that belongs to a file.
+    ///
+    /// Hunk line counts are followed, so a removed line reading `- ` is part of the diff
+    /// while a `-- ` signature after the last hunk is not. Returns 0 for an empty diff.
+    pub fn end_line(&self) -> usize {
+        self.files.last().map_or(0, |file| match file.hunks.last() {
+            Some(hunk) => hunk.start_line + 1 + hunk.lines.len(),
+            None => file.start_line + file.header.len(),
+        })
+    }
+
+    /// Returns the index of the file shown at `line` of the diff text, if any.
+    pub fn file_at_line(&self, line: usize) -> Option<usize> {
+        self.files.iter().rposition(|file| file.start_line <= line)
+    }
+}
+
+/// Strips the `a/`/`b/` prefix, quotes and trailing timestamp of a diff path.
+///
+/// # Returns
+/// The path, or None for `/dev/null`.
+fn clean_path(path: &str) -> Option<ArcStr> {
+    let path = path.split('\t').next().unwrap_or(path).trim_end();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(path);
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(ArcStr::from(path))
+}
+
+/// Extracts the old and new paths of a `diff --git a/x b/y` line.
+///
+/// Paths may contain spaces, so the split point is the ` b/` that leaves the same
+/// path on both sides, falling back to the first one.
+fn git_paths(line: &str) -> (Option<ArcStr>, Option<ArcStr>) {
+    let rest = line.trim_start_matches("diff --git ");
+    let splits: Vec<usize> = rest.match_indices(" b/").map(|(idx, _)| idx).collect();
+    let split = splits
+        .iter()
+        .copied()
+        .find(|&idx| rest[..idx].strip_prefix("a/") == Some(&rest[idx + 3..]))
+        .or(splits.first().copied());
+    match split {
+        Some(idx) => (clean_path(&rest[..idx]), clean_path(&rest[idx + 1..])),
+        None => match rest.split_once(' ') {
+            Some((old, new)) => (clean_path(old), clean_path(new)),
+            None => (None, None),
+        },
+    }
+}
+
+/// Parses `-a,b` or `+c,d` hunk ranges, where the count defaults to 1.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let range = &range[1..];
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Parses a `@@ -a,b +c,d @@ section` line into an empty hunk.
+///
+/// # Returns
+/// The hunk with no lines yet and a `start_line` of 0, or None if `line` is not a
+/// hunk header.
+pub fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ ")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    if !old.starts_with('-') || !new.starts_with('+') {
+        return None;
+    }
+    let (old_start, old_count) = parse_range(old)?;
+    let (new_start, new_count) = parse_range(new)?;
+    Some(DiffHunk {
+        header: ArcStr::from(line),
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+        section: ArcStr::from(section.trim()),
+        lines: Vec::new(),
+        start_line: 0,
+    })
+}
+
+/// Creates a file entry with nothing known about it yet.
+fn new_file(start_line: usize) -> DiffFile {
+    DiffFile {
+        old_path: None,
+        new_path: None,
+        status: FileStatus::Modified,
+        old_mode: None,
+        new_mode: None,
+        similarity: None,
+        binary: false,
+        header: Vec::new(),
+        hunks: Vec::new(),
+        start_line,
+    }
+}
+
+/// Settles the status of a file once all of its header lines are known.
+fn finish_file(mut file: DiffFile, files: &mut Vec<DiffFile>) {
+    if file.status == FileStatus::Modified {
+        if file.old_path.is_none() && file.new_path.is_some() {
+            file.status = FileStatus::Added;
+        } else if file.new_path.is_none() && file.old_path.is_some() {
+            file.status = FileStatus::Deleted;
+        }
+    }
+    files.push(file);
+}
+
+/// Parses the diff portion of a patch into files, hunks and lines.
+///
+/// Text that is not part of a file diff, such as a leading commit message, is skipped.
+///
+/// # Arguments
+/// * `text` - The diff, as found in [`LorePatchMessage::diff`](super::LorePatchMessage)
+///
+/// # Returns
+/// The structured diff, empty if `text` holds no diff.
+pub fn parse_diff(text: &str) -> Diff {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut files = Vec::new();
+    let mut file: Option<DiffFile> = None;
+    // Lines still expected on the old and new side of the current hunk
+    let mut remaining = (0usize, 0usize);
+    let mut numbers = (0usize, 0usize);
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        let in_hunk = remaining != (0, 0);
+
+        if in_hunk && let Some(current) = file.as_mut() {
+            let hunk = current.hunks.last_mut().expect("in a hunk");
+            let (kind, content) = match line.as_bytes().first() {
+                Some(b'\\') => {
+                    hunk.lines.push(DiffLine {
+                        kind: LineKind::NoNewline,
+                        content: ArcStr::from(line.trim_start_matches('\\').trim()),
+                        old_line: None,
+                        new_line: None,
+                    });
+                    idx += 1;
+                    continue;
+                }
+                Some(b' ') => (LineKind::Context, &line[1..]),
+                // Mail clients like to strip the space of empty context lines
+                None => (LineKind::Context, ""),
+                Some(b'-') => (LineKind::Removed, &line[1..]),
+                Some(b'+') => (LineKind::Added, &line[1..]),
+                _ => {
+                    // The hunk is shorter than announced, resume normal parsing
+                    remaining = (0, 0);
+                    continue;
+                }
+            };
+            let (old_line, new_line) = match kind {
+                LineKind::Context => (Some(numbers.0), Some(numbers.1)),
+                LineKind::Removed => (Some(numbers.0), None),
+                _ => (None, Some(numbers.1)),
+            };
+            if old_line.is_some() {
+                numbers.0 += 1;
+                remaining.0 = remaining.0.saturating_sub(1);
+            }
+            if new_line.is_some() {
+                numbers.1 += 1;
+                remaining.1 = remaining.1.saturating_sub(1);
+            }
+            hunk.lines.push(DiffLine {
+                kind,
+                content: ArcStr::from(content),
+                old_line,
+                new_line,
+            });
+            idx += 1;
+            continue;
+        }
+
+        if line.starts_with('\\') {
+            if let Some(hunk) = file.as_mut().and_then(|f| f.hunks.last_mut()) {
+                hunk.lines.push(DiffLine {
+                    kind: LineKind::NoNewline,
+                    content: ArcStr::from(line.trim_start_matches('\\').trim()),
+                    old_line: None,
+                    new_line: None,
+                });
+            }
+            idx += 1;
+            continue;
+        }
+
+        if line.starts_with("diff --git ") {
+            if let Some(done) = file.take() {
+                finish_file(done, &mut files);
+            }
+            let mut current = new_file(idx);
+            (current.old_path, current.new_path) = git_paths(line);
+            current.header.push(ArcStr::from(line));
+            file = Some(current);
+            idx += 1;
+            continue;
+        }
+
+        let plain_start = line.starts_with("--- ")
+            && lines
+                .get(idx + 1)
+                .is_some_and(|next| next.starts_with("+++ "));
+        if plain_start {
+            // A plain unified diff starts a file here, a git one already did
+            let continues = file.as_ref().is_some_and(|f| f.hunks.is_empty());
+            if !continues {
+                if let Some(done) = file.take() {
+                    finish_file(done, &mut files);
+                }
+                file = Some(new_file(idx));
+            }
+            let current = file.as_mut().expect("file just set");
+            current.old_path = clean_path(&line[4..]);
+            current.new_path = clean_path(&lines[idx + 1][4..]);
+            current.header.push(ArcStr::from(line));
+            current.header.push(ArcStr::from(lines[idx + 1]));
+            idx += 2;
+            continue;
+        }
+
+        let Some(current) = file.as_mut() else {
+            idx += 1;
+            continue;
+        };
+
+        if let Some(mut hunk) = parse_hunk_header(line) {
+            hunk.start_line = idx;
+            remaining = (hunk.old_count, hunk.new_count);
+            numbers = (hunk.old_start, hunk.new_start);
+            current.hunks.push(hunk);
+            idx += 1;
+            continue;
+        }
+
+        if current.hunks.is_empty() {
+            if let Some(mode) = line.strip_prefix("old mode ") {
+                current.old_mode = Some(ArcStr::from(mode.trim()));
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                current.new_mode = Some(ArcStr::from(mode.trim()));
+            } else if let Some(mode) = line.strip_prefix("new file mode ") {
+                current.status = FileStatus::Added;
+                current.old_path = None;
+                current.new_mode = Some(ArcStr::from(mode.trim()));
+            } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+                current.status = FileStatus::Deleted;
+                current.new_path = None;
+                current.old_mode = Some(ArcStr::from(mode.trim()));
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                current.status = FileStatus::Renamed;
+                current.old_path = clean_path(path);
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                current.new_path = clean_path(path);
+            } else if let Some(path) = line.strip_prefix("copy from ") {
+                current.status = FileStatus::Copied;
+                current.old_path = clean_path(path);
+            } else if let Some(path) = line.strip_prefix("copy to ") {
+                current.new_path = clean_path(path);
+            } else if let Some(similarity) = line.strip_prefix("similarity index ") {
+                current.similarity = similarity.trim_end_matches('%').parse().ok();
+            } else if let Some(rest) = line.strip_prefix("index ") {
+                // `index abc..def 100644` states the mode when it does not change
+                if let Some((_, mode)) = rest.split_once(' ')
+                    && current.old_mode.is_none()
+                    && current.new_mode.is_none()
+                {
+                    current.old_mode = Some(ArcStr::from(mode.trim()));
+                    current.new_mode = Some(ArcStr::from(mode.trim()));
+                }
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                current.binary = true;
+            } else if !line.starts_with("Index: ") && !line.starts_with("===") {
+                idx += 1;
+                continue;
+            }
+            current.header.push(ArcStr::from(line));
+        }
+        idx += 1;
+    }
+
+    if let Some(done) = file {
+        finish_file(done, &mut files);
+    }
+    Diff { files }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/net/core.c b/net/core.c
+index 1111111..2222222 100644
+--- a/net/core.c
++++ b/net/core.c
+@@ -10,4 +10,5 @@ static int setup(void)
+ int a;
+-int b;
+\\ No newline at end of file
++int b = 0;
++int c;
+
+ return 0;
+\\ No newline at end of file
+diff --git a/old name.c b/new name.c
+similarity index 95%
+rename from old name.c
+rename to new name.c
+diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..3333333
+Binary files /dev/null and b/logo.png differ
+diff --git a/gone.c b/gone.c
+deleted file mode 100644
+index 4444444..0000000
+--- a/gone.c
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+
+    #[test]
+    fn test_parse_diff_files() {
+        let diff = parse_diff(DIFF);
+        let summary: Vec<(&str, FileStatus)> =
+            diff.files.iter().map(|f| (f.path(), f.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("net/core.c", FileStatus::Modified),
+                ("new name.c", FileStatus::Renamed),
+                ("script.sh", FileStatus::Modified),
+                ("logo.png", FileStatus::Added),
+                ("gone.c", FileStatus::Deleted),
+            ]
+        );
+
+        let rename = &diff.files[1];
+        assert_eq!(rename.old_path.as_deref(), Some("old name.c"));
+        assert_eq!(rename.similarity, Some(95));
+        assert!(rename.hunks.is_empty());
+
+        assert!(diff.files[2].mode_changed());
+        assert_eq!(diff.files[2].new_mode.as_deref(), Some("100755"));
+        assert!(!diff.files[0].mode_changed());
+
+        assert!(diff.files[3].binary);
+        assert_eq!(diff.files[3].old_path, None);
+        assert_eq!(diff.files[4].new_path, None);
+        assert_eq!(diff.files[4].deletions(), 1);
+
+        assert_eq!((diff.additions(), diff.deletions()), (2, 2));
+        assert_eq!(diff.file_at_line(0), Some(0));
+        assert_eq!(diff.file_at_line(14), Some(1));
+        assert_eq!(diff.end_line(), DIFF.lines().count());
+    }
+
+    #[test]
+    fn test_parse_diff_hunk_lines() {
+        let diff = parse_diff(DIFF);
+        let hunk = &diff.files[0].hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_count), (10, 4));
+        assert_eq!((hunk.new_start, hunk.new_count), (10, 5));
+        assert_eq!(hunk.section, ArcStr::from("static int setup(void)"));
+        assert_eq!(hunk.start_line, 4);
+
+        let lines: Vec<(LineKind, Option<usize>, Option<usize>)> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.kind, l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (LineKind::Context, Some(10), Some(10)),
+                (LineKind::Removed, Some(11), None),
+                (LineKind::NoNewline, None, None),
+                (LineKind::Added, None, Some(11)),
+                (LineKind::Added, None, Some(12)),
+                // The stripped empty context line still counts
+                (LineKind::Context, Some(12), Some(13)),
+                (LineKind::Context, Some(13), Some(14)),
+                (LineKind::NoNewline, None, None),
+            ]
+        );
+        assert_eq!(hunk.lines[3].content, ArcStr::from("int b = 0;"));
+    }
+
+    #[test]
+    fn test_parse_plain_unified_diff() {
+        let diff = parse_diff(
+            "Some text first\n--- lib/a.c\t2025-01-01\n+++ lib/a.c\t2025-01-02\n@@ -1 +1 @@\n-x\n+y\n",
+        );
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path(), "lib/a.c");
+        assert_eq!(diff.files[0].hunks[0].lines.len(), 2);
+        assert!(parse_diff("no diff here").is_empty());
+    }
+
+    #[test]
+    fn test_touches() {
+        let diff = parse_diff(DIFF);
+        assert!(diff.touches("net"));
+        assert!(diff.touches("net/"));
+        assert!(diff.touches("net/core.c"));
+        assert!(diff.touches("old name.c"));
+        assert!(!diff.touches("ne"));
+        assert!(!diff.touches("drivers"));
+    }
+}
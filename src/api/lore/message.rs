@@ -1,6 +1,7 @@
 use tokio::sync::oneshot::Sender;
 
-use super::data::{LoreMailingList, LorePage, LorePatchMetadata};
+use super::data::{LoreMailingList, LorePage, LorePatchMetadata, LoreThread};
+use super::query::LoreSearch;
 use crate::{ArcSlice, ArcStr};
 
 /// Messages that can be sent to a [`LoreApiCore`] actor.
@@ -18,6 +19,15 @@ pub enum LoreApiMessage {
         /// Response channel for the operation result
         tx: Sender<anyhow::Result<Option<LorePage<LorePatchMetadata>>>>,
     },
+    /// Fetches a page of the patches matching a search
+    SearchPage {
+        /// The query and the list it runs against
+        search: LoreSearch,
+        /// The offset for pagination (0-based)
+        min_index: usize,
+        /// Response channel for the operation result
+        tx: Sender<anyhow::Result<Option<LorePage<LorePatchMetadata>>>>,
+    },
     GetAvailableLists {
         tx: Sender<anyhow::Result<ArcSlice<LoreMailingList>>>,
     },
@@ -46,6 +56,15 @@ pub enum LoreApiMessage {
         /// Response channel for the operation result
         tx: Sender<anyhow::Result<ArcStr>>,
     },
+    /// Fetches the whole thread a message belongs to as a reply tree
+    GetThread {
+        /// The mailing list name
+        target_list: ArcStr,
+        /// The message ID of any message in the thread
+        message_id: ArcStr,
+        /// Response channel for the operation result
+        tx: Sender<anyhow::Result<LoreThread>>,
+    },
     /// Fetches patch metadata in JSON format
     GetPatchMetadata {
         /// The mailing list name
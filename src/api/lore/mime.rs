@@ -0,0 +1,902 @@
+//! Parsing of raw RFC 5322 messages into a structured [`LorePatchMessage`].
+//!
+//! Messages served by lore are usually plain `git format-patch` output, but mail clients
+//! along the way fold headers, encode non-ASCII names as RFC 2047 encoded-words, wrap the
+//! body in `multipart/*` containers and apply quoted-printable or base64 transfer
+//! encodings in various charsets. Everything here undoes that so that the rest of the
+//! application can work on the decoded text.
+
+use anyhow::Context;
+use base64::Engine;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+
+use super::data::{LoreHeaders, LorePatchMessage, LoreTrailer};
+use super::diff::parse_diff;
+use super::parse::{parse_address, parse_date, parse_message_ids};
+use crate::ArcStr;
+
+/// Base64 engine that tolerates missing padding and stray trailing bits, as seen in the wild
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+/// How deep nested `multipart/*` containers are followed before giving up
+const MAX_DEPTH: usize = 8;
+
+/// How many bytes of text go in one encoded-word, keeping it under the 75 characters
+/// RFC 2047 allows
+const MAX_WORD_BYTES: usize = 45;
+
+/// Decodes base64 text, ignoring line breaks and other whitespace.
+///
+/// # Errors
+/// Returns an error if the text is not valid base64.
+pub fn decode_base64(text: &str) -> anyhow::Result<Vec<u8>> {
+    let compact: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    BASE64.decode(compact).context("Invalid base64 data")
+}
+
+/// Decodes quoted-printable text (RFC 2045, section 6.7).
+///
+/// Soft line breaks are removed and `=XX` escapes are turned into bytes. Malformed
+/// escapes are kept literally instead of failing, since the text is meant for reading.
+pub fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        if let Some(byte) = hex_pair(bytes.get(i + 1..i + 3)) {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        // A soft line break may be followed by transport padding before the newline
+        let rest = &bytes[i + 1..];
+        let pad = rest
+            .iter()
+            .take_while(|b| matches!(b, b' ' | b'\t' | b'\r'))
+            .count();
+        match rest.get(pad) {
+            Some(b'\n') => i += 1 + pad + 1,
+            None => i = bytes.len(),
+            Some(_) => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decodes the `Q` encoding of RFC 2047 encoded-words.
+fn decode_q(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'_' => out.push(b' '),
+            b'=' => {
+                if let Some(byte) = hex_pair(bytes.get(i + 1..i + 3)) {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+                out.push(b'=');
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Parses two hexadecimal digits into a byte.
+fn hex_pair(pair: Option<&[u8]>) -> Option<u8> {
+    let pair = pair?;
+    let high = (pair[0] as char).to_digit(16)?;
+    let low = (pair[1] as char).to_digit(16)?;
+    Some((high * 16 + low) as u8)
+}
+
+/// Converts bytes in the given charset to a string.
+///
+/// Unknown or missing charsets are treated as UTF-8, and invalid sequences are
+/// replaced rather than rejected.
+pub fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim().as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+/// Decodes a single encoded-word at the start of `text`.
+///
+/// # Returns
+/// The decoded text and the length of the encoded-word, or None if `text` does not
+/// start with a well-formed encoded-word.
+fn decode_encoded_word(text: &str) -> Option<(String, usize)> {
+    let inner = text.strip_prefix("=?")?;
+    let (charset, rest) = inner.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let end = rest.find("?=")?;
+    let encoded = &rest[..end];
+    if charset.is_empty()
+        || charset.contains(char::is_whitespace)
+        || encoded.contains(char::is_whitespace)
+    {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => decode_base64(encoded).ok()?,
+        "Q" | "q" => decode_q(encoded),
+        _ => return None,
+    };
+    // RFC 2231 allows a language after the charset, as in `utf-8*en`
+    let label = charset.split('*').next().unwrap_or(charset);
+    let len = 2 + charset.len() + 1 + encoding.len() + 1 + encoded.len() + 2;
+    Some((decode_charset(&bytes, Some(label)), len))
+}
+
+/// Decodes the RFC 2047 encoded-words of a header value.
+///
+/// Whitespace between two adjacent encoded-words is dropped, as the standard requires,
+/// so that a name split across several words comes out whole. Anything that looks like
+/// an encoded-word but is not well formed is kept as is.
+pub fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+        match decode_encoded_word(candidate) {
+            Some((decoded, len)) => {
+                if !(after_word && before.chars().all(char::is_whitespace)) {
+                    out.push_str(before);
+                }
+                out.push_str(&decoded);
+                rest = &candidate[len..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &candidate[2..];
+                after_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Encodes a header value as RFC 2047 encoded-words, unless it is plain ASCII.
+///
+/// The text is split on character boundaries into base64 UTF-8 encoded-words short
+/// enough for the standard, folded onto continuation lines. Decoders drop the folding
+/// whitespace between them, so [`decode_encoded_words`] gives the value back.
+pub fn encode_encoded_words(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in value.chars() {
+        if chunk.len() + c.len_utf8() > MAX_WORD_BYTES {
+            words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    words.push(format!("=?UTF-8?B?{}?=", BASE64.encode(&chunk)));
+    words.join("\n ")
+}
+
+/// Splits a message into its unfolded, still encoded header fields and its body.
+///
+/// The mbox `From_` line, if any, is skipped. Continuation lines are joined to the
+/// field they belong to with a single space.
+fn split_message(raw: &str) -> (Vec<(String, String)>, &str) {
+    let mut rest = raw;
+    if rest.starts_with("From ") {
+        rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+
+    let mut fields: Vec<(String, String)> = Vec::new();
+    loop {
+        let (line, next) = match rest.split_once('\n') {
+            Some((line, next)) => (line.trim_end_matches('\r'), next),
+            None => (rest.trim_end_matches('\r'), ""),
+        };
+        if line.is_empty() {
+            rest = next;
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = fields.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':')
+            && !name.is_empty()
+            && name.bytes().all(|b| b.is_ascii_graphic())
+        {
+            fields.push((name.to_string(), value.trim().to_string()));
+        } else if fields.is_empty() {
+            // Not a header block at all, the whole text is body
+            return (fields, raw);
+        }
+        if next.is_empty() {
+            rest = next;
+            break;
+        }
+        rest = next;
+    }
+    (fields, rest)
+}
+
+/// Parses the header block of a raw message into its decoded fields.
+///
+/// Folded fields are unfolded and their encoded-words decoded. The mbox `From_` line,
+/// if any, is skipped.
+pub fn parse_headers(raw: &str) -> LoreHeaders {
+    let (fields, _) = split_message(raw);
+    decode_fields(&fields)
+}
+
+/// Decodes the encoded-words of unfolded header fields.
+fn decode_fields(fields: &[(String, String)]) -> LoreHeaders {
+    LoreHeaders {
+        fields: fields
+            .iter()
+            .map(|(name, value)| {
+                (
+                    ArcStr::from(name.as_str()),
+                    ArcStr::from(decode_encoded_words(value)),
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Gets the value of the first field with the given (case insensitive) name.
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// A parsed `Content-Type` header.
+struct ContentType {
+    /// The lowercase media type, e.g. `text/plain`
+    mime: String,
+    /// The parameters, with lowercase names and unquoted values
+    params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// Parses a `Content-Type` value, defaulting to `text/plain` as RFC 2045 does.
+    fn parse(value: Option<&str>) -> Self {
+        let value = value.unwrap_or("text/plain");
+        let mut pieces = split_params(value).into_iter();
+        let mime = pieces
+            .next()
+            .map(|mime| mime.trim().to_ascii_lowercase())
+            .filter(|mime| mime.contains('/'))
+            .unwrap_or_else(|| "text/plain".to_string());
+        let params = pieces
+            .filter_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                let value = value.trim().trim_matches('"').to_string();
+                Some((name.trim().to_ascii_lowercase(), value))
+            })
+            .collect();
+        Self { mime, params }
+    }
+
+    /// Gets the value of a parameter.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Splits a structured header value on the semicolons that are not inside quotes.
+fn split_params(value: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                pieces.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    pieces.push(current);
+    pieces
+}
+
+/// Splits the body of a `multipart/*` entity into its parts.
+///
+/// The preamble and epilogue are dropped, as is the line break that precedes each
+/// boundary delimiter.
+fn split_multipart<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if let Some(tail) = trimmed.strip_prefix(&delimiter)
+            && (tail.is_empty() || tail == "--")
+        {
+            if let Some(start) = start {
+                let part = &body[start..offset];
+                let part = part.strip_suffix('\n').unwrap_or(part);
+                parts.push(part.strip_suffix('\r').unwrap_or(part));
+            }
+            if tail == "--" {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    // Tolerate a missing close delimiter
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+/// Returns true if an entity holds text worth showing: plain text or an attached patch.
+fn is_text(content_type: &ContentType, disposition: Option<&str>) -> bool {
+    let mime = content_type.mime.as_str();
+    if mime == "text/html" {
+        return false;
+    }
+    if mime.starts_with("text/") {
+        return true;
+    }
+    // Some clients attach patches as generic binary data
+    let name = disposition
+        .map(|d| ContentType::parse(Some(d)))
+        .and_then(|d| d.param("filename").map(str::to_string))
+        .or_else(|| content_type.param("name").map(str::to_string))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    matches!(
+        mime,
+        "application/x-patch" | "application/x-diff" | "application/octet-stream"
+    ) && (name.ends_with(".patch") || name.ends_with(".diff"))
+}
+
+/// Collects the decoded text of an entity and of its nested parts, in order.
+fn collect_text(fields: &[(String, String)], body: &str, depth: usize, out: &mut Vec<String>) {
+    let content_type = ContentType::parse(field(fields, "Content-Type"));
+
+    if content_type.mime.starts_with("multipart/")
+        && depth < MAX_DEPTH
+        && let Some(boundary) = content_type.param("boundary")
+    {
+        let parts = split_multipart(body, boundary);
+        if content_type.mime == "multipart/alternative" {
+            // Every part says the same thing, keep the plain text one
+            let plain = parts.iter().find(|part| {
+                let (fields, _) = split_part(part);
+                ContentType::parse(field(&fields, "Content-Type")).mime == "text/plain"
+            });
+            if let Some(part) = plain.or(parts.first()) {
+                let (fields, body) = split_part(part);
+                collect_text(&fields, body, depth + 1, out);
+            }
+        } else {
+            for part in parts {
+                let (fields, body) = split_part(part);
+                collect_text(&fields, body, depth + 1, out);
+            }
+        }
+        return;
+    }
+
+    if !is_text(&content_type, field(fields, "Content-Disposition")) {
+        return;
+    }
+    let charset = content_type.param("charset");
+    let encoding = field(fields, "Content-Transfer-Encoding")
+        .unwrap_or("7bit")
+        .trim()
+        .to_ascii_lowercase();
+    let text = match encoding.as_str() {
+        "quoted-printable" => decode_charset(&decode_quoted_printable(body), charset),
+        "base64" => match decode_base64(body) {
+            Ok(bytes) => decode_charset(&bytes, charset),
+            Err(_) => body.to_string(),
+        },
+        // 7bit, 8bit and binary text already went through UTF-8 on its way here
+        _ => body.replace("\r\n", "\n"),
+    };
+    out.push(text);
+}
+
+/// Splits a MIME part into its header fields and body, allowing parts without headers.
+fn split_part(part: &str) -> (Vec<(String, String)>, &str) {
+    match part
+        .strip_prefix('\n')
+        .or_else(|| part.strip_prefix("\r\n"))
+    {
+        Some(body) => (Vec::new(), body),
+        None => split_message(part),
+    }
+}
+
+/// Returns true if `lines[idx]` starts the diff of a patch.
+fn is_diff_start(lines: &[&str], idx: usize) -> bool {
+    let line = lines[idx];
+    line.starts_with("diff ")
+        || line.starts_with("Index: ")
+        || (line.starts_with("--- ")
+            && lines
+                .get(idx + 1)
+                .is_some_and(|next| next.starts_with("+++ ")))
+}
+
+/// Parses a trailer line into its key and value.
+fn parse_trailer(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let valid = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    (valid && !value.trim().is_empty()).then(|| (key, value.trim()))
+}
+
+/// Splits the last paragraph of a commit message off as trailers, if it is a trailer block.
+///
+/// The block may hold continuation lines (indented) and bracketed maintainer notes,
+/// such as `[jane: fixed typo]`, but needs at least one `Key: value` line.
+fn split_trailers(lines: &[&str]) -> (usize, Vec<LoreTrailer>) {
+    let end = lines.len();
+    let start = lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(0, |blank| blank + 1);
+    if start >= end {
+        return (end, Vec::new());
+    }
+
+    let mut trailers: Vec<(String, String)> = Vec::new();
+    for line in &lines[start..end] {
+        if line.starts_with([' ', '\t']) {
+            match trailers.last_mut() {
+                Some((_, value)) => {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+                None => return (end, Vec::new()),
+            }
+        } else if let Some((key, value)) = parse_trailer(line) {
+            trailers.push((key.to_string(), value.to_string()));
+        } else if !(line.starts_with('[') && line.ends_with(']')) {
+            return (end, Vec::new());
+        }
+    }
+    if trailers.is_empty() {
+        return (end, Vec::new());
+    }
+
+    let trailers = trailers
+        .into_iter()
+        .map(|(key, value)| LoreTrailer {
+            key: ArcStr::from(key),
+            value: ArcStr::from(value),
+        })
+        .collect();
+    (start, trailers)
+}
+
+/// Returns true if a line belongs to a diffstat, summary line excluded.
+fn is_diffstat_line(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix(' ') else {
+        return false;
+    };
+    match rest.split_once(" | ") {
+        Some((path, stat)) => {
+            let stat = stat.trim_start();
+            !path.trim().is_empty()
+                && (stat.starts_with(|c: char| c.is_ascii_digit()) || stat.starts_with("Bin"))
+        }
+        None => [
+            "create mode ",
+            "delete mode ",
+            "rename ",
+            "copy ",
+            "mode change ",
+        ]
+        .iter()
+        .any(|prefix| rest.starts_with(prefix)),
+    }
+}
+
+/// Returns true if a line is the `N files changed, ...` summary of a diffstat.
+fn is_diffstat_summary(line: &str) -> bool {
+    let line = line.trim_start();
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0
+        && (line[digits..].starts_with(" file changed")
+            || line[digits..].starts_with(" files changed"))
+}
+
+/// Joins lines with newlines, dropping leading and trailing blank lines.
+fn join_trimmed(lines: &[&str]) -> ArcStr {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => ArcStr::from(lines[start..=end].join("\n")),
+        _ => ArcStr::default(),
+    }
+}
+
+/// Splits a decoded patch body into commit message, trailers, notes, diffstat and diff.
+fn split_patch_body(body: &str, message: &mut LorePatchMessage) {
+    let mut lines: Vec<&str> = body.lines().collect();
+    let diff_start = (0..lines.len()).find(|&idx| is_diff_start(&lines, idx));
+
+    // The signature follows the diff, a removed `- ` line within a hunk is no signature
+    let content_end = diff_start.map_or(0, |start| {
+        start + parse_diff(&lines[start..].join("\n")).end_line()
+    });
+    if let Some(signature) = (content_end..lines.len()).rfind(|&idx| lines[idx] == "-- ") {
+        lines.truncate(signature);
+    }
+    let Some(diff_start) = diff_start else {
+        let (end, trailers) = split_trailers(&lines);
+        message.commit_message = join_trimmed(&lines[..end]);
+        message.trailers = trailers;
+        return;
+    };
+
+    let separator = lines[..diff_start].iter().position(|line| *line == "---");
+    let commit_end = separator.unwrap_or(diff_start);
+    let commit: Vec<&str> = {
+        let end = lines[..commit_end]
+            .iter()
+            .rposition(|l| !l.trim().is_empty())
+            .map_or(0, |last| last + 1);
+        lines[..end].to_vec()
+    };
+    let (end, trailers) = split_trailers(&commit);
+    message.commit_message = join_trimmed(&commit[..end]);
+    message.trailers = trailers;
+
+    if let Some(separator) = separator {
+        let between = &lines[separator + 1..diff_start];
+        match between.iter().rposition(|line| is_diffstat_summary(line)) {
+            Some(summary) => {
+                let mut stat_start = summary;
+                while stat_start > 0 && is_diffstat_line(between[stat_start - 1]) {
+                    stat_start -= 1;
+                }
+                let mut stat_end = summary + 1;
+                while stat_end < between.len() && is_diffstat_line(between[stat_end]) {
+                    stat_end += 1;
+                }
+                message.diffstat = join_trimmed(&between[stat_start..stat_end]);
+                let mut notes = between[..stat_start].to_vec();
+                notes.extend_from_slice(&between[stat_end..]);
+                message.notes = join_trimmed(&notes);
+            }
+            None => message.notes = join_trimmed(between),
+        }
+    }
+
+    let mut diff = lines[diff_start..].join("\n");
+    diff.push('\n');
+    message.diff = ArcStr::from(diff);
+}
+
+/// Parses a raw message into a [`LorePatchMessage`].
+///
+/// Headers are unfolded and decoded, the body is extracted from its MIME structure
+/// (keeping `text/*` parts and attached patches, skipping HTML and binary parts),
+/// transfer-decoded and converted to UTF-8, then split into its `git format-patch`
+/// sections.
+///
+/// # Arguments
+/// * `raw` - The raw message, optionally starting with an mbox `From_` line
+///
+/// # Returns
+/// The structured message.
+///
+/// # Errors
+/// Returns an error if the text does not start with a header block.
+pub fn parse_patch_message(raw: &str) -> anyhow::Result<LorePatchMessage> {
+    let (fields, body) = split_message(raw);
+    if fields.is_empty() {
+        anyhow::bail!("Message has no headers");
+    }
+
+    let mut texts = Vec::new();
+    collect_text(&fields, body, 0, &mut texts);
+    let mut body = String::new();
+    for text in texts {
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        body.push_str(&text);
+    }
+
+    let headers = decode_fields(&fields);
+    let (author, email) = headers
+        .get("From")
+        .map(|from| parse_address(from))
+        .unwrap_or_default();
+
+    let mut message = LorePatchMessage {
+        subject: headers.get("Subject").cloned().unwrap_or_default(),
+        author,
+        email,
+        date: headers.get("Date").and_then(|date| parse_date(date)),
+        message_id: headers
+            .get("Message-ID")
+            .and_then(|id| parse_message_ids(id).into_iter().next()),
+        body: ArcStr::from(body.as_str()),
+        headers,
+        ..Default::default()
+    };
+    split_patch_body(&body, &mut message);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970
+From: =?UTF-8?q?Andr=C3=A9?= Almeida <andre@example.com>
+Subject: [PATCH v2 1/2] net: do
+ the thing
+Date: Mon, 6 Jan 2025 10:00:00 +0000
+Message-ID: <20250106.1-andre@example.com>
+
+The thing needs doing.
+
+Fixes: 0123456789ab (\"net: do the other thing\")
+Signed-off-by: André Almeida <andre@example.com>
+Reviewed-by: Jane Doe
+ <jane@example.com>
+---
+v2: rebased
+
+ net/file.c | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/net/file.c b/net/file.c
+--- a/net/file.c
++++ b/net/file.c
+@@ -1 +1 @@
+-old
++new
+-- \n\
+2.43.0
+";
+
+    #[test]
+    fn test_decode_encoded_words() {
+        assert_eq!(
+            decode_encoded_words("=?UTF-8?B?QW5kcsOp?= Almeida"),
+            "André Almeida"
+        );
+        // Whitespace between adjacent encoded-words is not part of the text
+        assert_eq!(
+            decode_encoded_words("=?iso-8859-1?q?J=F6rg?= =?utf-8?q?_M=C3=BCller?="),
+            "Jörg Müller"
+        );
+        assert_eq!(
+            decode_encoded_words("=?utf-8*en?Q?caf=C3=A9?= au lait"),
+            "café au lait"
+        );
+        assert_eq!(decode_encoded_words("a =? b ?= c"), "a =? b ?= c");
+        assert_eq!(decode_encoded_words("no words"), "no words");
+    }
+
+    #[test]
+    fn test_encode_encoded_words() {
+        assert_eq!(encode_encoded_words("Jane Doe"), "Jane Doe");
+        assert_eq!(encode_encoded_words("André"), "=?UTF-8?B?QW5kcsOp?=");
+        let long = "Ünïcödé ".repeat(10);
+        let encoded = encode_encoded_words(&long);
+        assert!(encoded.lines().all(|line| line.trim().len() <= 75));
+        assert!(encoded.lines().count() > 1);
+        assert_eq!(decode_encoded_words(&encoded), long);
+
+        let headers = parse_headers(&format!("Subject: {encoded}\nFrom: a@b.c\n\nbody"));
+        assert_eq!(
+            headers.get("subject").map(|s| s.as_ref()),
+            Some(long.as_str())
+        );
+    }
+
+    #[test]
+    fn test_decode_quoted_printable() {
+        assert_eq!(
+            decode_quoted_printable("caf=C3=A9 =3D soft=\nbreak=  \nend=XZ"),
+            "café = softbreakend=XZ".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_decode_charset() {
+        assert_eq!(decode_charset(b"J\xf6rg", Some("ISO-8859-1")), "Jörg");
+        assert_eq!(decode_charset("Jörg".as_bytes(), Some("bogus")), "Jörg");
+        assert_eq!(decode_charset(b"\xff", None), "\u{fffd}");
+    }
+
+    #[test]
+    fn test_parse_patch_message_sections() {
+        let message = parse_patch_message(PATCH).unwrap();
+        assert_eq!(
+            message.subject,
+            ArcStr::from("[PATCH v2 1/2] net: do the thing")
+        );
+        assert_eq!(message.author, ArcStr::from("André Almeida"));
+        assert_eq!(message.email, ArcStr::from("andre@example.com"));
+        assert_eq!(
+            message.message_id,
+            Some(ArcStr::from("20250106.1-andre@example.com"))
+        );
+        assert!(message.date.is_some());
+        assert_eq!(
+            message.commit_message,
+            ArcStr::from("The thing needs doing.")
+        );
+        assert_eq!(message.trailers.len(), 3);
+        assert_eq!(
+            message.trailer("reviewed-by").collect::<Vec<_>>(),
+            vec![&ArcStr::from("Jane Doe <jane@example.com>")]
+        );
+        assert_eq!(message.notes, ArcStr::from("v2: rebased"));
+        assert_eq!(
+            message.diffstat,
+            ArcStr::from(" net/file.c | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)")
+        );
+        assert!(message.diff.starts_with("diff --git a/net/file.c"));
+        assert!(message.diff.ends_with("+new\n"));
+        assert!(message.is_patch());
+    }
+
+    #[test]
+    fn test_parse_patch_message_removed_dash_line() {
+        // Removing a `- ` list item gives a `-- ` hunk line, and there is no signature
+        let raw = "From: jane@example.com
+Subject: [PATCH] docs: drop an item
+
+Drop it.
+---
+ docs/list.yaml | 2 --
+ 1 file changed, 2 deletions(-)
+
+diff --git a/docs/list.yaml b/docs/list.yaml
+--- a/docs/list.yaml
++++ b/docs/list.yaml
+@@ -1,4 +1,2 @@
+ items:
+-- 
+-- empty
+   - kept
+";
+        let message = parse_patch_message(raw).unwrap();
+        assert_eq!(
+            message.diffstat,
+            ArcStr::from(" docs/list.yaml | 2 --\n 1 file changed, 2 deletions(-)")
+        );
+        assert!(message.diff.ends_with("-- \n-- empty\n   - kept\n"));
+        let diff = message.parse_diff();
+        assert_eq!(diff.deletions(), 2);
+        assert_eq!(diff.files[0].hunks[0].lines.len(), 4);
+
+        // A signature after the diff is still dropped
+        let signed = format!("{raw}-- \n2.43.0\n");
+        let message = parse_patch_message(&signed).unwrap();
+        assert!(message.diff.ends_with("   - kept\n"));
+    }
+
+    #[test]
+    fn test_parse_patch_message_without_diff() {
+        let raw = "From: jane@example.com\nSubject: Re: [PATCH] x\n\nLooks good.\n\nAcked-by: Jane <jane@example.com>\n";
+        let message = parse_patch_message(raw).unwrap();
+        assert!(!message.is_patch());
+        assert_eq!(message.commit_message, ArcStr::from("Looks good."));
+        assert_eq!(message.trailers[0].key, ArcStr::from("Acked-by"));
+        assert!(message.diffstat.is_empty());
+        assert!(parse_patch_message("").is_err());
+    }
+
+    #[test]
+    fn test_parse_patch_message_quoted_printable() {
+        let raw = "From: a@b.c\r
+Subject: x\r
+Content-Type: text/plain; charset=\"iso-8859-1\"\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+J=F6rg's fix, with a very long line that the mail client had to wr=\r
+ap.\r
+---\r
+diff --git a/f b/f\r
+";
+        let message = parse_patch_message(raw).unwrap();
+        assert_eq!(
+            message.commit_message,
+            ArcStr::from("Jörg's fix, with a very long line that the mail client had to wrap.")
+        );
+        assert_eq!(message.diff, ArcStr::from("diff --git a/f b/f\n"));
+    }
+
+    #[test]
+    fn test_parse_patch_message_multipart() {
+        let raw = "From: a@b.c
+Subject: [PATCH] attached
+MIME-Version: 1.0
+Content-Type: multipart/mixed; boundary=\"=-=-=\"
+
+This is a multi-part message in MIME format.
+--=-=-=
+Content-Type: multipart/alternative; boundary=alt
+
+--alt
+Content-Type: text/html
+
+<p>ignored</p>
+--alt
+Content-Type: text/plain; charset=utf-8
+
+Commit message.
+--alt--
+--=-=-=
+Content-Type: application/octet-stream; name=\"fix.patch\"
+Content-Disposition: attachment; filename=\"fix.patch\"
+Content-Transfer-Encoding: base64
+
+ZGlmZiAtLWdpdCBhL2YgYi9mCi0tLSBhL2YKKysrIGIvZgo=
+--=-=-=
+Content-Type: image/png
+Content-Transfer-Encoding: base64
+
+iVBORw0KGgo=
+--=-=-=--
+epilogue
+";
+        let message = parse_patch_message(raw).unwrap();
+        assert_eq!(
+            message.body,
+            ArcStr::from("Commit message.\ndiff --git a/f b/f\n--- a/f\n+++ b/f\n")
+        );
+        assert_eq!(message.commit_message, ArcStr::from("Commit message."));
+        assert!(message.diff.starts_with("diff --git a/f b/f\n"));
+    }
+
+    #[test]
+    fn test_to_text() {
+        let message = parse_patch_message(PATCH).unwrap();
+        let text = message.to_text();
+        assert!(text.starts_with(
+            "From: André Almeida <andre@example.com>\nDate: Mon, 6 Jan 2025 10:00:00 +0000\nSubject: [PATCH v2 1/2] net: do the thing\n\nThe thing"
+        ));
+    }
+}
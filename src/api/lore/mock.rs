@@ -3,7 +3,10 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::utils::ArcSlice;
-use crate::{ArcStr, api::lore::{LorePage, LorePatchMetadata, LoreMailingList}};
+use crate::{
+    ArcStr,
+    api::lore::{LoreMailingList, LorePage, LorePatchMetadata, LoreSearch, LoreThread},
+};
 
 /// Mock implementation of the Lore API for testing purposes.
 ///
@@ -50,10 +53,41 @@ impl Mock {
         let xml = responses.get(&key).cloned().ok_or_else(|| {
             anyhow::anyhow!("Patch feed page not found in mock responses: {}", key)
         })?;
-        
+
         // Parse the XML string into LorePage<LorePatchMetadata>
-        let page: LorePage<LorePatchMetadata> =
-            crate::api::lore::parse::parse_patch_feed_xml(&xml, min_index)?;
+        let page: LorePage<LorePatchMetadata> = crate::api::lore::parse::parse_patch_feed_xml(
+            &xml,
+            min_index,
+            crate::api::lore::DEFAULT_DOMAIN,
+        )?;
+        Ok(Some(page))
+    }
+
+    /// Fetches a page of the patches matching a search.
+    ///
+    /// # Arguments
+    /// * `search` - The query and the list it runs against
+    /// * `min_index` - The offset for pagination (0-based)
+    ///
+    /// # Returns
+    /// The parsed page of results, or an error if not found in mock responses.
+    pub async fn search_page(
+        &self,
+        search: LoreSearch,
+        min_index: usize,
+    ) -> anyhow::Result<Option<LorePage<LorePatchMetadata>>> {
+        let responses = self.responses.lock().await;
+        let key = format!("search_page_{}_{}_{min_index}", search.list, search.query);
+        let xml = responses
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Search page not found in mock responses: {}", key))?;
+
+        let page: LorePage<LorePatchMetadata> = crate::api::lore::parse::parse_patch_feed_xml(
+            &xml,
+            min_index,
+            crate::api::lore::DEFAULT_DOMAIN,
+        )?;
         Ok(Some(page))
     }
 
@@ -73,15 +107,15 @@ impl Mock {
         let html = responses.get(&key).cloned().ok_or_else(|| {
             anyhow::anyhow!("Available lists page not found in mock responses: {}", key)
         })?;
-        
-        let page: LorePage<LoreMailingList> =
-            crate::api::lore::parse::parse_available_lists_html(&html, min_index)?
-                .ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "No available lists page found in mock responses: {}",
-                        key
-                    )
-                })?;
+
+        let page: LorePage<LoreMailingList> = crate::api::lore::parse::parse_available_lists_html(
+            &html,
+            min_index,
+            crate::api::lore::DEFAULT_DOMAIN,
+        )?
+        .ok_or_else(|| {
+            anyhow::anyhow!("No available lists page found in mock responses: {}", key)
+        })?;
         Ok(Some(page))
     }
 
@@ -93,17 +127,18 @@ impl Mock {
         let responses = self.responses.lock().await;
         let mut all_lists = Vec::new();
         let mut min_index = 0;
-        
+
         loop {
             let key = format!("available_lists_page_{min_index}");
             if let Some(html) = responses.get(&key) {
                 let page: LorePage<LoreMailingList> =
-                    crate::api::lore::parse::parse_available_lists_html(html, min_index)?
-                        .ok_or_else(|| {
-                        anyhow::anyhow!(
-                            "No available lists page found in mock responses: {}",
-                            key
-                        )
+                    crate::api::lore::parse::parse_available_lists_html(
+                        html,
+                        min_index,
+                        crate::api::lore::DEFAULT_DOMAIN,
+                    )?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("No available lists page found in mock responses: {}", key)
                     })?;
                 all_lists.extend(page.items.iter().cloned());
                 if page.next_page_index.is_none() {
@@ -114,7 +149,7 @@ impl Mock {
                 break;
             }
         }
-        
+
         Ok(ArcSlice::from(all_lists))
     }
 
@@ -133,9 +168,10 @@ impl Mock {
     ) -> anyhow::Result<ArcStr> {
         let responses = self.responses.lock().await;
         let key = format!("patch_html_{target_list}_{message_id}");
-        responses.get(&key).cloned().ok_or_else(|| {
-            anyhow::anyhow!("Patch HTML not found in mock responses: {}", key)
-        })
+        responses
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Patch HTML not found in mock responses: {}", key))
     }
 
     /// Fetches a raw patch in plain text format.
@@ -153,9 +189,35 @@ impl Mock {
     ) -> anyhow::Result<ArcStr> {
         let responses = self.responses.lock().await;
         let key = format!("raw_patch_{target_list}_{message_id}");
-        responses.get(&key).cloned().ok_or_else(|| {
-            anyhow::anyhow!("Raw patch not found in mock responses: {}", key)
-        })
+        responses
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Raw patch not found in mock responses: {}", key))
+    }
+
+    /// Fetches the whole thread a message belongs to.
+    ///
+    /// The mock response holds the uncompressed thread mbox.
+    ///
+    /// # Arguments
+    /// * `target_list` - The mailing list name
+    /// * `message_id` - The message ID of any message in the thread
+    ///
+    /// # Returns
+    /// The parsed thread, or an error if not found in mock responses.
+    pub async fn get_thread(
+        &self,
+        target_list: ArcStr,
+        message_id: ArcStr,
+    ) -> anyhow::Result<LoreThread> {
+        let responses = self.responses.lock().await;
+        let key = format!("thread_{target_list}_{message_id}");
+        let mbox = responses
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Thread not found in mock responses: {}", key))?;
+
+        Ok(crate::api::lore::parse::parse_thread_mbox(&mbox))
     }
 
     /// Fetches patch metadata in JSON format.
@@ -173,8 +235,9 @@ impl Mock {
     ) -> anyhow::Result<ArcStr> {
         let responses = self.responses.lock().await;
         let key = format!("patch_metadata_{target_list}_{message_id}");
-        responses.get(&key).cloned().ok_or_else(|| {
-            anyhow::anyhow!("Patch metadata not found in mock responses: {}", key)
-        })
+        responses
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Patch metadata not found in mock responses: {}", key))
     }
 }
@@ -1,95 +1,128 @@
-use super::data::{LoreMailingList, LorePage, LorePatchMetadata};
+use super::data::{
+    LoreFeedDiagnostic, LoreMailingList, LoreMessage, LorePage, LorePatchMetadata, LorePatchTitle,
+    LoreThread, LoreThreadNode,
+};
+use super::mime::parse_headers;
 use crate::{ArcStr, SequenceNumber};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use regex::Regex;
+use scraper::{Html, Selector};
 use serde::Deserialize;
 use serde_xml_rs::from_str;
+use std::collections::HashMap;
+use std::io::Read;
 
-/// Parses the HTML listing of available mailing lists into structured data using regex.
+/// Reads the `* 2025-07-14 13:47 -` marker that ends the text before a list link.
+///
+/// # Returns
+/// The date and time of the marker, or None if the text does not end with one.
+fn list_entry_marker(text: &str) -> Option<&str> {
+    let line = text.trim_end().rsplit('\n').next()?.trim();
+    let marker = line.strip_prefix('*')?.trim_start();
+    let marker = marker.strip_suffix('-').unwrap_or(marker).trim_end();
+    (!marker.is_empty()).then_some(marker)
+}
+
+/// Reads the description that follows a list link, up to the next `*` entry.
+fn list_entry_description(text: &str) -> String {
+    let mut lines = text.lines();
+    // The rest of the link's own line, usually empty
+    let first = lines.next().unwrap_or_default().trim();
+    std::iter::once(first)
+        .chain(
+            lines
+                .map(str::trim)
+                .take_while(|line| !line.starts_with('*')),
+        )
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses the HTML listing of available mailing lists into structured data.
+///
+/// The page is parsed into a DOM, and every link preceded by a `* <date> <time> -`
+/// marker is taken as a list, with the text after it (if any) as its description.
+/// Other links (navigation, pagination) are ignored, so layout changes around the
+/// entries do not matter. Entries whose date cannot be parsed are left out and
+/// reported in the page's diagnostics.
 ///
 /// # Arguments
 /// * `html` - The HTML content as a string
 /// * `start_index` - The current start index for pagination
+/// * `archive` - The base URL of the archive the page comes from
 ///
 /// # Returns
 /// A `LorePage<LoreMailingList>` struct containing pagination info and a list of items, or None if no items are found.
 ///
 /// # Errors
-/// Returns an error if parsing fails.
+/// Returns an error if the pagination information cannot be parsed.
 pub fn parse_available_lists_html(
     html: &str,
     start_index: usize,
+    archive: &str,
 ) -> anyhow::Result<Option<LorePage<LoreMailingList>>> {
     use anyhow::{Context, anyhow};
 
+    let selector =
+        |css: &str| Selector::parse(css).map_err(|e| anyhow!("Invalid selector '{}': {}", css, e));
+    let document = Html::parse_document(html);
+
     let mut items = Vec::new();
-    let mut next_page_index = None;
-    let mut total_items = None;
+    let mut diagnostics = Vec::new();
+    for link in document.select(&selector("a[href]")?) {
+        let Some(marker) = link
+            .prev_sibling()
+            .and_then(|node| node.value().as_text().map(|text| &**text))
+            .and_then(list_entry_marker)
+        else {
+            continue;
+        };
+        let name = link.text().collect::<String>();
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
 
-    let mut lines = html.lines().peekable();
-    while let Some(line) = lines.next() {
-        let line = line.trim();
-        if line.starts_with('*') {
-            // Extract the date and time (first two fields after '*')
-            let mut parts = line.split_whitespace();
-            parts.next(); // skip '*'
-            let date = parts
-                .next()
-                .ok_or_else(|| anyhow!("Missing date in mailing list entry: '{}'", line))?;
-            let time = parts
-                .next()
-                .ok_or_else(|| anyhow!("Missing time in mailing list entry: '{}'", line))?;
-            let datetime_str = format!("{date} {time}");
-            let last_update = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M")
-                .map(|ndt| DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc))
-                .with_context(|| {
-                    format!(
-                        "Failed to parse date/time '{datetime_str}' in mailing list entry: '{line}'"
-                    )
-                })?;
-
-            // Next line: href="all/">all</a>
-            let name_line = lines
-                .next()
-                .ok_or_else(|| anyhow!("Missing name line after entry: '{}'", line))?
-                .trim();
-            let name = if let Some(gt_idx) = name_line.find('>') {
-                let after_gt = &name_line[gt_idx + 1..];
-                if let Some(end_tag) = after_gt.find("</a>") {
-                    after_gt[..end_tag].trim()
-                } else {
-                    after_gt.trim()
-                }
-            } else {
-                return Err(anyhow!(
-                    "Failed to find mailing list name in line: '{}'",
-                    name_line
-                ));
-            };
+        let last_update = match NaiveDateTime::parse_from_str(marker, "%Y-%m-%d %H:%M") {
+            Ok(ndt) => DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc),
+            Err(e) => {
+                diagnostics.push(LoreFeedDiagnostic {
+                    position: start_index + items.len() + diagnostics.len(),
+                    title: ArcStr::from(name),
+                    reason: ArcStr::from(format!("Invalid update date '{}': {}", marker, e)),
+                });
+                continue;
+            }
+        };
+        let description = link
+            .next_sibling()
+            .and_then(|node| {
+                node.value()
+                    .as_text()
+                    .map(|text| list_entry_description(text))
+            })
+            .unwrap_or_default();
 
-            // Next line: description
-            let desc_line = lines
-                .next()
-                .ok_or_else(|| anyhow!("Missing description line after entry: '{}'", line))?
-                .trim();
-            let description = desc_line.to_string();
-
-            items.push(LoreMailingList {
-                name: ArcStr::from(name),
-                description: ArcStr::from(&description),
-                last_update,
-            });
-        }
+        items.push(LoreMailingList {
+            name: ArcStr::from(name),
+            description: ArcStr::from(&description),
+            last_update,
+            archive: ArcStr::from(archive),
+        });
     }
 
-    // Regex to find the next page index from the <a rel=next> link
-    let next_re = Regex::new(r#"<a[^>]*rel=next[^>]*href="\?&o=([0-9]+)""#)
-        .context("Failed to compile next page regex")?;
-    if let Some(cap) = next_re.captures(html) {
-        let idx_str = cap
-            .get(1)
-            .ok_or_else(|| anyhow!("Failed to capture next page index"))?
-            .as_str();
+    // The next page index comes from the <a rel=next href="?&o=200"> link
+    let mut next_page_index = None;
+    if let Some(href) = document
+        .select(&selector("a[rel=next][href]")?)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+    {
+        let idx_str = href
+            .split(['?', '&'])
+            .find_map(|param| param.strip_prefix("o="))
+            .ok_or_else(|| anyhow!("Failed to find next page index in '{}'", href))?;
         let idx = idx_str
             .parse::<usize>()
             .with_context(|| format!("Failed to parse next page index: '{idx_str}'"))?;
@@ -97,33 +130,28 @@ pub fn parse_available_lists_html(
     }
 
     // Regex to extract next page index and total items from "Results 1-200 of ~337"
+    let text = document.root_element().text().collect::<String>();
     let total_re = Regex::new(r"Results [0-9]+(-[0-9]+)? of ~?([0-9,]+)")
         .context("Failed to compile total items regex")?;
-    if let Some(cap) = total_re.captures(html) {
-        if cap.len() < 2 {
-            return Err(anyhow!("Failed to capture results count information"));
-        }
-
-        if cap.len() == 3 {
-            if let Some(next) = cap.get(1) {
-                let next_str = next.as_str().replace("-", "");
-                let idx = next_str
-                    .parse::<usize>()
-                    .with_context(|| format!("Failed to parse next page index: '{next_str}'"))?;
-                next_page_index = Some(idx);
-            }
-        }
-
-        if let Some(total) = cap.get(cap.len() - 1) {
-            let total_str = total.as_str().replace(",", "");
-            let total_val = total_str
+    let mut total_items = None;
+    if let Some(cap) = total_re.captures(&text) {
+        if let Some(next) = cap.get(1) {
+            let next_str = next.as_str().replace("-", "");
+            let idx = next_str
                 .parse::<usize>()
-                .with_context(|| format!("Failed to parse total items: '{total_str}'"))?;
-            total_items = Some(total_val);
+                .with_context(|| format!("Failed to parse next page index: '{next_str}'"))?;
+            next_page_index = Some(idx);
         }
+
+        let total_str = cap[2].replace(",", "");
+        let total_val = total_str
+            .parse::<usize>()
+            .with_context(|| format!("Failed to parse total items: '{total_str}'"))?;
+        total_items = Some(total_val);
     }
 
-    if start_index == total_items.unwrap_or(0) {
+    // Past the last page, or a page that is not a listing at all
+    if items.is_empty() && diagnostics.is_empty() {
         return Ok(None);
     }
 
@@ -132,70 +160,107 @@ pub fn parse_available_lists_html(
         next_page_index,
         total_items,
         items,
+        diagnostics,
     }))
 }
 
-/// Parses a patch title to extract version and sequence information.
+/// Parses the bracketed tags of a patch title.
+///
+/// Every leading `[...]` group is read, so both `[RFC][PATCH v2 1/3]` and
+/// `[PATCH] [media]` work. Inside the groups, tokens separated by spaces or commas
+/// are classified case-insensitively:
+/// - `PATCH` (also `PATCHv2`): marks the title as a patch
+/// - `RFC` and `RESEND`: set the matching flags
+/// - `vN`: the version, 1 if absent
+/// - `x/y`: the position in the series, absent for a single patch
+/// - anything else: a tree or subsystem prefix such as `net-next`, `bpf` or `6.1`
 ///
-/// The patch title must start with one of the following patterns:
-/// - [PATCH]: version 1, simple series (1 out of 1 patch)
-/// - [PATCH x/y]: version 1, patch x in a series of y patches
-/// - [PATCH vZ]: version Z, simple series
-/// - [PATCH vZ x/y]: version Z, patch x in a series of y patches
+/// So `[PATCH net-next v3 02/10]`, `[RFC PATCH]`, `[PATCH v2 RFC]`, `[PATCH RESEND]`
+/// and the stable review titles like `[PATCH 6.1 12/40]` are all understood.
 ///
 /// # Arguments
 /// * `title` - The patch title to parse
 ///
 /// # Returns
-/// A tuple of (version, sequence_number) where:
-/// - version: The patch version (defaults to 1 if not specified)
-/// - sequence_number: The sequence number in the series (None if simple series)
+/// The tags of the title along with the subject that follows them.
 ///
 /// # Errors
-/// Returns an error if the title doesn't match any expected pattern.
-pub fn parse_patch_title(title: &str) -> anyhow::Result<(usize, Option<SequenceNumber>)> {
-    use anyhow::{Context, anyhow};
+/// Returns an error if the title has no `PATCH` or `RFC` tag (replies, pull requests and
+/// announcements) or if a version or sequence tag is malformed.
+pub fn parse_patch_title(title: &str) -> anyhow::Result<LorePatchTitle> {
+    use anyhow::{Context, anyhow, bail};
 
-    // Regex to match patch title patterns with named captures
-    let patch_regex = Regex::new(
-        r"^\[PATCH\s*(?:v(?P<version>\d+))?\s*(?:(?P<current>\d+)/(?P<total>\d+))?\s*\]",
-    )
-    .context("Failed to compile patch title regex")?;
-
-    if let Some(captures) = patch_regex.captures(title) {
-        // Extract version (defaults to 1 if not specified)
-        let version = if let Some(version_match) = captures.name("version") {
-            version_match.as_str().parse::<usize>().with_context(|| {
-                format!(
-                    "Failed to parse version number: '{}'",
-                    version_match.as_str()
-                )
-            })?
-        } else {
-            1
+    let mut rest = title.trim_start();
+    let mut tokens = Vec::new();
+    while let Some(group) = rest.strip_prefix('[') {
+        let end = group
+            .find(']')
+            .with_context(|| format!("Unterminated tag in patch title: '{}'", title))?;
+        tokens.extend(
+            group[..end]
+                .split([' ', '\t', ','])
+                .filter(|token| !token.is_empty()),
+        );
+        rest = group[end + 1..].trim_start();
+    }
+
+    let mut parsed = LorePatchTitle {
+        version: 1,
+        sequence: None,
+        rfc: false,
+        resend: false,
+        prefixes: Vec::new(),
+        subject: ArcStr::from(rest.trim_end()),
+    };
+    let mut is_patch = false;
+    for token in tokens {
+        let upper = token.to_ascii_uppercase();
+        // `PATCHv2` and `PATCH-v2` carry the version in the same token
+        let tag = match upper.strip_prefix("PATCH") {
+            Some(suffix) => {
+                is_patch = true;
+                suffix.trim_start_matches('-')
+            }
+            None if upper == "RFC" => {
+                parsed.rfc = true;
+                continue;
+            }
+            None if upper == "RESEND" => {
+                parsed.resend = true;
+                continue;
+            }
+            None => upper.as_str(),
         };
 
-        // Extract sequence information
-        let sequence = if let (Some(current_match), Some(total_match)) =
-            (captures.name("current"), captures.name("total"))
+        if tag.is_empty() {
+            continue;
+        }
+        if let Some(number) = tag.strip_prefix('V')
+            && number.bytes().all(|b| b.is_ascii_digit())
         {
-            let seq_str = format!("{}/{}", current_match.as_str(), total_match.as_str());
-            seq_str
-                .parse::<SequenceNumber>()
-                .with_context(|| format!("Failed to parse sequence number: '{}'", seq_str))
-                .map(Some)?
+            parsed.version = number
+                .parse::<usize>()
+                .with_context(|| format!("Failed to parse version number: '{}'", token))?;
+        } else if token.contains('/') && token.bytes().all(|b| b.is_ascii_digit() || b == b'/') {
+            parsed.sequence = Some(
+                token
+                    .parse::<SequenceNumber>()
+                    .with_context(|| format!("Failed to parse sequence number: '{}'", token))?,
+            );
+        } else if tag.len() == upper.len() {
+            parsed.prefixes.push(ArcStr::from(token));
         } else {
-            None
-        };
+            bail!("Unknown patch tag '{}' in title: '{}'", token, title);
+        }
+    }
 
-        Ok((version, sequence))
-    } else {
-        // If the title doesn't match the expected pattern, skip it
-        Err(anyhow!(
+    if !is_patch && !parsed.rfc {
+        return Err(anyhow!(
             "Patch title does not match expected format: '{}'",
             title
-        ))
+        ));
     }
+    Ok(parsed)
 }
 
 /// Parses the XML patch feed into structured data using serde_xml_rs.
@@ -203,15 +268,19 @@ pub fn parse_patch_title(title: &str) -> anyhow::Result<(usize, Option<SequenceN
 /// # Arguments
 /// * `xml` - The XML content as a string
 /// * `start_index` - The current start index for pagination
+/// * `domain` - The base URL of the archive, which entry links must point into
 ///
 /// # Returns
 /// A `LorePage<LorePatchMetadata>` struct containing pagination info and a list of patches.
+/// Entries whose title, date or link cannot be parsed are left out of the items and
+/// reported in the page's diagnostics instead.
 ///
 /// # Errors
-/// Returns an error if parsing fails.
+/// Returns an error if the XML itself cannot be parsed.
 pub fn parse_patch_feed_xml(
     xml: &str,
     start_index: usize,
+    domain: &str,
 ) -> anyhow::Result<LorePage<LorePatchMetadata>> {
     #[derive(Debug, Deserialize)]
     struct Feed {
@@ -230,6 +299,8 @@ pub fn parse_patch_feed_xml(
         id: String,
         updated: String,
         link: Link,
+        #[serde(rename = "thr:in-reply-to", default)]
+        in_reply_to: Option<Link>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -246,52 +317,359 @@ pub fn parse_patch_feed_xml(
         rel: Option<String>,
     }
 
+    /// Converts a feed entry into patch metadata, explaining what is wrong with it otherwise.
+    fn parse_entry(
+        entry: Entry,
+        list_message_id_regex: &Regex,
+    ) -> anyhow::Result<LorePatchMetadata> {
+        use anyhow::Context;
+
+        let tags = parse_patch_title(&entry.title)?;
+        let datetime = DateTime::parse_from_rfc3339(&entry.updated)
+            .map(|dt| dt.with_timezone(&Utc))
+            .with_context(|| format!("Invalid update date: '{}'", entry.updated))?;
+
+        let link = entry.link.href.context("Entry has no link")?;
+        let captures = list_message_id_regex
+            .captures(&link)
+            .with_context(|| format!("Link does not point to a Lore message: '{}'", link))?;
+        let list = &captures[1];
+        let message_id = &captures[2];
+
+        let in_reply_to = entry
+            .in_reply_to
+            .and_then(|parent| parent.href)
+            .and_then(|href| {
+                let captures = list_message_id_regex.captures(&href)?;
+                Some(ArcStr::from(captures.get(2)?.as_str()))
+            });
+
+        Ok(LorePatchMetadata {
+            author: ArcStr::from(&entry.author.name),
+            email: ArcStr::from(&entry.author.email),
+            last_update: datetime,
+            title: ArcStr::from(&entry.title),
+            version: tags.version,
+            sequence: tags.sequence,
+            link: ArcStr::from(&link),
+            list: ArcStr::from(list),
+            message_id: ArcStr::from(message_id),
+            in_reply_to,
+            rfc: tags.rfc,
+            resend: tags.resend,
+            prefixes: tags.prefixes,
+        })
+    }
+
     use anyhow::Context;
-    use chrono::{DateTime, Utc};
     let feed: Feed = from_str(xml).context("Failed to parse patch feed XML")?;
-    let list_message_id_regex = Regex::new(r"https://lore.kernel.org/([^/]+)/([^/]+)/")
-        .context("Failed to compile list message ID regex")?;
-
-    let items = feed
-        .entries
-        .into_iter()
-        .filter_map(|entry| {
-            // Parse patch title to extract version and sequence information
-            let (version, sequence) = parse_patch_title(&entry.title).ok()?;
-
-            let datetime = DateTime::parse_from_rfc3339(&entry.updated)
-                .map(|dt| dt.with_timezone(&Utc))
-                .ok()?;
-
-            let link = entry.link.href?;
-            let captures = list_message_id_regex.captures(&link)?;
-
-            let list = captures.get(1)?.as_str();
-
-            let message_id = captures.get(2)?.as_str();
-
-            Some(LorePatchMetadata {
-                author: ArcStr::from(&entry.author.name),
-                email: ArcStr::from(&entry.author.email),
-                last_update: datetime,
-                title: ArcStr::from(&entry.title),
-                version,
-                sequence,
-                link: ArcStr::from(&link),
-                list: ArcStr::from(list),
-                message_id: ArcStr::from(message_id),
-            })
-        })
-        .collect::<Vec<_>>();
+    // Mirrors may be reached over either scheme, only the host and path must match
+    let base = domain.trim_end_matches('/');
+    let base = base.split_once("://").map_or(base, |(_, rest)| rest);
+    let list_message_id_regex = Regex::new(&format!(
+        r"^https?://{}/([^/]+)/([^/]+)/",
+        regex::escape(base)
+    ))
+    .context("Failed to compile list message ID regex")?;
+
+    let entries = feed.entries.len();
+    let mut items = Vec::with_capacity(entries);
+    let mut diagnostics = Vec::new();
+    for (offset, entry) in feed.entries.into_iter().enumerate() {
+        let title = ArcStr::from(&entry.title);
+        match parse_entry(entry, &list_message_id_regex) {
+            Ok(item) => items.push(item),
+            Err(e) => diagnostics.push(LoreFeedDiagnostic {
+                position: start_index + offset,
+                title,
+                reason: ArcStr::from(format!("{:#}", e)),
+            }),
+        }
+    }
 
     Ok(LorePage {
         start_index,
-        next_page_index: Some(start_index + items.len()),
+        // Skipped entries still take up a slot in the feed's pagination
+        next_page_index: Some(start_index + entries),
         total_items: Some(items.len()),
         items,
+        diagnostics,
     })
 }
 
+/// Decompresses a gzip-compressed mbox, such as the `t.mbox.gz` thread archives served by Lore.
+///
+/// # Arguments
+/// * `bytes` - The gzip-compressed content
+///
+/// # Returns
+/// The decompressed mbox as text. Invalid UTF-8 sequences are replaced rather than rejected,
+/// since mailing list archives often carry messages in legacy charsets.
+///
+/// # Errors
+/// Returns an error if the content is not valid gzip data.
+pub fn decompress_mbox_gz(bytes: &[u8]) -> anyhow::Result<String> {
+    use anyhow::Context;
+
+    let mut decoder = flate2::read::MultiGzDecoder::new(bytes);
+    let mut buf = Vec::new();
+    decoder
+        .read_to_end(&mut buf)
+        .context("Failed to decompress gzip mbox")?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Splits an mbox into its individual raw messages.
+///
+/// Messages are delimited by lines starting with `From ` at the beginning of the mbox or
+/// after a blank line. The delimiter line itself is dropped and `>From ` quoting (as used
+/// by the mboxrd format) is undone.
+///
+/// # Arguments
+/// * `mbox` - The mbox content as a string
+///
+/// # Returns
+/// The raw messages, in the order they appear in the mbox.
+pub fn split_mbox(mbox: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    // A lone message without the mbox delimiter is still accepted
+    let mut current = (!mbox.trim().is_empty() && !mbox.starts_with("From ")).then(String::new);
+    let mut previous_blank = true;
+
+    for line in mbox.lines() {
+        if previous_blank && line.starts_with("From ") {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(String::new());
+            previous_blank = false;
+            continue;
+        }
+
+        previous_blank = line.is_empty();
+        let Some(message) = current.as_mut() else {
+            continue;
+        };
+
+        let unquoted = line.trim_start_matches('>');
+        if unquoted.starts_with("From ") && unquoted.len() < line.len() {
+            message.push_str(&line[1..]);
+        } else {
+            message.push_str(line);
+        }
+        message.push('\n');
+    }
+
+    if let Some(message) = current {
+        messages.push(message);
+    }
+
+    // The blank line separating two messages belongs to the mbox, not to the message
+    for message in &mut messages {
+        while message.ends_with("\n\n") {
+            message.pop();
+        }
+    }
+
+    messages
+}
+
+/// Extracts every `<message-id>` from a header value, without the angle brackets.
+pub(super) fn parse_message_ids(value: &str) -> Vec<ArcStr> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(ArcStr::from)
+        .collect()
+}
+
+/// Splits a `From` header value into an author name and an email.
+pub(super) fn parse_address(value: &str) -> (ArcStr, ArcStr) {
+    if let Some((name, rest)) = value.rsplit_once('<') {
+        let email = rest.trim_end().trim_end_matches('>').trim();
+        let name = name.trim().trim_matches('"').trim();
+        let name = if name.is_empty() { email } else { name };
+        (ArcStr::from(name), ArcStr::from(email))
+    } else {
+        // Either a bare address or the legacy `email (Name)` form
+        let (email, name) = match value.split_once('(') {
+            Some((email, name)) => (email.trim(), name.trim_end_matches(')').trim()),
+            None => (value.trim(), value.trim()),
+        };
+        (ArcStr::from(name), ArcStr::from(email))
+    }
+}
+
+/// Parses a `Date` header value.
+///
+/// Trailing comments such as `(UTC)`, which chrono does not accept, are dropped.
+pub(super) fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.split_once(" (").map_or(value, |(date, _)| date);
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Parses a single raw message into a [`LoreMessage`].
+///
+/// # Arguments
+/// * `raw` - The raw message, headers included
+///
+/// # Returns
+/// The parsed message.
+///
+/// # Errors
+/// Returns an error if the message has no `Message-ID` header.
+pub fn parse_mbox_message(raw: &str) -> anyhow::Result<LoreMessage> {
+    use anyhow::anyhow;
+
+    let headers = parse_headers(raw);
+
+    let message_id = headers
+        .get("Message-ID")
+        .and_then(|value| parse_message_ids(value).into_iter().next())
+        .ok_or_else(|| anyhow!("Message has no Message-ID header"))?;
+    let in_reply_to = headers
+        .get("In-Reply-To")
+        .and_then(|value| parse_message_ids(value).into_iter().next());
+    let references = headers
+        .get("References")
+        .map(|value| parse_message_ids(value))
+        .unwrap_or_default();
+    let subject = headers.get("Subject").cloned().unwrap_or_default();
+    let (author, email) = headers
+        .get("From")
+        .map(|value| parse_address(value))
+        .unwrap_or_default();
+    let date = headers.get("Date").and_then(|value| parse_date(value));
+
+    Ok(LoreMessage {
+        message_id,
+        in_reply_to,
+        references,
+        subject,
+        author,
+        email,
+        date,
+        raw: ArcStr::from(raw),
+    })
+}
+
+/// Builds a reply tree out of the messages of a thread.
+///
+/// The parent of a message is the one named in its `In-Reply-To` header, falling back
+/// to the closest message in its `References` header that is part of the thread.
+/// Messages whose ancestors are all missing become roots. Duplicate message IDs are
+/// dropped, keeping the first occurrence, and links that would form a cycle are ignored.
+///
+/// # Arguments
+/// * `messages` - The messages of the thread, in any order
+///
+/// # Returns
+/// The thread with its nodes linked to each other.
+pub fn build_thread(messages: Vec<LoreMessage>) -> LoreThread {
+    let mut index: HashMap<ArcStr, usize> = HashMap::new();
+    let mut nodes = Vec::with_capacity(messages.len());
+    for message in messages {
+        if index.contains_key(&message.message_id) {
+            continue;
+        }
+        index.insert(message.message_id.clone(), nodes.len());
+        nodes.push(LoreThreadNode {
+            message,
+            parent: None,
+            children: Vec::new(),
+        });
+    }
+
+    for idx in 0..nodes.len() {
+        let message = &nodes[idx].message;
+        let candidates = message
+            .in_reply_to
+            .iter()
+            .chain(message.references.iter().rev());
+
+        let mut parent = None;
+        for candidate in candidates {
+            let Some(&candidate) = index.get(candidate) else {
+                continue;
+            };
+            // Walk up from the candidate to make sure we are not one of its ancestors
+            let mut ancestor = Some(candidate);
+            while let Some(current) = ancestor {
+                if current == idx {
+                    break;
+                }
+                ancestor = nodes[current].parent;
+            }
+            if ancestor.is_none() {
+                parent = Some(candidate);
+                break;
+            }
+        }
+        nodes[idx].parent = parent;
+    }
+
+    let mut roots = Vec::new();
+    for idx in 0..nodes.len() {
+        match nodes[idx].parent {
+            Some(parent) => nodes[parent].children.push(idx),
+            None => roots.push(idx),
+        }
+    }
+
+    let by_date = |nodes: &[LoreThreadNode], list: &mut Vec<usize>| {
+        list.sort_by_key(|&idx| nodes[idx].message.date);
+    };
+    for idx in 0..nodes.len() {
+        let mut children = std::mem::take(&mut nodes[idx].children);
+        by_date(&nodes, &mut children);
+        nodes[idx].children = children;
+    }
+    by_date(&nodes, &mut roots);
+
+    LoreThread {
+        nodes,
+        roots,
+        skipped: Vec::new(),
+    }
+}
+
+/// Parses a thread mbox into a reply tree.
+///
+/// Messages that cannot be parsed, such as stray ones without a `Message-ID`, are
+/// left out and the reason is recorded in [`LoreThread::skipped`], so one of them
+/// does not make the rest of the discussion unreadable.
+///
+/// # Arguments
+/// * `mbox` - The mbox content holding every message of the thread
+///
+/// # Returns
+/// The thread built from the messages of the mbox that could be parsed.
+pub fn parse_thread_mbox(mbox: &str) -> LoreThread {
+    let mut messages = Vec::new();
+    let mut skipped = Vec::new();
+    for (idx, raw) in split_mbox(mbox).iter().enumerate() {
+        match parse_mbox_message(raw) {
+            Ok(message) => messages.push(message),
+            Err(e) => {
+                let headers = super::mime::parse_headers(raw);
+                let subject = headers.get("Subject").map_or("", |s| s.as_ref());
+                skipped.push(ArcStr::from(format!(
+                    "Message {idx} '{subject}' of the thread mbox: {e:#}"
+                )));
+            }
+        }
+    }
+
+    let mut thread = build_thread(messages);
+    thread.skipped = skipped;
+    thread
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,40 +678,40 @@ mod tests {
     fn test_parse_patch_title_simple() {
         let title = "[PATCH] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 1); // version
-        assert_eq!(result.1, None); // sequence
+        assert_eq!(result.version, 1); // version
+        assert_eq!(result.sequence, None); // sequence
     }
 
     #[test]
     fn test_parse_patch_title_with_sequence() {
         let title = "[PATCH 2/5] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 1); // version
-        assert_eq!(result.1, Some(SequenceNumber::new(2, 5))); // sequence
+        assert_eq!(result.version, 1); // version
+        assert_eq!(result.sequence, Some(SequenceNumber::new(2, 5))); // sequence
     }
 
     #[test]
     fn test_parse_patch_title_with_version() {
         let title = "[PATCH v3] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 3); // version
-        assert_eq!(result.1, None); // sequence
+        assert_eq!(result.version, 3); // version
+        assert_eq!(result.sequence, None); // sequence
     }
 
     #[test]
     fn test_parse_patch_title_with_version_and_sequence() {
         let title = "[PATCH v2 3/7] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 2); // version
-        assert_eq!(result.1, Some(SequenceNumber::new(3, 7))); // sequence
+        assert_eq!(result.version, 2); // version
+        assert_eq!(result.sequence, Some(SequenceNumber::new(3, 7))); // sequence
     }
 
     #[test]
     fn test_parse_patch_title_with_extra_spaces() {
         let title = "[PATCH  v4  1/10  ] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 4); // version
-        assert_eq!(result.1, Some(SequenceNumber::new(1, 10))); // sequence
+        assert_eq!(result.version, 4); // version
+        assert_eq!(result.sequence, Some(SequenceNumber::new(1, 10))); // sequence
     }
 
     #[test]
@@ -362,13 +740,394 @@ mod tests {
         // Test that the named captures correctly extract sequence numbers
         let title = "[PATCH 3/7] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 1); // version defaults to 1
-        assert_eq!(result.1, Some(SequenceNumber::new(3, 7))); // sequence 3/7
+        assert_eq!(result.version, 1); // version defaults to 1
+        assert_eq!(result.sequence, Some(SequenceNumber::new(3, 7))); // sequence 3/7
 
         // Test with version and sequence
         let title = "[PATCH v2 5/10] Add new feature";
         let result = parse_patch_title(title).unwrap();
-        assert_eq!(result.0, 2); // version 2
-        assert_eq!(result.1, Some(SequenceNumber::new(5, 10))); // sequence 5/10
+        assert_eq!(result.version, 2); // version 2
+        assert_eq!(result.sequence, Some(SequenceNumber::new(5, 10))); // sequence 5/10
+    }
+
+    #[test]
+    fn test_parse_patch_title_tags() {
+        let result = parse_patch_title("[RFC PATCH] Add new feature").unwrap();
+        assert!(result.rfc && !result.resend);
+        assert_eq!(result.version, 1);
+        assert_eq!(result.subject, ArcStr::from("Add new feature"));
+
+        let result = parse_patch_title("[PATCH net-next v3 02/10] net: do the thing").unwrap();
+        assert_eq!(result.version, 3);
+        assert_eq!(result.sequence, Some(SequenceNumber::new(2, 10)));
+        assert_eq!(result.prefixes, vec![ArcStr::from("net-next")]);
+        assert_eq!(result.subject, ArcStr::from("net: do the thing"));
+
+        let result = parse_patch_title("[PATCH RESEND] Add new feature").unwrap();
+        assert!(result.resend && !result.rfc);
+
+        let result = parse_patch_title("[PATCH 6.1 12/40] mm: fix leak").unwrap();
+        assert_eq!(result.prefixes, vec![ArcStr::from("6.1")]);
+        assert_eq!(result.sequence, Some(SequenceNumber::new(12, 40)));
+
+        let result = parse_patch_title("[PATCH v2 RFC] Add new feature").unwrap();
+        assert!(result.rfc);
+        assert_eq!(result.version, 2);
+    }
+
+    #[test]
+    fn test_parse_patch_title_loose_forms() {
+        let result = parse_patch_title("[RFC][PATCHv2 bpf-next 1/3] bpf: x").unwrap();
+        assert!(result.rfc);
+        assert_eq!(result.version, 2);
+        assert_eq!(result.prefixes, vec![ArcStr::from("bpf-next")]);
+        assert_eq!(result.sequence, Some(SequenceNumber::new(1, 3)));
+
+        let result = parse_patch_title("[PATCH, V4, 0/2] [media] vfs: y").unwrap();
+        assert_eq!(result.version, 4);
+        assert_eq!(result.sequence, Some(SequenceNumber::new(0, 2)));
+        assert_eq!(result.prefixes, vec![ArcStr::from("media")]);
+        assert_eq!(result.subject, ArcStr::from("vfs: y"));
+
+        assert!(parse_patch_title("Re: [PATCH] Add new feature").is_err());
+        assert!(parse_patch_title("[GIT PULL] net for 6.10").is_err());
+        assert!(parse_patch_title("[PATCH Add new feature").is_err());
+    }
+
+    const THREAD_MBOX: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Jane Doe <jane@example.com>
+Subject: [PATCH 0/2] Add new feature
+Date: Mon, 14 Jul 2025 10:00:00 +0000
+Message-ID: <cover@example.com>
+
+Cover letter body
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Jane Doe <jane@example.com>
+Subject: [PATCH 1/2] First change
+Date: Mon, 14 Jul 2025 10:01:00 +0000
+Message-ID: <patch1@example.com>
+In-Reply-To: <cover@example.com>
+References: <cover@example.com>
+
+>From the commit message
+---
+ file.c | 1 +
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+From: \"John Roe\" <john@example.com>
+Subject: Re: [PATCH 1/2] First change
+Date: Mon, 14 Jul 2025 12:00:00 +0000 (UTC)
+Message-ID: <reply@example.com>
+In-Reply-To: <patch1@example.com>
+References: <cover@example.com>
+ <patch1@example.com>
+
+Looks good.
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Jane Doe <jane@example.com>
+Subject: [PATCH 2/2] Second change
+Date: Mon, 14 Jul 2025 10:02:00 +0000
+Message-ID: <patch2@example.com>
+In-Reply-To: <cover@example.com>
+References: <cover@example.com>
+
+Body
+";
+
+    #[test]
+    fn test_split_mbox() {
+        let messages = split_mbox(THREAD_MBOX);
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0].starts_with("From: Jane Doe"));
+        assert!(messages[0].ends_with("Cover letter body\n"));
+        // mboxrd quoting is undone
+        assert!(messages[1].contains("\nFrom the commit message\n"));
+    }
+
+    #[test]
+    fn test_parse_mbox_message_headers() {
+        let messages = split_mbox(THREAD_MBOX);
+        let reply = parse_mbox_message(&messages[2]).unwrap();
+        assert_eq!(&*reply.message_id, "reply@example.com");
+        assert_eq!(reply.in_reply_to.as_deref(), Some("patch1@example.com"));
+        // Folded References header is unfolded
+        assert_eq!(reply.references.len(), 2);
+        assert_eq!(&*reply.author, "John Roe");
+        assert_eq!(&*reply.email, "john@example.com");
+        assert_eq!(&*reply.subject, "Re: [PATCH 1/2] First change");
+        assert!(reply.date.is_some());
+    }
+
+    #[test]
+    fn test_parse_mbox_message_without_message_id() {
+        let result = parse_mbox_message("From: a@b.c\nSubject: hi\n\nbody\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_thread_mbox_tree() {
+        let thread = parse_thread_mbox(THREAD_MBOX);
+        assert_eq!(thread.len(), 4);
+        assert_eq!(thread.roots.len(), 1);
+
+        let root = thread.root().unwrap();
+        assert_eq!(&*root.message.message_id, "cover@example.com");
+        let children: Vec<_> = thread
+            .children(thread.roots[0])
+            .map(|node| node.message.message_id.to_string())
+            .collect();
+        assert_eq!(children, vec!["patch1@example.com", "patch2@example.com"]);
+
+        let reply = thread.find("reply@example.com").unwrap();
+        let parent = thread.parent(reply).unwrap();
+        assert_eq!(&*parent.message.message_id, "patch1@example.com");
+
+        let order: Vec<_> = thread
+            .walk()
+            .into_iter()
+            .map(|(idx, depth)| (thread.nodes[idx].message.message_id.to_string(), depth))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("cover@example.com".to_string(), 0),
+                ("patch1@example.com".to_string(), 1),
+                ("reply@example.com".to_string(), 2),
+                ("patch2@example.com".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_thread_missing_parent_falls_back_to_references() {
+        let mbox = "From mboxrd@z Thu Jan  1 00:00:00 1970
+Message-ID: <root@x>
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+Message-ID: <child@x>
+In-Reply-To: <missing@x>
+References: <root@x> <missing@x>
+";
+        let thread = parse_thread_mbox(mbox);
+        assert_eq!(thread.roots, vec![0]);
+        assert_eq!(thread.nodes[1].parent, Some(0));
+    }
+
+    #[test]
+    fn test_parse_thread_mbox_skips_bad_message() {
+        let mbox = "From mboxrd@z Thu Jan  1 00:00:00 1970
+Message-ID: <root@x>
+Subject: [PATCH] root
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+Subject: stray
+In-Reply-To: <root@x>
+
+No Message-ID here.
+
+From mboxrd@z Thu Jan  1 00:00:00 1970
+Message-ID: <reply@x>
+In-Reply-To: <root@x>
+";
+        let thread = parse_thread_mbox(mbox);
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread.nodes[1].parent, Some(0));
+        assert_eq!(thread.skipped.len(), 1);
+        assert!(thread.skipped[0].contains("Message 1 'stray'"));
+        assert!(thread.skipped[0].contains("Message-ID"));
+        assert!(parse_mbox_message(&split_mbox(mbox)[1]).is_err());
+    }
+
+    #[test]
+    fn test_build_thread_ignores_cycles_and_duplicates() {
+        let message = |id: &str, parent: &str| LoreMessage {
+            message_id: ArcStr::from(id),
+            in_reply_to: Some(ArcStr::from(parent)),
+            references: Vec::new(),
+            subject: ArcStr::from(""),
+            author: ArcStr::from(""),
+            email: ArcStr::from(""),
+            date: None,
+            raw: ArcStr::from(""),
+        };
+        let thread = build_thread(vec![
+            message("a", "b"),
+            message("b", "a"),
+            message("a", "c"),
+        ]);
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread.nodes[0].parent, Some(1));
+        assert_eq!(thread.nodes[1].parent, None);
+        assert_eq!(thread.roots, vec![1]);
+    }
+
+    #[test]
+    fn test_decompress_mbox_gz() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(THREAD_MBOX.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress_mbox_gz(&compressed).unwrap(), THREAD_MBOX);
+        assert!(decompress_mbox_gz(b"not gzip").is_err());
+    }
+
+    #[test]
+    fn test_parse_patch_feed_xml_in_reply_to() {
+        let xml = r#"<?xml version="1.0" encoding="us-ascii"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:thr="http://purl.org/syndication/thread/1.0">
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH 1/2] First change</title><updated>2025-07-14T10:01:00Z</updated>
+<link href="https://lore.kernel.org/list/patch1@example.com/"/><id>urn:uuid:1</id>
+<thr:in-reply-to ref="urn:uuid:0" href="https://lore.kernel.org/list/cover@example.com/"/></entry>
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH 0/2] Add new feature</title><updated>2025-07-14T10:00:00Z</updated>
+<link href="https://lore.kernel.org/list/cover@example.com/"/><id>urn:uuid:0</id></entry>
+</feed>"#;
+
+        let page = parse_patch_feed_xml(xml, 0, "https://lore.kernel.org").unwrap();
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(
+            page.items[0].in_reply_to.as_deref(),
+            Some("cover@example.com")
+        );
+        assert_eq!(page.items[1].in_reply_to, None);
+    }
+
+    #[test]
+    fn test_parse_patch_feed_xml_diagnostics() {
+        let xml = r#"<?xml version="1.0" encoding="us-ascii"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] Good change</title><updated>2025-07-14T10:02:00Z</updated>
+<link href="https://lore.kernel.org/list/good@example.com/"/><id>urn:uuid:2</id></entry>
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[GIT PULL] Some tree</title><updated>2025-07-14T10:01:00Z</updated>
+<link href="https://lore.kernel.org/list/pull@example.com/"/><id>urn:uuid:1</id></entry>
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] Bad date</title><updated>yesterday</updated>
+<link href="https://lore.kernel.org/list/date@example.com/"/><id>urn:uuid:0</id></entry>
+</feed>"#;
+
+        let page = parse_patch_feed_xml(xml, 200, "https://lore.kernel.org").unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.next_page_index, Some(203));
+        assert_eq!(page.diagnostics.len(), 2);
+        assert_eq!(page.diagnostics[0].position, 201);
+        assert_eq!(
+            page.diagnostics[0].title,
+            ArcStr::from("[GIT PULL] Some tree")
+        );
+        assert!(page.diagnostics[0].reason.contains("expected format"));
+        assert_eq!(page.diagnostics[1].position, 202);
+        assert!(page.diagnostics[1].reason.contains("Invalid update date"));
+    }
+
+    #[test]
+    fn test_parse_patch_feed_xml_custom_domain() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] Mirrored change</title><updated>2025-07-14T10:01:00Z</updated>
+<link href="http://mirror.example.com/lore/list/mirror@example.com/"/><id>urn:uuid:1</id></entry>
+<entry><author><name>Jane Doe</name><email>jane@example.com</email></author>
+<title>[PATCH] Upstream change</title><updated>2025-07-14T10:00:00Z</updated>
+<link href="https://lore.kernel.org/list/upstream@example.com/"/><id>urn:uuid:0</id></entry>
+</feed>"#;
+
+        let page = parse_patch_feed_xml(xml, 0, "https://mirror.example.com/lore/").unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].list, ArcStr::from("list"));
+        assert_eq!(page.items[0].message_id, ArcStr::from("mirror@example.com"));
+        assert_eq!(page.diagnostics.len(), 1);
+        assert!(
+            page.diagnostics[0]
+                .reason
+                .contains("does not point to a Lore message")
+        );
+    }
+
+    #[test]
+    fn test_parse_available_lists_first_page() {
+        let html = include_str!("fixtures/lists_first_page.html");
+        let page = parse_available_lists_html(html, 0, "https://lore.kernel.org")
+            .unwrap()
+            .unwrap();
+
+        let names: Vec<&str> = page.items.iter().map(|list| &*list.name).collect();
+        assert_eq!(names, vec!["linux-arch", "amd-gfx", "netdev"]);
+        assert_eq!(
+            page.items[0].description,
+            ArcStr::from("Linux-Arch Archive on lore.kernel.org")
+        );
+        assert_eq!(
+            page.items[0].last_update,
+            DateTime::parse_from_rfc3339("2025-07-14T13:47:00Z").unwrap()
+        );
+        assert_eq!(
+            page.items[2].archive,
+            ArcStr::from("https://lore.kernel.org")
+        );
+        assert_eq!(page.next_page_index, Some(200));
+        assert_eq!(page.total_items, Some(337));
+        assert!(page.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_available_lists_last_page() {
+        let html = include_str!("fixtures/lists_last_page.html");
+        let page = parse_available_lists_html(html, 200, "https://lore.kernel.org")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(page.items.len(), 3);
+        assert_eq!(
+            page.items[0].description,
+            ArcStr::from("Kernel Summit & Maintainers Summit discussions")
+        );
+        assert_eq!(page.items[1].name, ArcStr::from("empty-desc"));
+        assert_eq!(page.items[1].description, ArcStr::from(""));
+        assert_eq!(
+            page.items[2].description,
+            ArcStr::from("A description that was wrapped over two lines")
+        );
+        assert_eq!(page.next_page_index, Some(203));
+        assert_eq!(page.total_items, Some(203));
+    }
+
+    #[test]
+    fn test_parse_available_lists_custom_layout() {
+        let html = include_str!("fixtures/lists_custom_layout.html");
+        let page = parse_available_lists_html(html, 0, "https://inbox.example.com")
+            .unwrap()
+            .unwrap();
+
+        let lists: Vec<(&str, &str)> = page
+            .items
+            .iter()
+            .map(|list| (&*list.name, &*list.description))
+            .collect();
+        assert_eq!(
+            lists,
+            vec![
+                ("internal-dev", "Internal development list"),
+                ("internal-ci", ""),
+                ("internal-announce", "Announcements"),
+            ]
+        );
+        assert_eq!(page.diagnostics.len(), 1);
+        assert_eq!(page.diagnostics[0].title, ArcStr::from("broken"));
+        assert_eq!(page.next_page_index, None);
+        assert_eq!(page.total_items, None);
+    }
+
+    #[test]
+    fn test_parse_available_lists_past_the_end() {
+        let html = "<html><body><pre>Results 338 of ~337\n</pre></body></html>";
+        let page = parse_available_lists_html(html, 337, "https://lore.kernel.org").unwrap();
+        assert!(page.is_none());
     }
 }
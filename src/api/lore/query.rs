@@ -0,0 +1,306 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ArcStr;
+
+/// The list searched when a search is not restricted to one mailing list
+pub const ALL_LISTS: &str = "all";
+
+/// Terms matching patch submissions (and RFCs) but not the replies to them
+const PATCH_TERMS: [&str; 2] = ["(s:patch OR s:rfc)", "NOT s:re:"];
+
+/// A public-inbox search query, built term by term.
+///
+/// Every term must match, so `LoreQuery::patches().author("jane").touches("mm/")` finds
+/// the patches sent by Jane that change files under `mm/`. Values containing spaces or
+/// parentheses are quoted so they are searched as a phrase.
+///
+/// # Examples
+/// ```ignore
+/// let query = LoreQuery::patches()
+///     .subject("drm/amd")
+///     .date(Some("2.weeks.ago"), None);
+/// assert_eq!(query.to_string(), "(s:patch OR s:rfc) AND NOT s:re: AND s:drm/amd AND d:2.weeks.ago..");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LoreQuery {
+    /// Whether only patch submissions are matched
+    patches: bool,
+    /// The terms added to the query, each one ready to be joined with `AND`
+    terms: Vec<ArcStr>,
+}
+
+impl LoreQuery {
+    /// Creates a query matching every message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a query matching patch submissions and RFCs, leaving replies out.
+    ///
+    /// This is the query behind the patch feed of a mailing list.
+    pub fn patches() -> Self {
+        Self {
+            patches: true,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Matches messages whose author name or address contains `author` (`f:`).
+    pub fn author(self, author: &str) -> Self {
+        self.prefixed("f", author)
+    }
+
+    /// Matches messages whose subject contains `subject` (`s:`).
+    pub fn subject(self, subject: &str) -> Self {
+        self.prefixed("s", subject)
+    }
+
+    /// Matches patches touching `file`, either side of a rename included (`dfn:`).
+    pub fn touches(self, file: &str) -> Self {
+        self.prefixed("dfn", file)
+    }
+
+    /// Matches patches touching at least one of `files` (`dfn:` terms joined by `OR`).
+    ///
+    /// Blank entries are ignored, and so is the whole call if no entry is left.
+    pub fn touches_any(mut self, files: &[ArcStr]) -> Self {
+        let terms: Vec<String> = files.iter().filter_map(|file| term("dfn", file)).collect();
+        match terms.len() {
+            0 => {}
+            1 => self.terms.push(ArcStr::from(&terms[0])),
+            _ => self
+                .terms
+                .push(ArcStr::from(format!("({})", terms.join(" OR ")))),
+        }
+        self
+    }
+
+    /// Matches messages whose body contains `body` (`b:`).
+    pub fn body(self, body: &str) -> Self {
+        self.prefixed("b", body)
+    }
+
+    /// Matches messages sent to or copied to `address` (`tc:`).
+    pub fn addressed_to(self, address: &str) -> Self {
+        self.prefixed("tc", address)
+    }
+
+    /// Matches messages sent within a date range (`d:`).
+    ///
+    /// Both ends accept anything public-inbox understands, such as `2024-01-31`,
+    /// `20240131` or `2.weeks.ago`. A missing end leaves the range open on that side,
+    /// and the query is left untouched if both are missing.
+    pub fn date(mut self, start: Option<&str>, end: Option<&str>) -> Self {
+        if start.is_none() && end.is_none() {
+            return self;
+        }
+        let range = format!("{}..{}", start.unwrap_or(""), end.unwrap_or(""));
+        self.terms.push(ArcStr::from(format!("d:{}", range)));
+        self
+    }
+
+    /// Adds an expression in public-inbox syntax as is, e.g. `f:jane s:"mm: fix"`.
+    ///
+    /// Expressions with several words are grouped, so they do not change how the
+    /// other terms combine. Blank expressions are ignored.
+    pub fn raw(mut self, expression: &str) -> Self {
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return self;
+        }
+        let term = if expression.contains(char::is_whitespace) {
+            format!("({})", expression)
+        } else {
+            expression.to_string()
+        };
+        self.terms.push(ArcStr::from(term));
+        self
+    }
+
+    /// Returns the terms added on top of the patch filter.
+    pub fn terms(&self) -> &[ArcStr] {
+        &self.terms
+    }
+
+    /// Returns true if the query only matches patch submissions.
+    pub fn is_patches(&self) -> bool {
+        self.patches
+    }
+
+    /// Encodes the query for the `q` parameter of a public-inbox URL.
+    ///
+    /// The whole query is grouped in parentheses and spaces become `+`.
+    pub fn encode(&self) -> String {
+        let mut encoded = String::from("(");
+        for byte in self.to_string().bytes() {
+            match byte {
+                b' ' => encoded.push('+'),
+                b'A'..=b'Z'
+                | b'a'..=b'z'
+                | b'0'..=b'9'
+                | b'-'
+                | b'_'
+                | b'.'
+                | b'~'
+                | b'*'
+                | b':'
+                | b'/'
+                | b'@'
+                | b'('
+                | b')' => encoded.push(byte as char),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded.push(')');
+        encoded
+    }
+
+    fn prefixed(mut self, prefix: &str, value: &str) -> Self {
+        if let Some(term) = term(prefix, value) {
+            self.terms.push(ArcStr::from(term));
+        }
+        self
+    }
+}
+
+/// Formats `prefix:value`, quoting values that would not parse as a single term.
+fn term(prefix: &str, value: &str) -> Option<String> {
+    let value = value.trim().replace('"', "");
+    if value.is_empty() {
+        return None;
+    }
+    if value.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        Some(format!("{}:\"{}\"", prefix, value))
+    } else {
+        Some(format!("{}:{}", prefix, value))
+    }
+}
+
+impl fmt::Display for LoreQuery {
+    /// Writes the query in public-inbox syntax, with its terms joined by `AND`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patch_terms = if self.patches { &PATCH_TERMS[..] } else { &[] };
+        let terms: Vec<&str> = patch_terms
+            .iter()
+            .copied()
+            .chain(self.terms.iter().map(|term| term.as_ref()))
+            .collect();
+        write!(f, "{}", terms.join(" AND "))
+    }
+}
+
+/// A query run against one mailing list, or against all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LoreSearch {
+    /// The mailing list searched, or [`ALL_LISTS`]
+    pub list: ArcStr,
+    /// The query to run
+    pub query: LoreQuery,
+}
+
+impl LoreSearch {
+    /// Creates a search of `list` (or [`ALL_LISTS`]).
+    pub fn new(list: ArcStr, query: LoreQuery) -> Self {
+        Self { list, query }
+    }
+
+    /// Returns the key the results of this search are cached under.
+    ///
+    /// The key is stable across runs and safe to use as a file name: it is made of the
+    /// list name and a hash of the query, e.g. `search-all-5f2c0e9b1d7a3c44`.
+    pub fn cache_key(&self) -> ArcStr {
+        // FNV-1a, since the std hashers are not guaranteed to be stable between releases
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.query.to_string().bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        let list: String = self
+            .list
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        ArcStr::from(format!("search-{}-{:016x}", list, hash))
+    }
+
+    /// Returns a short description of the search, as shown in titles.
+    pub fn describe(&self) -> String {
+        let terms: Vec<&str> = self.query.terms().iter().map(|t| t.as_ref()).collect();
+        let terms = if terms.is_empty() {
+            "*".to_string()
+        } else {
+            terms.join(" ")
+        };
+        format!("{} in {}", terms, self.list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patches_query_matches_the_feed_filter() {
+        let query = LoreQuery::patches();
+        assert_eq!(query.to_string(), "(s:patch OR s:rfc) AND NOT s:re:");
+        assert_eq!(query.encode(), "((s:patch+OR+s:rfc)+AND+NOT+s:re:)");
+    }
+
+    #[test]
+    fn test_query_builder_prefixes() {
+        let query = LoreQuery::new()
+            .author("jane@example.com")
+            .subject("mm: fix (again)")
+            .touches("mm/page_alloc.c")
+            .date(Some("2024-01-01"), None)
+            .body("use-after-free")
+            .addressed_to("linux-mm@kvack.org")
+            .raw("  ")
+            .raw("s:v2 OR s:v3");
+        assert_eq!(
+            query.to_string(),
+            "f:jane@example.com AND s:\"mm: fix (again)\" AND dfn:mm/page_alloc.c AND \
+             d:2024-01-01.. AND b:use-after-free AND tc:linux-mm@kvack.org AND (s:v2 OR s:v3)"
+        );
+        assert_eq!(
+            LoreQuery::new().subject("a \"b\"+c").encode(),
+            "(s:%22a+b%2Bc%22)"
+        );
+        assert_eq!(LoreQuery::new().date(None, None).to_string(), "");
+        assert_eq!(
+            LoreQuery::patches()
+                .touches_any(&[ArcStr::from("drivers/gpu/drm/amd/"), ArcStr::from(" ")])
+                .encode(),
+            "((s:patch+OR+s:rfc)+AND+NOT+s:re:+AND+dfn:drivers/gpu/drm/amd/)"
+        );
+        assert_eq!(
+            LoreQuery::new()
+                .touches_any(&[ArcStr::from("mm/"), ArcStr::from("include/linux/mm.h")])
+                .to_string(),
+            "(dfn:mm/ OR dfn:include/linux/mm.h)"
+        );
+    }
+
+    #[test]
+    fn test_search_cache_key() {
+        let search = LoreSearch::new(ArcStr::from(ALL_LISTS), LoreQuery::patches().author("jane"));
+        let key = search.cache_key();
+        assert!(key.starts_with("search-all-"));
+        assert_eq!(key.len(), "search-all-".len() + 16);
+        assert_eq!(key, search.clone().cache_key());
+        assert_ne!(
+            key,
+            LoreSearch::new(ArcStr::from(ALL_LISTS), LoreQuery::patches().author("john"))
+                .cache_key()
+        );
+        assert_eq!(search.describe(), "f:jane in all");
+    }
+}
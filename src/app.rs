@@ -2,9 +2,14 @@ use anyhow::{Context, Result};
 use std::sync::Arc;
 use tokio::sync::{Mutex, mpsc::Sender, oneshot};
 
+pub mod apply;
 pub mod cache;
 pub mod config;
+pub mod mail;
+pub mod maintainers;
+pub mod reply;
 pub mod ui;
+pub mod watch;
 
 mod core;
 mod data;
@@ -48,8 +53,9 @@ impl App {
 
     /// Execute a CLI command and exit (resolve mode)
     ///
-    /// Handles Lists, Feed, and Patch commands by coordinating with
-    /// appropriate actors and caches. This is for one-shot CLI execution.
+    /// Handles Lists, Feed, Search, Inbox, Maintainers, Watch, Patch, Apply, Review and Reply
+    /// commands by coordinating with appropriate actors and caches. This is for one-shot CLI
+    /// execution.
     pub async fn resolve(&self, command: Command) -> Result<()> {
         match self {
             Self::Ready(core) => {
@@ -59,14 +65,66 @@ impl App {
                     Command::Lists { page, count } => {
                         core_ref.handle_lists_command(page, count).await
                     }
-                    Command::Feed { list, page, count } => {
-                        core_ref.handle_feed_command(list, page, count).await
+                    Command::Feed {
+                        list,
+                        page,
+                        count,
+                        review,
+                    } => {
+                        core_ref
+                            .handle_feed_command(list, page, count, review)
+                            .await
+                    }
+                    Command::Search {
+                        search,
+                        page,
+                        count,
+                    } => core_ref.handle_search_command(search, page, count).await,
+                    Command::Inbox { page, count } => {
+                        core_ref.handle_inbox_command(page, count).await
+                    }
+                    Command::Maintainers { filter } => {
+                        core_ref.handle_maintainers_command(filter).await
+                    }
+                    Command::Watch { once, interval } => {
+                        core_ref.handle_watch_command(once, interval).await
                     }
                     Command::Patch {
                         list,
                         message_id,
                         html,
                     } => core_ref.handle_patch_command(list, message_id, html).await,
+                    Command::Apply {
+                        list,
+                        message_id,
+                        series,
+                        options,
+                    } => {
+                        core_ref
+                            .handle_apply_command(list, message_id, series, options)
+                            .await
+                    }
+                    Command::Review {
+                        message_id,
+                        state,
+                        clear,
+                        note,
+                    } => {
+                        core_ref
+                            .handle_review_command(message_id, state, clear, note)
+                            .await
+                    }
+                    Command::Reply {
+                        list,
+                        message_id,
+                        trailers,
+                        edit,
+                    } => {
+                        core_ref
+                            .handle_reply_command(list, message_id, trailers, edit)
+                            .await
+                    }
+                    Command::Send { path } => core_ref.handle_send_command(path).await,
                 }?;
                 // Persist caches before exiting
                 core_ref.handle_shutdown().await
@@ -0,0 +1,118 @@
+mod core;
+mod data;
+mod message;
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Context;
+use tokio::sync::mpsc::Sender;
+
+use crate::ArcStr;
+use crate::app::cache::PatchCache;
+use crate::app::config::Config;
+use crate::log::Log;
+use crate::shell::Shell;
+
+pub use data::{
+    ApplyConflict, ApplyOptions, ApplyReport, ApplyStatus, build_mbox, parse_am_output, subject_of,
+};
+pub use mock::Request as MockRequest;
+
+/// The apply actor that applies patches from the archive to a local git tree.
+///
+/// Patches are pulled from the [`PatchCache`] and fed to `git am` in the repository
+/// configured by `PathOpt::RepoPath`. Conflicts are reported back as structured
+/// results instead of leaving a half-applied series behind.
+///
+/// # Examples
+/// ```ignore
+/// let apply = Apply::spawn(shell, patch_cache, config, log);
+/// let report = apply.apply(list, vec![message_id], ApplyOptions::default()).await?;
+/// ```
+///
+/// # Thread Safety
+/// This type is designed to be safely shared between threads. Cloning is cheap as it only
+/// copies the channel sender or mock reference.
+#[derive(Debug, Clone)]
+pub enum Apply {
+    /// A real apply actor that runs `git am`
+    Actual(Sender<message::Message>),
+    /// A mock implementation for testing that records requests in memory
+    Mock(mock::Mock),
+}
+
+impl Apply {
+    /// Creates a new apply instance and spawns its actor.
+    ///
+    /// # Arguments
+    /// * `shell` - The shell actor for running git
+    /// * `patch_cache` - The patch cache for fetching raw patches
+    /// * `config` - The configuration actor for the repository path and default flags
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new apply instance with a spawned actor.
+    pub fn spawn(shell: Shell, patch_cache: PatchCache, config: Config, log: Log) -> Self {
+        let (apply, _) = core::Core::new(shell, patch_cache, config, log).spawn();
+        apply
+    }
+
+    /// Creates a new mock apply instance for testing.
+    ///
+    /// # Returns
+    /// A new mock apply instance that records requests in memory.
+    pub fn mock() -> Self {
+        Self::Mock(mock::Mock::new())
+    }
+
+    /// Applies patches of a mailing list to the configured repository with `git am`.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list the patches belong to
+    /// * `message_ids` - The message IDs of the patches, in the order they must be applied
+    /// * `options` - Flags for `git am`, combined with the configured defaults
+    ///
+    /// # Returns
+    /// A report of the applied patches, or of the conflict that stopped `git am`.
+    /// Errors are reserved for failures unrelated to the patches themselves, such as
+    /// a missing repository or a patch that cannot be fetched.
+    pub async fn apply(
+        &self,
+        list: ArcStr,
+        message_ids: Vec<ArcStr>,
+        options: ApplyOptions,
+    ) -> anyhow::Result<ApplyReport> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Apply {
+                        list,
+                        message_ids,
+                        options,
+                        tx,
+                    })
+                    .await
+                    .context("Applying patches with Apply actor")
+                    .expect("apply actor died");
+                rx.await
+                    .context("Awaiting response for patch application with Apply actor")
+                    .expect("apply actor died")
+            }
+            Self::Mock(mock) => mock.apply(list, message_ids, options).await,
+        }
+    }
+
+    /// Gets all apply requests from the mock implementation.
+    /// This method is only available for mock instances and is useful for testing.
+    ///
+    /// # Returns
+    /// A vector of all apply requests, or None if this is not a mock instance.
+    pub async fn get_requests(&self) -> Option<Vec<MockRequest>> {
+        match self {
+            Self::Mock(mock) => Some(mock.get_requests().await),
+            Self::Actual(_) => None,
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::Context;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::JoinHandle;
+
+use crate::app::cache::PatchCache;
+use crate::app::config::{BoolOpt, Config, PathOpt};
+use crate::log::Log;
+use crate::shell::{Output, Shell};
+use crate::{ArcPath, ArcSlice, ArcStr};
+
+use super::data::{
+    ApplyOptions, ApplyReport, ApplyStatus, build_mbox, parse_am_output, subject_of,
+};
+use super::message::Message;
+
+const SCOPE: &str = "app.apply";
+
+/// The core implementation of the apply actor.
+///
+/// Patches are pulled from the patch cache, joined into one mbox and fed to
+/// `git am` in the configured repository through the shell actor.
+pub struct Core {
+    /// The shell actor for running git
+    shell: Shell,
+    /// The patch cache for fetching raw patches
+    patch_cache: PatchCache,
+    /// The configuration actor for the repository path and default flags
+    config: Config,
+    /// The logging actor
+    log: Log,
+}
+
+impl Core {
+    /// Creates a new apply actor core.
+    ///
+    /// # Arguments
+    /// * `shell` - The shell actor for running git
+    /// * `patch_cache` - The patch cache for fetching raw patches
+    /// * `config` - The configuration actor for the repository path and default flags
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new apply actor core instance.
+    pub fn new(shell: Shell, patch_cache: PatchCache, config: Config, log: Log) -> Self {
+        Self {
+            shell,
+            patch_cache,
+            config,
+            log,
+        }
+    }
+
+    /// Spawns the apply actor and returns the handle and join handle.
+    ///
+    /// # Returns
+    /// A tuple containing the apply actor handle and the join handle for the spawned task.
+    pub fn spawn(self) -> (super::Apply, JoinHandle<anyhow::Result<()>>) {
+        let (tx, rx) = mpsc::channel(32);
+        let handle = super::Apply::Actual(tx);
+        let join_handle = tokio::spawn(self.run(rx));
+        (handle, join_handle)
+    }
+
+    /// Runs the apply actor event loop.
+    ///
+    /// # Arguments
+    /// * `rx` - The message receiver
+    ///
+    /// # Returns
+    /// Result indicating success or failure of the actor
+    async fn run(self, mut rx: Receiver<Message>) -> anyhow::Result<()> {
+        while let Some(message) = rx.recv().await {
+            match message {
+                Message::Apply {
+                    list,
+                    message_ids,
+                    options,
+                    tx,
+                } => {
+                    let result = self.handle_apply(list, message_ids, options).await;
+                    let _ = tx.send(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the given patches with `git am`.
+    ///
+    /// If `git am` stops on a patch, the operation is aborted with `git am --abort`
+    /// so the repository is left as it was, and the conflict is reported along with
+    /// the patches the abort rolled back. If the abort fails too, an error asks the
+    /// user to abort by hand.
+    async fn handle_apply(
+        &self,
+        list: ArcStr,
+        message_ids: Vec<ArcStr>,
+        options: ApplyOptions,
+    ) -> anyhow::Result<ApplyReport> {
+        if message_ids.is_empty() {
+            anyhow::bail!("No patches to apply");
+        }
+
+        let repo = self.config.path(PathOpt::RepoPath).await;
+        let three_way = options.three_way || self.config.bool(BoolOpt::ApplyThreeWay).await;
+        let signoff = options.signoff || self.config.bool(BoolOpt::ApplySignoff).await;
+
+        let mut patches = Vec::with_capacity(message_ids.len());
+        for message_id in &message_ids {
+            let raw = self
+                .patch_cache
+                .get(list.clone(), message_id.clone())
+                .await
+                .with_context(|| format!("Fetching patch {message_id} from {list}"))?;
+            patches.push(raw);
+        }
+        let subjects: Vec<ArcStr> = patches.iter().map(|raw| subject_of(raw)).collect();
+
+        if let Some(branch) = &options.branch {
+            self.checkout(&repo, branch).await?;
+        }
+
+        let mut args = vec![ArcStr::from("am")];
+        if three_way {
+            args.push(ArcStr::from("--3way"));
+        }
+        if signoff {
+            args.push(ArcStr::from("--signoff"));
+        }
+        self.log.info(
+            SCOPE,
+            format!(
+                "Applying {} patch(es) from {} to {}",
+                patches.len(),
+                list,
+                repo.display()
+            ),
+        );
+        let result = self.git(&repo, args, Some(build_mbox(&patches))).await?;
+
+        if result.is_success() {
+            return Ok(ApplyReport {
+                repo,
+                branch: options.branch,
+                applied: subjects,
+                status: ApplyStatus::Applied,
+            });
+        }
+
+        let Some(mut conflict) = parse_am_output(&result.stdout, &result.stderr) else {
+            anyhow::bail!("git am failed: {}", result.stderr.trim());
+        };
+        self.log.warn(
+            SCOPE,
+            format!(
+                "Patch {} '{}' failed to apply, aborting",
+                conflict.index, conflict.subject
+            ),
+        );
+        let abort = self
+            .git(
+                &repo,
+                vec![ArcStr::from("am"), ArcStr::from("--abort")],
+                None,
+            )
+            .await?;
+        if abort.is_failure() {
+            self.log.error(
+                SCOPE,
+                format!("git am --abort failed: {}", abort.stderr.trim()),
+            );
+            anyhow::bail!(
+                "Patch {} '{}' failed to apply and `git am --abort` failed: {}\n\
+                 The repository is still in the middle of `git am`, run `git am --abort` in {}",
+                conflict.index,
+                conflict.subject,
+                abort.stderr.trim(),
+                repo.display()
+            );
+        }
+
+        // The abort resets HEAD, undoing the patches that did apply
+        conflict.rolled_back = subjects
+            .into_iter()
+            .take(conflict.index.saturating_sub(1))
+            .collect();
+        Ok(ApplyReport {
+            repo,
+            branch: options.branch,
+            applied: Vec::new(),
+            status: ApplyStatus::Conflict(conflict),
+        })
+    }
+
+    /// Checks out `branch`, creating it from the current HEAD if it does not exist.
+    async fn checkout(&self, repo: &ArcPath, branch: &ArcStr) -> anyhow::Result<()> {
+        let exists = self
+            .git(
+                repo,
+                vec![
+                    ArcStr::from("rev-parse"),
+                    ArcStr::from("--verify"),
+                    ArcStr::from("--quiet"),
+                    ArcStr::from(format!("refs/heads/{branch}")),
+                ],
+                None,
+            )
+            .await?
+            .is_success();
+
+        let mut args = vec![ArcStr::from("checkout")];
+        if !exists {
+            args.push(ArcStr::from("-b"));
+        }
+        args.push(branch.clone());
+
+        let result = self.git(repo, args, None).await?;
+        if result.is_failure() {
+            anyhow::bail!(
+                "Failed to check out branch '{}': {}",
+                branch,
+                result.stderr.trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Runs a git subcommand inside `repo`.
+    async fn git(
+        &self,
+        repo: &ArcPath,
+        args: Vec<ArcStr>,
+        stdin: Option<String>,
+    ) -> anyhow::Result<Output> {
+        let mut full = vec![ArcStr::from("-C"), ArcStr::from(&repo.to_string_lossy())];
+        full.extend(args);
+        self.shell
+            .execute(
+                ArcStr::from("git"),
+                ArcSlice::from(full),
+                stdin.map(ArcStr::from),
+            )
+            .await
+    }
+}
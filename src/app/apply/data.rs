@@ -0,0 +1,187 @@
+use std::fmt;
+
+use crate::api::lore::parse_patch_message;
+use crate::{ArcPath, ArcStr};
+
+/// Options for applying patches with `git am`.
+///
+/// Flags left unset fall back to the configured defaults, so a flag can only be
+/// turned on per invocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplyOptions {
+    /// Fall back to a 3-way merge if the patch does not apply cleanly (`-3`)
+    pub three_way: bool,
+    /// Add a `Signed-off-by` trailer to every applied commit (`--signoff`)
+    pub signoff: bool,
+    /// Branch to check out (and create if missing) before applying
+    pub branch: Option<ArcStr>,
+}
+
+/// Describes where and why `git am` stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyConflict {
+    /// Position (1-based) of the failing patch in the mbox
+    pub index: usize,
+    /// Subject of the failing patch
+    pub subject: ArcStr,
+    /// Files that failed to apply or merge
+    pub files: Vec<ArcStr>,
+    /// Subjects of the patches that applied before the failing one, undone by the abort
+    pub rolled_back: Vec<ArcStr>,
+    /// Combined output of `git am`
+    pub output: ArcStr,
+}
+
+/// Final state of an apply operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyStatus {
+    /// Every patch was applied
+    Applied,
+    /// `git am` stopped on a patch; the operation was aborted and the tree left untouched
+    Conflict(ApplyConflict),
+}
+
+/// Structured result of applying one or more patches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyReport {
+    /// The repository the patches were applied to
+    pub repo: ArcPath,
+    /// The branch that was checked out before applying, if any
+    pub branch: Option<ArcStr>,
+    /// Subjects of the patches that were applied, in order
+    pub applied: Vec<ArcStr>,
+    /// Whether the whole mbox applied or where it stopped
+    pub status: ApplyStatus,
+}
+
+impl ApplyReport {
+    /// Returns true if every patch was applied.
+    pub fn is_success(&self) -> bool {
+        matches!(self.status, ApplyStatus::Applied)
+    }
+}
+
+impl fmt::Display for ApplyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Repository: {}", self.repo.display())?;
+        if let Some(branch) = &self.branch {
+            write!(f, " (branch {branch})")?;
+        }
+        writeln!(f)?;
+        writeln!(f)?;
+        for subject in &self.applied {
+            writeln!(f, "Applied: {subject}")?;
+        }
+        match &self.status {
+            ApplyStatus::Applied => write!(f, "\n{} patch(es) applied", self.applied.len()),
+            ApplyStatus::Conflict(conflict) => {
+                for subject in &conflict.rolled_back {
+                    writeln!(f, "Rolled back: {subject}")?;
+                }
+                writeln!(
+                    f,
+                    "Failed:  {} (patch {})",
+                    conflict.subject, conflict.index
+                )?;
+                if !conflict.files.is_empty() {
+                    writeln!(f, "\nConflicting files:")?;
+                    for file in &conflict.files {
+                        writeln!(f, "  {file}")?;
+                    }
+                }
+                writeln!(f, "\nThe operation was aborted, the tree is unchanged.")?;
+                write!(f, "\ngit am output:\n{}", conflict.output)
+            }
+        }
+    }
+}
+
+/// Joins raw patches into a single mbox that `git am` can read from stdin.
+///
+/// Patches fetched from lore usually carry their own `From ` separator; the ones
+/// that do not get a placeholder separator so `git am` sees one message each.
+pub fn build_mbox(patches: &[String]) -> String {
+    let mut mbox = String::new();
+    for raw in patches {
+        if !raw.starts_with("From ") {
+            mbox.push_str("From mboxrd@z Thu Jan  1 00:00:00 1970\n");
+        }
+        mbox.push_str(raw);
+        if !raw.ends_with('\n') {
+            mbox.push('\n');
+        }
+        mbox.push('\n');
+    }
+    mbox
+}
+
+/// Extracts the (unfolded and decoded) subject of a raw patch email.
+pub fn subject_of(raw: &str) -> ArcStr {
+    parse_patch_message(raw)
+        .map(|message| message.subject)
+        .unwrap_or_default()
+}
+
+/// Looks for the point where `git am` stopped in its output.
+///
+/// # Arguments
+/// * `stdout` - The standard output of `git am`
+/// * `stderr` - The standard error output of `git am`
+///
+/// # Returns
+/// The conflict if `git am` reported a failing patch, `None` if it did not (either
+/// because everything applied or because it failed before reaching any patch).
+pub fn parse_am_output(stdout: &str, stderr: &str) -> Option<ApplyConflict> {
+    let output = format!("{stdout}{stderr}");
+
+    // "Patch failed at 0002 net: do the thing"
+    let (index, subject) = output.lines().find_map(|line| {
+        let rest = line.strip_prefix("Patch failed at ")?;
+        let (index, subject) = rest.split_once(' ').unwrap_or((rest, ""));
+        Some((index.parse().ok()?, ArcStr::from(subject)))
+    })?;
+
+    let mut files: Vec<ArcStr> = Vec::new();
+    for line in output.lines() {
+        let file = if let Some(rest) = line.strip_prefix("error: patch failed: ") {
+            rest.rsplit_once(':').map_or(rest, |(file, _)| file)
+        } else if let Some(rest) = line.strip_prefix("error: ") {
+            match [
+                ": patch does not apply",
+                ": does not exist in index",
+                ": already exists in working directory",
+            ]
+            .iter()
+            .find_map(|suffix| rest.strip_suffix(suffix))
+            {
+                Some(file) => file,
+                None => continue,
+            }
+        } else if let Some((_, file)) = line
+            .strip_prefix("CONFLICT (")
+            .and_then(|rest| rest.split_once(": Merge conflict in "))
+        {
+            file
+        } else {
+            continue;
+        };
+        if !files.iter().any(|known| &**known == file) {
+            files.push(ArcStr::from(file));
+        }
+    }
+
+    Some(ApplyConflict {
+        index,
+        subject,
+        files,
+        rolled_back: Vec::new(),
+        // Resolution hints do not apply, the operation is aborted right after
+        output: ArcStr::from(
+            output
+                .lines()
+                .filter(|line| !line.starts_with("hint:") && !line.contains("\"git am --"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    })
+}
@@ -0,0 +1,21 @@
+use tokio::sync::oneshot::Sender;
+
+use crate::ArcStr;
+
+use super::data::{ApplyOptions, ApplyReport};
+
+/// Messages that can be sent to the apply actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Apply patches of a mailing list to the configured repository
+    Apply {
+        /// The mailing list the patches belong to
+        list: ArcStr,
+        /// The message IDs of the patches, in the order they must be applied
+        message_ids: Vec<ArcStr>,
+        /// Flags for `git am`
+        options: ApplyOptions,
+        /// Response channel for the apply report
+        tx: Sender<anyhow::Result<ApplyReport>>,
+    },
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::{ArcPath, ArcStr};
+
+use super::data::{ApplyOptions, ApplyReport, ApplyStatus};
+
+/// A request recorded by the mock apply actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The mailing list the patches belong to
+    pub list: ArcStr,
+    /// The message IDs of the patches
+    pub message_ids: Vec<ArcStr>,
+    /// Flags for `git am`
+    pub options: ApplyOptions,
+}
+
+/// Mock implementation of the Apply actor for testing purposes.
+///
+/// This struct stores apply requests in memory and reports every patch as applied,
+/// allowing tests to run without touching a git repository.
+#[derive(Debug, Clone, Default)]
+pub struct Mock {
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with an empty request store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies patches to a repository.
+    /// Mock implementation stores the request and reports every message ID as applied.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list the patches belong to
+    /// * `message_ids` - The message IDs of the patches, in order
+    /// * `options` - Flags for `git am`
+    ///
+    /// # Returns
+    /// A successful report listing the message IDs.
+    pub async fn apply(
+        &self,
+        list: ArcStr,
+        message_ids: Vec<ArcStr>,
+        options: ApplyOptions,
+    ) -> anyhow::Result<ApplyReport> {
+        let mut lock = self.requests.lock().await;
+        lock.push(Request {
+            list,
+            message_ids: message_ids.clone(),
+            options: options.clone(),
+        });
+        Ok(ApplyReport {
+            repo: ArcPath::from("."),
+            branch: options.branch,
+            applied: message_ids,
+            status: ApplyStatus::Applied,
+        })
+    }
+
+    /// Gets all recorded apply requests.
+    ///
+    /// # Returns
+    /// A vector of all apply requests.
+    pub async fn get_requests(&self) -> Vec<Request> {
+        let lock = self.requests.lock().await;
+        lock.clone()
+    }
+}
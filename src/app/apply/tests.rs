@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use super::{Apply, ApplyOptions, ApplyStatus, build_mbox, parse_am_output, subject_of};
+use crate::ArcStr;
+use crate::app::cache::{PatchCache, PatchMockData};
+use crate::app::config::{BoolOpt, Config, Data};
+use crate::log::Log;
+use crate::shell::Shell;
+
+const PATCH_1: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Jane Doe <jane@example.com>
+Subject: [PATCH 1/2] net: do
+ the thing
+
+diff --git a/file.c b/file.c
+";
+
+const PATCH_2: &str = "From: Jane Doe <jane@example.com>
+Subject: [PATCH 2/2] net: do the other thing
+
+diff --git a/other.c b/other.c";
+
+#[test]
+fn test_subject_of() {
+    assert_eq!(
+        subject_of(PATCH_1),
+        ArcStr::from("[PATCH 1/2] net: do the thing")
+    );
+    assert_eq!(
+        subject_of(PATCH_2),
+        ArcStr::from("[PATCH 2/2] net: do the other thing")
+    );
+    assert_eq!(
+        subject_of("Subject: =?utf-8?q?=5BPATCH=5D_caf=C3=A9?=\n\nbody"),
+        ArcStr::from("[PATCH] café")
+    );
+    assert_eq!(subject_of("no headers"), ArcStr::from(""));
+}
+
+#[test]
+fn test_build_mbox() {
+    let mbox = build_mbox(&[PATCH_1.to_string(), PATCH_2.to_string()]);
+    assert_eq!(mbox.matches("From mboxrd@z").count(), 2);
+    assert!(mbox.find("1/2").unwrap() < mbox.find("2/2").unwrap());
+    assert!(mbox.ends_with("diff --git a/other.c b/other.c\n\n"));
+}
+
+#[test]
+fn test_parse_am_output_conflict() {
+    let stdout = "Applying: net: do the thing
+Applying: net: do the other thing
+Using index info to reconstruct a base tree...
+M\tother.c
+Falling back to patching base and 3-way merge...
+Auto-merging other.c
+CONFLICT (content): Merge conflict in other.c
+Patch failed at 0002 net: do the other thing
+";
+    let stderr = "error: patch failed: other.c:12
+error: other.c: patch does not apply
+error: Failed to merge in the changes.
+hint: Use 'git am --show-current-patch=diff' to see the failed patch
+";
+
+    let conflict = parse_am_output(stdout, stderr).unwrap();
+    assert_eq!(conflict.index, 2);
+    assert_eq!(conflict.subject, ArcStr::from("net: do the other thing"));
+    assert_eq!(conflict.files, vec![ArcStr::from("other.c")]);
+    assert!(conflict.output.contains("Failed to merge"));
+    assert!(!conflict.output.contains("hint:"));
+}
+
+#[test]
+fn test_parse_am_output_without_failed_patch() {
+    assert!(parse_am_output("", "fatal: not a git repository").is_none());
+    assert!(parse_am_output("Applying: net: do the thing\n", "").is_none());
+}
+
+#[tokio::test]
+async fn test_apply_runs_git_am_with_series_mbox() {
+    let shell = Shell::mock();
+    let patch_cache = PatchCache::mock(PatchMockData {
+        patches: HashMap::from([
+            ("netdev:p1".to_string(), PATCH_1.to_string()),
+            ("netdev:p2".to_string(), PATCH_2.to_string()),
+        ]),
+    });
+    let config = Config::mock(Data::default());
+    config.set_bool(BoolOpt::ApplySignoff, true).await;
+    let apply = Apply::spawn(shell.clone(), patch_cache, config, Log::mock());
+
+    let report = apply
+        .apply(
+            ArcStr::from("netdev"),
+            vec![ArcStr::from("p1"), ArcStr::from("p2")],
+            ApplyOptions {
+                three_way: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(report.status, ApplyStatus::Applied);
+    assert_eq!(report.applied.len(), 2);
+
+    let commands = shell.get_commands().await.unwrap();
+    assert_eq!(commands.len(), 1);
+    let args: Vec<&str> = commands[0].args.iter().map(|arg| &**arg).collect();
+    assert_eq!(args, vec!["-C", ".", "am", "--3way", "--signoff"]);
+    let stdin = commands[0].stdin.as_ref().unwrap();
+    assert!(stdin.find("1/2").unwrap() < stdin.find("2/2").unwrap());
+}
+
+#[tokio::test]
+async fn test_apply_checks_out_branch() {
+    let shell = Shell::mock();
+    let patch_cache = PatchCache::mock(PatchMockData {
+        patches: HashMap::from([("netdev:p1".to_string(), PATCH_1.to_string())]),
+    });
+    let apply = Apply::spawn(
+        shell.clone(),
+        patch_cache,
+        Config::mock(Data::default()),
+        Log::mock(),
+    );
+
+    apply
+        .apply(
+            ArcStr::from("netdev"),
+            vec![ArcStr::from("p1")],
+            ApplyOptions {
+                branch: Some(ArcStr::from("review")),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let commands = shell.get_commands().await.unwrap();
+    let subcommands: Vec<&str> = commands.iter().map(|cmd| &*cmd.args[2]).collect();
+    assert_eq!(subcommands, vec!["rev-parse", "checkout", "am"]);
+    assert_eq!(&*commands[1].args[3], "review");
+}
+
+#[tokio::test]
+async fn test_apply_fails_on_missing_patch() {
+    let apply = Apply::spawn(
+        Shell::mock(),
+        PatchCache::mock(PatchMockData::default()),
+        Config::mock(Data::default()),
+        Log::mock(),
+    );
+
+    let result = apply
+        .apply(
+            ArcStr::from("netdev"),
+            vec![ArcStr::from("missing")],
+            ApplyOptions::default(),
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+/// A patch cache with both patches and a shell whose `git am` stops on the second one.
+fn conflicting_apply(abort: (i32, &str)) -> (Apply, Shell) {
+    let stdout = "Applying: net: do the thing
+Applying: net: do the other thing
+error: patch failed: other.c:12
+Patch failed at 0002 net: do the other thing
+";
+    let shell = Shell::mock_with_outputs(HashMap::from([
+        (
+            ArcStr::from("git -C . am"),
+            (1, ArcStr::from(stdout), ArcStr::from("")),
+        ),
+        (
+            ArcStr::from("git -C . am --abort"),
+            (abort.0, ArcStr::from(""), ArcStr::from(abort.1)),
+        ),
+    ]));
+    let patch_cache = PatchCache::mock(PatchMockData {
+        patches: HashMap::from([
+            ("netdev:p1".to_string(), PATCH_1.to_string()),
+            ("netdev:p2".to_string(), PATCH_2.to_string()),
+        ]),
+    });
+    let apply = Apply::spawn(
+        shell.clone(),
+        patch_cache,
+        Config::mock(Data::default()),
+        Log::mock(),
+    );
+    (apply, shell)
+}
+
+#[tokio::test]
+async fn test_apply_conflict_rolls_back_applied_patches() {
+    let (apply, shell) = conflicting_apply((0, ""));
+
+    let report = apply
+        .apply(
+            ArcStr::from("netdev"),
+            vec![ArcStr::from("p1"), ArcStr::from("p2")],
+            ApplyOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let commands = shell.get_commands().await.unwrap();
+    let args: Vec<Vec<&str>> = commands
+        .iter()
+        .map(|cmd| cmd.args.iter().map(|arg| &**arg).collect())
+        .collect();
+    assert_eq!(
+        args,
+        vec![vec!["-C", ".", "am"], vec!["-C", ".", "am", "--abort"]]
+    );
+
+    assert!(report.applied.is_empty());
+    let ApplyStatus::Conflict(conflict) = &report.status else {
+        panic!("expected a conflict, got {:?}", report.status);
+    };
+    assert_eq!(conflict.index, 2);
+    assert_eq!(conflict.files, vec![ArcStr::from("other.c")]);
+    assert_eq!(
+        conflict.rolled_back,
+        vec![ArcStr::from("[PATCH 1/2] net: do the thing")]
+    );
+    let text = report.to_string();
+    assert!(!text.contains("Applied:"));
+    assert!(text.contains("Rolled back: [PATCH 1/2] net: do the thing"));
+}
+
+#[tokio::test]
+async fn test_apply_conflict_fails_if_abort_fails() {
+    let (apply, shell) = conflicting_apply((128, "fatal: index.lock exists"));
+
+    let result = apply
+        .apply(
+            ArcStr::from("netdev"),
+            vec![ArcStr::from("p1"), ArcStr::from("p2")],
+            ApplyOptions::default(),
+        )
+        .await;
+
+    let error = format!("{:#}", result.unwrap_err());
+    assert!(error.contains("index.lock exists"));
+    assert!(error.contains("run `git am --abort`"));
+    assert_eq!(shell.get_commands().await.unwrap().len(), 2);
+}
@@ -4,14 +4,17 @@ mod core;
 mod data;
 mod mock;
 pub mod message;
+pub mod series;
 
 use crate::ArcStr;
-use crate::api::lore::{LoreApi, LorePatchMetadata};
+use crate::api::lore::{LoreApi, LorePatchMetadata, LoreSearch};
 use crate::app::config::Config;
 use crate::fs::Fs;
 use crate::log::Log;
 use message::Message;
 
+pub use series::PatchSeries;
+
 /// The Feed Actor provides per-mailing-list caching of patch metadata.
 ///
 /// This actor caches patch metadata for each mailing list separately, providing
@@ -26,6 +29,8 @@ pub enum FeedCache {
 #[derive(Debug, Clone, Default)]
 pub struct MockData {
     pub feeds: std::collections::HashMap<ArcStr, Vec<LorePatchMetadata>>,
+    pub skipped: std::collections::HashMap<ArcStr, usize>,
+    pub path_filters: std::collections::HashMap<ArcStr, Vec<ArcStr>>,
 }
 
 impl FeedCache {
@@ -89,6 +94,90 @@ impl FeedCache {
         }
     }
 
+    /// Fetches a range of patch series for a given mailing list.
+    ///
+    /// The flat feed is grouped into submissions (see [`series::group_series`]) and the
+    /// range indexes series, not feed items. More feed pages are fetched as needed so
+    /// that the last series of the range is not cut in half.
+    pub async fn get_series(
+        &self,
+        list: ArcStr,
+        range: std::ops::Range<usize>,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetSeries { list, range, tx })
+                    .await
+                    .context("Sending message to FeedCache actor")
+                    .expect("FeedCache actor died");
+                rx.await
+                    .context("Awaiting response from FeedCache actor")
+                    .expect("FeedCache actor died")
+            }
+            Self::Mock(mock) => {
+                mock.get_series(list, range).await
+            }
+        }
+    }
+
+    /// Fetches every cached revision (v1, v2, ...) of the series containing a message.
+    ///
+    /// Revisions are linked by author email and normalised subject, and are returned
+    /// ordered by version. Only the cached part of the feed is searched.
+    pub async fn get_revisions(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetRevisions {
+                        list,
+                        message_id,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to FeedCache actor")
+                    .expect("FeedCache actor died");
+                rx.await
+                    .context("Awaiting response from FeedCache actor")
+                    .expect("FeedCache actor died")
+            }
+            Self::Mock(mock) => {
+                mock.get_revisions(list, message_id).await
+            }
+        }
+    }
+
+    /// Registers a search so that its results can be browsed like a mailing list feed.
+    ///
+    /// The returned key (see [`LoreSearch::cache_key`]) stands for the search in every
+    /// other method, e.g. `get_slice(key, 0..20)` fetches the first twenty results.
+    /// Results are persisted along with the search, so running the same search again
+    /// starts from the cached results.
+    pub async fn search(&self, search: LoreSearch) -> ArcStr {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Search { search, tx })
+                    .await
+                    .context("Sending message to FeedCache actor")
+                    .expect("FeedCache actor died");
+                rx.await
+                    .context("Awaiting response from FeedCache actor")
+                    .expect("FeedCache actor died")
+            }
+            Self::Mock(mock) => {
+                mock.search(search).await
+            }
+        }
+    }
+
     /// Refreshes the cache for a specific mailing list.
     pub async fn refresh(&self, list: ArcStr) -> anyhow::Result<()> {
         match self {
@@ -169,6 +258,50 @@ impl FeedCache {
         }
     }
 
+    /// Returns how many feed entries of a mailing list were skipped because they could not
+    /// be parsed.
+    pub async fn skipped(&self, list: ArcStr) -> usize {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Skipped { list, tx })
+                    .await
+                    .context("Sending message to FeedCache actor")
+                    .expect("FeedCache actor died");
+                rx.await
+                    .context("Awaiting response from FeedCache actor")
+                    .expect("FeedCache actor died")
+            }
+            Self::Mock(mock) => {
+                mock.skipped(list).await
+            }
+        }
+    }
+
+    /// Returns the files and directories a patch must touch to be part of the feed of a
+    /// mailing list, as set in the `path_filters` configuration.
+    ///
+    /// An empty filter means every patch is shown.
+    pub async fn path_filter(&self, list: ArcStr) -> Vec<ArcStr> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::PathFilter { list, tx })
+                    .await
+                    .context("Sending message to FeedCache actor")
+                    .expect("FeedCache actor died");
+                rx.await
+                    .context("Awaiting response from FeedCache actor")
+                    .expect("FeedCache actor died")
+            }
+            Self::Mock(mock) => {
+                mock.path_filter(list).await
+            }
+        }
+    }
+
     /// Returns true if the cache is empty for a given mailing list.
     pub async fn is_empty(&self, list: ArcStr) -> bool {
         self.len(list).await == 0
@@ -211,7 +344,8 @@ impl FeedCache {
         }
     }
 
-    /// Persists the cache for a specific mailing list to the filesystem.
+    /// Persists the changes to the cache for a specific mailing list to the filesystem.
+    /// An empty list name persists the changes to every feed.
     pub async fn persist(&self, list: ArcStr) -> anyhow::Result<()> {
         match self {
             Self::Actual(sender) => {
@@ -1,13 +1,17 @@
-use super::data::FeedData;
+use super::data::{CacheData, FeedData, FeedMeta, FeedRecord, META_KEY, entry_position};
 use super::message::Message;
+use super::series::{self, PatchSeries};
 use crate::ArcPath;
 use crate::ArcStr;
-use crate::api::lore::{LoreApi, LorePatchMetadata};
-use crate::app::config::Config;
+use crate::api::lore::{
+    LoreApi, LoreFeedDiagnostic, LorePage, LorePatchMetadata, LoreQuery, LoreSearch,
+};
+use crate::app::cache::store::Store;
+use crate::app::config::{Config, MapOpt};
 use crate::fs::Fs;
 use crate::log::Log;
 use anyhow::Context;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
@@ -26,6 +30,10 @@ pub struct Core {
     log: Log,
     /// Internal state
     data: FeedData,
+    /// The opened store of every feed
+    stores: HashMap<String, Store<FeedRecord>>,
+    /// The metadata of every feed as last persisted
+    metas: HashMap<String, FeedMeta>,
 }
 
 impl Core {
@@ -41,6 +49,8 @@ impl Core {
             config,
             log,
             data,
+            stores: HashMap::new(),
+            metas: HashMap::new(),
         })
     }
 
@@ -60,6 +70,22 @@ impl Core {
                         let result = core.handle_get_slice(&list, range).await;
                         let _ = tx.send(result);
                     }
+                    Message::GetSeries { list, range, tx } => {
+                        let result = core.handle_get_series(&list, range).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::GetRevisions {
+                        list,
+                        message_id,
+                        tx,
+                    } => {
+                        let result = core.handle_get_revisions(&list, &message_id);
+                        let _ = tx.send(result);
+                    }
+                    Message::Search { search, tx } => {
+                        let result = core.handle_search(search);
+                        let _ = tx.send(result);
+                    }
                     Message::Refresh { list, tx } => {
                         let result = core.refresh_cache(&list).await;
                         let _ = tx.send(result);
@@ -76,6 +102,14 @@ impl Core {
                         let result = core.data.len(&list);
                         let _ = tx.send(result);
                     }
+                    Message::Skipped { list, tx } => {
+                        let result = core.data.skipped(&list);
+                        let _ = tx.send(result);
+                    }
+                    Message::PathFilter { list, tx } => {
+                        let result = core.path_filter(&list).await;
+                        let _ = tx.send(result);
+                    }
                     Message::Persist { list, tx } => {
                         let result = core.persist_cache(&list).await;
                         let _ = tx.send(result);
@@ -101,6 +135,8 @@ impl Core {
         list: &str,
         index: usize,
     ) -> anyhow::Result<Option<LorePatchMetadata>> {
+        self.sync_filter(list).await;
+
         // Check if we have the item in cache
         if let Some(item) = self.data.feeds.get(list).and_then(|v| v.get(index)) {
             return Ok(Some(item.clone()));
@@ -124,6 +160,8 @@ impl Core {
         list: &str,
         range: std::ops::Range<usize>,
     ) -> anyhow::Result<Vec<LorePatchMetadata>> {
+        self.sync_filter(list).await;
+
         // Check if we have the entire range in cache
         if self.data.contains_range(list, range.clone()) {
             let feed = self.data.feeds.get(list).unwrap();
@@ -147,10 +185,128 @@ impl Core {
         }
     }
 
+    /// Handles getting a range of patch series.
+    ///
+    /// Since a series spans an unknown number of feed items, this keeps fetching pages
+    /// until there is at least one series past the end of the range (so the last one
+    /// in range is complete) or the feed is exhausted.
+    async fn handle_get_series(
+        &mut self,
+        list: &str,
+        range: std::ops::Range<usize>,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        self.sync_filter(list).await;
+        loop {
+            let grouped = self
+                .data
+                .feeds
+                .get(list)
+                .map(|feed| series::group_series(feed))
+                .unwrap_or_default();
+
+            let cached = self.data.len(list);
+            if grouped.len() <= range.end {
+                self.fetch_until_index(list, cached).await?;
+                if self.data.len(list) > cached {
+                    continue;
+                }
+            }
+
+            let start = range.start.min(grouped.len());
+            let end = range.end.min(grouped.len());
+            return Ok(grouped[start..end].to_vec());
+        }
+    }
+
+    /// Handles getting every cached revision of the series containing a message.
+    fn handle_get_revisions(
+        &self,
+        list: &str,
+        message_id: &str,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        let grouped = self
+            .data
+            .feeds
+            .get(list)
+            .map(|feed| series::group_series(feed))
+            .unwrap_or_default();
+        Ok(series::find_revisions(&grouped, message_id))
+    }
+
+    /// Handles registering a search, returning the key its results are cached under.
+    fn handle_search(&mut self, search: LoreSearch) -> ArcStr {
+        let key = search.cache_key();
+        self.log.info(
+            SCOPE,
+            format!("Caching search '{}' as '{}'", search.query, key),
+        );
+        self.data.searches.insert(key.to_string(), search);
+        key
+    }
+
+    /// Fetches a page of a feed, which is either a mailing list or a registered search.
+    ///
+    /// The feed of a mailing list with a path filter is the search for the patches
+    /// touching the filtered paths.
+    async fn fetch_page(
+        &self,
+        list: &str,
+        min_index: usize,
+    ) -> anyhow::Result<Option<LorePage<LorePatchMetadata>>> {
+        if let Some(search) = self.data.searches.get(list) {
+            return self.lore.search_page(search.clone(), min_index).await;
+        }
+        let filter = self.path_filter(list).await;
+        if filter.is_empty() {
+            return self
+                .lore
+                .get_patch_feed_page(ArcStr::from(list), min_index)
+                .await;
+        }
+        let query = LoreQuery::patches().touches_any(&filter);
+        let search = LoreSearch::new(ArcStr::from(list), query);
+        self.lore.search_page(search, min_index).await
+    }
+
+    /// Returns the paths a patch must touch to be part of the feed of a mailing list.
+    ///
+    /// The filters come from the `path_filters` configuration, searches have none.
+    async fn path_filter(&self, list: &str) -> Vec<ArcStr> {
+        if self.data.searches.contains_key(list) {
+            return Vec::new();
+        }
+        self.config
+            .map(MapOpt::PathFilters)
+            .await
+            .remove(list)
+            .unwrap_or_default()
+    }
+
+    /// Drops the cached feed of a mailing list if it was fetched with another path filter.
+    async fn sync_filter(&mut self, list: &str) {
+        let filter = self.path_filter(list).await;
+        let cached = self.data.filters.get(list).cloned().unwrap_or_default();
+        if filter == cached {
+            return;
+        }
+        self.log.info(
+            SCOPE,
+            format!(
+                "Path filter of list '{}' changed from {:?} to {:?}, dropping its cached feed",
+                list, cached, filter
+            ),
+        );
+        self.data.clear(list);
+        if filter.is_empty() {
+            self.data.filters.remove(list);
+        } else {
+            self.data.filters.insert(list.to_string(), filter);
+        }
+    }
+
     /// Handles invalidating the cache for a specific mailing list.
     async fn handle_invalidate(&mut self, list: &str) -> anyhow::Result<()> {
-        self.data.feeds.remove(list);
-        self.data.last_updated.remove(list);
+        self.data.clear(list);
         self.persist_cache(list).await
     }
 
@@ -176,14 +332,30 @@ impl Core {
             .unwrap_or(false)
     }
 
+    /// Logs the feed entries that could not be parsed and adds them to the skipped count.
+    fn record_diagnostics(&mut self, list: &str, diagnostics: &[LoreFeedDiagnostic]) {
+        for diagnostic in diagnostics {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Skipped entry {} of list '{}' ({}): {}",
+                    diagnostic.position, list, diagnostic.title, diagnostic.reason
+                ),
+            );
+        }
+        if !diagnostics.is_empty() {
+            *self.data.skipped.entry(list.to_string()).or_default() += diagnostics.len();
+        }
+    }
+
     /// Fetches pages until we have enough data to reach the specified index.
     async fn fetch_until_index(&mut self, list: &str, target_index: usize) -> anyhow::Result<()> {
-        let mut min_index = self.data.len(list);
-
         // If we already have enough data, no need to fetch
-        if min_index > target_index {
+        if self.data.len(list) > target_index {
             return Ok(());
         }
+        // Skipped entries still count towards the feed offset
+        let mut min_index = self.data.len(list) + self.data.skipped(list);
 
         self.log.info(
             SCOPE,
@@ -195,10 +367,7 @@ impl Core {
 
         // Fetch pages until we have enough data or reach the end
         loop {
-            let page = self
-                .lore
-                .get_patch_feed_page(ArcStr::from(list), min_index)
-                .await?;
+            let page = self.fetch_page(list, min_index).await?;
 
             match page {
                 Some(page) => {
@@ -208,16 +377,14 @@ impl Core {
                         break;
                     }
 
-                    // Add new items to the cache (prepend since they're newer)
-                    let feed = self
-                        .data
-                        .feeds
-                        .entry(list.to_string())
-                        .or_insert_with(Vec::new);
-                    feed.extend(page.items);
+                    self.record_diagnostics(list, &page.diagnostics);
+
+                    // Older pages go at the end; new patches may shift items already
+                    // cached into them
+                    self.data.append_older(list, page.items);
 
                     // Update last_updated with the newest item's timestamp
-                    if let Some(newest_item) = feed.first() {
+                    if let Some(newest_item) = self.get_newest_cached_item(list) {
                         let last_update = newest_item.last_update;
                         self.data
                             .update_last_updated(list.to_string(), Some(last_update));
@@ -227,7 +394,7 @@ impl Core {
                     min_index = page.next_page_index.unwrap_or(min_index + items_len);
 
                     // If no next page or we have enough data, we're done
-                    if page.next_page_index.is_none() || min_index > target_index {
+                    if page.next_page_index.is_none() || self.data.len(list) > target_index {
                         break;
                     }
                 }
@@ -254,9 +421,13 @@ impl Core {
     }
 
     /// Refreshes the cache for a specific mailing list with smart pagination.
+    ///
+    /// Pages are fetched from the top of the feed until an item already cached shows up.
+    /// The items before it are merged into the front of the cached feed, newest first.
     async fn refresh_cache(&mut self, list: &str) -> anyhow::Result<()> {
         self.log
             .info(SCOPE, &format!("Refreshing feed cache for list: {}", list));
+        self.sync_filter(list).await;
 
         // Check if cache is empty
         if self
@@ -275,82 +446,72 @@ impl Core {
             return Ok(());
         }
 
-        // Cache is not empty, fetch pages until we find an item we already have
-        let newest_cached_message_id = self
-            .get_newest_cached_item(list)
-            .expect("Cache should not be empty at this point")
-            .message_id
-            .clone();
-
         self.log.info(
             SCOPE,
             &format!(
-                "Cache not empty for list '{}', fetching until we find item: {}",
-                list, newest_cached_message_id
+                "Cache not empty for list '{}', fetching until a cached item shows up",
+                list
             ),
         );
 
         let mut min_index = 0;
-        let mut new_items_count = 0;
+        let mut fresh = Vec::new();
 
-        // Fetch pages until we find a page containing our newest cached item
+        // Fetch pages until we find one containing an item we already have
         loop {
-            let page = self
-                .lore
-                .get_patch_feed_page(ArcStr::from(list), min_index)
-                .await?;
-
-            match page {
-                Some(page) => {
-                    let items_len = page.items.len();
-                    if items_len == 0 {
-                        // No more items available
-                        break;
-                    }
-
-                    // Check if this page contains our newest cached item
-                    let contains_cached_item = page
-                        .items
-                        .iter()
-                        .any(|item| item.message_id == newest_cached_message_id);
-
-                    if contains_cached_item {
-                        // Found our newest cached item, we're done
-                        self.log.info(
-                            SCOPE,
-                            &format!(
-                                "Found newest cached item in page for list '{}', stopping refresh",
-                                list
-                            ),
-                        );
-                        break;
-                    }
-
-                    // This page contains only new items, add them to the cache
-                    let feed = self.data.feeds.get_mut(list).unwrap();
-                    feed.extend(page.items);
-                    new_items_count += items_len;
-
-                    // Update last_updated with the newest item's timestamp
-                    if let Some(newest_item) = feed.first() {
-                        let last_update = newest_item.last_update;
-                        self.data
-                            .update_last_updated(list.to_string(), Some(last_update));
-                    }
-
-                    // Update min_index for next page
-                    min_index = page.next_page_index.unwrap_or(min_index + items_len);
+            let Some(page) = self.fetch_page(list, min_index).await? else {
+                // No more pages available
+                break;
+            };
+            let items_len = page.items.len();
+            if items_len == 0 {
+                // No more items available
+                break;
+            }
 
-                    // If no next page, we're done
-                    if page.next_page_index.is_none() {
-                        break;
-                    }
+            match self.data.first_known(list, &page.items) {
+                Some(known) => {
+                    // Only the entries before the cached item are new, skipped ones included
+                    let position = entry_position(&page.diagnostics, min_index, known);
+                    let diagnostics: Vec<_> = page
+                        .diagnostics
+                        .into_iter()
+                        .filter(|diagnostic| diagnostic.position < position)
+                        .collect();
+                    self.record_diagnostics(list, &diagnostics);
+                    fresh.extend(page.items.into_iter().take(known));
+                    self.log.info(
+                        SCOPE,
+                        &format!(
+                            "Found a cached item at position {} for list '{}', stopping refresh",
+                            position, list
+                        ),
+                    );
+                    break;
                 }
                 None => {
-                    // No more pages available
-                    break;
+                    // This page contains only new items
+                    self.record_diagnostics(list, &page.diagnostics);
+                    fresh.extend(page.items);
                 }
             }
+
+            // Update min_index for next page
+            min_index = page.next_page_index.unwrap_or(min_index + items_len);
+
+            // If no next page, we're done
+            if page.next_page_index.is_none() {
+                break;
+            }
+        }
+
+        let new_items_count = self.data.merge_newer(list, fresh);
+
+        // Update last_updated with the newest item's timestamp
+        if let Some(newest_item) = self.get_newest_cached_item(list) {
+            let last_update = newest_item.last_update;
+            self.data
+                .update_last_updated(list.to_string(), Some(last_update));
         }
 
         // Persist the updated cache
@@ -369,60 +530,127 @@ impl Core {
         Ok(())
     }
 
-    /// Persists the cache for a specific mailing list to the filesystem.
-    async fn persist_cache(&self, list: &str) -> anyhow::Result<()> {
-        let cache_path = self.data.get_cache_path(list);
+    /// Takes the store of a feed out of the opened ones, opening it if needed.
+    async fn take_store(&mut self, list: &str) -> anyhow::Result<Store<FeedRecord>> {
+        if let Some(store) = self.stores.remove(list) {
+            return Ok(store);
+        }
+        let store = Store::open(self.fs.clone(), self.data.get_store_path(list))
+            .await
+            .with_context(|| format!("Failed to open the feed store of list '{}'", list))?;
+        if store.truncated() > 0 {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Dropped {} bytes of an interrupted write from the feed store of list '{}'",
+                    store.truncated(),
+                    list
+                ),
+            );
+        }
+        Ok(store)
+    }
 
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = cache_path.parent() {
-            self.fs
-                .mkdir(ArcPath::from(parent))
-                .await
-                .context("Failed to create cache directory")?;
+    /// Persists the changes to the cache for a specific mailing list to its store.
+    ///
+    /// Only the items changed since the last time are written, along with the metadata
+    /// of the feed if it changed. An empty list name persists every feed.
+    async fn persist_cache(&mut self, list: &str) -> anyhow::Result<()> {
+        if !list.is_empty() {
+            return self.persist_feed(list).await;
+        }
+        let lists: HashSet<String> = self
+            .data
+            .dirty
+            .keys()
+            .chain(&self.data.cleared)
+            .chain(self.stores.keys())
+            .cloned()
+            .collect();
+        for list in lists {
+            self.persist_feed(&list).await?;
         }
+        Ok(())
+    }
 
-        // Create cache data for this list only
-        let cache_data = super::data::CacheData {
-            feeds: {
-                let mut feeds = HashMap::new();
-                if let Some(items) = self.data.feeds.get(list) {
-                    feeds.insert(list.to_string(), items.clone());
-                }
-                feeds
-            },
-            last_updated: {
-                let mut last_updated = HashMap::new();
-                if let Some(updated) = self.data.last_updated.get(list) {
-                    last_updated.insert(list.to_string(), *updated);
-                }
-                last_updated
-            },
-        };
+    /// Writes the changes to a feed to its store with a single append.
+    async fn persist_feed(&mut self, list: &str) -> anyhow::Result<()> {
+        let (cleared, mut batch) = self.data.take_changes(list);
+        if cleared {
+            self.metas.remove(list);
+        }
+        let meta = self.data.meta(list);
+        if self.metas.get(list) != Some(&meta) {
+            batch.push((META_KEY.to_string(), Some(FeedRecord::Meta(meta.clone()))));
+        }
+        if !cleared && batch.is_empty() {
+            return Ok(());
+        }
 
-        let content =
-            toml::to_string_pretty(&cache_data).context("Failed to serialize cache data")?;
+        let mut store = self.take_store(list).await?;
+        let written = async {
+            if cleared {
+                store.clear().await?;
+            }
+            store.write(batch.clone()).await
+        }
+        .await;
+        if let Err(e) = written {
+            // Keep the changes around for the next attempt
+            if cleared {
+                self.data.cleared.insert(list.to_string());
+            }
+            self.data.dirty.entry(list.to_string()).or_default().extend(
+                batch
+                    .into_iter()
+                    .filter(|(key, _)| key != META_KEY)
+                    .map(|(key, _)| ArcStr::from(key)),
+            );
+            self.stores.insert(list.to_string(), store);
+            return Err(e).context("Failed to write feed store");
+        }
+        self.metas.insert(list.to_string(), meta);
 
-        // Write the file
-        let mut file = self
-            .fs
-            .write_file(cache_path)
-            .await
-            .context("Failed to open cache file for writing")?;
+        let compacted = store.compact().await;
+        self.stores.insert(list.to_string(), store);
+        if compacted.context("Failed to compact feed store")? {
+            self.log.info(
+                SCOPE,
+                format!("Compacted the feed store of list '{}'", list),
+            );
+        }
+        Ok(())
+    }
 
-        use tokio::io::AsyncWriteExt;
-        file.write_all(content.as_bytes())
-            .await
-            .context("Failed to write cache file")?;
+    /// Loads the cache for a specific mailing list from its store.
+    ///
+    /// A list still cached in the TOML file of earlier versions is migrated to a store.
+    async fn load_cache(&mut self, list: &str) -> anyhow::Result<()> {
+        let store = self.take_store(list).await?;
+        let records = store.load_all().await;
+        self.stores.insert(list.to_string(), store);
+        let records = records.context("Failed to read feed store")?;
+        if records.is_empty() {
+            return self.migrate_cache(list).await;
+        }
 
+        self.data.restore(list, records);
+        self.metas.insert(list.to_string(), self.data.meta(list));
+
+        self.log.info(
+            SCOPE,
+            &format!("Loaded {} items for list: {}", self.data.len(list), list),
+        );
         Ok(())
     }
 
-    /// Loads the cache for a specific mailing list from the filesystem.
-    async fn load_cache(&mut self, list: &str) -> anyhow::Result<()> {
-        let cache_path = self.data.get_cache_path(list);
+    /// Moves the cache for a specific mailing list from its TOML file to its store, if
+    /// there is such a file.
+    async fn migrate_cache(&mut self, list: &str) -> anyhow::Result<()> {
+        let legacy_path = self.data.get_legacy_path(list);
 
         // Check if file exists by trying to read it
-        let file = match self.fs.read_file(cache_path).await {
+        let file = match self.fs.read_file(legacy_path.clone()).await {
             Ok(file) => file,
             Err(_) => return Ok(()), // File doesn't exist, that's ok
         };
@@ -435,16 +663,22 @@ impl Core {
             .await
             .context("Failed to read cache file content")?;
 
-        let cache_data: super::data::CacheData =
+        let cache_data: CacheData =
             toml::from_str(&content).context("Failed to deserialize cache data")?;
-
-        // Merge with existing data
-        self.data.feeds.extend(cache_data.feeds);
-        self.data.last_updated.extend(cache_data.last_updated);
+        self.data.import(list, cache_data);
+        self.persist_cache(list).await?;
+        self.fs
+            .remove_file(legacy_path)
+            .await
+            .context("Failed to remove migrated cache file")?;
 
         self.log.info(
             SCOPE,
-            &format!("Loaded {} items for list: {}", self.data.len(list), list),
+            &format!(
+                "Migrated {} items for list '{}' to its store",
+                self.data.len(list),
+                list
+            ),
         );
         Ok(())
     }
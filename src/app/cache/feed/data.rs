@@ -1,16 +1,60 @@
-use crate::ArcPath;
-use crate::api::lore::LorePatchMetadata;
+use crate::api::lore::{LoreFeedDiagnostic, LorePatchMetadata, LoreSearch};
+use crate::{ArcPath, ArcStr};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-/// Data structure for persisting the feed cache to disk.
+/// Key of the [`FeedRecord::Meta`] record in the store of a feed.
+///
+/// Message IDs are never empty, so it cannot clash with an item.
+pub const META_KEY: &str = "";
+
+/// A record of the on-disk store of a feed.
+///
+/// Items are stored under their message ID and the metadata under [`META_KEY`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FeedRecord {
+    /// What is known about the feed as a whole
+    Meta(FeedMeta),
+    /// A cached item and its position in the feed
+    Item {
+        /// Sorts the items of the feed, newest first
+        seq: i64,
+        /// The patch metadata
+        patch: LorePatchMetadata,
+    },
+}
+
+/// The state of a feed that is not tied to one of its items.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedMeta {
+    /// Last updated time for cache validation
+    pub last_updated: Option<DateTime<Utc>>,
+    /// Number of feed entries that could not be parsed
+    pub skipped: usize,
+    /// The search whose results are cached, for feeds keyed by a search
+    pub search: Option<LoreSearch>,
+    /// The path filter the cached patches were fetched with
+    pub filter: Vec<ArcStr>,
+}
+
+/// Data structure of the TOML files the feed cache was persisted to before it moved
+/// to a store, read once to migrate them.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CacheData {
     /// Cached patch metadata per mailing list
     pub feeds: HashMap<String, Vec<LorePatchMetadata>>,
     /// Last updated time per mailing list for cache validation
     pub last_updated: HashMap<String, Option<DateTime<Utc>>>,
+    /// Number of feed entries per mailing list that could not be parsed
+    #[serde(default)]
+    pub skipped: HashMap<String, usize>,
+    /// The search whose results are cached, for feeds keyed by a search
+    #[serde(default)]
+    pub searches: HashMap<String, LoreSearch>,
+    /// The path filter the cached patches of each filtered mailing list were fetched with
+    #[serde(default)]
+    pub filters: HashMap<String, Vec<ArcStr>>,
 }
 
 /// Internal state for the Feed Actor.
@@ -19,6 +63,18 @@ pub struct FeedData {
     pub feeds: HashMap<String, Vec<LorePatchMetadata>>,
     /// Last updated time per mailing list for cache validation
     pub last_updated: HashMap<String, Option<DateTime<Utc>>>,
+    /// Number of feed entries per mailing list that could not be parsed
+    pub skipped: HashMap<String, usize>,
+    /// The search behind each feed keyed by a search rather than a mailing list
+    pub searches: HashMap<String, LoreSearch>,
+    /// The path filter the cached patches of each filtered mailing list were fetched with
+    pub filters: HashMap<String, Vec<ArcStr>>,
+    /// The position of every cached item per mailing list (see [`FeedRecord::Item`])
+    pub seqs: HashMap<String, HashMap<ArcStr, i64>>,
+    /// Items per mailing list changed since the feed was last persisted
+    pub dirty: HashMap<String, HashSet<ArcStr>>,
+    /// Mailing lists whose stored feed must be dropped before persisting again
+    pub cleared: HashSet<String>,
     /// Directory for cache files
     pub cache_dir: ArcPath,
 }
@@ -29,12 +85,23 @@ impl FeedData {
         Self {
             feeds: HashMap::new(),
             last_updated: HashMap::new(),
+            skipped: HashMap::new(),
+            searches: HashMap::new(),
+            filters: HashMap::new(),
+            seqs: HashMap::new(),
+            dirty: HashMap::new(),
+            cleared: HashSet::new(),
             cache_dir,
         }
     }
 
-    /// Gets the cache file path for a specific mailing list.
-    pub fn get_cache_path(&self, list: &str) -> ArcPath {
+    /// Gets the store path for a specific mailing list.
+    pub fn get_store_path(&self, list: &str) -> ArcPath {
+        ArcPath::from(&self.cache_dir.join(format!("{}.log", list)))
+    }
+
+    /// Gets the path of the TOML file a mailing list was cached in before stores.
+    pub fn get_legacy_path(&self, list: &str) -> ArcPath {
         ArcPath::from(&self.cache_dir.join(format!("{}.toml", list)))
     }
 
@@ -54,18 +121,94 @@ impl FeedData {
         }
     }
 
-    /// Converts to CacheData for persistence.
-    pub fn to_cache_data(&self) -> CacheData {
-        CacheData {
-            feeds: self.feeds.clone(),
-            last_updated: self.last_updated.clone(),
+    /// Drops the cached feed of a mailing list, here and on disk once persisted.
+    ///
+    /// The search and path filter of the feed are kept.
+    pub fn clear(&mut self, list: &str) {
+        self.feeds.remove(list);
+        self.last_updated.remove(list);
+        self.skipped.remove(list);
+        self.seqs.remove(list);
+        self.dirty.remove(list);
+        self.cleared.insert(list.to_string());
+    }
+
+    /// Gets the state of a feed that is not tied to one of its items.
+    pub fn meta(&self, list: &str) -> FeedMeta {
+        FeedMeta {
+            last_updated: self.last_updated.get(list).copied().flatten(),
+            skipped: self.skipped(list),
+            search: self.searches.get(list).cloned(),
+            filter: self.filters.get(list).cloned().unwrap_or_default(),
         }
     }
 
-    /// Updates from CacheData after loading from disk.
-    pub fn from_cache_data(&mut self, data: CacheData) {
-        self.feeds = data.feeds;
-        self.last_updated = data.last_updated;
+    /// Takes the items of a feed changed since it was last persisted.
+    ///
+    /// # Returns
+    /// Whether the stored feed must be dropped first, and the item records to write.
+    pub fn take_changes(&mut self, list: &str) -> (bool, Vec<(String, Option<FeedRecord>)>) {
+        let cleared = self.cleared.remove(list);
+        let dirty = self.dirty.remove(list).unwrap_or_default();
+        let (Some(feed), Some(seqs)) = (self.feeds.get(list), self.seqs.get(list)) else {
+            return (cleared, Vec::new());
+        };
+        let records = feed
+            .iter()
+            .filter(|item| dirty.contains(&item.message_id))
+            .map(|item| {
+                let record = FeedRecord::Item {
+                    seq: seqs[&item.message_id],
+                    patch: item.clone(),
+                };
+                (item.message_id.to_string(), Some(record))
+            })
+            .collect();
+        (cleared, records)
+    }
+
+    /// Rebuilds the feed of a mailing list from the records of its store.
+    pub fn restore(&mut self, list: &str, records: Vec<(String, FeedRecord)>) {
+        let mut items = Vec::with_capacity(records.len());
+        for (_, record) in records {
+            match record {
+                FeedRecord::Meta(meta) => {
+                    self.last_updated
+                        .insert(list.to_string(), meta.last_updated);
+                    self.skipped.insert(list.to_string(), meta.skipped);
+                    if let Some(search) = meta.search {
+                        self.searches.insert(list.to_string(), search);
+                    }
+                    if !meta.filter.is_empty() {
+                        self.filters.insert(list.to_string(), meta.filter);
+                    }
+                }
+                FeedRecord::Item { seq, patch } => items.push((seq, patch)),
+            }
+        }
+        items.sort_by_key(|(seq, _)| *seq);
+        self.seqs.insert(
+            list.to_string(),
+            items
+                .iter()
+                .map(|(seq, patch)| (patch.message_id.clone(), *seq))
+                .collect(),
+        );
+        self.feeds.insert(
+            list.to_string(),
+            items.into_iter().map(|(_, patch)| patch).collect(),
+        );
+    }
+
+    /// Takes in the feed of a mailing list read from its legacy TOML file, marking every
+    /// item as changed so the whole feed gets persisted.
+    pub fn import(&mut self, list: &str, data: CacheData) {
+        self.last_updated.extend(data.last_updated);
+        self.skipped.extend(data.skipped);
+        self.searches.extend(data.searches);
+        self.filters.extend(data.filters);
+        let feed = data.feeds.get(list).cloned().unwrap_or_default();
+        self.append_older(list, feed);
     }
 
     /// Gets the number of cached items for a mailing list.
@@ -73,6 +216,11 @@ impl FeedData {
         self.feeds.get(list).map(|v| v.len()).unwrap_or(0)
     }
 
+    /// Gets the number of skipped feed entries for a mailing list.
+    pub fn skipped(&self, list: &str) -> usize {
+        self.skipped.get(list).copied().unwrap_or(0)
+    }
+
     /// Checks if a range is available for a mailing list.
     pub fn contains_range(&self, list: &str, range: std::ops::Range<usize>) -> bool {
         self.feeds
@@ -80,4 +228,273 @@ impl FeedData {
             .map(|v| v.len() >= range.end)
             .unwrap_or(false)
     }
+
+    /// Finds where the already cached part of a feed starts in a page fetched from its top.
+    ///
+    /// An item is known if it is cached with the same or a later update; items cached
+    /// with an earlier update were updated since and still count as new.
+    ///
+    /// # Returns
+    /// The index of the first known item in `items`, or `None` if all of them are new.
+    pub fn first_known(&self, list: &str, items: &[LorePatchMetadata]) -> Option<usize> {
+        let feed = self.feeds.get(list)?;
+        let cached: HashMap<&ArcStr, DateTime<Utc>> = feed
+            .iter()
+            .map(|item| (&item.message_id, item.last_update))
+            .collect();
+        items.iter().position(|item| {
+            cached
+                .get(&item.message_id)
+                .is_some_and(|last_update| item.last_update <= *last_update)
+        })
+    }
+
+    /// Merges items newer than everything cached into the front of a feed.
+    ///
+    /// The items are sorted newest first. Duplicates, among them or with cached items,
+    /// collapse into the copy with the latest update, which moves to the front.
+    ///
+    /// # Returns
+    /// How many items were not cached before.
+    pub fn merge_newer(&mut self, list: &str, items: Vec<LorePatchMetadata>) -> usize {
+        let mut fresh = dedup_newest(items);
+        let feed = self.feeds.entry(list.to_string()).or_default();
+        let before = feed.len();
+        feed.retain(|cached| {
+            match fresh
+                .iter_mut()
+                .find(|item| item.message_id == cached.message_id)
+            {
+                Some(item) => {
+                    if cached.last_update > item.last_update {
+                        *item = cached.clone();
+                    }
+                    false
+                }
+                None => true,
+            }
+        });
+        let moved = before - feed.len();
+        // Stable, so items updated at the same time keep the order of the feed
+        fresh.sort_by_key(|item| std::cmp::Reverse(item.last_update));
+        let added = fresh.len() - moved;
+
+        // Positions below the newest cached item
+        let seqs = self.seqs.entry(list.to_string()).or_default();
+        let dirty = self.dirty.entry(list.to_string()).or_default();
+        let first = seqs.values().min().copied().unwrap_or(0);
+        for (i, item) in fresh.iter().enumerate() {
+            seqs.insert(item.message_id.clone(), first - (fresh.len() - i) as i64);
+            dirty.insert(item.message_id.clone());
+        }
+        feed.splice(0..0, fresh);
+        added
+    }
+
+    /// Appends a page of items older than everything cached to the end of a feed.
+    ///
+    /// Items already cached, which the archive may hand out again when new patches
+    /// shift its pages, are not appended; the cached copy takes the later update.
+    ///
+    /// # Returns
+    /// How many items were not cached before.
+    pub fn append_older(&mut self, list: &str, items: Vec<LorePatchMetadata>) -> usize {
+        let feed = self.feeds.entry(list.to_string()).or_default();
+        let seqs = self.seqs.entry(list.to_string()).or_default();
+        let dirty = self.dirty.entry(list.to_string()).or_default();
+        let mut positions: HashMap<ArcStr, usize> = feed
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (item.message_id.clone(), i))
+            .collect();
+        // Positions above the oldest cached item
+        let mut next = seqs.values().max().map_or(0, |last| last + 1);
+        let before = feed.len();
+        for item in items {
+            match positions.get(&item.message_id) {
+                Some(&i) => {
+                    if item.last_update > feed[i].last_update {
+                        dirty.insert(item.message_id.clone());
+                        feed[i] = item;
+                    }
+                }
+                None => {
+                    positions.insert(item.message_id.clone(), feed.len());
+                    seqs.insert(item.message_id.clone(), next);
+                    dirty.insert(item.message_id.clone());
+                    next += 1;
+                    feed.push(item);
+                }
+            }
+        }
+        feed.len() - before
+    }
+}
+
+/// Collapses items sharing a message ID into the copy with the latest update, keeping
+/// the position of the first copy.
+fn dedup_newest(items: Vec<LorePatchMetadata>) -> Vec<LorePatchMetadata> {
+    let mut positions: HashMap<ArcStr, usize> = HashMap::new();
+    let mut unique: Vec<LorePatchMetadata> = Vec::with_capacity(items.len());
+    for item in items {
+        match positions.get(&item.message_id) {
+            Some(&i) => {
+                if item.last_update > unique[i].last_update {
+                    unique[i] = item;
+                }
+            }
+            None => {
+                positions.insert(item.message_id.clone(), unique.len());
+                unique.push(item);
+            }
+        }
+    }
+    unique
+}
+
+/// Returns the position in the feed of the `index`-th parsed item of a page.
+///
+/// Positions count the entries that could not be parsed as well, so every diagnostic
+/// of an entry before the item pushes it one further.
+pub fn entry_position(diagnostics: &[LoreFeedDiagnostic], start: usize, index: usize) -> usize {
+    let mut positions: Vec<usize> = diagnostics.iter().map(|d| d.position).collect();
+    positions.sort_unstable();
+    let mut position = start + index;
+    for skipped in positions {
+        if skipped <= position {
+            position += 1;
+        }
+    }
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, minutes: i64) -> LorePatchMetadata {
+        LorePatchMetadata {
+            author: ArcStr::from("Jane Doe"),
+            email: ArcStr::from("jane@example.com"),
+            last_update: DateTime::from_timestamp(1_700_000_000 + minutes * 60, 0).unwrap(),
+            title: ArcStr::from(format!("[PATCH] {id}")),
+            version: 1,
+            sequence: None,
+            link: ArcStr::from(format!("https://lore.kernel.org/list/{id}/")),
+            list: ArcStr::from("list"),
+            message_id: ArcStr::from(id),
+            in_reply_to: None,
+            rfc: false,
+            resend: false,
+            prefixes: Vec::new(),
+        }
+    }
+
+    fn ids(data: &FeedData) -> Vec<&str> {
+        data.feeds["list"]
+            .iter()
+            .map(|item| item.message_id.as_ref())
+            .collect()
+    }
+
+    fn cached(items: Vec<LorePatchMetadata>) -> FeedData {
+        let mut data = FeedData::new(ArcPath::from("/tmp/feeds"));
+        data.append_older("list", items);
+        data
+    }
+
+    #[test]
+    fn test_first_known_stops_at_unchanged_item() {
+        let data = cached(vec![item("b", 2), item("a", 1)]);
+        let page = [item("d", 4), item("c", 3), item("b", 2), item("a", 1)];
+        assert_eq!(data.first_known("list", &page), Some(2));
+
+        // An item updated since it was cached is new again
+        let page = [item("a", 5), item("b", 2)];
+        assert_eq!(data.first_known("list", &page), Some(1));
+
+        assert_eq!(data.first_known("list", &[item("e", 6)]), None);
+        assert_eq!(data.first_known("other", &page), None);
+    }
+
+    #[test]
+    fn test_merge_newer_inserts_at_front_in_date_order() {
+        let mut data = cached(vec![item("b", 2), item("a", 1)]);
+        let added = data.merge_newer("list", vec![item("c", 3), item("d", 4)]);
+        assert_eq!(added, 2);
+        assert_eq!(ids(&data), vec!["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_merge_newer_collapses_duplicates() {
+        let mut data = cached(vec![item("c", 3), item("b", 2), item("a", 1)]);
+        let added = data.merge_newer(
+            "list",
+            vec![item("d", 4), item("a", 5), item("d", 6), item("e", 4)],
+        );
+        assert_eq!(added, 2);
+        assert_eq!(ids(&data), vec!["d", "a", "e", "c", "b"]);
+        assert_eq!(data.feeds["list"][0].last_update, item("d", 6).last_update);
+    }
+
+    #[test]
+    fn test_entry_position_counts_skipped_entries() {
+        let skipped = |position| LoreFeedDiagnostic {
+            position,
+            title: ArcStr::from("[PATCH] unparsable"),
+            reason: ArcStr::from("missing author"),
+        };
+        let diagnostics = [skipped(203), skipped(200)];
+        assert_eq!(entry_position(&diagnostics, 200, 0), 201);
+        assert_eq!(entry_position(&diagnostics, 200, 2), 204);
+        assert_eq!(entry_position(&[], 200, 2), 202);
+    }
+
+    #[test]
+    fn test_append_older_skips_cached_items() {
+        let mut data = cached(vec![item("c", 3), item("b", 2)]);
+        let added = data.append_older("list", vec![item("b", 2), item("a", 1), item("a", 1)]);
+        assert_eq!(added, 1);
+        assert_eq!(ids(&data), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_changes_restore_feed_order() {
+        let mut data = cached(vec![item("b", 2), item("a", 1)]);
+        data.merge_newer("list", vec![item("d", 4), item("c", 3)]);
+        let (cleared, mut records) = data.take_changes("list");
+        assert!(!cleared);
+        assert_eq!(records.len(), 4);
+
+        // Only what changed since is written next
+        data.append_older("list", vec![item("a", 5), item("0", 0)]);
+        let (_, changes) = data.take_changes("list");
+        let mut written: Vec<_> = changes.iter().map(|(key, _)| key.as_str()).collect();
+        written.sort();
+        assert_eq!(written, vec!["0", "a"]);
+
+        records.retain(|(key, _)| key != "a");
+        records.extend(changes);
+        records.push((
+            META_KEY.to_string(),
+            Some(FeedRecord::Meta(data.meta("list"))),
+        ));
+        let mut restored = FeedData::new(ArcPath::from("/tmp/feeds"));
+        let records = records
+            .into_iter()
+            .map(|(key, record)| (key, record.unwrap()))
+            .rev()
+            .collect();
+        restored.restore("list", records);
+        assert_eq!(ids(&restored), vec!["d", "c", "b", "a", "0"]);
+        assert_eq!(
+            restored.feeds["list"][3].last_update,
+            item("a", 5).last_update
+        );
+
+        data.clear("list");
+        let (cleared, records) = data.take_changes("list");
+        assert!(cleared);
+        assert!(records.is_empty());
+    }
 }
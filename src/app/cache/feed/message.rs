@@ -1,5 +1,6 @@
 use crate::ArcStr;
-use crate::api::lore::LorePatchMetadata;
+use crate::api::lore::{LorePatchMetadata, LoreSearch};
+use crate::app::cache::feed::PatchSeries;
 use tokio::sync::oneshot;
 
 /// Messages for the Feed Actor.
@@ -17,6 +18,23 @@ pub enum Message {
         range: std::ops::Range<usize>,
         tx: oneshot::Sender<anyhow::Result<Vec<LorePatchMetadata>>>,
     },
+    /// Get a range of patch series for a given mailing list
+    GetSeries {
+        list: ArcStr,
+        range: std::ops::Range<usize>,
+        tx: oneshot::Sender<anyhow::Result<Vec<PatchSeries>>>,
+    },
+    /// Get every cached revision of the series containing a message
+    GetRevisions {
+        list: ArcStr,
+        message_id: ArcStr,
+        tx: oneshot::Sender<anyhow::Result<Vec<PatchSeries>>>,
+    },
+    /// Register a search so its results can be read like a feed, under the returned key
+    Search {
+        search: LoreSearch,
+        tx: oneshot::Sender<ArcStr>,
+    },
     /// Refresh the cache for a specific mailing list
     Refresh {
         list: ArcStr,
@@ -38,6 +56,16 @@ pub enum Message {
         list: ArcStr,
         tx: oneshot::Sender<usize>,
     },
+    /// Get the number of feed entries skipped for a mailing list because they could not be parsed
+    Skipped {
+        list: ArcStr,
+        tx: oneshot::Sender<usize>,
+    },
+    /// Get the path filter applied to the feed of a mailing list
+    PathFilter {
+        list: ArcStr,
+        tx: oneshot::Sender<Vec<ArcStr>>,
+    },
     /// Persist the cache to filesystem
     Persist {
         list: ArcStr,
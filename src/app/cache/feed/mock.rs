@@ -2,8 +2,8 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::ArcStr;
-use crate::api::lore::LorePatchMetadata;
-use crate::app::cache::feed::MockData;
+use crate::api::lore::{LorePatchMetadata, LoreSearch};
+use crate::app::cache::feed::{MockData, PatchSeries, series};
 
 /// Mock implementation of the FeedCache actor for testing purposes.
 ///
@@ -61,10 +61,71 @@ impl Mock {
         Ok(data
             .feeds
             .get(&list)
-            .map(|v| v[range].to_vec())
+            .map(|v| v[range.start.min(v.len())..range.end.min(v.len())].to_vec())
             .unwrap_or_default())
     }
 
+    /// Fetches a range of patch series for a given mailing list.
+    /// Mock implementation groups the stored feed into series.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list name
+    /// * `range` - The range of series to retrieve
+    ///
+    /// # Returns
+    /// Ok(Vec<PatchSeries>) with the requested series
+    pub async fn get_series(
+        &self,
+        list: ArcStr,
+        range: std::ops::Range<usize>,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        let data = self.data.lock().await;
+        let grouped = data
+            .feeds
+            .get(&list)
+            .map(|v| series::group_series(v))
+            .unwrap_or_default();
+        let start = range.start.min(grouped.len());
+        let end = range.end.min(grouped.len());
+        Ok(grouped[start..end].to_vec())
+    }
+
+    /// Fetches every revision of the series containing a message.
+    /// Mock implementation groups the stored feed and links its revisions.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list name
+    /// * `message_id` - The message ID of any message of the series
+    ///
+    /// # Returns
+    /// Ok(Vec<PatchSeries>) with the revisions ordered by version
+    pub async fn get_revisions(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+    ) -> anyhow::Result<Vec<PatchSeries>> {
+        let data = self.data.lock().await;
+        let grouped = data
+            .feeds
+            .get(&list)
+            .map(|v| series::group_series(v))
+            .unwrap_or_default();
+        Ok(series::find_revisions(&grouped, &message_id))
+    }
+
+    /// Registers a search.
+    /// Mock implementation only derives the key, the results are whatever is stored
+    /// under it.
+    ///
+    /// # Arguments
+    /// * `search` - The search to register
+    ///
+    /// # Returns
+    /// The key the results are stored under
+    pub async fn search(&self, search: LoreSearch) -> ArcStr {
+        search.cache_key()
+    }
+
     /// Refreshes the cache for a specific mailing list.
     /// Mock implementation is a no-op that always succeeds.
     ///
@@ -121,6 +182,32 @@ impl Mock {
         data.feeds.get(&list).map(|v| v.len()).unwrap_or(0)
     }
 
+    /// Returns how many feed entries were skipped for a given mailing list.
+    /// Mock implementation reads the count from stored data.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list name
+    ///
+    /// # Returns
+    /// The number of skipped entries, 0 if none were recorded
+    pub async fn skipped(&self, list: ArcStr) -> usize {
+        let data = self.data.lock().await;
+        data.skipped.get(&list).copied().unwrap_or(0)
+    }
+
+    /// Returns the path filter of a given mailing list.
+    /// Mock implementation reads the filter from stored data.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list name
+    ///
+    /// # Returns
+    /// The filtered paths, empty if the list has no filter
+    pub async fn path_filter(&self, list: ArcStr) -> Vec<ArcStr> {
+        let data = self.data.lock().await;
+        data.path_filters.get(&list).cloned().unwrap_or_default()
+    }
+
     /// Checks if the cache has been loaded from disk for a given mailing list.
     /// Mock implementation checks if the list exists in stored data.
     ///
@@ -0,0 +1,366 @@
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::ArcStr;
+use crate::api::lore::LorePatchMetadata;
+
+/// A patch submission grouped out of the flat feed.
+///
+/// A series is keyed by the message ID of its thread root, which is the cover letter
+/// for multi-patch series or the patch itself for single patches. Patches whose root
+/// is not in the feed (e.g. the cover letter is on an older page) are still grouped
+/// together under the missing root's message ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchSeries {
+    /// The message ID of the thread root
+    pub root: ArcStr,
+    /// The mailing list which the series belongs to
+    pub list: ArcStr,
+    /// The title of the cover letter, or of the first known patch if there is none
+    pub title: ArcStr,
+    /// The author name of the series
+    pub author: ArcStr,
+    /// The email of the author of the series
+    pub email: ArcStr,
+    /// The version of the series
+    pub version: usize,
+    /// The number of patches the series announces
+    pub total: usize,
+    /// The newest update among the messages of the series
+    pub last_update: DateTime<Utc>,
+    /// The cover letter (`0/N`), if it is in the feed
+    pub cover: Option<LorePatchMetadata>,
+    /// The patches of the series that are in the feed, ordered by sequence number
+    pub patches: Vec<LorePatchMetadata>,
+}
+
+impl PatchSeries {
+    /// Returns the sequence numbers (1-based) of the patches that never arrived.
+    pub fn missing(&self) -> Vec<usize> {
+        let present: HashSet<usize> = self
+            .patches
+            .iter()
+            .map(|patch| patch.sequence.as_ref().map_or(1, |seq| seq.current))
+            .collect();
+        (1..=self.total).filter(|n| !present.contains(n)).collect()
+    }
+
+    /// Returns true if every patch announced by the series is in the feed.
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// Returns true if `message_id` is the root, the cover letter or a patch of the series.
+    pub fn contains(&self, message_id: &str) -> bool {
+        &*self.root == message_id
+            || self
+                .cover
+                .iter()
+                .chain(self.patches.iter())
+                .any(|p| &*p.message_id == message_id)
+    }
+
+    /// Returns the message IDs of the patches of the series, skipping the cover letter.
+    pub fn patch_ids(&self) -> Vec<ArcStr> {
+        self.patches.iter().map(|p| p.message_id.clone()).collect()
+    }
+
+    /// Returns the message the series should be opened at: the cover letter if there is
+    /// one, otherwise the first known patch.
+    pub fn head(&self) -> Option<&LorePatchMetadata> {
+        self.cover.as_ref().or_else(|| self.patches.first())
+    }
+}
+
+/// Normalises a patch subject so revisions of the same series compare equal.
+///
+/// Leading bracketed tags (`[PATCH v3 2/5]`, `[RFC]`...) and reply prefixes are dropped,
+/// whitespace is collapsed and the result is lowercased.
+pub fn normalize_subject(subject: &str) -> String {
+    let mut rest = subject.trim();
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        if rest.starts_with('[') {
+            match rest.find(']') {
+                Some(end) => rest = rest[end + 1..].trim_start(),
+                None => break,
+            }
+        } else if let Some(prefix) = ["re:", "fwd:", "fw:"]
+            .iter()
+            .find(|prefix| lower.starts_with(*prefix))
+        {
+            rest = rest[prefix.len()..].trim_start();
+        } else {
+            break;
+        }
+    }
+
+    rest.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Returns true if both series are revisions of the same submission, i.e. they share
+/// the author email and the normalised subject.
+pub fn same_submission(a: &PatchSeries, b: &PatchSeries) -> bool {
+    a.email.eq_ignore_ascii_case(&b.email)
+        && normalize_subject(&a.title) == normalize_subject(&b.title)
+}
+
+/// Finds every known revision of the series containing `message_id`.
+///
+/// # Arguments
+/// * `series` - The series to search, as returned by [`group_series`]
+/// * `message_id` - The message ID of the cover letter or any patch of the series
+///
+/// # Returns
+/// The revisions ordered by version, then by date. Empty if no series contains the message.
+pub fn find_revisions(series: &[PatchSeries], message_id: &str) -> Vec<PatchSeries> {
+    let Some(target) = series.iter().find(|s| s.contains(message_id)) else {
+        return Vec::new();
+    };
+
+    let mut revisions: Vec<PatchSeries> = series
+        .iter()
+        .filter(|s| same_submission(s, target))
+        .cloned()
+        .collect();
+    revisions.sort_by_key(|s| (s.version, s.last_update));
+    revisions
+}
+
+/// Returns the number of patches announced by a feed item.
+fn total_of(item: &LorePatchMetadata) -> usize {
+    item.sequence.as_ref().map_or(1, |seq| seq.total)
+}
+
+/// Returns true if the item is a cover letter or a standalone patch, i.e. a message
+/// that is expected to start its own thread.
+fn starts_thread(item: &LorePatchMetadata) -> bool {
+    item.sequence.as_ref().is_none_or(|seq| seq.current == 0)
+}
+
+/// Finds the thread root key of a feed item by following `in_reply_to` links.
+///
+/// Links are only followed towards messages of the same submission (same version and
+/// patch count), so a `v2` posted in reply to a `v1` starts a series of its own.
+fn root_of(item: &LorePatchMetadata, index: &HashMap<&str, &LorePatchMetadata>) -> ArcStr {
+    let mut current = item;
+    // Bounded walk so malformed reply chains cannot loop forever
+    for _ in 0..index.len() {
+        let Some(parent_id) = current.in_reply_to.as_ref() else {
+            break;
+        };
+        match index.get(&**parent_id) {
+            Some(parent)
+                if parent.version == item.version && total_of(parent) == total_of(item) =>
+            {
+                current = parent;
+            }
+            Some(_) => break,
+            None if starts_thread(current) => break,
+            None => return parent_id.clone(),
+        }
+    }
+    current.message_id.clone()
+}
+
+/// Groups a flat feed into patch series.
+///
+/// Series are returned in the order their first message appears in the feed, so a feed
+/// sorted newest first yields series sorted by most recent activity.
+///
+/// # Arguments
+/// * `items` - The feed items to group
+///
+/// # Returns
+/// The series found in the feed, one per submission.
+pub fn group_series(items: &[LorePatchMetadata]) -> Vec<PatchSeries> {
+    let index: HashMap<&str, &LorePatchMetadata> =
+        items.iter().map(|item| (&*item.message_id, item)).collect();
+
+    let mut order: Vec<ArcStr> = Vec::new();
+    let mut groups: HashMap<ArcStr, Vec<&LorePatchMetadata>> = HashMap::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for item in items {
+        if !seen.insert(&item.message_id) {
+            continue;
+        }
+        let root = root_of(item, &index);
+        groups
+            .entry(root.clone())
+            .or_insert_with(|| {
+                order.push(root);
+                Vec::new()
+            })
+            .push(item);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|root| {
+            let members = groups.remove(&root)?;
+            let (covers, mut patches): (Vec<_>, Vec<_>) = members
+                .into_iter()
+                .partition(|item| item.sequence.as_ref().is_some_and(|seq| seq.current == 0));
+            patches.sort_by_key(|item| item.sequence.as_ref().map_or(1, |seq| seq.current));
+
+            let cover = covers.into_iter().next().cloned();
+            let head = cover.as_ref().or(patches.first().copied())?;
+            let last_update = cover
+                .iter()
+                .chain(patches.iter().copied())
+                .map(|item| item.last_update)
+                .max()?;
+
+            Some(PatchSeries {
+                root,
+                list: head.list.clone(),
+                title: head.title.clone(),
+                author: head.author.clone(),
+                email: head.email.clone(),
+                version: head.version,
+                total: total_of(head),
+                last_update,
+                cover: cover.clone(),
+                patches: patches.into_iter().cloned().collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SequenceNumber;
+
+    fn item(
+        id: &str,
+        parent: Option<&str>,
+        version: usize,
+        seq: Option<(usize, usize)>,
+    ) -> LorePatchMetadata {
+        LorePatchMetadata {
+            author: ArcStr::from("Jane Doe"),
+            email: ArcStr::from("jane@example.com"),
+            last_update: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            title: ArcStr::from(format!("[PATCH] {id}")),
+            version,
+            sequence: seq.map(|(current, total)| SequenceNumber::new(current, total)),
+            link: ArcStr::from(format!("https://lore.kernel.org/list/{id}/")),
+            list: ArcStr::from("list"),
+            message_id: ArcStr::from(id),
+            in_reply_to: parent.map(ArcStr::from),
+            rfc: false,
+            resend: false,
+            prefixes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_group_series_with_cover_letter() {
+        let items = vec![
+            item("p2", Some("cover"), 1, Some((2, 3))),
+            item("p1", Some("cover"), 1, Some((1, 3))),
+            item("cover", None, 1, Some((0, 3))),
+            item("single", None, 1, None),
+            item("p3", Some("cover"), 1, Some((3, 3))),
+        ];
+
+        let series = group_series(&items);
+        assert_eq!(series.len(), 2);
+
+        assert_eq!(&*series[0].root, "cover");
+        assert_eq!(&*series[0].cover.as_ref().unwrap().message_id, "cover");
+        let ids: Vec<_> = series[0]
+            .patches
+            .iter()
+            .map(|p| p.message_id.to_string())
+            .collect();
+        assert_eq!(ids, vec!["p1", "p2", "p3"]);
+        assert!(series[0].is_complete());
+
+        assert_eq!(&*series[1].root, "single");
+        assert_eq!(series[1].total, 1);
+        assert!(series[1].is_complete());
+    }
+
+    #[test]
+    fn test_normalize_subject() {
+        assert_eq!(
+            normalize_subject("[PATCH v3 0/5]  Add   New Feature"),
+            "add new feature"
+        );
+        assert_eq!(
+            normalize_subject("Re: [RFC][PATCH] Add new feature"),
+            "add new feature"
+        );
+        assert_eq!(normalize_subject("No tags"), "no tags");
+    }
+
+    #[test]
+    fn test_find_revisions() {
+        let mut v1 = item("v1-cover", None, 1, Some((0, 1)));
+        v1.title = ArcStr::from("[PATCH 0/1] Add new feature");
+        let mut v1_patch = item("v1-p1", Some("v1-cover"), 1, Some((1, 1)));
+        v1_patch.title = ArcStr::from("[PATCH 1/1] net: do the thing");
+        let mut v3 = item("v3-cover", None, 3, Some((0, 1)));
+        v3.title = ArcStr::from("[PATCH v3 0/1] Add  new feature");
+        let mut other = item("other", None, 1, None);
+        other.title = ArcStr::from("[PATCH] Something else");
+
+        let series = group_series(&[v3, other, v1, v1_patch]);
+        let revisions = find_revisions(&series, "v1-p1");
+        let versions: Vec<_> = revisions.iter().map(|s| s.version).collect();
+        assert_eq!(versions, vec![1, 3]);
+
+        assert!(find_revisions(&series, "unknown").is_empty());
+    }
+
+    #[test]
+    fn test_group_series_flags_missing_patches() {
+        let items = vec![
+            item("cover", None, 2, Some((0, 5))),
+            item("p1", Some("cover"), 2, Some((1, 5))),
+            item("p2", Some("p1"), 2, Some((2, 5))),
+            item("p4", Some("cover"), 2, Some((4, 5))),
+        ];
+
+        let series = group_series(&items);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].missing(), vec![3, 5]);
+        assert!(!series[0].is_complete());
+    }
+
+    #[test]
+    fn test_group_series_without_cover_in_feed() {
+        let items = vec![
+            item("p1", Some("cover"), 1, Some((1, 2))),
+            item("p2", Some("cover"), 1, Some((2, 2))),
+        ];
+
+        let series = group_series(&items);
+        assert_eq!(series.len(), 1);
+        assert_eq!(&*series[0].root, "cover");
+        assert!(series[0].cover.is_none());
+        assert_eq!(&*series[0].head().unwrap().message_id, "p1");
+    }
+
+    #[test]
+    fn test_group_series_splits_versions_sent_in_reply() {
+        let items = vec![
+            item("v2-cover", Some("v1-cover"), 2, Some((0, 1))),
+            item("v2-p1", Some("v2-cover"), 2, Some((1, 1))),
+            item("v1-cover", None, 1, Some((0, 1))),
+            item("v1-p1", Some("v1-cover"), 1, Some((1, 1))),
+        ];
+
+        let series = group_series(&items);
+        assert_eq!(series.len(), 2);
+        assert_eq!(&*series[0].root, "v2-cover");
+        assert_eq!(series[0].version, 2);
+        assert_eq!(&*series[1].root, "v1-cover");
+        assert_eq!(series[1].patches.len(), 1);
+    }
+}
@@ -1,7 +1,8 @@
-use super::data::MailingListData;
+use super::data::{CacheData, MailingListData};
 use super::message::Message;
 use crate::ArcPath;
 use crate::api::lore::{LoreApi, LoreMailingList};
+use crate::app::cache::store::Store;
 use crate::app::config::Config;
 use crate::fs::Fs;
 use crate::log::Log;
@@ -24,14 +25,17 @@ pub struct Core {
     log: Log,
     /// Internal state
     data: MailingListData,
+    /// The store the lists are persisted to, once opened
+    store: Option<Store<LoreMailingList>>,
 }
 
 impl Core {
     /// Creates a new Core instance.
     pub async fn new(lore: LoreApi, fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
         let cache_dir = config.path(crate::app::config::PathOpt::CachePath).await;
-        let cache_path = ArcPath::from(&cache_dir.join("mailing_lists.toml"));
-        let data = MailingListData::new(cache_path);
+        let cache_path = ArcPath::from(&cache_dir.join("mailing_lists.log"));
+        let legacy_path = ArcPath::from(&cache_dir.join("mailing_lists.toml"));
+        let data = MailingListData::new(cache_path, legacy_path);
 
         Ok(Self {
             lore,
@@ -39,6 +43,7 @@ impl Core {
             config,
             log,
             data,
+            store: None,
         })
     }
 
@@ -120,8 +125,7 @@ impl Core {
 
     /// Handles invalidating the cache.
     async fn handle_invalidate(&mut self) -> anyhow::Result<()> {
-        self.data.lists.clear();
-        self.data.last_updated = None;
+        self.data.clear();
         self.persist_cache().await
     }
 
@@ -148,31 +152,11 @@ impl Core {
     async fn refresh_cache(&mut self) -> anyhow::Result<()> {
         self.log.info(SCOPE, "Refreshing mailing list cache");
 
-        let mut all_lists = Vec::new();
-        let mut min_index = 0;
+        // Fetch the lists of every configured archive
+        let all_lists = self.lore.get_available_lists().await?.to_vec();
 
-        loop {
-            let page = self.lore.get_available_lists_page(min_index).await?;
-            match page {
-                Some(page) => {
-                    let items_len = page.items.len();
-                    all_lists.extend(page.items);
-                    min_index = page.next_page_index.unwrap_or(min_index + items_len);
-
-                    if page.next_page_index.is_none() {
-                        break;
-                    }
-                }
-                None => break,
-            }
-        }
-
-        // Sort alphabetically
-        all_lists.sort_by(|a, b| a.name.cmp(&b.name));
-
-        // Update internal state
-        self.data.lists = all_lists;
-        self.data.update_last_updated();
+        // Update internal state, sorting alphabetically
+        self.data.replace(all_lists);
 
         // Persist to disk
         self.persist_cache().await?;
@@ -184,39 +168,80 @@ impl Core {
         Ok(())
     }
 
-    /// Persists the cache to the filesystem.
-    async fn persist_cache(&self) -> anyhow::Result<()> {
-        let cache_data = self.data.to_cache_data();
-        let content =
-            toml::to_string_pretty(&cache_data).context("Failed to serialize cache data")?;
-
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = self.data.cache_path.parent() {
-            self.fs
-                .mkdir(ArcPath::from(parent))
-                .await
-                .context("Failed to create cache directory")?;
+    /// Takes the store out of the actor, opening it if needed.
+    async fn take_store(&mut self) -> anyhow::Result<Store<LoreMailingList>> {
+        if let Some(store) = self.store.take() {
+            return Ok(store);
         }
-
-        // Write the file
-        let mut file = self
-            .fs
-            .write_file(self.data.cache_path.clone())
+        let store = Store::open(self.fs.clone(), self.data.cache_path.clone())
             .await
-            .context("Failed to open cache file for writing")?;
+            .context("Failed to open the mailing list store")?;
+        if store.truncated() > 0 {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Dropped {} bytes of an interrupted write from the mailing list store",
+                    store.truncated()
+                ),
+            );
+        }
+        Ok(store)
+    }
 
-        use tokio::io::AsyncWriteExt;
-        file.write_all(content.as_bytes())
-            .await
-            .context("Failed to write cache file")?;
+    /// Persists the changes to the cache since the last time to the store.
+    async fn persist_cache(&mut self) -> anyhow::Result<()> {
+        let (cleared, batch) = self.data.take_changes();
+        if !cleared && batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut store = self.take_store().await?;
+        let written = async {
+            if cleared {
+                store.clear().await?;
+            }
+            store.write(batch.clone()).await?;
+            store.compact().await
+        }
+        .await;
+        self.store = Some(store);
+        if let Err(e) = written {
+            // Keep the changes around for the next attempt
+            self.data.cleared |= cleared;
+            for (key, list) in batch {
+                self.data.changes.entry(key).or_insert(list);
+            }
+            return Err(e).context("Failed to write mailing list store");
+        }
 
         Ok(())
     }
 
-    /// Loads the cache from the filesystem.
+    /// Loads the cache from the store.
+    ///
+    /// Lists still cached in the TOML file of earlier versions are migrated to the store.
     async fn load_cache(&mut self) -> anyhow::Result<()> {
+        let store = self.take_store().await?;
+        let records = store.load_all().await;
+        self.store = Some(store);
+        let records = records.context("Failed to read mailing list store")?;
+        if records.is_empty() {
+            return self.migrate_cache().await;
+        }
+
+        self.data.restore(records);
+
+        self.log.info(
+            SCOPE,
+            &format!("Loaded {} mailing lists from cache", self.data.lists.len()),
+        );
+        Ok(())
+    }
+
+    /// Moves the cache from its TOML file to the store, if there is such a file.
+    async fn migrate_cache(&mut self) -> anyhow::Result<()> {
         // Check if file exists by trying to read it
-        let file = match self.fs.read_file(self.data.cache_path.clone()).await {
+        let file = match self.fs.read_file(self.data.legacy_path.clone()).await {
             Ok(file) => file,
             Err(_) => return Ok(()), // File doesn't exist, that's ok
         };
@@ -229,14 +254,21 @@ impl Core {
             .await
             .context("Failed to read cache file content")?;
 
-        let cache_data: super::data::CacheData =
+        let cache_data: CacheData =
             toml::from_str(&content).context("Failed to deserialize cache data")?;
-
-        self.data.from_cache_data(cache_data);
+        self.data.replace(cache_data.lists);
+        self.persist_cache().await?;
+        self.fs
+            .remove_file(self.data.legacy_path.clone())
+            .await
+            .context("Failed to remove migrated cache file")?;
 
         self.log.info(
             SCOPE,
-            &format!("Loaded {} mailing lists from cache", self.data.lists.len()),
+            format!(
+                "Migrated {} mailing lists to the store",
+                self.data.lists.len()
+            ),
         );
         Ok(())
     }
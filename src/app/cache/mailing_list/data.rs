@@ -2,8 +2,10 @@ use crate::ArcPath;
 use crate::api::lore::LoreMailingList;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Data structure for persisting the mailing list cache to disk.
+/// Data structure of the TOML file the mailing list cache was persisted to before it
+/// moved to a store, read once to migrate it.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CacheData {
     /// Cached mailing lists sorted alphabetically
@@ -18,20 +20,36 @@ pub struct MailingListData {
     pub lists: Vec<LoreMailingList>,
     /// Last updated time from the 0-th item for cache validation
     pub last_updated: Option<DateTime<Utc>>,
-    /// Path to the cache file
+    /// Lists changed since the cache was last persisted by store key, `None` if removed
+    pub changes: HashMap<String, Option<LoreMailingList>>,
+    /// Whether the stored lists must be dropped before persisting again
+    pub cleared: bool,
+    /// Path to the store
     pub cache_path: ArcPath,
+    /// Path to the TOML file of earlier versions
+    pub legacy_path: ArcPath,
 }
 
 impl MailingListData {
     /// Creates a new MailingListData instance.
-    pub fn new(cache_path: ArcPath) -> Self {
+    pub fn new(cache_path: ArcPath, legacy_path: ArcPath) -> Self {
         Self {
             lists: Vec::new(),
             last_updated: None,
+            changes: HashMap::new(),
+            cleared: false,
             cache_path,
+            legacy_path,
         }
     }
 
+    /// Gets the key a mailing list is stored under.
+    ///
+    /// Lists are told apart by archive, as two archives may host lists of the same name.
+    pub fn store_key(list: &LoreMailingList) -> String {
+        format!("{}/{}", list.archive, list.name)
+    }
+
     /// Sorts the mailing lists alphabetically by name.
     pub fn sort_lists(&mut self) {
         self.lists.sort_by(|a, b| a.name.cmp(&b.name));
@@ -52,17 +70,86 @@ impl MailingListData {
         }
     }
 
-    /// Converts to CacheData for persistence.
-    pub fn to_cache_data(&self) -> CacheData {
-        CacheData {
-            lists: self.lists.clone(),
-            last_updated: self.last_updated,
+    /// Replaces the cached mailing lists, recording what changed to persist it later.
+    pub fn replace(&mut self, lists: Vec<LoreMailingList>) {
+        let mut old: HashMap<String, LoreMailingList> = self
+            .lists
+            .drain(..)
+            .map(|list| (Self::store_key(&list), list))
+            .collect();
+        for list in &lists {
+            let key = Self::store_key(list);
+            if old.remove(&key).as_ref() != Some(list) {
+                self.changes.insert(key, Some(list.clone()));
+            }
+        }
+        for key in old.into_keys() {
+            self.changes.insert(key, None);
+        }
+        self.lists = lists;
+        self.sort_lists();
+        self.update_last_updated();
+    }
+
+    /// Drops the cached mailing lists, here and on disk once persisted.
+    pub fn clear(&mut self) {
+        self.lists.clear();
+        self.last_updated = None;
+        self.changes.clear();
+        self.cleared = true;
+    }
+
+    /// Takes the changes to the mailing lists since they were last persisted.
+    ///
+    /// # Returns
+    /// Whether the stored lists must be dropped first, and the records to write.
+    pub fn take_changes(&mut self) -> (bool, Vec<(String, Option<LoreMailingList>)>) {
+        let cleared = std::mem::take(&mut self.cleared);
+        (cleared, self.changes.drain().collect())
+    }
+
+    /// Rebuilds the mailing lists from the records of the store.
+    pub fn restore(&mut self, records: Vec<(String, LoreMailingList)>) {
+        self.lists = records.into_iter().map(|(_, list)| list).collect();
+        self.sort_lists();
+        self.update_last_updated();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArcStr;
+
+    fn list(name: &str, minutes: i64) -> LoreMailingList {
+        LoreMailingList {
+            name: ArcStr::from(name),
+            description: ArcStr::from(format!("The {name} list")),
+            last_update: DateTime::from_timestamp(1_700_000_000 + minutes * 60, 0).unwrap(),
+            archive: ArcStr::from("https://lore.kernel.org"),
         }
     }
 
-    /// Updates from CacheData after loading from disk.
-    pub fn from_cache_data(&mut self, data: CacheData) {
-        self.lists = data.lists;
-        self.last_updated = data.last_updated;
+    #[test]
+    fn test_replace_records_changed_lists() {
+        let mut data = MailingListData::new(ArcPath::from("a.log"), ArcPath::from("a.toml"));
+        data.replace(vec![list("b", 1), list("a", 2)]);
+        assert_eq!(data.lists[0].name, ArcStr::from("a"));
+        assert_eq!(data.take_changes().1.len(), 2);
+
+        data.replace(vec![list("a", 2), list("c", 3), list("b", 4)]);
+        let (cleared, mut changes) = data.take_changes();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(!cleared);
+        let keys: Vec<_> = changes.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["https://lore.kernel.org/b", "https://lore.kernel.org/c"]
+        );
+
+        data.replace(vec![list("a", 2)]);
+        let (_, changes) = data.take_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|(_, list)| list.is_none()));
     }
 }
@@ -4,17 +4,29 @@
 //! - **Mailing List Actor**: Caches mailing lists with alphabetical sorting
 //! - **Feed Actor**: Caches patch metadata per mailing list
 //! - **Patch Actor**: Caches individual patch content
+//! - **Review Actor**: Stores the review status of patches
+//! - **Seen Actor**: Remembers which patches were already opened
+//!
+//! The feed, mailing list and patch caches persist to the append-only
+//! [`store::Store`], which takes incremental writes and survives crashes.
 
 pub mod feed;
 pub mod mailing_list;
 pub mod patch;
+pub mod review;
+pub mod seen;
+pub mod store;
 
 // Re-export the main cache actors
-pub use feed::FeedCache;
+pub use feed::{FeedCache, PatchSeries};
 pub use mailing_list::MailingListCache;
-pub use patch::PatchCache;
+pub use patch::{PatchCache, PatchEntry, PatchFormat};
+pub use review::{ReviewCache, ReviewFilter, ReviewState, ReviewStatus};
+pub use seen::SeenCache;
 
 // Re-export mock data types for testing
 pub use feed::MockData as FeedMockData;
 pub use mailing_list::MockData as MailingListMockData;
 pub use patch::MockData as PatchMockData;
+pub use review::MockData as ReviewMockData;
+pub use seen::MockData as SeenMockData;
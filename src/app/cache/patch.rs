@@ -2,8 +2,11 @@ use anyhow::Context;
 
 mod core;
 mod data;
-mod mock;
+pub mod interdiff;
 pub mod message;
+mod mock;
+#[cfg(test)]
+mod tests;
 
 use crate::ArcStr;
 use crate::api::lore::LoreApi;
@@ -12,11 +15,18 @@ use crate::fs::Fs;
 use crate::log::Log;
 use message::Message;
 
+pub use data::{PatchEntry, PatchFormat};
+
 /// The Patch Actor provides caching for individual patch content.
 ///
 /// This actor caches raw patch content with permanent validity. Once a patch
-/// is cached, it's considered valid forever. It provides a small in-memory
-/// buffer for fast access to recently used patches.
+/// is cached, it's considered valid until evicted. It provides a small in-memory
+/// buffer for fast access to recently used patches, and keeps a manifest of the
+/// patches stored on disk (size, fetch time and format) loaded at startup.
+///
+/// Both the buffer and the disk store are bounded by the number, total size and
+/// age of their patches as configured (see `USizeOpt::PatchBufferEntries` and
+/// `USizeOpt::PatchCacheEntries`).
 #[derive(Debug, Clone)]
 pub enum PatchCache {
     Actual(tokio::sync::mpsc::Sender<Message>),
@@ -59,9 +69,7 @@ impl PatchCache {
                     .context("Awaiting response from PatchCache actor")
                     .expect("PatchCache actor died")
             }
-            Self::Mock(mock) => {
-                mock.get(list, message_id).await
-            }
+            Self::Mock(mock) => mock.get(list, message_id).await,
         }
     }
 
@@ -83,13 +91,12 @@ impl PatchCache {
                     .context("Awaiting response from PatchCache actor")
                     .expect("PatchCache actor died")
             }
-            Self::Mock(mock) => {
-                mock.invalidate(list, message_id).await
-            }
+            Self::Mock(mock) => mock.invalidate(list, message_id).await,
         }
     }
 
-    /// Checks if a patch is available in cache.
+    /// Checks if a patch is available in cache, either buffered or on disk, so that
+    /// getting it does not hit the network.
     pub async fn is_available(&self, list: ArcStr, message_id: ArcStr) -> bool {
         match self {
             Self::Actual(sender) => {
@@ -107,9 +114,72 @@ impl PatchCache {
                     .context("Awaiting response from PatchCache actor")
                     .expect("PatchCache actor died")
             }
-            Self::Mock(mock) => {
-                mock.is_available(list, message_id).await
+            Self::Mock(mock) => mock.is_available(list, message_id).await,
+        }
+    }
+
+    /// Lists the patches stored on disk, most recently fetched first.
+    ///
+    /// # Arguments
+    /// * `list` - Only list the patches of this mailing list, or all of them if `None`
+    pub async fn entries(&self, list: Option<ArcStr>) -> Vec<PatchEntry> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Entries { list, tx })
+                    .await
+                    .context("Sending message to PatchCache actor")
+                    .expect("PatchCache actor died");
+                rx.await
+                    .context("Awaiting response from PatchCache actor")
+                    .expect("PatchCache actor died")
+            }
+            Self::Mock(mock) => mock.entries(list).await,
+        }
+    }
+
+    /// Gets the total size in bytes of the patches stored on disk.
+    pub async fn total_size(&self) -> u64 {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::TotalSize { tx })
+                    .await
+                    .context("Sending message to PatchCache actor")
+                    .expect("PatchCache actor died");
+                rx.await
+                    .context("Awaiting response from PatchCache actor")
+                    .expect("PatchCache actor died")
+            }
+            Self::Mock(mock) => mock.total_size().await,
+        }
+    }
+
+    /// Evicts the patches stored on disk that go over the configured limits.
+    ///
+    /// Patches fetched more than `USizeOpt::PatchCacheMaxAge` days ago go first, then
+    /// the least recently fetched ones until no more than `USizeOpt::PatchCacheEntries`
+    /// patches and `USizeOpt::PatchCacheBytes` bytes are left. This also runs at
+    /// startup and after every patch fetched from the archive.
+    ///
+    /// # Returns
+    /// The number of evicted patches.
+    pub async fn collect_garbage(&self) -> anyhow::Result<usize> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::CollectGarbage { tx })
+                    .await
+                    .context("Sending message to PatchCache actor")
+                    .expect("PatchCache actor died");
+                rx.await
+                    .context("Awaiting response from PatchCache actor")
+                    .expect("PatchCache actor died")
             }
+            Self::Mock(mock) => mock.collect_garbage().await,
         }
     }
 }
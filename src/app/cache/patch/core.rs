@@ -1,12 +1,15 @@
-use super::data::PatchData;
+use super::data::{PatchData, PatchEntry, PatchFormat, PatchLimits};
 use super::message::Message;
 use crate::ArcPath;
 use crate::ArcStr;
 use crate::api::lore::LoreApi;
-use crate::app::config::Config;
+use crate::app::cache::store::Store;
+use crate::app::config::{Config, PathOpt, USizeOpt};
 use crate::fs::Fs;
 use crate::log::Log;
 use anyhow::Context;
+use chrono::{Duration, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
@@ -25,14 +28,18 @@ pub struct Core {
     log: Log,
     /// Internal state
     data: PatchData,
+    /// The opened store of every mailing list
+    stores: HashMap<String, Store<String>>,
+    /// The store the manifest is persisted to, once opened
+    manifest: Option<Store<PatchEntry>>,
 }
 
 impl Core {
     /// Creates a new Core instance.
     pub async fn new(lore: LoreApi, fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
-        let cache_dir = config.path(crate::app::config::PathOpt::CachePath).await;
+        let cache_dir = config.path(PathOpt::CachePath).await;
         let patch_cache_dir = ArcPath::from(&cache_dir.join("patch"));
-        let data = PatchData::new(patch_cache_dir);
+        let data = PatchData::new(patch_cache_dir, Self::buffer_limits(&config).await);
 
         Ok(Self {
             lore,
@@ -40,6 +47,8 @@ impl Core {
             config,
             log,
             data,
+            stores: HashMap::new(),
+            manifest: None,
         })
     }
 
@@ -49,6 +58,15 @@ impl Core {
         let handle = tokio::spawn(async move {
             let mut core = self;
 
+            // Load the manifest on startup
+            if let Err(e) = core.load_manifest().await {
+                core.log
+                    .error(SCOPE, format!("Failed to load patch manifest: {:#}", e));
+            } else if let Err(e) = core.collect_garbage().await {
+                core.log
+                    .error(SCOPE, format!("Failed to evict stored patches: {:#}", e));
+            }
+
             while let Some(message) = rx.recv().await {
                 match message {
                     Message::Get {
@@ -75,6 +93,18 @@ impl Core {
                         let result = core.handle_is_available(&list, &message_id);
                         let _ = tx.send(result);
                     }
+                    Message::Entries { list, tx } => {
+                        let result = core.data.entries(list.as_deref());
+                        let _ = tx.send(result);
+                    }
+                    Message::TotalSize { tx } => {
+                        let result = core.data.total_size();
+                        let _ = tx.send(result);
+                    }
+                    Message::CollectGarbage { tx } => {
+                        let result = core.collect_garbage().await;
+                        let _ = tx.send(result);
+                    }
                 }
             }
         });
@@ -85,15 +115,22 @@ impl Core {
     /// Handles getting a patch by mailing list and message ID.
     async fn handle_get(&mut self, list: &str, message_id: &str) -> anyhow::Result<String> {
         // First check the buffer
-        if let Some(content) = self.data.get_from_buffer(list, message_id) {
+        if let Some(content) = self.data.get_from_buffer(list, message_id, Utc::now()) {
             return Ok(content);
         }
 
-        // Check if the patch exists on disk
-        if self.patch_exists_on_disk(list, message_id).await? {
-            // Load from disk and add to buffer
-            let content = self.load_patch_from_disk(list, message_id).await?;
-            self.data.add_to_buffer(list, message_id, content.clone());
+        // Check if the patch is in the store
+        if let Some(content) = self.store(list).await?.get(message_id).await? {
+            // A crash may have come between storing the patch and listing it
+            if !self
+                .data
+                .manifest
+                .contains_key(&self.data.get_key(list, message_id))
+            {
+                self.record(list, message_id, &content).await;
+            }
+            self.data
+                .add_to_buffer(list, message_id, content.clone(), Utc::now());
             return Ok(content);
         }
 
@@ -108,19 +145,32 @@ impl Core {
             .get_raw_patch(ArcStr::from(list), ArcStr::from(message_id))
             .await?;
 
-        // Save to disk and add to buffer
+        // Save to the store and add to buffer
         let content_str = content.to_string();
-        if let Err(e) = self
-            .save_patch_to_disk(list, message_id, &content_str)
-            .await
-        {
-            self.log.error(
+        let saved = self
+            .store(list)
+            .await?
+            .write(vec![(message_id.to_string(), Some(content_str.clone()))])
+            .await;
+        match saved {
+            Ok(()) => {
+                self.log.info(
+                    SCOPE,
+                    &format!("Saved patch {} to disk for list: {}", message_id, list),
+                );
+                self.record(list, message_id, &content_str).await;
+                if let Err(e) = self.collect_garbage().await {
+                    self.log
+                        .error(SCOPE, format!("Failed to evict stored patches: {e:#}"));
+                }
+            }
+            Err(e) => self.log.error(
                 SCOPE,
                 &format!("Failed to save patch {list}/{message_id} to disk: {e}"),
-            );
+            ),
         }
         self.data
-            .add_to_buffer(list, message_id, content_str.clone());
+            .add_to_buffer(list, message_id, content_str.clone(), Utc::now());
 
         Ok(content_str)
     }
@@ -128,97 +178,316 @@ impl Core {
     /// Handles invalidating a specific patch.
     async fn handle_invalidate(&mut self, list: &str, message_id: &str) -> anyhow::Result<()> {
         // Remove from buffer
-        let key = self.data.get_buffer_key(list, message_id);
-        self.data.buffer.pop(&key);
-
-        // Remove from disk
-        let cache_path = self.data.get_cache_path(list, message_id);
-        if let Err(e) = self.fs.remove_file(cache_path).await {
-            // Ignore errors if file doesn't exist
-            if !e.to_string().contains("No such file") {
-                return Err(e.into());
-            }
+        self.data.remove_from_buffer(list, message_id);
+
+        // Remove from the manifest first, so it never lists a patch that is not stored
+        let key = self.data.get_key(list, message_id);
+        if self.data.manifest.contains_key(&key) {
+            self.update_manifest(vec![(key, None)]).await?;
+        }
+
+        // Remove from the store
+        let store = self.store(list).await?;
+        if store.contains(message_id) {
+            store.write(vec![(message_id.to_string(), None)]).await?;
+            store.compact().await?;
         }
 
         Ok(())
     }
 
-    /// Handles checking if a patch is available.
+    /// Handles checking if a patch is available without fetching it.
     fn handle_is_available(&self, list: &str, message_id: &str) -> bool {
-        // Check buffer first
-        if self.data.is_in_buffer(list, message_id) {
-            return true;
+        let key = self.data.get_key(list, message_id);
+        self.data.is_in_buffer(list, message_id, Utc::now())
+            || self.data.manifest.contains_key(&key)
+    }
+
+    /// Evicts the stored patches that go over the limits of the disk store, as
+    /// currently configured.
+    ///
+    /// Like an invalidated patch, an evicted one leaves the manifest before its store.
+    ///
+    /// # Returns
+    /// The number of evicted patches.
+    async fn collect_garbage(&mut self) -> anyhow::Result<usize> {
+        let limits = Self::store_limits(&self.config).await;
+        let victims = self.data.victims(&limits, Utc::now());
+        if victims.is_empty() {
+            return Ok(0);
         }
 
-        // Check disk (this is a synchronous check, so we'll assume it exists)
-        // In a real implementation, you might want to make this async
-        true
+        let mut batch = Vec::with_capacity(victims.len());
+        let mut lists: BTreeMap<&str, Vec<(String, Option<String>)>> = BTreeMap::new();
+        for victim in &victims {
+            batch.push((self.data.get_key(&victim.list, &victim.message_id), None));
+            lists
+                .entry(&victim.list)
+                .or_default()
+                .push((victim.message_id.to_string(), None));
+        }
+        self.update_manifest(batch).await?;
+        for (list, removed) in lists {
+            let store = self.store(list).await?;
+            store.write(removed).await?;
+            store.compact().await?;
+        }
+
+        let bytes: u64 = victims.iter().map(|victim| victim.size).sum();
+        self.log.info(
+            SCOPE,
+            format!("Evicted {} stored patches ({bytes} bytes)", victims.len()),
+        );
+        Ok(victims.len())
     }
 
-    /// Checks if a patch exists on disk.
-    async fn patch_exists_on_disk(&self, list: &str, message_id: &str) -> anyhow::Result<bool> {
-        let cache_path = self.data.get_cache_path(list, message_id);
+    /// Reads the limits of the in-memory buffer from the configuration.
+    async fn buffer_limits(config: &Config) -> PatchLimits {
+        let max_age = config.usize(USizeOpt::PatchBufferMaxAge).await;
+        PatchLimits {
+            entries: config.usize(USizeOpt::PatchBufferEntries).await,
+            bytes: config.usize(USizeOpt::PatchBufferBytes).await as u64,
+            max_age: (max_age > 0).then(|| Duration::minutes(max_age as i64)),
+        }
+    }
+
+    /// Reads the limits of the disk store from the configuration.
+    async fn store_limits(config: &Config) -> PatchLimits {
+        let max_age = config.usize(USizeOpt::PatchCacheMaxAge).await;
+        PatchLimits {
+            entries: config.usize(USizeOpt::PatchCacheEntries).await,
+            bytes: config.usize(USizeOpt::PatchCacheBytes).await as u64,
+            max_age: (max_age > 0).then(|| Duration::days(max_age as i64)),
+        }
+    }
 
-        // Try to read the file to check if it exists
-        match self.fs.read_file(cache_path).await {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
+    /// Lists a patch just stored in the manifest, logging failures since the patch
+    /// itself is stored anyway.
+    async fn record(&mut self, list: &str, message_id: &str, content: &str) {
+        let entry = PatchEntry {
+            list: ArcStr::from(list),
+            message_id: ArcStr::from(message_id),
+            size: content.len() as u64,
+            fetched: Utc::now(),
+            format: PatchFormat::Mbox,
+        };
+        let key = self.data.get_key(list, message_id);
+        if let Err(e) = self.update_manifest(vec![(key, Some(entry))]).await {
+            self.log.error(
+                SCOPE,
+                format!("Failed to list patch {list}/{message_id} in the manifest: {e:#}"),
+            );
         }
     }
 
-    /// Loads a patch from disk.
-    async fn load_patch_from_disk(&self, list: &str, message_id: &str) -> anyhow::Result<String> {
-        let cache_path = self.data.get_cache_path(list, message_id);
+    /// Puts entries in the manifest, or removes them with `None`, and persists it.
+    async fn update_manifest(
+        &mut self,
+        batch: Vec<(String, Option<PatchEntry>)>,
+    ) -> anyhow::Result<()> {
+        for (key, entry) in &batch {
+            match entry {
+                Some(entry) => self.data.manifest.insert(key.clone(), entry.clone()),
+                None => self.data.manifest.remove(key),
+            };
+        }
+        let mut manifest = self.take_manifest().await?;
+        let written = async {
+            manifest.write(batch).await?;
+            manifest.compact().await
+        }
+        .await;
+        self.manifest = Some(manifest);
+        written.context("Failed to write patch manifest")?;
+        Ok(())
+    }
 
-        let file = self
-            .fs
-            .read_file(cache_path)
+    /// Takes the manifest store out of the actor, opening it if needed.
+    async fn take_manifest(&mut self) -> anyhow::Result<Store<PatchEntry>> {
+        if let Some(manifest) = self.manifest.take() {
+            return Ok(manifest);
+        }
+        let manifest = Store::open(self.fs.clone(), self.data.get_manifest_path())
             .await
-            .context("Failed to open patch file for reading")?;
+            .context("Failed to open the patch manifest")?;
+        if manifest.truncated() > 0 {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Dropped {} bytes of an interrupted write from the patch manifest",
+                    manifest.truncated()
+                ),
+            );
+        }
+        Ok(manifest)
+    }
 
-        // Read the content
-        use tokio::io::AsyncReadExt;
-        let mut content = String::new();
-        let mut file = file;
-        file.read_to_string(&mut content)
+    /// Loads the manifest of the stored patches, building it from the stores if there
+    /// is none yet.
+    async fn load_manifest(&mut self) -> anyhow::Result<()> {
+        let exists = self
+            .fs
+            .exists(self.data.get_manifest_path())
             .await
-            .context("Failed to read patch file content")?;
+            .context("Failed to check for the patch manifest")?;
+        if !exists {
+            return self.build_manifest().await;
+        }
 
-        Ok(content)
+        let manifest = self.take_manifest().await?;
+        let records = manifest.load_all().await;
+        self.manifest = Some(manifest);
+        self.data
+            .manifest
+            .extend(records.context("Failed to read patch manifest")?);
+
+        self.log.info(
+            SCOPE,
+            format!(
+                "Loaded manifest of {} patches ({} bytes)",
+                self.data.manifest.len(),
+                self.data.total_size()
+            ),
+        );
+        Ok(())
     }
 
-    /// Saves a patch to disk.
-    async fn save_patch_to_disk(
-        &self,
-        list: &str,
-        message_id: &str,
-        content: &str,
-    ) -> anyhow::Result<()> {
-        let cache_path = self.data.get_cache_path(list, message_id);
+    /// Builds the manifest from the patches stored before there was one, migrating the
+    /// `.mbox` files of earlier versions along the way.
+    ///
+    /// When they were fetched is not known, so they count as fetched now.
+    async fn build_manifest(&mut self) -> anyhow::Result<()> {
+        let Ok(paths) = self.fs.read_dir(self.data.cache_dir.clone()).await else {
+            return Ok(()); // Nothing cached yet
+        };
 
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = cache_path.parent() {
-            self.fs
-                .mkdir(ArcPath::from(parent))
+        // Stores of mailing lists, and directories of `.mbox` files
+        let mut lists = BTreeSet::new();
+        for path in paths {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if name.starts_with('.') {
+                continue;
+            }
+            if let Some(list) = name.strip_suffix(".log") {
+                lists.insert(list.to_string());
+            } else if self
+                .fs
+                .metadata(path.clone())
                 .await
-                .context("Failed to create patch cache directory")?;
+                .is_ok_and(|m| m.is_dir())
+            {
+                lists.insert(name.to_string());
+            }
         }
 
-        // Write the file
-        let mut file = self
-            .fs
-            .write_file(cache_path)
-            .await
-            .context("Failed to open patch file for writing")?;
+        let now = Utc::now();
+        let mut batch = Vec::new();
+        for list in lists {
+            for (message_id, content) in self.store(&list).await?.load_all().await? {
+                let entry = PatchEntry {
+                    list: ArcStr::from(&list),
+                    message_id: ArcStr::from(&message_id),
+                    size: content.len() as u64,
+                    fetched: now,
+                    format: PatchFormat::Mbox,
+                };
+                batch.push((self.data.get_key(&list, &message_id), Some(entry)));
+            }
+        }
+        if batch.is_empty() {
+            return Ok(());
+        }
 
-        use tokio::io::AsyncWriteExt;
-        file.write_all(content.as_bytes())
-            .await
-            .context("Failed to write patch file")?;
+        let mut manifest = self.take_manifest().await?;
+        let written = manifest.write(batch.clone()).await;
+        self.manifest = Some(manifest);
+        written.context("Failed to write patch manifest")?;
+        self.data.manifest.extend(
+            batch
+                .into_iter()
+                .filter_map(|(key, entry)| Some((key, entry?))),
+        );
+
+        self.log.info(
+            SCOPE,
+            format!(
+                "Built manifest of {} stored patches ({} bytes)",
+                self.data.manifest.len(),
+                self.data.total_size()
+            ),
+        );
+        Ok(())
+    }
+
+    /// Gets the store of a mailing list, opening it if needed.
+    ///
+    /// Patches cached as `.mbox` files by earlier versions are migrated to the store
+    /// the first time it is opened.
+    async fn store(&mut self, list: &str) -> anyhow::Result<&mut Store<String>> {
+        let store = match self.stores.remove(list) {
+            Some(store) => store,
+            None => {
+                let mut store = Store::open(self.fs.clone(), self.data.get_store_path(list))
+                    .await
+                    .with_context(|| format!("Failed to open the patch store of list {list}"))?;
+                if store.truncated() > 0 {
+                    self.log.warn(
+                        SCOPE,
+                        format!(
+                            "Dropped {} bytes of an interrupted write from the patch store of list {list}",
+                            store.truncated()
+                        ),
+                    );
+                }
+                if store.is_empty() {
+                    self.migrate_patches(list, &mut store).await?;
+                }
+                store
+            }
+        };
+        Ok(self.stores.entry(list.to_string()).or_insert(store))
+    }
+
+    /// Moves the `.mbox` files of a mailing list into its store and removes them.
+    async fn migrate_patches(&self, list: &str, store: &mut Store<String>) -> anyhow::Result<()> {
+        let legacy_dir = self.data.get_legacy_dir(list);
+        let Ok(entries) = self.fs.read_dir(legacy_dir.clone()).await else {
+            return Ok(()); // Nothing cached by earlier versions
+        };
+
+        let mut batch = Vec::new();
+        for path in entries {
+            if path.extension().is_none_or(|extension| extension != "mbox") {
+                continue;
+            }
+            let Some(message_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let mut file = self
+                .fs
+                .read_file(path.clone())
+                .await
+                .context("Failed to open patch file for reading")?;
 
+            use tokio::io::AsyncReadExt;
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .await
+                .context("Failed to read patch file content")?;
+            batch.push((message_id.to_string(), Some(content)));
+        }
+
+        let migrated = batch.len();
+        store.write(batch).await?;
+        self.fs
+            .rmdir(legacy_dir)
+            .await
+            .context("Failed to remove migrated patch directory")?;
         self.log.info(
             SCOPE,
-            &format!("Saved patch {} to disk for list: {}", message_id, list),
+            format!("Migrated {migrated} patches of list {list} to its store"),
         );
         Ok(())
     }
@@ -1,7 +1,65 @@
 use crate::ArcPath;
 use crate::ArcStr;
+use chrono::{DateTime, Duration, Utc};
 use lru::LruCache;
-use std::num::NonZeroUsize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The format a cached patch is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PatchFormat {
+    /// The raw message as served by the archive, in mbox format
+    Mbox,
+}
+
+/// What the manifest of the patch cache records about a stored patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchEntry {
+    /// The mailing list the patch was fetched from
+    pub list: ArcStr,
+    /// The message ID of the patch
+    pub message_id: ArcStr,
+    /// Size of the stored content in bytes
+    pub size: u64,
+    /// When the patch was fetched from the archive
+    pub fetched: DateTime<Utc>,
+    /// The format the patch is stored in
+    pub format: PatchFormat,
+}
+
+/// Limits on the patches kept in the buffer or stored on disk.
+///
+/// A limit of 0 (or no maximum age) leaves that dimension unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PatchLimits {
+    /// Maximum number of patches
+    pub entries: usize,
+    /// Maximum total size of the patches in bytes
+    pub bytes: u64,
+    /// How long a patch is kept after it was added
+    pub max_age: Option<Duration>,
+}
+
+impl PatchLimits {
+    /// Checks if `entries` patches totalling `bytes` bytes go over the limits.
+    pub fn exceeded(&self, entries: usize, bytes: u64) -> bool {
+        (self.entries > 0 && entries > self.entries) || (self.bytes > 0 && bytes > self.bytes)
+    }
+
+    /// Checks if a patch added at `added` is too old to be kept at `now`.
+    pub fn expired(&self, added: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.max_age.is_some_and(|max_age| added + max_age < now)
+    }
+}
+
+/// A patch kept in the in-memory buffer.
+#[derive(Debug, Clone)]
+pub struct Buffered {
+    /// The raw patch content
+    pub content: String,
+    /// When the patch was buffered
+    pub added: DateTime<Utc>,
+}
 
 /// Represents cached patch content with available formats.
 ///
@@ -62,50 +120,286 @@ impl LorePatch {
 /// Internal state for the Patch Actor.
 pub struct PatchData {
     /// Small in-memory buffer for fast access to recently used patches
-    pub buffer: LruCache<String, String>,
+    pub buffer: LruCache<String, Buffered>,
+    /// Total size in bytes of the buffered patches
+    pub buffer_bytes: u64,
+    /// Limits on the buffered patches
+    pub buffer_limits: PatchLimits,
+    /// The patches stored on disk, by the same key as the buffer
+    pub manifest: HashMap<String, PatchEntry>,
     /// Directory for cache files
     pub cache_dir: ArcPath,
 }
 
 impl PatchData {
     /// Creates a new PatchData instance.
-    pub fn new(cache_dir: ArcPath) -> Self {
-        // Use a small buffer size for memory efficiency
-        let buffer = LruCache::new(NonZeroUsize::new(50).unwrap());
+    ///
+    /// # Arguments
+    /// * `cache_dir` - Directory for cache files
+    /// * `buffer_limits` - Limits on the buffered patches, enforced as they are added
+    pub fn new(cache_dir: ArcPath, buffer_limits: PatchLimits) -> Self {
+        Self {
+            buffer: LruCache::unbounded(),
+            buffer_bytes: 0,
+            buffer_limits,
+            manifest: HashMap::new(),
+            cache_dir,
+        }
+    }
+
+    /// Gets the store path for a specific mailing list.
+    pub fn get_store_path(&self, list: &str) -> ArcPath {
+        ArcPath::from(&self.cache_dir.join(format!("{}.log", list)))
+    }
 
-        Self { buffer, cache_dir }
+    /// Gets the directory earlier versions cached the patches of a mailing list in, as
+    /// one `<message_id>.mbox` file each.
+    pub fn get_legacy_dir(&self, list: &str) -> ArcPath {
+        ArcPath::from(&self.cache_dir.join(list))
     }
 
-    /// Gets the cache file path for a specific patch.
-    pub fn get_cache_path(&self, list: &str, message_id: &str) -> ArcPath {
-        ArcPath::from(
-            &self
-                .cache_dir
-                .join(list)
-                .join(format!("{}.mbox", message_id)),
-        )
+    /// Gets the path of the manifest of the stored patches.
+    ///
+    /// Its name starts with a dot, which no mailing list name does.
+    pub fn get_manifest_path(&self) -> ArcPath {
+        ArcPath::from(&self.cache_dir.join(".manifest.log"))
     }
 
-    /// Gets the buffer key for a patch.
-    pub fn get_buffer_key(&self, list: &str, message_id: &str) -> String {
+    /// Gets the key a patch is buffered and listed in the manifest under.
+    pub fn get_key(&self, list: &str, message_id: &str) -> String {
         format!("{}:{}", list, message_id)
     }
 
-    /// Adds a patch to the buffer.
-    pub fn add_to_buffer(&mut self, list: &str, message_id: &str, content: String) {
-        let key = self.get_buffer_key(list, message_id);
-        self.buffer.put(key, content);
+    /// Adds a patch to the buffer, evicting the least recently used patches while the
+    /// buffer is over its limits.
+    ///
+    /// A patch larger than the whole buffer is not buffered.
+    pub fn add_to_buffer(
+        &mut self,
+        list: &str,
+        message_id: &str,
+        content: String,
+        now: DateTime<Utc>,
+    ) {
+        if self.buffer_limits.exceeded(1, content.len() as u64) {
+            return;
+        }
+        let key = self.get_key(list, message_id);
+        self.buffer_bytes += content.len() as u64;
+        let buffered = Buffered {
+            content,
+            added: now,
+        };
+        if let Some(old) = self.buffer.put(key, buffered) {
+            self.buffer_bytes -= old.content.len() as u64;
+        }
+        while self
+            .buffer_limits
+            .exceeded(self.buffer.len(), self.buffer_bytes)
+        {
+            let Some((_, evicted)) = self.buffer.pop_lru() else {
+                break;
+            };
+            self.buffer_bytes -= evicted.content.len() as u64;
+        }
     }
 
-    /// Gets a patch from the buffer.
-    pub fn get_from_buffer(&mut self, list: &str, message_id: &str) -> Option<String> {
-        let key = self.get_buffer_key(list, message_id);
-        self.buffer.get(&key).cloned()
+    /// Gets a patch from the buffer, dropping it if it was buffered for too long.
+    pub fn get_from_buffer(
+        &mut self,
+        list: &str,
+        message_id: &str,
+        now: DateTime<Utc>,
+    ) -> Option<String> {
+        let key = self.get_key(list, message_id);
+        let added = self.buffer.peek(&key)?.added;
+        if self.buffer_limits.expired(added, now) {
+            self.remove_from_buffer(list, message_id);
+            return None;
+        }
+        self.buffer
+            .get(&key)
+            .map(|buffered| buffered.content.clone())
     }
 
-    /// Checks if a patch is in the buffer.
-    pub fn is_in_buffer(&self, list: &str, message_id: &str) -> bool {
-        let key = self.get_buffer_key(list, message_id);
-        self.buffer.contains(&key)
+    /// Removes a patch from the buffer.
+    pub fn remove_from_buffer(&mut self, list: &str, message_id: &str) {
+        let key = self.get_key(list, message_id);
+        if let Some(removed) = self.buffer.pop(&key) {
+            self.buffer_bytes -= removed.content.len() as u64;
+        }
+    }
+
+    /// Checks if a patch is in the buffer and was not buffered for too long.
+    pub fn is_in_buffer(&self, list: &str, message_id: &str, now: DateTime<Utc>) -> bool {
+        let key = self.get_key(list, message_id);
+        self.buffer
+            .peek(&key)
+            .is_some_and(|buffered| !self.buffer_limits.expired(buffered.added, now))
+    }
+
+    /// Lists the stored patches, most recently fetched first.
+    ///
+    /// # Arguments
+    /// * `list` - Only list the patches of this mailing list, or all of them if `None`
+    pub fn entries(&self, list: Option<&str>) -> Vec<PatchEntry> {
+        let mut entries: Vec<PatchEntry> = self
+            .manifest
+            .values()
+            .filter(|entry| list.is_none_or(|list| entry.list.as_ref() as &str == list))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| {
+            b.fetched
+                .cmp(&a.fetched)
+                .then_with(|| a.message_id.cmp(&b.message_id))
+        });
+        entries
+    }
+
+    /// Gets the total size in bytes of the stored patches.
+    pub fn total_size(&self) -> u64 {
+        self.manifest.values().map(|entry| entry.size).sum()
+    }
+
+    /// Picks the stored patches to evict for the rest to be within the limits.
+    ///
+    /// Patches fetched too long ago go first, then the least recently fetched ones
+    /// until few and small enough are left.
+    ///
+    /// # Returns
+    /// The patches to evict, least recently fetched first.
+    pub fn victims(&self, limits: &PatchLimits, now: DateTime<Utc>) -> Vec<PatchEntry> {
+        let mut entries = self.entries(None);
+        let mut bytes = self.total_size();
+        let mut victims = Vec::new();
+        while let Some(oldest) = entries.pop() {
+            if !limits.expired(oldest.fetched, now) && !limits.exceeded(entries.len() + 1, bytes) {
+                break;
+            }
+            bytes -= oldest.size;
+            victims.push(oldest);
+        }
+        victims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(list: &str, message_id: &str, size: u64, minutes: i64) -> PatchEntry {
+        PatchEntry {
+            list: ArcStr::from(list),
+            message_id: ArcStr::from(message_id),
+            size,
+            fetched: DateTime::from_timestamp(1_700_000_000 + minutes * 60, 0).unwrap(),
+            format: PatchFormat::Mbox,
+        }
+    }
+
+    #[test]
+    fn test_entries_and_total_size() {
+        let mut data = PatchData::new(ArcPath::from("cache/patch"), PatchLimits::default());
+        for entry in [
+            entry("linux-mm", "a", 100, 1),
+            entry("amd-gfx", "b", 20, 3),
+            entry("linux-mm", "c", 3, 2),
+        ] {
+            let key = data.get_key(&entry.list, &entry.message_id);
+            data.manifest.insert(key, entry);
+        }
+
+        let ids: Vec<_> = data
+            .entries(None)
+            .into_iter()
+            .map(|entry| entry.message_id)
+            .collect();
+        assert_eq!(
+            ids,
+            vec![ArcStr::from("b"), ArcStr::from("c"), ArcStr::from("a")]
+        );
+        assert_eq!(data.entries(Some("linux-mm")).len(), 2);
+        assert!(data.entries(Some("netdev")).is_empty());
+        assert_eq!(data.total_size(), 123);
+    }
+
+    #[test]
+    fn test_buffer_limits() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let limits = PatchLimits {
+            entries: 2,
+            bytes: 10,
+            max_age: Some(Duration::minutes(5)),
+        };
+        let mut data = PatchData::new(ArcPath::from("cache/patch"), limits);
+
+        data.add_to_buffer("linux-mm", "a", "aaaa".to_string(), now);
+        data.add_to_buffer("linux-mm", "b", "bbbb".to_string(), now);
+        // Using `a` makes `b` the least recently used
+        assert!(data.get_from_buffer("linux-mm", "a", now).is_some());
+        data.add_to_buffer("linux-mm", "c", "cc".to_string(), now);
+        assert!(!data.is_in_buffer("linux-mm", "b", now));
+        assert_eq!(data.buffer_bytes, 6);
+
+        // Over the size limit
+        data.add_to_buffer("linux-mm", "d", "dddddddd".to_string(), now);
+        assert!(!data.is_in_buffer("linux-mm", "a", now));
+        assert_eq!(data.buffer_bytes, 10);
+        data.add_to_buffer("linux-mm", "e", "e".repeat(11), now);
+        assert!(!data.is_in_buffer("linux-mm", "e", now));
+        assert_eq!(data.buffer.len(), 2);
+        assert_eq!(data.buffer_bytes, 10);
+
+        // Over the age limit
+        let later = now + Duration::minutes(6);
+        assert!(!data.is_in_buffer("linux-mm", "d", later));
+        assert!(data.get_from_buffer("linux-mm", "d", later).is_none());
+        assert_eq!(data.buffer.len(), 1);
+        assert_eq!(data.buffer_bytes, 2);
+    }
+
+    #[test]
+    fn test_victims() {
+        let mut data = PatchData::new(ArcPath::from("cache/patch"), PatchLimits::default());
+        for entry in [
+            entry("linux-mm", "a", 100, 1),
+            entry("amd-gfx", "b", 20, 3),
+            entry("linux-mm", "c", 3, 2),
+            entry("linux-mm", "d", 5, 60 * 24 * 3),
+        ] {
+            let key = data.get_key(&entry.list, &entry.message_id);
+            data.manifest.insert(key, entry);
+        }
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap() + Duration::days(3);
+        let ids = |limits: PatchLimits| -> Vec<ArcStr> {
+            data.victims(&limits, now)
+                .into_iter()
+                .map(|entry| entry.message_id)
+                .collect()
+        };
+
+        assert!(ids(PatchLimits::default()).is_empty());
+        assert_eq!(
+            ids(PatchLimits {
+                entries: 2,
+                ..PatchLimits::default()
+            }),
+            vec![ArcStr::from("a"), ArcStr::from("c")]
+        );
+        assert_eq!(
+            ids(PatchLimits {
+                bytes: 30,
+                ..PatchLimits::default()
+            }),
+            vec![ArcStr::from("a")]
+        );
+        assert_eq!(
+            ids(PatchLimits {
+                max_age: Some(Duration::days(1)),
+                ..PatchLimits::default()
+            }),
+            vec![ArcStr::from("a"), ArcStr::from("c"), ArcStr::from("b")]
+        );
     }
 }
@@ -0,0 +1,138 @@
+use similar::TextDiff;
+
+use crate::app::cache::feed::series::normalize_subject;
+
+/// Reduces a raw patch email to the parts that matter when comparing revisions.
+///
+/// Mail headers are dropped except for the subject (without its `[PATCH vN x/y]` tag),
+/// `index` lines are removed since blob hashes change on every rebase, hunk headers keep
+/// only their function context, and the trailing `-- ` signature is cut off.
+pub fn normalize_patch(raw: &str) -> String {
+    let (headers, body) = raw.split_once("\n\n").unwrap_or(("", raw));
+
+    let mut subject = String::new();
+    let mut in_subject = false;
+    for line in headers.lines() {
+        if in_subject && line.starts_with([' ', '\t']) {
+            subject.push(' ');
+            subject.push_str(line.trim());
+            continue;
+        }
+        in_subject = false;
+        if let Some(value) = line
+            .split_once(':')
+            .filter(|(name, _)| name.eq_ignore_ascii_case("subject"))
+            .map(|(_, value)| value)
+        {
+            subject = value.trim().to_string();
+            in_subject = true;
+        }
+    }
+
+    let mut out = format!("Subject: {}\n\n", normalize_subject(&subject));
+    for line in body.lines() {
+        if line == "-- " {
+            break;
+        }
+        if line.starts_with("index ") {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("@@ ") {
+            // `@@ -a,b +c,d @@ context` -> `@@ context`
+            let context = rest.split_once(" @@").map_or("", |(_, ctx)| ctx);
+            out.push_str("@@");
+            out.push_str(context);
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Computes an interdiff between two revisions of a series.
+///
+/// Each revision is given as its raw patches in series order. The patches are normalised
+/// with [`normalize_patch`], concatenated and compared line by line.
+///
+/// # Arguments
+/// * `old` - The raw patches of the older revision
+/// * `new` - The raw patches of the newer revision
+/// * `old_label` - The name of the older revision in the diff header (e.g. `v1`)
+/// * `new_label` - The name of the newer revision in the diff header (e.g. `v2`)
+///
+/// # Returns
+/// A unified diff between the revisions, or an empty string if they are identical.
+pub fn interdiff(old: &[String], new: &[String], old_label: &str, new_label: &str) -> String {
+    let join = |patches: &[String]| {
+        patches
+            .iter()
+            .map(|raw| normalize_patch(raw))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let old = join(old);
+    let new = join(new);
+
+    if old == new {
+        return String::new();
+    }
+
+    TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = "From: Jane Doe <jane@example.com>
+Subject: [PATCH 1/1] net: do
+ the thing
+Message-ID: <v1@example.com>
+
+Commit message.
+---
+diff --git a/file.c b/file.c
+index 1111111..2222222 100644
+--- a/file.c
++++ b/file.c
+@@ -10,3 +10,3 @@ int main(void)
+ a
+-b
++c
+-- \n\
+2.43.0
+";
+
+    #[test]
+    fn test_normalize_patch() {
+        let normalized = normalize_patch(V1);
+        assert!(normalized.starts_with("Subject: net: do the thing\n\n"));
+        assert!(!normalized.contains("index 1111111"));
+        assert!(normalized.contains("\n@@ int main(void)\n"));
+        assert!(!normalized.contains("2.43.0"));
+        assert!(!normalized.contains("Message-ID"));
+    }
+
+    #[test]
+    fn test_interdiff_ignores_rebase_noise() {
+        let v2 = V1
+            .replace("[PATCH 1/1]", "[PATCH v2 1/1]")
+            .replace("index 1111111..2222222", "index 3333333..4444444")
+            .replace("@@ -10,3 +10,3 @@", "@@ -12,3 +12,3 @@");
+        assert_eq!(interdiff(&[V1.to_string()], &[v2], "v1", "v2"), "");
+    }
+
+    #[test]
+    fn test_interdiff_shows_changes() {
+        let v2 = V1.replace("+c", "+d");
+        let diff = interdiff(&[V1.to_string()], &[v2], "v1", "v2");
+        assert!(diff.starts_with("--- v1\n+++ v2\n"));
+        assert!(diff.contains("-+c\n"));
+        assert!(diff.contains("++d\n"));
+    }
+}
@@ -1,3 +1,4 @@
+use super::data::PatchEntry;
 use crate::ArcStr;
 use tokio::sync::oneshot;
 
@@ -22,4 +23,15 @@ pub enum Message {
         message_id: ArcStr,
         tx: oneshot::Sender<bool>,
     },
+    /// List the stored patches, of one mailing list or all of them
+    Entries {
+        list: Option<ArcStr>,
+        tx: oneshot::Sender<Vec<PatchEntry>>,
+    },
+    /// Get the total size of the stored patches
+    TotalSize { tx: oneshot::Sender<u64> },
+    /// Evict the stored patches that go over the configured limits
+    CollectGarbage {
+        tx: oneshot::Sender<anyhow::Result<usize>>,
+    },
 }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use super::data::{PatchEntry, PatchFormat};
 use crate::ArcStr;
 use crate::app::cache::patch::MockData;
 
@@ -73,6 +74,54 @@ impl Mock {
         data.patches.contains_key(&key)
     }
 
+    /// Lists the stored patches.
+    /// Mock implementation lists the stored data, as fetched at the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `list` - Only list the patches of this mailing list, or all of them if `None`
+    ///
+    /// # Returns
+    /// The entries sorted by message ID
+    pub async fn entries(&self, list: Option<ArcStr>) -> Vec<PatchEntry> {
+        let data = self.data.lock().await;
+        let mut entries: Vec<PatchEntry> = data
+            .patches
+            .iter()
+            .filter_map(|(key, content)| {
+                let (patch_list, message_id) = key.split_once(':')?;
+                Some(PatchEntry {
+                    list: ArcStr::from(patch_list),
+                    message_id: ArcStr::from(message_id),
+                    size: content.len() as u64,
+                    fetched: chrono::DateTime::UNIX_EPOCH,
+                    format: PatchFormat::Mbox,
+                })
+            })
+            .filter(|entry| list.as_ref().is_none_or(|list| &entry.list == list))
+            .collect();
+        entries.sort_by(|a, b| a.message_id.cmp(&b.message_id));
+        entries
+    }
+
+    /// Gets the total size of the stored patches.
+    /// Mock implementation sums the size of the stored data.
+    pub async fn total_size(&self) -> u64 {
+        let data = self.data.lock().await;
+        data.patches
+            .values()
+            .map(|content| content.len() as u64)
+            .sum()
+    }
+
+    /// Evicts the patches that go over the limits.
+    /// Mock implementation has no limits, so nothing is evicted.
+    ///
+    /// # Returns
+    /// Ok(0) always
+    pub async fn collect_garbage(&self) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
     /// Gets the mock data for inspection in tests.
     ///
     /// # Returns
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use tokio::io::AsyncWriteExt;
+
+use super::{PatchCache, PatchFormat};
+use crate::api::lore::LoreApi;
+use crate::app::config::{Config, Data, PathOpt, USizeOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::{ArcPath, ArcStr};
+
+async fn config() -> Config {
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::CachePath, ArcPath::from("cache"))
+        .await;
+    config
+}
+
+fn lore(patches: &[(&str, &str)]) -> LoreApi {
+    LoreApi::mock(
+        patches
+            .iter()
+            .map(|(id, content)| (format!("raw_patch_linux-mm_{id}"), ArcStr::from(*content)))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+#[tokio::test]
+async fn test_manifest_survives_restart() {
+    let fs = Fs::mock();
+    let config = config().await;
+    let list = ArcStr::from("linux-mm");
+    let id = ArcStr::from("a");
+
+    let cache = PatchCache::spawn(
+        lore(&[("a", "content")]),
+        fs.clone(),
+        config.clone(),
+        Log::mock(),
+    )
+    .await
+    .unwrap();
+    assert!(!cache.is_available(list.clone(), id.clone()).await);
+    assert_eq!(
+        cache.get(list.clone(), id.clone()).await.unwrap(),
+        "content"
+    );
+    assert!(cache.is_available(list.clone(), id.clone()).await);
+
+    // The manifest is loaded at startup, before the store of the list is opened
+    let cache = PatchCache::spawn(LoreApi::mock_empty(), fs, config, Log::mock())
+        .await
+        .unwrap();
+    assert!(cache.is_available(list.clone(), id.clone()).await);
+    let entries = cache.entries(Some(list.clone())).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].size, 7);
+    assert_eq!(entries[0].format, PatchFormat::Mbox);
+    assert_eq!(cache.total_size().await, 7);
+    assert_eq!(
+        cache.get(list.clone(), id.clone()).await.unwrap(),
+        "content"
+    );
+
+    cache.invalidate(list.clone(), id.clone()).await.unwrap();
+    assert!(!cache.is_available(list, id).await);
+    assert!(cache.entries(None).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_manifest_built_from_legacy_files() {
+    let fs = Fs::mock();
+    fs.mkdir(ArcPath::from("cache/patch/linux-mm"))
+        .await
+        .unwrap();
+    let mut file = fs
+        .write_file(ArcPath::from("cache/patch/linux-mm/a.mbox"))
+        .await
+        .unwrap();
+    file.write_all(b"legacy").await.unwrap();
+    drop(file);
+
+    let cache = PatchCache::spawn(
+        LoreApi::mock_empty(),
+        fs.clone(),
+        config().await,
+        Log::mock(),
+    )
+    .await
+    .unwrap();
+    assert!(
+        cache
+            .is_available(ArcStr::from("linux-mm"), ArcStr::from("a"))
+            .await
+    );
+    assert_eq!(cache.total_size().await, 6);
+    assert_eq!(
+        cache
+            .get(ArcStr::from("linux-mm"), ArcStr::from("a"))
+            .await
+            .unwrap(),
+        "legacy"
+    );
+    assert!(
+        !fs.exists(ArcPath::from("cache/patch/linux-mm"))
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_store_limits() {
+    let fs = Fs::mock();
+    let config = config().await;
+    config.set_usize(USizeOpt::PatchCacheEntries, 2).await;
+    config.set_usize(USizeOpt::PatchBufferEntries, 1).await;
+    let list = ArcStr::from("linux-mm");
+
+    let cache = PatchCache::spawn(
+        lore(&[("a", "first"), ("b", "second"), ("c", "third")]),
+        fs.clone(),
+        config.clone(),
+        Log::mock(),
+    )
+    .await
+    .unwrap();
+    for id in ["a", "b", "c"] {
+        cache.get(list.clone(), ArcStr::from(id)).await.unwrap();
+    }
+    // Only the last patch is buffered, and the first one was evicted from disk
+    assert!(!cache.is_available(list.clone(), ArcStr::from("a")).await);
+    assert_eq!(cache.entries(None).await.len(), 2);
+    assert_eq!(cache.total_size().await, 11);
+
+    // Lowered limits apply to the next pass, which also runs at startup
+    config.set_usize(USizeOpt::PatchCacheBytes, 5).await;
+    assert_eq!(cache.collect_garbage().await.unwrap(), 1);
+    assert_eq!(cache.collect_garbage().await.unwrap(), 0);
+    config.set_usize(USizeOpt::PatchCacheEntries, 0).await;
+    config.set_usize(USizeOpt::PatchCacheBytes, 0).await;
+    let cache = PatchCache::spawn(LoreApi::mock_empty(), fs, config, Log::mock())
+        .await
+        .unwrap();
+    let entries = cache.entries(None).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].message_id, ArcStr::from("c"));
+    assert!(cache.get(list, ArcStr::from("b")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_mock_entries() {
+    let cache = PatchCache::mock(super::MockData {
+        patches: HashMap::from([
+            ("linux-mm:a".to_string(), "content".to_string()),
+            ("amd-gfx:b".to_string(), "more".to_string()),
+        ]),
+    });
+    assert_eq!(cache.entries(None).await.len(), 2);
+    assert_eq!(cache.entries(Some(ArcStr::from("amd-gfx"))).await.len(), 1);
+    assert_eq!(cache.total_size().await, 11);
+}
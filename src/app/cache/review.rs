@@ -0,0 +1,154 @@
+use anyhow::Context;
+use std::collections::HashMap;
+
+mod core;
+mod data;
+mod mock;
+pub mod message;
+
+use crate::ArcStr;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+use message::Message;
+
+pub use data::{ReviewFilter, ReviewState, ReviewStatus};
+
+/// The Review Actor stores the review status of patches.
+///
+/// Statuses are keyed by message ID and persisted in their own file under the cache
+/// directory, independently of the feed cache, so invalidating a feed never loses them.
+#[derive(Debug, Clone)]
+pub enum ReviewCache {
+    Actual(tokio::sync::mpsc::Sender<Message>),
+    Mock(mock::Mock),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockData {
+    pub reviews: HashMap<ArcStr, ReviewStatus>,
+}
+
+impl ReviewCache {
+    /// Spawns a new ReviewCache actor.
+    pub async fn spawn(fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
+        let core = core::Core::new(fs, config, log).await?;
+        let (state, _handle) = core.spawn();
+        Ok(state)
+    }
+
+    /// Creates a new mock ReviewCache actor for testing.
+    pub fn mock(data: MockData) -> Self {
+        Self::Mock(mock::Mock::new(data))
+    }
+
+    /// Gets the review status of a message, if it has one.
+    pub async fn get(&self, message_id: ArcStr) -> Option<ReviewStatus> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Get { message_id, tx })
+                    .await
+                    .context("Sending message to ReviewCache actor")
+                    .expect("ReviewCache actor died");
+                rx.await
+                    .context("Awaiting response from ReviewCache actor")
+                    .expect("ReviewCache actor died")
+            }
+            Self::Mock(mock) => mock.get(message_id).await,
+        }
+    }
+
+    /// Gets the review status of several messages; messages without one are left out.
+    pub async fn get_many(&self, message_ids: Vec<ArcStr>) -> HashMap<ArcStr, ReviewStatus> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetMany { message_ids, tx })
+                    .await
+                    .context("Sending message to ReviewCache actor")
+                    .expect("ReviewCache actor died");
+                rx.await
+                    .context("Awaiting response from ReviewCache actor")
+                    .expect("ReviewCache actor died")
+            }
+            Self::Mock(mock) => mock.get_many(message_ids).await,
+        }
+    }
+
+    /// Sets (or clears, with `None`) the review state of a message and persists the store.
+    ///
+    /// Returns the updated status, or `None` if the message no longer has one.
+    pub async fn set_state(
+        &self,
+        message_id: ArcStr,
+        state: Option<ReviewState>,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::SetState {
+                        message_id,
+                        state,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to ReviewCache actor")
+                    .expect("ReviewCache actor died");
+                rx.await
+                    .context("Awaiting response from ReviewCache actor")
+                    .expect("ReviewCache actor died")
+            }
+            Self::Mock(mock) => mock.set_state(message_id, state).await,
+        }
+    }
+
+    /// Sets (or clears, with an empty string) the note of a message and persists the store.
+    ///
+    /// Returns the updated status, or `None` if the message no longer has one.
+    pub async fn set_note(
+        &self,
+        message_id: ArcStr,
+        note: ArcStr,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::SetNote {
+                        message_id,
+                        note,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to ReviewCache actor")
+                    .expect("ReviewCache actor died");
+                rx.await
+                    .context("Awaiting response from ReviewCache actor")
+                    .expect("ReviewCache actor died")
+            }
+            Self::Mock(mock) => mock.set_note(message_id, note).await,
+        }
+    }
+
+    /// Persists the store to the filesystem.
+    pub async fn persist(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Persist { tx })
+                    .await
+                    .context("Sending message to ReviewCache actor")
+                    .expect("ReviewCache actor died");
+                rx.await
+                    .context("Awaiting response from ReviewCache actor")
+                    .expect("ReviewCache actor died")
+            }
+            Self::Mock(mock) => mock.persist().await,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::data::{ReviewData, ReviewState, ReviewStatus};
+use super::message::Message;
+use crate::ArcPath;
+use crate::ArcStr;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+use anyhow::Context;
+use chrono::Utc;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+const BUFFER_SIZE: usize = 100;
+const SCOPE: &str = "app.cache.review";
+
+/// Core implementation for the Review Actor.
+pub struct Core {
+    /// Filesystem actor for persistence
+    fs: Fs,
+    /// Config actor for configuration
+    config: Config,
+    /// Log actor for logging
+    log: Log,
+    /// Internal state
+    data: ReviewData,
+}
+
+impl Core {
+    /// Creates a new Core instance.
+    pub async fn new(fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
+        let cache_dir = config.path(crate::app::config::PathOpt::CachePath).await;
+        let cache_path = ArcPath::from(&cache_dir.join("reviews.toml"));
+        let data = ReviewData::new(cache_path);
+
+        Ok(Self {
+            fs,
+            config,
+            log,
+            data,
+        })
+    }
+
+    /// Spawns the actor and returns the public interface and join handle.
+    pub fn spawn(self) -> (super::ReviewCache, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::channel(BUFFER_SIZE);
+        let handle = tokio::spawn(async move {
+            let mut core = self;
+
+            // Load the store on startup
+            if let Err(e) = core.load_cache().await {
+                core.log
+                    .error(SCOPE, format!("Failed to load review store: {}", e));
+            }
+
+            while let Some(message) = rx.recv().await {
+                match message {
+                    Message::Get { message_id, tx } => {
+                        let result = core.data.reviews.get(&message_id).cloned();
+                        let _ = tx.send(result);
+                    }
+                    Message::GetMany { message_ids, tx } => {
+                        let result = core.handle_get_many(message_ids);
+                        let _ = tx.send(result);
+                    }
+                    Message::SetState {
+                        message_id,
+                        state,
+                        tx,
+                    } => {
+                        let result = core.handle_set_state(message_id, state).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::SetNote {
+                        message_id,
+                        note,
+                        tx,
+                    } => {
+                        let result = core.handle_set_note(message_id, note).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Persist { tx } => {
+                        let result = core.persist_cache().await;
+                        let _ = tx.send(result);
+                    }
+                }
+            }
+        });
+
+        (super::ReviewCache::Actual(tx), handle)
+    }
+
+    /// Handles getting the review status of several messages, skipping unreviewed ones.
+    fn handle_get_many(&self, message_ids: Vec<ArcStr>) -> HashMap<ArcStr, ReviewStatus> {
+        message_ids
+            .into_iter()
+            .filter_map(|id| {
+                let status = self.data.reviews.get(&id)?.clone();
+                Some((id, status))
+            })
+            .collect()
+    }
+
+    /// Handles setting or clearing the review state of a message.
+    async fn handle_set_state(
+        &mut self,
+        message_id: ArcStr,
+        state: Option<ReviewState>,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        self.log.info(
+            SCOPE,
+            format!(
+                "Setting review state of {} to {}",
+                message_id,
+                state.map_or_else(|| "none".to_string(), |s| s.to_string())
+            ),
+        );
+        let status = self
+            .data
+            .update(message_id, Utc::now(), |status| status.state = state);
+        self.persist_cache().await?;
+        Ok(status)
+    }
+
+    /// Handles setting or clearing the note of a message.
+    async fn handle_set_note(
+        &mut self,
+        message_id: ArcStr,
+        note: ArcStr,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        self.log
+            .info(SCOPE, format!("Setting review note of {}", message_id));
+        let status = self
+            .data
+            .update(message_id, Utc::now(), |status| status.note = note);
+        self.persist_cache().await?;
+        Ok(status)
+    }
+
+    /// Persists the store to the filesystem.
+    async fn persist_cache(&self) -> anyhow::Result<()> {
+        let cache_data = self.data.to_cache_data();
+        let content =
+            toml::to_string_pretty(&cache_data).context("Failed to serialize review store")?;
+
+        // Replace the file atomically, creating the cache directory if needed
+        self.fs
+            .write_atomic(self.data.cache_path.clone(), content.into_bytes())
+            .await
+            .context("Failed to write review store")?;
+
+        Ok(())
+    }
+
+    /// Loads the store from the filesystem.
+    async fn load_cache(&mut self) -> anyhow::Result<()> {
+        // Check if file exists by trying to read it
+        let file = match self.fs.read_file(self.data.cache_path.clone()).await {
+            Ok(file) => file,
+            Err(_) => return Ok(()), // File doesn't exist, that's ok
+        };
+
+        // Read the content
+        use tokio::io::AsyncReadExt;
+        let mut content = String::new();
+        let mut file = file;
+        file.read_to_string(&mut content)
+            .await
+            .context("Failed to read review store content")?;
+
+        let cache_data: super::data::CacheData =
+            toml::from_str(&content).context("Failed to deserialize review store")?;
+
+        self.data.restore(cache_data);
+
+        self.log.info(
+            SCOPE,
+            format!("Loaded {} review statuses", self.data.reviews.len()),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ArcPath, ArcStr};
+
+/// The review verdict a user gave to a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReviewState {
+    /// The patch was reviewed and looks good
+    Reviewed,
+    /// The patch was reviewed and needs another revision
+    NeedsWork,
+    /// The patch is not of interest
+    Ignored,
+}
+
+impl ReviewState {
+    /// Gets the short marker shown next to feed rows.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            ReviewState::Reviewed => "✓",
+            ReviewState::NeedsWork => "✗",
+            ReviewState::Ignored => "-",
+        }
+    }
+
+    /// Gets the state that follows this one when cycling through states with a single key.
+    /// Cycling past the last state clears the review.
+    pub fn next(state: Option<Self>) -> Option<Self> {
+        match state {
+            None => Some(ReviewState::Reviewed),
+            Some(ReviewState::Reviewed) => Some(ReviewState::NeedsWork),
+            Some(ReviewState::NeedsWork) => Some(ReviewState::Ignored),
+            Some(ReviewState::Ignored) => None,
+        }
+    }
+}
+
+impl fmt::Display for ReviewState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewState::Reviewed => write!(f, "reviewed"),
+            ReviewState::NeedsWork => write!(f, "needs-work"),
+            ReviewState::Ignored => write!(f, "ignored"),
+        }
+    }
+}
+
+impl FromStr for ReviewState {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "reviewed" => Ok(ReviewState::Reviewed),
+            "needs-work" => Ok(ReviewState::NeedsWork),
+            "ignored" => Ok(ReviewState::Ignored),
+            _ => Err(anyhow::anyhow!(
+                "Invalid review state '{}', expected reviewed, needs-work or ignored",
+                s
+            )),
+        }
+    }
+}
+
+/// A filter over review states, as accepted by the CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewFilter {
+    /// Patches with the given state
+    State(ReviewState),
+    /// Patches without a state
+    Unreviewed,
+}
+
+impl ReviewFilter {
+    /// Returns true if a patch with the given state passes the filter.
+    pub fn matches(&self, state: Option<ReviewState>) -> bool {
+        match self {
+            ReviewFilter::State(wanted) => state == Some(*wanted),
+            ReviewFilter::Unreviewed => state.is_none(),
+        }
+    }
+}
+
+impl fmt::Display for ReviewFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewFilter::State(state) => write!(f, "{}", state),
+            ReviewFilter::Unreviewed => write!(f, "unreviewed"),
+        }
+    }
+}
+
+impl FromStr for ReviewFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unreviewed" | "none" => Ok(ReviewFilter::Unreviewed),
+            _ => s.parse().map(ReviewFilter::State),
+        }
+    }
+}
+
+/// The review status recorded for a single patch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewStatus {
+    /// The review verdict, if one was given
+    pub state: Option<ReviewState>,
+    /// Free-form notes about the patch
+    #[serde(default)]
+    pub note: ArcStr,
+    /// When the status was first recorded
+    pub created: DateTime<Utc>,
+    /// When the status was last changed
+    pub updated: DateTime<Utc>,
+}
+
+impl ReviewStatus {
+    /// Creates an empty status stamped with the given time.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            state: None,
+            note: ArcStr::default(),
+            created: now,
+            updated: now,
+        }
+    }
+
+    /// Returns true if the status carries neither a verdict nor a note.
+    pub fn is_empty(&self) -> bool {
+        self.state.is_none() && self.note.is_empty()
+    }
+}
+
+/// Data structure for persisting the review store to disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CacheData {
+    /// Review status per message ID
+    pub reviews: HashMap<ArcStr, ReviewStatus>,
+}
+
+/// Internal state for the Review Actor.
+pub struct ReviewData {
+    /// Review status per message ID
+    pub reviews: HashMap<ArcStr, ReviewStatus>,
+    /// Path of the review store file
+    pub cache_path: ArcPath,
+}
+
+impl ReviewData {
+    /// Creates a new ReviewData instance.
+    pub fn new(cache_path: ArcPath) -> Self {
+        Self {
+            reviews: HashMap::new(),
+            cache_path,
+        }
+    }
+
+    /// Converts to CacheData for persistence.
+    pub fn to_cache_data(&self) -> CacheData {
+        CacheData {
+            reviews: self.reviews.clone(),
+        }
+    }
+
+    /// Restores the statuses from CacheData loaded from disk.
+    pub fn restore(&mut self, data: CacheData) {
+        self.reviews = data.reviews;
+    }
+
+    /// Applies `update` to the status of a message, creating it if needed.
+    ///
+    /// Statuses left with neither a verdict nor a note are dropped.
+    pub fn update(
+        &mut self,
+        message_id: ArcStr,
+        now: DateTime<Utc>,
+        update: impl FnOnce(&mut ReviewStatus),
+    ) -> Option<ReviewStatus> {
+        let status = self
+            .reviews
+            .entry(message_id.clone())
+            .or_insert_with(|| ReviewStatus::new(now));
+        update(status);
+        status.updated = now;
+
+        if status.is_empty() {
+            self.reviews.remove(&message_id);
+            None
+        } else {
+            Some(status.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_state_from_str() {
+        assert_eq!(
+            "reviewed".parse::<ReviewState>().unwrap(),
+            ReviewState::Reviewed
+        );
+        assert_eq!(
+            "Needs_Work".parse::<ReviewState>().unwrap(),
+            ReviewState::NeedsWork
+        );
+        assert!("later".parse::<ReviewState>().is_err());
+    }
+
+    #[test]
+    fn test_review_filter() {
+        let filter: ReviewFilter = "unreviewed".parse().unwrap();
+        assert!(filter.matches(None));
+        assert!(!filter.matches(Some(ReviewState::Ignored)));
+
+        let filter: ReviewFilter = "ignored".parse().unwrap();
+        assert!(filter.matches(Some(ReviewState::Ignored)));
+        assert!(!filter.matches(None));
+    }
+
+    #[test]
+    fn test_review_state_cycle() {
+        let mut state = None;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            state = ReviewState::next(state);
+            seen.push(state);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some(ReviewState::Reviewed),
+                Some(ReviewState::NeedsWork),
+                Some(ReviewState::Ignored),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update_drops_empty_status() {
+        let mut data = ReviewData::new(ArcPath::from("/tmp/reviews.toml"));
+        let id = ArcStr::from("<20250101.1234-1-jane@example.com>");
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let t1 = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+
+        let status = data
+            .update(id.clone(), t0, |s| s.state = Some(ReviewState::NeedsWork))
+            .unwrap();
+        assert_eq!(status.created, t0);
+
+        let status = data
+            .update(id.clone(), t1, |s| s.note = ArcStr::from("v2 please"))
+            .unwrap();
+        assert_eq!(status.created, t0);
+        assert_eq!(status.updated, t1);
+
+        data.update(id.clone(), t1, |s| s.state = None);
+        assert!(data.reviews.contains_key(&id));
+        data.update(id.clone(), t1, |s| s.note = ArcStr::default());
+        assert!(!data.reviews.contains_key(&id));
+    }
+
+    #[test]
+    fn test_cache_data_roundtrip() {
+        let mut data = ReviewData::new(ArcPath::from("/tmp/reviews.toml"));
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        data.update(ArcStr::from("a.b@c.d"), now, |s| {
+            s.state = Some(ReviewState::Reviewed)
+        });
+
+        let toml = toml::to_string_pretty(&data.to_cache_data()).unwrap();
+        let loaded: CacheData = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.reviews, data.reviews);
+    }
+}
@@ -0,0 +1,35 @@
+use super::data::{ReviewState, ReviewStatus};
+use crate::ArcStr;
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+
+/// Messages for the Review Actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Get the review status of a message
+    Get {
+        message_id: ArcStr,
+        tx: oneshot::Sender<Option<ReviewStatus>>,
+    },
+    /// Get the review status of several messages at once
+    GetMany {
+        message_ids: Vec<ArcStr>,
+        tx: oneshot::Sender<HashMap<ArcStr, ReviewStatus>>,
+    },
+    /// Set or clear the review state of a message
+    SetState {
+        message_id: ArcStr,
+        state: Option<ReviewState>,
+        tx: oneshot::Sender<anyhow::Result<Option<ReviewStatus>>>,
+    },
+    /// Set or clear the note of a message
+    SetNote {
+        message_id: ArcStr,
+        note: ArcStr,
+        tx: oneshot::Sender<anyhow::Result<Option<ReviewStatus>>>,
+    },
+    /// Persist the store to the filesystem
+    Persist {
+        tx: oneshot::Sender<anyhow::Result<()>>,
+    },
+}
@@ -0,0 +1,126 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::data::{ReviewState, ReviewStatus};
+use crate::ArcStr;
+use crate::app::cache::review::MockData;
+
+/// Mock implementation of the ReviewCache actor for testing purposes.
+///
+/// This struct stores review statuses in memory,
+/// allowing tests to run without creating actual store files.
+#[derive(Debug, Clone)]
+pub struct Mock {
+    data: Arc<Mutex<MockData>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with the provided review data.
+    ///
+    /// # Arguments
+    /// * `data` - Initial review statuses
+    pub fn new(data: MockData) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+        }
+    }
+
+    /// Gets the review status of a message.
+    /// Mock implementation retrieves the status from stored data.
+    ///
+    /// # Arguments
+    /// * `message_id` - The message ID of the patch
+    ///
+    /// # Returns
+    /// The review status, if any
+    pub async fn get(&self, message_id: ArcStr) -> Option<ReviewStatus> {
+        let data = self.data.lock().await;
+        data.reviews.get(&message_id).cloned()
+    }
+
+    /// Gets the review status of several messages.
+    /// Mock implementation retrieves the statuses from stored data.
+    ///
+    /// # Arguments
+    /// * `message_ids` - The message IDs of the patches
+    ///
+    /// # Returns
+    /// The statuses of the messages that have one
+    pub async fn get_many(&self, message_ids: Vec<ArcStr>) -> HashMap<ArcStr, ReviewStatus> {
+        let data = self.data.lock().await;
+        message_ids
+            .into_iter()
+            .filter_map(|id| {
+                let status = data.reviews.get(&id)?.clone();
+                Some((id, status))
+            })
+            .collect()
+    }
+
+    /// Sets or clears the review state of a message.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `message_id` - The message ID of the patch
+    /// * `state` - The new state, or None to clear it
+    ///
+    /// # Returns
+    /// The updated status, or None if it became empty
+    pub async fn set_state(
+        &self,
+        message_id: ArcStr,
+        state: Option<ReviewState>,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        self.update(message_id, |status| status.state = state).await
+    }
+
+    /// Sets or clears the note of a message.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `message_id` - The message ID of the patch
+    /// * `note` - The new note, or an empty string to clear it
+    ///
+    /// # Returns
+    /// The updated status, or None if it became empty
+    pub async fn set_note(
+        &self,
+        message_id: ArcStr,
+        note: ArcStr,
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        self.update(message_id, |status| status.note = note).await
+    }
+
+    /// Persists the store to the filesystem.
+    /// Mock implementation is a no-op that always succeeds.
+    ///
+    /// # Returns
+    /// Ok(()) always
+    pub async fn persist(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Applies an update to the stored status of a message.
+    async fn update(
+        &self,
+        message_id: ArcStr,
+        update: impl FnOnce(&mut ReviewStatus),
+    ) -> anyhow::Result<Option<ReviewStatus>> {
+        let mut data = self.data.lock().await;
+        let now = Utc::now();
+        let status = data
+            .reviews
+            .entry(message_id.clone())
+            .or_insert_with(|| ReviewStatus::new(now));
+        update(status);
+        status.updated = now;
+        if status.is_empty() {
+            data.reviews.remove(&message_id);
+            Ok(None)
+        } else {
+            Ok(data.reviews.get(&message_id).cloned())
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+mod core;
+mod data;
+mod mock;
+pub mod message;
+
+use crate::ArcStr;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+use message::Message;
+
+/// The Seen Actor remembers which patches the user already opened.
+///
+/// Patches are keyed by message ID and persisted in their own file under the cache
+/// directory, next to the review store, so invalidating a feed never marks its patches
+/// as unread again.
+#[derive(Debug, Clone)]
+pub enum SeenCache {
+    Actual(tokio::sync::mpsc::Sender<Message>),
+    Mock(mock::Mock),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockData {
+    /// When each opened patch was first opened, by message ID
+    pub seen: HashMap<ArcStr, DateTime<Utc>>,
+}
+
+impl SeenCache {
+    /// Spawns a new SeenCache actor.
+    pub async fn spawn(fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
+        let core = core::Core::new(fs, config, log).await?;
+        let (state, _handle) = core.spawn();
+        Ok(state)
+    }
+
+    /// Creates a new mock SeenCache actor for testing.
+    pub fn mock(data: MockData) -> Self {
+        Self::Mock(mock::Mock::new(data))
+    }
+
+    /// Gets which of several messages were already opened.
+    pub async fn get_many(&self, message_ids: Vec<ArcStr>) -> HashSet<ArcStr> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetMany { message_ids, tx })
+                    .await
+                    .context("Sending message to SeenCache actor")
+                    .expect("SeenCache actor died");
+                rx.await
+                    .context("Awaiting response from SeenCache actor")
+                    .expect("SeenCache actor died")
+            }
+            Self::Mock(mock) => mock.get_many(message_ids).await,
+        }
+    }
+
+    /// Marks messages as opened and persists the store if any of them was not yet.
+    ///
+    /// Returns how many of the messages were not opened before.
+    pub async fn mark_seen(&self, message_ids: Vec<ArcStr>) -> anyhow::Result<usize> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::MarkSeen { message_ids, tx })
+                    .await
+                    .context("Sending message to SeenCache actor")
+                    .expect("SeenCache actor died");
+                rx.await
+                    .context("Awaiting response from SeenCache actor")
+                    .expect("SeenCache actor died")
+            }
+            Self::Mock(mock) => mock.mark_seen(message_ids).await,
+        }
+    }
+
+    /// Persists the store to the filesystem.
+    pub async fn persist(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::Persist { tx })
+                    .await
+                    .context("Sending message to SeenCache actor")
+                    .expect("SeenCache actor died");
+                rx.await
+                    .context("Awaiting response from SeenCache actor")
+                    .expect("SeenCache actor died")
+            }
+            Self::Mock(mock) => mock.persist().await,
+        }
+    }
+}
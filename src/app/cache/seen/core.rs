@@ -0,0 +1,138 @@
+use super::data::SeenData;
+use super::message::Message;
+use crate::ArcPath;
+use crate::ArcStr;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+use anyhow::Context;
+use chrono::Utc;
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+const BUFFER_SIZE: usize = 100;
+const SCOPE: &str = "app.cache.seen";
+
+/// Core implementation for the Seen Actor.
+pub struct Core {
+    /// Filesystem actor for persistence
+    fs: Fs,
+    /// Config actor for configuration
+    config: Config,
+    /// Log actor for logging
+    log: Log,
+    /// Internal state
+    data: SeenData,
+}
+
+impl Core {
+    /// Creates a new Core instance.
+    pub async fn new(fs: Fs, config: Config, log: Log) -> anyhow::Result<Self> {
+        let cache_dir = config.path(crate::app::config::PathOpt::CachePath).await;
+        let cache_path = ArcPath::from(&cache_dir.join("seen.toml"));
+        let data = SeenData::new(cache_path);
+
+        Ok(Self {
+            fs,
+            config,
+            log,
+            data,
+        })
+    }
+
+    /// Spawns the actor and returns the public interface and join handle.
+    pub fn spawn(self) -> (super::SeenCache, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::channel(BUFFER_SIZE);
+        let handle = tokio::spawn(async move {
+            let mut core = self;
+
+            // Load the store on startup
+            if let Err(e) = core.load_cache().await {
+                core.log
+                    .error(SCOPE, format!("Failed to load seen store: {}", e));
+            }
+
+            while let Some(message) = rx.recv().await {
+                match message {
+                    Message::GetMany { message_ids, tx } => {
+                        let result = core.handle_get_many(message_ids);
+                        let _ = tx.send(result);
+                    }
+                    Message::MarkSeen { message_ids, tx } => {
+                        let result = core.handle_mark_seen(message_ids).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Persist { tx } => {
+                        let result = core.persist_cache().await;
+                        let _ = tx.send(result);
+                    }
+                }
+            }
+        });
+
+        (super::SeenCache::Actual(tx), handle)
+    }
+
+    /// Handles getting which of several messages were already opened.
+    fn handle_get_many(&self, message_ids: Vec<ArcStr>) -> HashSet<ArcStr> {
+        message_ids
+            .into_iter()
+            .filter(|id| self.data.seen.contains_key(id))
+            .collect()
+    }
+
+    /// Handles marking messages as opened, writing the store only if something changed.
+    async fn handle_mark_seen(&mut self, message_ids: Vec<ArcStr>) -> anyhow::Result<usize> {
+        let marked = self.data.mark(message_ids, Utc::now());
+        if marked > 0 {
+            self.log
+                .info(SCOPE, format!("Marked {} patch(es) as seen", marked));
+            self.persist_cache().await?;
+        }
+        Ok(marked)
+    }
+
+    /// Persists the store to the filesystem.
+    async fn persist_cache(&self) -> anyhow::Result<()> {
+        let cache_data = self.data.to_cache_data();
+        let content =
+            toml::to_string_pretty(&cache_data).context("Failed to serialize seen store")?;
+
+        // Replace the file atomically, creating the cache directory if needed
+        self.fs
+            .write_atomic(self.data.cache_path.clone(), content.into_bytes())
+            .await
+            .context("Failed to write seen store")?;
+
+        Ok(())
+    }
+
+    /// Loads the store from the filesystem.
+    async fn load_cache(&mut self) -> anyhow::Result<()> {
+        // Check if file exists by trying to read it
+        let file = match self.fs.read_file(self.data.cache_path.clone()).await {
+            Ok(file) => file,
+            Err(_) => return Ok(()), // File doesn't exist, that's ok
+        };
+
+        // Read the content
+        use tokio::io::AsyncReadExt;
+        let mut content = String::new();
+        let mut file = file;
+        file.read_to_string(&mut content)
+            .await
+            .context("Failed to read seen store content")?;
+
+        let cache_data: super::data::CacheData =
+            toml::from_str(&content).context("Failed to deserialize seen store")?;
+
+        self.data.restore(cache_data);
+
+        self.log.info(
+            SCOPE,
+            format!("Loaded {} seen patches", self.data.seen.len()),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{ArcPath, ArcStr};
+
+/// Data structure for persisting the seen store to disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CacheData {
+    /// When each opened patch was first opened, by message ID
+    pub seen: HashMap<ArcStr, DateTime<Utc>>,
+}
+
+/// Internal state for the Seen Actor.
+pub struct SeenData {
+    /// When each opened patch was first opened, by message ID
+    pub seen: HashMap<ArcStr, DateTime<Utc>>,
+    /// Path of the seen store file
+    pub cache_path: ArcPath,
+}
+
+impl SeenData {
+    /// Creates a new SeenData instance.
+    pub fn new(cache_path: ArcPath) -> Self {
+        Self {
+            seen: HashMap::new(),
+            cache_path,
+        }
+    }
+
+    /// Converts to CacheData for persistence.
+    pub fn to_cache_data(&self) -> CacheData {
+        CacheData {
+            seen: self.seen.clone(),
+        }
+    }
+
+    /// Replaces the state with CacheData loaded from disk.
+    pub fn restore(&mut self, data: CacheData) {
+        self.seen = data.seen;
+    }
+
+    /// Marks messages as opened at the given time, keeping the time of earlier openings.
+    ///
+    /// Returns how many of the messages were not opened before.
+    pub fn mark(&mut self, message_ids: Vec<ArcStr>, now: DateTime<Utc>) -> usize {
+        let before = self.seen.len();
+        for message_id in message_ids {
+            self.seen.entry(message_id).or_insert(now);
+        }
+        self.seen.len() - before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_keeps_first_opening() {
+        let mut data = SeenData::new(ArcPath::from("/tmp/seen.toml"));
+        let t0 = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let t1 = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let a = ArcStr::from("a.b@c.d");
+        let b = ArcStr::from("e.f@g.h");
+
+        assert_eq!(data.mark(vec![a.clone()], t0), 1);
+        assert_eq!(data.mark(vec![a.clone(), b.clone(), b.clone()], t1), 1);
+        assert_eq!(data.seen[&a], t0);
+        assert_eq!(data.seen[&b], t1);
+        assert_eq!(data.mark(vec![a, b], t1), 0);
+    }
+
+    #[test]
+    fn test_cache_data_roundtrip() {
+        let mut data = SeenData::new(ArcPath::from("/tmp/seen.toml"));
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        data.mark(vec![ArcStr::from("a.b@c.d")], now);
+
+        let toml = toml::to_string_pretty(&data.to_cache_data()).unwrap();
+        let loaded: CacheData = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.seen, data.seen);
+    }
+}
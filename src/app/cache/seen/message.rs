@@ -0,0 +1,22 @@
+use crate::ArcStr;
+use std::collections::HashSet;
+use tokio::sync::oneshot;
+
+/// Messages for the Seen Actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Get which of several messages were already opened
+    GetMany {
+        message_ids: Vec<ArcStr>,
+        tx: oneshot::Sender<HashSet<ArcStr>>,
+    },
+    /// Mark messages as opened, returning how many were not yet
+    MarkSeen {
+        message_ids: Vec<ArcStr>,
+        tx: oneshot::Sender<anyhow::Result<usize>>,
+    },
+    /// Persist the store to the filesystem
+    Persist {
+        tx: oneshot::Sender<anyhow::Result<()>>,
+    },
+}
@@ -0,0 +1,71 @@
+use chrono::Utc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::ArcStr;
+use crate::app::cache::seen::MockData;
+
+/// Mock implementation of the SeenCache actor for testing purposes.
+///
+/// This struct stores the opened patches in memory,
+/// allowing tests to run without creating actual store files.
+#[derive(Debug, Clone)]
+pub struct Mock {
+    data: Arc<Mutex<MockData>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with the provided data.
+    ///
+    /// # Arguments
+    /// * `data` - Initially opened patches
+    pub fn new(data: MockData) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+        }
+    }
+
+    /// Gets which of several messages were already opened.
+    /// Mock implementation looks them up in stored data.
+    ///
+    /// # Arguments
+    /// * `message_ids` - The message IDs of the patches
+    ///
+    /// # Returns
+    /// The message IDs that were opened
+    pub async fn get_many(&self, message_ids: Vec<ArcStr>) -> HashSet<ArcStr> {
+        let data = self.data.lock().await;
+        message_ids
+            .into_iter()
+            .filter(|id| data.seen.contains_key(id))
+            .collect()
+    }
+
+    /// Marks messages as opened.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `message_ids` - The message IDs of the patches
+    ///
+    /// # Returns
+    /// How many of the messages were not opened before
+    pub async fn mark_seen(&self, message_ids: Vec<ArcStr>) -> anyhow::Result<usize> {
+        let mut data = self.data.lock().await;
+        let before = data.seen.len();
+        let now = Utc::now();
+        for message_id in message_ids {
+            data.seen.entry(message_id).or_insert(now);
+        }
+        Ok(data.seen.len() - before)
+    }
+
+    /// Persists the store to the filesystem.
+    /// Mock implementation is a no-op that always succeeds.
+    ///
+    /// # Returns
+    /// Ok(()) always
+    pub async fn persist(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
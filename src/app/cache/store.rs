@@ -0,0 +1,503 @@
+//! Append-only key-value store backing the persistent caches.
+//!
+//! A store is a single log file of records, each one putting a value under a key or
+//! deleting it. The position of the latest record of every key is kept in memory, so
+//! a value is read with one seek, and changes are appended without rewriting what is
+//! already on disk.
+//!
+//! # File Format
+//! The file starts with the magic bytes `phstore1`, followed by the records:
+//!
+//! ```text
+//! crc: u32 | key_len: u32 | value_len: u32 | kind: u8 | key | value
+//! ```
+//!
+//! Integers are little endian, `kind` is `1` for a put and `0` for a delete, and the
+//! CRC-32 covers everything after itself. Values are JSON.
+//!
+//! # Crash Safety
+//! Every write is a single append followed by an `fsync`. A write cut short by a crash
+//! leaves a torn record at the end of the file, which fails its checksum and is
+//! truncated away the next time the store is opened, so a write either lands entirely
+//! or not at all. Compaction replaces the log with [`Fs::write_atomic`], which never
+//! leaves a half-written log behind.
+
+use std::collections::HashMap;
+use std::io::{self, SeekFrom};
+use std::marker::PhantomData;
+
+use anyhow::{Context, bail};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+
+use crate::ArcPath;
+use crate::fs::Fs;
+
+/// Bytes every store file starts with
+const MAGIC: &[u8; 8] = b"phstore1";
+/// Size of the fixed part of a record
+const HEADER_LEN: usize = 13;
+/// Longest key accepted when reading a record, anything longer is corruption
+const MAX_KEY_LEN: usize = 64 * 1024;
+/// Longest value accepted when reading a record, anything longer is corruption
+const MAX_VALUE_LEN: usize = 256 * 1024 * 1024;
+/// Dead bytes a log must hold before it is worth compacting
+const COMPACT_MIN: u64 = 64 * 1024;
+
+const PUT: u8 = 1;
+const DELETE: u8 = 0;
+
+/// Where the latest record of a key sits in the log.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    offset: u64,
+    len: u64,
+}
+
+/// An append-only key-value store kept in a single file.
+///
+/// Keys are strings, like message IDs, and values anything serde can handle. The
+/// store does not cache values: callers keep what they need in memory and use the
+/// store to persist changes and read values back on demand.
+#[derive(Debug)]
+pub struct Store<V> {
+    /// Filesystem actor the log is accessed through
+    fs: Fs,
+    /// Path of the log file
+    path: ArcPath,
+    /// Latest record of every live key
+    index: HashMap<String, Slot>,
+    /// Size of the log file
+    size: u64,
+    /// Bytes taken by the records of live keys
+    live: u64,
+    /// Bytes of a torn or corrupt tail dropped when the store was opened
+    truncated: u64,
+    _value: PhantomData<V>,
+}
+
+impl<V: Serialize + DeserializeOwned> Store<V> {
+    /// Opens the store kept in a file, which does not need to exist yet.
+    ///
+    /// The log is scanned to build the index. A torn or corrupt tail is truncated and
+    /// reported by [`Store::truncated`].
+    ///
+    /// # Errors
+    /// Fails if the file cannot be read or is not a store.
+    pub async fn open(fs: Fs, path: ArcPath) -> anyhow::Result<Self> {
+        let mut store = Self {
+            fs,
+            path,
+            index: HashMap::new(),
+            size: 0,
+            live: 0,
+            truncated: 0,
+            _value: PhantomData,
+        };
+        let file = match store.fs.read_file(store.path.clone()).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(e) => return Err(e).context("Failed to open store"),
+        };
+        let len = file
+            .metadata()
+            .await
+            .context("Failed to read store metadata")?
+            .len();
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; MAGIC.len()];
+        let valid = match reader.read_exact(&mut magic).await {
+            Ok(_) if &magic == MAGIC => store.scan(&mut reader).await?,
+            Ok(_) => bail!("{} is not a store", store.path.display()),
+            // Cut short while being created
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => 0,
+            Err(e) => return Err(e).context("Failed to read store"),
+        };
+        if valid < len {
+            store.truncate(valid).await?;
+            store.truncated = len - valid;
+        }
+        store.size = valid;
+        Ok(store)
+    }
+
+    /// Reads the records after the magic bytes into the index.
+    ///
+    /// # Returns
+    /// Where the last intact record ends.
+    async fn scan(&mut self, reader: &mut BufReader<tokio::fs::File>) -> anyhow::Result<u64> {
+        let mut offset = MAGIC.len() as u64;
+        let mut header = [0; HEADER_LEN];
+        let mut body = Vec::new();
+        loop {
+            match reader.read_exact(&mut header).await {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e).context("Failed to read store"),
+            }
+            let crc = u32_at(&header, 0);
+            let key_len = u32_at(&header, 4) as usize;
+            let value_len = u32_at(&header, 8) as usize;
+            let kind = header[12];
+            if key_len > MAX_KEY_LEN || value_len > MAX_VALUE_LEN || kind > PUT {
+                break;
+            }
+            body.resize(key_len + value_len, 0);
+            match reader.read_exact(&mut body).await {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e).context("Failed to read store"),
+            }
+            let mut hasher = crc32fast::Hasher::new();
+            hasher.update(&header[4..]);
+            hasher.update(&body);
+            if hasher.finalize() != crc {
+                break;
+            }
+            let Ok(key) = std::str::from_utf8(&body[..key_len]) else {
+                break;
+            };
+
+            let len = (HEADER_LEN + body.len()) as u64;
+            if let Some(old) = self.index.remove(key) {
+                self.live -= old.len;
+            }
+            if kind == PUT {
+                self.index.insert(key.to_string(), Slot { offset, len });
+                self.live += len;
+            }
+            offset += len;
+        }
+        Ok(offset)
+    }
+
+    /// Cuts the log file down to `len` bytes.
+    async fn truncate(&self, len: u64) -> anyhow::Result<()> {
+        let file = self
+            .fs
+            .append_file(self.path.clone())
+            .await
+            .context("Failed to open store for truncating")?;
+        file.set_len(len)
+            .await
+            .context("Failed to truncate store")?;
+        file.sync_all().await.context("Failed to sync store")?;
+        Ok(())
+    }
+
+    /// Returns how many bytes of a torn or corrupt tail were dropped on opening.
+    pub fn truncated(&self) -> u64 {
+        self.truncated
+    }
+
+    /// Returns the path of the log file.
+    pub fn path(&self) -> &ArcPath {
+        &self.path
+    }
+
+    /// Returns the number of live keys.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns true if no key is live.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Returns true if a value is stored under `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Returns the live keys, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// Reads the value stored under `key`.
+    ///
+    /// # Returns
+    /// The value, or `None` if the key is not live.
+    pub async fn get(&self, key: &str) -> anyhow::Result<Option<V>> {
+        let Some(slot) = self.index.get(key).copied() else {
+            return Ok(None);
+        };
+        let mut file = self
+            .fs
+            .read_file(self.path.clone())
+            .await
+            .context("Failed to open store")?;
+        file.seek(SeekFrom::Start(slot.offset))
+            .await
+            .context("Failed to seek in store")?;
+        let mut record = vec![0; slot.len as usize];
+        file.read_exact(&mut record)
+            .await
+            .context("Failed to read store record")?;
+        decode(&record).map(Some)
+    }
+
+    /// Reads every live value.
+    ///
+    /// # Returns
+    /// The keys and values, in the order they were last written.
+    pub async fn load_all(&self) -> anyhow::Result<Vec<(String, V)>> {
+        if self.index.is_empty() {
+            return Ok(Vec::new());
+        }
+        let content = self.read_log().await?;
+        let mut slots: Vec<_> = self.index.iter().collect();
+        slots.sort_by_key(|(_, slot)| slot.offset);
+        slots
+            .into_iter()
+            .map(|(key, slot)| {
+                let start = slot.offset as usize;
+                let record = &content[start..start + slot.len as usize];
+                Ok((key.clone(), decode(record)?))
+            })
+            .collect()
+    }
+
+    /// Writes a batch of changes with a single append.
+    ///
+    /// # Arguments
+    /// * `batch` - Values to put under their key, or `None` to delete the key
+    ///
+    /// # Errors
+    /// Fails if a value cannot be serialized or the log cannot be written, in which
+    /// case none of the batch is stored.
+    pub async fn write(&mut self, batch: Vec<(String, Option<V>)>) -> anyhow::Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let mut buf = Vec::new();
+        if self.size == 0 {
+            if let Some(parent) = self.path.parent() {
+                self.fs
+                    .mkdir(ArcPath::from(parent))
+                    .await
+                    .context("Failed to create store directory")?;
+            }
+            buf.extend_from_slice(MAGIC);
+        }
+        let mut slots = Vec::with_capacity(batch.len());
+        for (key, value) in batch {
+            let value = value
+                .map(|value| serde_json::to_vec(&value))
+                .transpose()
+                .context("Failed to serialize store value")?;
+            let offset = self.size + buf.len() as u64;
+            encode(&mut buf, &key, value.as_deref());
+            let len = self.size + buf.len() as u64 - offset;
+            slots.push((key, value.map(|_| Slot { offset, len })));
+        }
+
+        let mut file = self
+            .fs
+            .append_file(self.path.clone())
+            .await
+            .context("Failed to open store for writing")?;
+        let written = async {
+            file.write_all(&buf).await?;
+            file.sync_data().await
+        }
+        .await;
+        if let Err(e) = written {
+            // Leave no torn record for the next write to land after
+            let _ = file.set_len(self.size).await;
+            return Err(e).context("Failed to write store");
+        }
+
+        self.size += buf.len() as u64;
+        for (key, slot) in slots {
+            if let Some(old) = self.index.remove(&key) {
+                self.live -= old.len;
+            }
+            if let Some(slot) = slot {
+                self.live += slot.len;
+                self.index.insert(key, slot);
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes every key, removing the log file.
+    pub async fn clear(&mut self) -> anyhow::Result<()> {
+        match self.fs.remove_file(self.path.clone()).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to remove store"),
+        }
+        self.index.clear();
+        self.size = 0;
+        self.live = 0;
+        Ok(())
+    }
+
+    /// Rewrites the log with only the live records once most of it is dead.
+    ///
+    /// # Returns
+    /// Whether the log was compacted.
+    pub async fn compact(&mut self) -> anyhow::Result<bool> {
+        let dead = self.size.saturating_sub(MAGIC.len() as u64 + self.live);
+        if dead < COMPACT_MIN || dead < self.live {
+            return Ok(false);
+        }
+        let content = self.read_log().await?;
+        let mut slots: Vec<_> = self.index.values_mut().collect();
+        slots.sort_by_key(|slot| slot.offset);
+        let mut buf = Vec::with_capacity(MAGIC.len() + self.live as usize);
+        buf.extend_from_slice(MAGIC);
+        for slot in slots {
+            let start = slot.offset as usize;
+            slot.offset = buf.len() as u64;
+            buf.extend_from_slice(&content[start..start + slot.len as usize]);
+        }
+
+        let size = buf.len() as u64;
+        self.fs
+            .write_atomic(self.path.clone(), buf)
+            .await
+            .context("Failed to replace store with its compacted copy")?;
+        self.size = size;
+        Ok(true)
+    }
+
+    /// Reads the whole log file.
+    async fn read_log(&self) -> anyhow::Result<Vec<u8>> {
+        let mut file = self
+            .fs
+            .read_file(self.path.clone())
+            .await
+            .context("Failed to open store")?;
+        let mut content = Vec::with_capacity(self.size as usize);
+        file.read_to_end(&mut content)
+            .await
+            .context("Failed to read store")?;
+        if (content.len() as u64) < self.size {
+            bail!("{} was cut short", self.path.display());
+        }
+        Ok(content)
+    }
+}
+
+/// Reads a little endian `u32` at `at`.
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+/// Appends a record to `buf`, a put if there is a value and a delete otherwise.
+fn encode(buf: &mut Vec<u8>, key: &str, value: Option<&[u8]>) {
+    let start = buf.len();
+    buf.extend_from_slice(&[0; 4]);
+    buf.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&(value.map_or(0, <[u8]>::len) as u32).to_le_bytes());
+    buf.push(if value.is_some() { PUT } else { DELETE });
+    buf.extend_from_slice(key.as_bytes());
+    buf.extend_from_slice(value.unwrap_or_default());
+    let crc = crc32fast::hash(&buf[start + 4..]);
+    buf[start..start + 4].copy_from_slice(&crc.to_le_bytes());
+}
+
+/// Decodes the value of a put record read back from the log.
+fn decode<V: DeserializeOwned>(record: &[u8]) -> anyhow::Result<V> {
+    let key_len = u32_at(record, 4) as usize;
+    let crc = crc32fast::hash(&record[4..]);
+    if crc != u32_at(record, 0) {
+        bail!("Store record failed its checksum");
+    }
+    serde_json::from_slice(&record[HEADER_LEN + key_len..])
+        .context("Failed to deserialize store value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path() -> ArcPath {
+        ArcPath::from("cache/test.log")
+    }
+
+    fn put(key: &str, value: &str) -> (String, Option<String>) {
+        (key.to_string(), Some(value.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_write_and_reopen() {
+        let fs = Fs::mock();
+        let mut store = Store::<String>::open(fs.clone(), path()).await.unwrap();
+        assert!(store.is_empty());
+        assert_eq!(store.get("a").await.unwrap(), None);
+
+        store
+            .write(vec![put("a", "first"), put("b", "second")])
+            .await
+            .unwrap();
+        store
+            .write(vec![put("a", "third"), ("b".to_string(), None)])
+            .await
+            .unwrap();
+        assert_eq!(store.get("a").await.unwrap(), Some("third".to_string()));
+        assert!(!store.contains("b"));
+
+        let store = Store::<String>::open(fs, path()).await.unwrap();
+        assert_eq!(store.truncated(), 0);
+        assert_eq!(store.len(), 1);
+        assert_eq!(
+            store.load_all().await.unwrap(),
+            vec![("a".to_string(), "third".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_torn_tail_is_truncated() {
+        let fs = Fs::mock();
+        let mut store = Store::<String>::open(fs.clone(), path()).await.unwrap();
+        store.write(vec![put("a", "kept")]).await.unwrap();
+        let size = store.size;
+
+        // A crash halfway through the second write
+        let mut torn = Vec::new();
+        encode(&mut torn, "b", Some(b"\"lost\""));
+        let mut file = fs.append_file(path()).await.unwrap();
+        file.write_all(&torn[..torn.len() - 3]).await.unwrap();
+        drop(file);
+
+        let mut store = Store::<String>::open(fs.clone(), path()).await.unwrap();
+        assert_eq!(store.truncated(), torn.len() as u64 - 3);
+        assert_eq!(store.size, size);
+        assert!(!store.contains("b"));
+
+        // Later writes land after the intact records
+        store.write(vec![put("c", "new")]).await.unwrap();
+        let store = Store::<String>::open(fs, path()).await.unwrap();
+        assert_eq!(store.truncated(), 0);
+        assert_eq!(store.get("a").await.unwrap(), Some("kept".to_string()));
+        assert_eq!(store.get("c").await.unwrap(), Some("new".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_compact_drops_dead_records() {
+        let fs = Fs::mock();
+        let mut store = Store::<String>::open(fs.clone(), path()).await.unwrap();
+        let value = "x".repeat(1024);
+        for _ in 0..100 {
+            store
+                .write(vec![put("a", &value), put("b", "small")])
+                .await
+                .unwrap();
+        }
+        let size = store.size;
+        assert!(store.compact().await.unwrap());
+        assert!(store.size < size / 50);
+        assert!(!store.compact().await.unwrap());
+
+        let store = Store::<String>::open(fs, path()).await.unwrap();
+        assert_eq!(store.truncated(), 0);
+        assert_eq!(store.get("a").await.unwrap(), Some(value));
+        assert_eq!(store.get("b").await.unwrap(), Some("small".to_string()));
+    }
+}
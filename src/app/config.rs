@@ -1,9 +1,11 @@
-use data::Data;
-pub use data::{PathOpt, Renderer, RendererOpt, USizeOpt};
+pub use data::{
+    BoolOpt, Data, ListOpt, MailBackend, MailBackendOpt, MapOpt, PathOpt, Renderer, RendererOpt, StrOpt, USizeOpt,
+};
 use message::Message;
 
-use crate::{ArcPath, env::Env, fs::Fs, log::LogLevel};
+use crate::{ArcPath, ArcStr, env::Env, fs::Fs, log::LogLevel};
 use anyhow::Context;
+use std::collections::HashMap;
 
 mod core;
 mod data;
@@ -238,6 +240,216 @@ impl Config {
         }
     }
 
+    /// Gets a boolean configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to retrieve
+    ///
+    /// # Returns
+    /// The requested boolean value.
+    pub async fn bool(&self, opt: BoolOpt) -> bool {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetBool { opt, tx })
+                    .await
+                    .context("Getting boolean value with Config actor")
+                    .expect("Config actor died");
+                rx.await
+                    .context("Awaiting response for boolean value with Config actor")
+                    .expect("Config actor died")
+            }
+            Self::Mock(mock) => {
+                mock.bool(opt).await
+            }
+        }
+    }
+
+    /// Sets a boolean configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to set
+    /// * `value` - The new boolean value
+    pub async fn set_bool(&self, opt: BoolOpt, value: bool) {
+        match self {
+            Self::Actual(sender) => {
+                let _ = sender.send(Message::SetBool { opt, value }).await;
+            }
+            Self::Mock(mock) => {
+                mock.set_bool(opt, value).await
+            }
+        }
+    }
+
+    /// Gets a string configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to retrieve
+    ///
+    /// # Returns
+    /// The requested string value.
+    pub async fn str(&self, opt: StrOpt) -> ArcStr {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetStr { opt, tx })
+                    .await
+                    .context("Getting string value with Config actor")
+                    .expect("Config actor died");
+                rx.await
+                    .context("Awaiting response for string value with Config actor")
+                    .expect("Config actor died")
+            }
+            Self::Mock(mock) => {
+                mock.str(opt).await
+            }
+        }
+    }
+
+    /// Sets a string configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to set
+    /// * `value` - The new string value
+    pub async fn set_str(&self, opt: StrOpt, value: ArcStr) {
+        match self {
+            Self::Actual(sender) => {
+                let _ = sender.send(Message::SetStr { opt, value }).await;
+            }
+            Self::Mock(mock) => {
+                mock.set_str(opt, value).await
+            }
+        }
+    }
+
+    /// Gets a string list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to retrieve
+    ///
+    /// # Returns
+    /// The requested string list.
+    pub async fn list(&self, opt: ListOpt) -> Vec<ArcStr> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetList { opt, tx })
+                    .await
+                    .context("Getting string list value with Config actor")
+                    .expect("Config actor died");
+                rx.await
+                    .context("Awaiting response for string list value with Config actor")
+                    .expect("Config actor died")
+            }
+            Self::Mock(mock) => {
+                mock.list(opt).await
+            }
+        }
+    }
+
+    /// Sets a string list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to set
+    /// * `values` - The new string list
+    pub async fn set_list(&self, opt: ListOpt, values: Vec<ArcStr>) {
+        match self {
+            Self::Actual(sender) => {
+                let _ = sender.send(Message::SetList { opt, values }).await;
+            }
+            Self::Mock(mock) => {
+                mock.set_list(opt, values).await
+            }
+        }
+    }
+
+    /// Gets a per-mailing-list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to retrieve
+    ///
+    /// # Returns
+    /// The requested values, by list name.
+    pub async fn map(&self, opt: MapOpt) -> HashMap<String, Vec<ArcStr>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetMap { opt, tx })
+                    .await
+                    .context("Getting per-list value with Config actor")
+                    .expect("Config actor died");
+                rx.await
+                    .context("Awaiting response for per-list value with Config actor")
+                    .expect("Config actor died")
+            }
+            Self::Mock(mock) => {
+                mock.map(opt).await
+            }
+        }
+    }
+
+    /// Sets a per-mailing-list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to set
+    /// * `values` - The new values, by list name
+    pub async fn set_map(&self, opt: MapOpt, values: HashMap<String, Vec<ArcStr>>) {
+        match self {
+            Self::Actual(sender) => {
+                let _ = sender.send(Message::SetMap { opt, values }).await;
+            }
+            Self::Mock(mock) => {
+                mock.set_map(opt, values).await
+            }
+        }
+    }
+
+    /// Gets a mail backend configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to retrieve
+    ///
+    /// # Returns
+    /// The requested mail backend value.
+    pub async fn mail_backend(&self, opt: MailBackendOpt) -> MailBackend {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetMailBackend { opt, tx })
+                    .await
+                    .context("Getting mail backend value with Config actor")
+                    .expect("Config actor died");
+                rx.await
+                    .context("Awaiting response for mail backend value with Config actor")
+                    .expect("Config actor died")
+            }
+            Self::Mock(mock) => {
+                mock.mail_backend(opt).await
+            }
+        }
+    }
+
+    /// Sets a mail backend configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to set
+    /// * `backend` - The new mail backend value
+    pub async fn set_mail_backend(&self, opt: MailBackendOpt, backend: MailBackend) {
+        match self {
+            Self::Actual(sender) => {
+                let _ = sender.send(Message::SetMailBackend { opt, backend }).await;
+            }
+            Self::Mock(mock) => {
+                mock.set_mail_backend(opt, backend).await
+            }
+        }
+    }
+
     /// Gets a renderer configuration value.
     ///
     /// # Arguments
@@ -79,6 +79,41 @@ impl Core {
                     Message::SetUSize { opt, size } => {
                         self.data.set_usize(opt, size);
                     }
+                    Message::GetBool { opt, tx } => {
+                        let res = self.data.bool(opt);
+                        let _ = tx.send(res);
+                    }
+                    Message::SetBool { opt, value } => {
+                        self.data.set_bool(opt, value);
+                    }
+                    Message::GetStr { opt, tx } => {
+                        let res = self.data.str(opt);
+                        let _ = tx.send(res);
+                    }
+                    Message::SetStr { opt, value } => {
+                        self.data.set_str(opt, value);
+                    }
+                    Message::GetList { opt, tx } => {
+                        let res = self.data.list(opt);
+                        let _ = tx.send(res);
+                    }
+                    Message::SetList { opt, values } => {
+                        self.data.set_list(opt, values);
+                    }
+                    Message::GetMap { opt, tx } => {
+                        let res = self.data.map(opt);
+                        let _ = tx.send(res);
+                    }
+                    Message::SetMap { opt, values } => {
+                        self.data.set_map(opt, values);
+                    }
+                    Message::GetMailBackend { opt, tx } => {
+                        let res = self.data.mail_backend(opt);
+                        let _ = tx.send(res);
+                    }
+                    Message::SetMailBackend { opt, backend } => {
+                        self.data.set_mail_backend(opt, backend);
+                    }
                     Message::GetRenderer { opt, tx } => {
                         let res = self.data.renderer(opt);
                         let _ = tx.send(res);
@@ -106,15 +141,16 @@ impl Core {
         Ok(())
     }
 
-    /// Saves the current configuration to the file.
+    /// Saves the current configuration to the file, atomically so an interrupted save
+    /// leaves the previous configuration in place.
     ///
     /// # Returns
     /// `Ok(())` if the configuration was saved successfully.
     async fn save(&self) -> anyhow::Result<()> {
         let contents = toml::to_string(&self.data)?;
-        let mut file = self.fs.write_file(self.path.clone()).await?;
-        use tokio::io::AsyncWriteExt;
-        file.write_all(contents.as_bytes()).await?;
+        self.fs
+            .write_atomic(self.path.clone(), contents.into_bytes())
+            .await?;
         Ok(())
     }
 }
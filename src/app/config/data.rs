@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::{ArcPath, log::LogLevel};
+use crate::{ArcPath, ArcStr, log::LogLevel};
 
 /// Available renderers for patch content.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +13,8 @@ pub enum Renderer {
     Bat,
     /// Use the `delta` program for diff highlighting
     Delta,
+    /// Highlight diffs natively, without any external program
+    Native,
 }
 
 impl Renderer {
@@ -24,6 +27,7 @@ impl Renderer {
             Renderer::None => "",
             Renderer::Bat => "bat",
             Renderer::Delta => "delta",
+            Renderer::Native => "",
         }
     }
 
@@ -41,10 +45,25 @@ impl Renderer {
                 "--color=always",
             ],
             Renderer::Delta => vec!["--paging=never", "--side-by-side=false"],
+            Renderer::Native => vec![],
         }
     }
 }
 
+/// Available backends for sending mail.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailBackend {
+    /// Do not send anything; write outgoing messages to the outbox directory
+    #[default]
+    DryRun,
+    /// Pipe messages to a sendmail-compatible command
+    Sendmail,
+    /// Hand messages to `git send-email`
+    GitSendEmail,
+    /// Deliver messages to an SMTP server
+    Smtp,
+}
+
 /// Options for path-based configuration values that can be accessed and modified.
 #[derive(Debug, Clone, Copy)]
 pub enum PathOpt {
@@ -52,6 +71,12 @@ pub enum PathOpt {
     LogDir,
     /// Path to the cache file
     CachePath,
+    /// Local git repository where patches are applied
+    RepoPath,
+    /// Directory where composed replies are written
+    ReplyDir,
+    /// Directory where the dry-run mail backend writes outgoing messages
+    OutboxDir,
 }
 
 /// Options for numeric configuration values that can be accessed and modified.
@@ -61,6 +86,75 @@ pub enum USizeOpt {
     MaxAge,
     /// Timeout for network requests in seconds
     Timeout,
+    /// Seconds between two checks of the watched lists and searches, 0 to only check on demand
+    WatchInterval,
+    /// Maximum number of patches kept in memory, 0 for no limit
+    PatchBufferEntries,
+    /// Maximum total size in bytes of the patches kept in memory, 0 for no limit
+    PatchBufferBytes,
+    /// Minutes a patch is kept in memory, 0 for no limit
+    PatchBufferMaxAge,
+    /// Maximum number of patches stored on disk, 0 for no limit
+    PatchCacheEntries,
+    /// Maximum total size in bytes of the patches stored on disk, 0 for no limit
+    PatchCacheBytes,
+    /// Days a patch is stored on disk after it was fetched, 0 for no limit
+    PatchCacheMaxAge,
+}
+
+/// Options for boolean configuration values that can be accessed and modified.
+#[derive(Debug, Clone, Copy)]
+pub enum BoolOpt {
+    /// Fall back to a 3-way merge when applying patches (`git am -3`)
+    ApplyThreeWay,
+    /// Add a `Signed-off-by` trailer when applying patches (`git am --signoff`)
+    ApplySignoff,
+    /// Ring the terminal bell when new patches arrive in a watched list or search
+    WatchBell,
+}
+
+/// Options for string configuration values that can be accessed and modified.
+#[derive(Debug, Clone, Copy)]
+pub enum StrOpt {
+    /// Name of the user, used in replies and trailers
+    UserName,
+    /// Email address of the user, used in replies and trailers
+    UserEmail,
+    /// Sendmail-compatible command used by the sendmail backend
+    SendmailCommand,
+    /// SMTP server (`host[:port]`) used by the SMTP backend
+    SmtpServer,
+    /// Base URL of the main Lore archive (e.g. `https://lore.kernel.org`)
+    LoreDomain,
+}
+
+/// Options for string list configuration values that can be accessed and modified.
+#[derive(Debug, Clone, Copy)]
+pub enum ListOpt {
+    /// Base URLs of further public-inbox archives whose lists are browsed alongside the main one
+    ExtraArchives,
+    /// Names of the `MAINTAINERS` entries the user maintains or reviews
+    Subsystems,
+    /// Names of the mailing lists checked for new patches in the background
+    WatchedLists,
+}
+
+/// Options for per-mailing-list configuration values that can be accessed and modified.
+///
+/// Each option maps a name, usually that of a list, to a list of strings.
+#[derive(Debug, Clone, Copy)]
+pub enum MapOpt {
+    /// Files and directories a patch must touch to be shown in the feed of a list
+    PathFilters,
+    /// Saved searches checked for new patches in the background, as public-inbox terms by name
+    WatchedSearches,
+}
+
+/// Options for mail backend configuration values that can be accessed and modified.
+#[derive(Debug, Clone, Copy)]
+pub enum MailBackendOpt {
+    /// The backend used to send replies
+    SendBackend,
 }
 
 /// Options for renderer configuration values that can be accessed and modified.
@@ -91,6 +185,114 @@ pub struct Data {
     timeout: usize,
     /// The renderer to use for patch content
     patch_renderer: Renderer,
+    /// Local git repository where patches are applied
+    #[serde(default = "default_repo_path")]
+    repo_path: ArcPath,
+    /// Whether to fall back to a 3-way merge when applying patches
+    #[serde(default)]
+    apply_three_way: bool,
+    /// Whether to add a `Signed-off-by` trailer when applying patches
+    #[serde(default)]
+    apply_signoff: bool,
+    /// Directory where composed replies are written
+    #[serde(default = "default_reply_dir")]
+    reply_dir: ArcPath,
+    /// Name of the user, used in replies and trailers
+    #[serde(default)]
+    user_name: ArcStr,
+    /// Email address of the user, used in replies and trailers
+    #[serde(default)]
+    user_email: ArcStr,
+    /// The backend used to send replies
+    #[serde(default)]
+    send_backend: MailBackend,
+    /// Directory where the dry-run mail backend writes outgoing messages
+    #[serde(default = "default_outbox_dir")]
+    outbox_dir: ArcPath,
+    /// Sendmail-compatible command used by the sendmail backend
+    #[serde(default = "default_sendmail_command")]
+    sendmail_command: ArcStr,
+    /// SMTP server (`host[:port]`) used by the SMTP backend
+    #[serde(default = "default_smtp_server")]
+    smtp_server: ArcStr,
+    /// Base URL of the main Lore archive
+    #[serde(default = "default_lore_domain")]
+    lore_domain: ArcStr,
+    /// Base URLs of further public-inbox archives
+    #[serde(default)]
+    extra_archives: Vec<ArcStr>,
+    /// Names of the `MAINTAINERS` entries the user maintains or reviews
+    #[serde(default)]
+    subsystems: Vec<ArcStr>,
+    /// Names of the mailing lists checked for new patches in the background
+    #[serde(default)]
+    watched_lists: Vec<ArcStr>,
+    /// Seconds between two checks of the watched lists and searches
+    #[serde(default = "default_watch_interval")]
+    watch_interval: usize,
+    /// Whether to ring the terminal bell when new patches arrive
+    #[serde(default = "default_watch_bell")]
+    watch_bell: bool,
+    /// Maximum number of patches kept in memory
+    #[serde(default = "default_patch_buffer_entries")]
+    patch_buffer_entries: usize,
+    /// Maximum total size in bytes of the patches kept in memory
+    #[serde(default)]
+    patch_buffer_bytes: usize,
+    /// Minutes a patch is kept in memory
+    #[serde(default)]
+    patch_buffer_max_age: usize,
+    /// Maximum number of patches stored on disk
+    #[serde(default)]
+    patch_cache_entries: usize,
+    /// Maximum total size in bytes of the patches stored on disk
+    #[serde(default)]
+    patch_cache_bytes: usize,
+    /// Days a patch is stored on disk after it was fetched
+    #[serde(default)]
+    patch_cache_max_age: usize,
+    /// Files and directories a patch must touch to be shown, per mailing list
+    #[serde(default)]
+    path_filters: HashMap<String, Vec<ArcStr>>,
+    /// Saved searches checked for new patches in the background, as public-inbox terms by name
+    #[serde(default)]
+    watched_searches: HashMap<String, Vec<ArcStr>>,
+}
+
+fn default_repo_path() -> ArcPath {
+    ArcPath::from(".")
+}
+
+fn default_reply_dir() -> ArcPath {
+    ArcPath::from("/tmp/patch-hub/replies")
+}
+
+fn default_outbox_dir() -> ArcPath {
+    ArcPath::from("/tmp/patch-hub/outbox")
+}
+
+fn default_sendmail_command() -> ArcStr {
+    ArcStr::from("/usr/sbin/sendmail -t -i")
+}
+
+fn default_smtp_server() -> ArcStr {
+    ArcStr::from("localhost:25")
+}
+
+fn default_lore_domain() -> ArcStr {
+    ArcStr::from(crate::api::lore::DEFAULT_DOMAIN)
+}
+
+fn default_watch_interval() -> usize {
+    300
+}
+
+fn default_watch_bell() -> bool {
+    true
+}
+
+fn default_patch_buffer_entries() -> usize {
+    50
 }
 
 impl Default for Data {
@@ -102,6 +304,30 @@ impl Default for Data {
             max_age: 0,
             timeout: 30,
             patch_renderer: Renderer::default(),
+            repo_path: default_repo_path(),
+            apply_three_way: false,
+            apply_signoff: false,
+            reply_dir: default_reply_dir(),
+            user_name: ArcStr::default(),
+            user_email: ArcStr::default(),
+            send_backend: MailBackend::default(),
+            outbox_dir: default_outbox_dir(),
+            sendmail_command: default_sendmail_command(),
+            smtp_server: default_smtp_server(),
+            lore_domain: default_lore_domain(),
+            extra_archives: Vec::new(),
+            subsystems: Vec::new(),
+            watched_lists: Vec::new(),
+            watch_interval: default_watch_interval(),
+            watch_bell: default_watch_bell(),
+            patch_buffer_entries: default_patch_buffer_entries(),
+            patch_buffer_bytes: 0,
+            patch_buffer_max_age: 0,
+            patch_cache_entries: 0,
+            patch_cache_bytes: 0,
+            patch_cache_max_age: 0,
+            path_filters: HashMap::new(),
+            watched_searches: HashMap::new(),
         }
     }
 }
@@ -118,6 +344,9 @@ impl Data {
         match opt {
             PathOpt::LogDir => self.log_dir.clone(),
             PathOpt::CachePath => self.cache_path.clone(),
+            PathOpt::RepoPath => self.repo_path.clone(),
+            PathOpt::ReplyDir => self.reply_dir.clone(),
+            PathOpt::OutboxDir => self.outbox_dir.clone(),
         }
     }
 
@@ -130,6 +359,9 @@ impl Data {
         match opt {
             PathOpt::LogDir => self.log_dir = path,
             PathOpt::CachePath => self.cache_path = path,
+            PathOpt::RepoPath => self.repo_path = path,
+            PathOpt::ReplyDir => self.reply_dir = path,
+            PathOpt::OutboxDir => self.outbox_dir = path,
         }
     }
 
@@ -160,6 +392,13 @@ impl Data {
         match opt {
             USizeOpt::MaxAge => self.max_age,
             USizeOpt::Timeout => self.timeout,
+            USizeOpt::WatchInterval => self.watch_interval,
+            USizeOpt::PatchBufferEntries => self.patch_buffer_entries,
+            USizeOpt::PatchBufferBytes => self.patch_buffer_bytes,
+            USizeOpt::PatchBufferMaxAge => self.patch_buffer_max_age,
+            USizeOpt::PatchCacheEntries => self.patch_cache_entries,
+            USizeOpt::PatchCacheBytes => self.patch_cache_bytes,
+            USizeOpt::PatchCacheMaxAge => self.patch_cache_max_age,
         }
     }
 
@@ -172,6 +411,151 @@ impl Data {
         match opt {
             USizeOpt::MaxAge => self.max_age = value,
             USizeOpt::Timeout => self.timeout = value,
+            USizeOpt::WatchInterval => self.watch_interval = value,
+            USizeOpt::PatchBufferEntries => self.patch_buffer_entries = value,
+            USizeOpt::PatchBufferBytes => self.patch_buffer_bytes = value,
+            USizeOpt::PatchBufferMaxAge => self.patch_buffer_max_age = value,
+            USizeOpt::PatchCacheEntries => self.patch_cache_entries = value,
+            USizeOpt::PatchCacheBytes => self.patch_cache_bytes = value,
+            USizeOpt::PatchCacheMaxAge => self.patch_cache_max_age = value,
+        }
+    }
+
+    /// Gets a boolean configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to retrieve
+    ///
+    /// # Returns
+    /// The requested boolean value.
+    pub fn bool(&self, opt: BoolOpt) -> bool {
+        match opt {
+            BoolOpt::ApplyThreeWay => self.apply_three_way,
+            BoolOpt::ApplySignoff => self.apply_signoff,
+            BoolOpt::WatchBell => self.watch_bell,
+        }
+    }
+
+    /// Sets a boolean configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to set
+    /// * `value` - The new boolean value
+    pub fn set_bool(&mut self, opt: BoolOpt, value: bool) {
+        match opt {
+            BoolOpt::ApplyThreeWay => self.apply_three_way = value,
+            BoolOpt::ApplySignoff => self.apply_signoff = value,
+            BoolOpt::WatchBell => self.watch_bell = value,
+        }
+    }
+
+    /// Gets a string configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to retrieve
+    ///
+    /// # Returns
+    /// The requested string value.
+    pub fn str(&self, opt: StrOpt) -> ArcStr {
+        match opt {
+            StrOpt::UserName => self.user_name.clone(),
+            StrOpt::UserEmail => self.user_email.clone(),
+            StrOpt::SendmailCommand => self.sendmail_command.clone(),
+            StrOpt::SmtpServer => self.smtp_server.clone(),
+            StrOpt::LoreDomain => self.lore_domain.clone(),
+        }
+    }
+
+    /// Sets a string configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to set
+    /// * `value` - The new string value
+    pub fn set_str(&mut self, opt: StrOpt, value: ArcStr) {
+        match opt {
+            StrOpt::UserName => self.user_name = value,
+            StrOpt::UserEmail => self.user_email = value,
+            StrOpt::SendmailCommand => self.sendmail_command = value,
+            StrOpt::SmtpServer => self.smtp_server = value,
+            StrOpt::LoreDomain => self.lore_domain = value,
+        }
+    }
+
+    /// Gets a string list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to retrieve
+    ///
+    /// # Returns
+    /// The requested string list.
+    pub fn list(&self, opt: ListOpt) -> Vec<ArcStr> {
+        match opt {
+            ListOpt::ExtraArchives => self.extra_archives.clone(),
+            ListOpt::Subsystems => self.subsystems.clone(),
+            ListOpt::WatchedLists => self.watched_lists.clone(),
+        }
+    }
+
+    /// Sets a string list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to set
+    /// * `values` - The new string list
+    pub fn set_list(&mut self, opt: ListOpt, values: Vec<ArcStr>) {
+        match opt {
+            ListOpt::ExtraArchives => self.extra_archives = values,
+            ListOpt::Subsystems => self.subsystems = values,
+            ListOpt::WatchedLists => self.watched_lists = values,
+        }
+    }
+
+    /// Gets a per-mailing-list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to retrieve
+    ///
+    /// # Returns
+    /// The requested values, by list name.
+    pub fn map(&self, opt: MapOpt) -> HashMap<String, Vec<ArcStr>> {
+        match opt {
+            MapOpt::PathFilters => self.path_filters.clone(),
+            MapOpt::WatchedSearches => self.watched_searches.clone(),
+        }
+    }
+
+    /// Sets a per-mailing-list configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to set
+    /// * `values` - The new values, by list name
+    pub fn set_map(&mut self, opt: MapOpt, values: HashMap<String, Vec<ArcStr>>) {
+        match opt {
+            MapOpt::PathFilters => self.path_filters = values,
+            MapOpt::WatchedSearches => self.watched_searches = values,
+        }
+    }
+
+    /// Gets a mail backend configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to retrieve
+    ///
+    /// # Returns
+    /// The requested mail backend value.
+    pub fn mail_backend(&self, opt: MailBackendOpt) -> MailBackend {
+        match opt {
+            MailBackendOpt::SendBackend => self.send_backend,
+        }
+    }
+
+    /// Sets a mail backend configuration value.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to set
+    /// * `backend` - The new mail backend value
+    pub fn set_mail_backend(&mut self, opt: MailBackendOpt, backend: MailBackend) {
+        match opt {
+            MailBackendOpt::SendBackend => self.send_backend = backend,
         }
     }
 
@@ -214,10 +598,44 @@ mod tests {
         );
         assert_eq!(data.usize(USizeOpt::MaxAge), 0);
         assert_eq!(data.usize(USizeOpt::Timeout), 30);
+        assert_eq!(data.usize(USizeOpt::PatchBufferEntries), 50);
+        assert_eq!(data.usize(USizeOpt::PatchCacheBytes), 0);
         assert_eq!(
             data.path(PathOpt::CachePath).to_str().unwrap(),
             "/tmp/patch-hub/cache"
         );
+        assert_eq!(data.path(PathOpt::RepoPath).to_str().unwrap(), ".");
+        assert!(!data.bool(BoolOpt::ApplyThreeWay));
+        assert!(!data.bool(BoolOpt::ApplySignoff));
+        assert_eq!(
+            data.path(PathOpt::ReplyDir).to_str().unwrap(),
+            "/tmp/patch-hub/replies"
+        );
+        assert!(data.str(StrOpt::UserName).is_empty());
+        assert!(data.str(StrOpt::UserEmail).is_empty());
+        assert_eq!(
+            data.mail_backend(MailBackendOpt::SendBackend),
+            MailBackend::DryRun
+        );
+        assert_eq!(
+            data.path(PathOpt::OutboxDir).to_str().unwrap(),
+            "/tmp/patch-hub/outbox"
+        );
+        assert_eq!(
+            data.str(StrOpt::SmtpServer),
+            ArcStr::from("localhost:25")
+        );
+        assert_eq!(
+            data.str(StrOpt::LoreDomain),
+            ArcStr::from("https://lore.kernel.org")
+        );
+        assert!(data.list(ListOpt::ExtraArchives).is_empty());
+        assert!(data.list(ListOpt::Subsystems).is_empty());
+        assert!(data.map(MapOpt::PathFilters).is_empty());
+        assert!(data.list(ListOpt::WatchedLists).is_empty());
+        assert!(data.map(MapOpt::WatchedSearches).is_empty());
+        assert_eq!(data.usize(USizeOpt::WatchInterval), 300);
+        assert!(data.bool(BoolOpt::WatchBell));
     }
 
     #[test]
@@ -244,6 +662,80 @@ mod tests {
         // Test timeout
         data.set_usize(USizeOpt::Timeout, 120);
         assert_eq!(data.usize(USizeOpt::Timeout), 120);
+
+        // Test apply options
+        data.set_bool(BoolOpt::ApplyThreeWay, true);
+        assert!(data.bool(BoolOpt::ApplyThreeWay));
+        data.set_bool(BoolOpt::ApplySignoff, true);
+        assert!(data.bool(BoolOpt::ApplySignoff));
+
+        // Test identity
+        data.set_str(StrOpt::UserName, ArcStr::from("Jane Doe"));
+        assert_eq!(data.str(StrOpt::UserName), ArcStr::from("Jane Doe"));
+        data.set_str(StrOpt::UserEmail, ArcStr::from("jane@example.com"));
+        assert_eq!(data.str(StrOpt::UserEmail), ArcStr::from("jane@example.com"));
+
+        // Test mail backend
+        data.set_mail_backend(MailBackendOpt::SendBackend, MailBackend::Smtp);
+        assert_eq!(
+            data.mail_backend(MailBackendOpt::SendBackend),
+            MailBackend::Smtp
+        );
+
+        // Test archives
+        let archives = vec![ArcStr::from("https://inbox.example.com")];
+        data.set_list(ListOpt::ExtraArchives, archives.clone());
+        assert_eq!(data.list(ListOpt::ExtraArchives), archives);
+
+        // Test subsystems
+        let subsystems = vec![ArcStr::from("MEMORY MANAGEMENT - CORE")];
+        data.set_list(ListOpt::Subsystems, subsystems.clone());
+        assert_eq!(data.list(ListOpt::Subsystems), subsystems);
+
+        // Test path filters
+        let filters = HashMap::from([(
+            "amd-gfx".to_string(),
+            vec![ArcStr::from("drivers/gpu/drm/amd/")],
+        )]);
+        data.set_map(MapOpt::PathFilters, filters.clone());
+        assert_eq!(data.map(MapOpt::PathFilters), filters);
+
+        // Test watch options
+        let lists = vec![ArcStr::from("linux-mm")];
+        data.set_list(ListOpt::WatchedLists, lists.clone());
+        assert_eq!(data.list(ListOpt::WatchedLists), lists);
+        let searches = HashMap::from([(
+            "mine".to_string(),
+            vec![ArcStr::from("f:jane@example.com")],
+        )]);
+        data.set_map(MapOpt::WatchedSearches, searches.clone());
+        assert_eq!(data.map(MapOpt::WatchedSearches), searches);
+        data.set_usize(USizeOpt::WatchInterval, 0);
+        assert_eq!(data.usize(USizeOpt::WatchInterval), 0);
+        data.set_bool(BoolOpt::WatchBell, false);
+        assert!(!data.bool(BoolOpt::WatchBell));
+    }
+
+    #[test]
+    fn test_data_path_filters_toml() {
+        let content = r#"
+cache_path = "/tmp/patch-hub/cache"
+log_dir = "/tmp/patch-hub/logs"
+log_level = "Warning"
+max_age = 0
+timeout = 30
+patch_renderer = "None"
+
+[path_filters]
+amd-gfx = ["drivers/gpu/drm/amd/", "include/drm/amd_asic_type.h"]
+"#;
+        let data: Data = toml::from_str(content).unwrap();
+        let filters = data.map(MapOpt::PathFilters);
+        assert_eq!(filters["amd-gfx"].len(), 2);
+
+        let saved = toml::to_string_pretty(&data).unwrap();
+        let reloaded: Data = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.map(MapOpt::PathFilters), filters);
     }
 
     #[test]
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
 use tokio::sync::oneshot;
 
-use crate::{ArcPath, log::LogLevel};
+use crate::{ArcPath, ArcStr, log::LogLevel};
 
-use super::data::{PathOpt, Renderer, RendererOpt, USizeOpt};
+use super::data::{
+    BoolOpt, ListOpt, MailBackend, MailBackendOpt, MapOpt, PathOpt, Renderer, RendererOpt, StrOpt,
+    USizeOpt,
+};
 
 /// Messages that can be sent to the configuration actor.
 ///
@@ -59,6 +63,76 @@ pub enum Message {
         /// The new numeric value
         size: usize,
     },
+    /// Get a boolean configuration value
+    GetBool {
+        /// The boolean option to retrieve
+        opt: BoolOpt,
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<bool>,
+    },
+    /// Set a boolean configuration value
+    SetBool {
+        /// The boolean option to set
+        opt: BoolOpt,
+        /// The new boolean value
+        value: bool,
+    },
+    /// Get a string configuration value
+    GetStr {
+        /// The string option to retrieve
+        opt: StrOpt,
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<ArcStr>,
+    },
+    /// Set a string configuration value
+    SetStr {
+        /// The string option to set
+        opt: StrOpt,
+        /// The new string value
+        value: ArcStr,
+    },
+    /// Get a string list configuration value
+    GetList {
+        /// The string list option to retrieve
+        opt: ListOpt,
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<Vec<ArcStr>>,
+    },
+    /// Set a string list configuration value
+    SetList {
+        /// The string list option to set
+        opt: ListOpt,
+        /// The new string list
+        values: Vec<ArcStr>,
+    },
+    /// Get a per-mailing-list configuration value
+    GetMap {
+        /// The per-list option to retrieve
+        opt: MapOpt,
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<HashMap<String, Vec<ArcStr>>>,
+    },
+    /// Set a per-mailing-list configuration value
+    SetMap {
+        /// The per-list option to set
+        opt: MapOpt,
+        /// The new values, by list name
+        values: HashMap<String, Vec<ArcStr>>,
+    },
+    /// Get a mail backend configuration value
+    GetMailBackend {
+        /// The mail backend option to retrieve
+        opt: MailBackendOpt,
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<MailBackend>,
+    },
+    /// Set a mail backend configuration value
+    SetMailBackend {
+        /// The mail backend option to set
+        opt: MailBackendOpt,
+        /// The new mail backend value
+        backend: MailBackend,
+    },
     /// Get a renderer configuration value
     GetRenderer {
         /// The renderer option to retrieve
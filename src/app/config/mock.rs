@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::{ArcPath, log::LogLevel};
-use crate::app::config::{Data, PathOpt, Renderer, RendererOpt, USizeOpt};
+use crate::{ArcPath, ArcStr, log::LogLevel};
+use crate::app::config::{
+    BoolOpt, Data, ListOpt, MailBackend, MailBackendOpt, MapOpt, PathOpt, Renderer, RendererOpt, StrOpt, USizeOpt,
+};
 
 /// Mock implementation of the Config actor for testing purposes.
 ///
@@ -110,6 +113,126 @@ impl Mock {
         data.set_usize(opt, value);
     }
 
+    /// Gets a boolean configuration value.
+    /// Mock implementation retrieves the value from stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to retrieve
+    ///
+    /// # Returns
+    /// The requested boolean value
+    pub async fn bool(&self, opt: BoolOpt) -> bool {
+        let data = self.data.lock().await;
+        data.bool(opt)
+    }
+
+    /// Sets a boolean configuration value.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The boolean option to set
+    /// * `value` - The new boolean value
+    pub async fn set_bool(&self, opt: BoolOpt, value: bool) {
+        let mut data = self.data.lock().await;
+        data.set_bool(opt, value);
+    }
+
+    /// Gets a string configuration value.
+    /// Mock implementation retrieves the value from stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to retrieve
+    ///
+    /// # Returns
+    /// The requested string value
+    pub async fn str(&self, opt: StrOpt) -> ArcStr {
+        let data = self.data.lock().await;
+        data.str(opt)
+    }
+
+    /// Sets a string configuration value.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The string option to set
+    /// * `value` - The new string value
+    pub async fn set_str(&self, opt: StrOpt, value: ArcStr) {
+        let mut data = self.data.lock().await;
+        data.set_str(opt, value);
+    }
+
+    /// Gets a string list configuration value.
+    /// Mock implementation retrieves the value from stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to retrieve
+    ///
+    /// # Returns
+    /// The requested string list
+    pub async fn list(&self, opt: ListOpt) -> Vec<ArcStr> {
+        let data = self.data.lock().await;
+        data.list(opt)
+    }
+
+    /// Sets a string list configuration value.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The string list option to set
+    /// * `values` - The new string list
+    pub async fn set_list(&self, opt: ListOpt, values: Vec<ArcStr>) {
+        let mut data = self.data.lock().await;
+        data.set_list(opt, values);
+    }
+
+    /// Gets a per-mailing-list configuration value.
+    /// Mock implementation retrieves the value from stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to retrieve
+    ///
+    /// # Returns
+    /// The requested values, by list name
+    pub async fn map(&self, opt: MapOpt) -> HashMap<String, Vec<ArcStr>> {
+        let data = self.data.lock().await;
+        data.map(opt)
+    }
+
+    /// Sets a per-mailing-list configuration value.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The per-list option to set
+    /// * `values` - The new values, by list name
+    pub async fn set_map(&self, opt: MapOpt, values: HashMap<String, Vec<ArcStr>>) {
+        let mut data = self.data.lock().await;
+        data.set_map(opt, values);
+    }
+
+    /// Gets a mail backend configuration value.
+    /// Mock implementation retrieves the value from stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to retrieve
+    ///
+    /// # Returns
+    /// The requested mail backend value
+    pub async fn mail_backend(&self, opt: MailBackendOpt) -> MailBackend {
+        let data = self.data.lock().await;
+        data.mail_backend(opt)
+    }
+
+    /// Sets a mail backend configuration value.
+    /// Mock implementation updates the stored data.
+    ///
+    /// # Arguments
+    /// * `opt` - The mail backend option to set
+    /// * `backend` - The new mail backend value
+    pub async fn set_mail_backend(&self, opt: MailBackendOpt, backend: MailBackend) {
+        let mut data = self.data.lock().await;
+        data.set_mail_backend(opt, backend);
+    }
+
     /// Gets a renderer configuration value.
     /// Mock implementation retrieves the value from stored data.
     ///
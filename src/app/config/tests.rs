@@ -1,6 +1,6 @@
 use crate::{
-    ArcPath,
-    app::config::{Config, PathOpt, USizeOpt, data::Data},
+    ArcPath, ArcStr,
+    app::config::{Config, ListOpt, MapOpt, PathOpt, USizeOpt, data::Data},
     env::Env,
     fs::Fs,
     log::LogLevel,
@@ -128,6 +128,37 @@ async fn test_actual_config_usize_operations() {
     assert_eq!(retrieved_value, new_value);
 }
 
+#[tokio::test]
+async fn test_actual_config_list_operations() {
+    let env = Env::mock();
+    let fs = Fs::mock();
+    let path = ArcPath::from("test_config.json");
+    let config = Config::spawn(env, fs, path);
+
+    assert!(config.list(ListOpt::ExtraArchives).await.is_empty());
+    let archives = vec![ArcStr::from("https://inbox.example.com")];
+    config
+        .set_list(ListOpt::ExtraArchives, archives.clone())
+        .await;
+    assert_eq!(config.list(ListOpt::ExtraArchives).await, archives);
+}
+
+#[tokio::test]
+async fn test_actual_config_map_operations() {
+    let env = Env::mock();
+    let fs = Fs::mock();
+    let path = ArcPath::from("test_config.json");
+    let config = Config::spawn(env, fs, path);
+
+    assert!(config.map(MapOpt::PathFilters).await.is_empty());
+    let filters = std::collections::HashMap::from([(
+        "amd-gfx".to_string(),
+        vec![ArcStr::from("drivers/gpu/drm/amd/")],
+    )]);
+    config.set_map(MapOpt::PathFilters, filters.clone()).await;
+    assert_eq!(config.map(MapOpt::PathFilters).await, filters);
+}
+
 #[tokio::test]
 async fn test_multiple_path_options() {
     let config = Config::mock(Data::default());
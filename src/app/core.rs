@@ -1,13 +1,23 @@
 use anyhow::Result;
+use std::collections::HashMap;
 use std::env::VarError;
 use std::path::Path;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
-use crate::api::lore::LoreApi;
-use crate::app::cache::{FeedCache, MailingListCache, PatchCache};
-use crate::app::config::{Config, PathOpt, USizeOpt};
+use crate::api::lore::{LoreApi, LorePatchMetadata, LoreSearch};
+use crate::app::apply::{Apply, ApplyOptions, ApplyStatus};
+use crate::app::mail::Mail;
+use crate::app::maintainers::{Maintainers, inbox_search, inbox_slice};
+use crate::app::reply::{Reply, ReplyOptions, Trailer};
+use crate::app::cache::{
+    FeedCache, MailingListCache, PatchCache, ReviewCache, ReviewFilter, ReviewState, ReviewStatus,
+    SeenCache,
+};
+use crate::app::config::{BoolOpt, Config, ListOpt, PathOpt, StrOpt, USizeOpt};
 use crate::app::ui::{NavigationAction, Ui};
+use crate::app::watch::{Watch, WatchUpdate};
 use crate::env::Env;
 use crate::fs::Fs;
 use crate::log::Log;
@@ -22,6 +32,8 @@ use super::message::Message;
 
 const BUFFER_SIZE: usize = 64;
 const SCOPE: &str = "app";
+/// How many feed items are looked at at once when filtering a feed by review state
+const REVIEW_SCAN_CHUNK: usize = 50;
 
 /// Core implementation of the App actor
 #[derive(Debug)]
@@ -50,6 +62,20 @@ pub struct Core {
     feed_cache: FeedCache,
     /// Patch cache actor
     patch_cache: PatchCache,
+    /// Apply actor
+    apply: Apply,
+    /// Review status store
+    review_cache: ReviewCache,
+    /// Store of the patches already opened
+    seen_cache: SeenCache,
+    /// Reply actor
+    reply: Reply,
+    /// Mail actor
+    mail: Mail,
+    /// Maintainers actor
+    maintainers: Maintainers,
+    /// Watch actor
+    watch: Watch,
 }
 
 impl Core {
@@ -102,7 +128,9 @@ impl Core {
 
         // Initialize network and API actors
         let net = Net::spawn(config.clone(), log.clone()).await;
-        let lore = LoreApi::spawn(net.clone());
+        let mut archives = vec![config.str(StrOpt::LoreDomain).await];
+        archives.extend(config.list(ListOpt::ExtraArchives).await);
+        let lore = LoreApi::spawn_with_archives(net.clone(), archives, log.clone());
 
         // Initialize shell and render actors
         let shell = Shell::spawn(log.clone()).await?;
@@ -116,6 +144,49 @@ impl Core {
         let patch_cache =
             PatchCache::spawn(lore.clone(), fs.clone(), config.clone(), log.clone()).await?;
 
+        // Initialize the apply actor on top of the patch cache
+        let apply = Apply::spawn(
+            shell.clone(),
+            patch_cache.clone(),
+            config.clone(),
+            log.clone(),
+        );
+
+        // Initialize the reply actor on top of the patch cache
+        let reply = Reply::spawn(
+            env.clone(),
+            fs.clone(),
+            shell.clone(),
+            patch_cache.clone(),
+            config.clone(),
+            log.clone(),
+        );
+
+        // Initialize the mail actor that sends composed replies
+        let mail = Mail::spawn(fs.clone(), shell.clone(), config.clone(), log.clone());
+
+        // Initialize the maintainers actor, which checks patches against the MAINTAINERS file
+        let maintainers = Maintainers::spawn(
+            fs.clone(),
+            patch_cache.clone(),
+            config.clone(),
+            log.clone(),
+        );
+
+        // Initialize the watch actor, which checks the watched lists and searches for new patches
+        let watch = Watch::spawn(
+            feed_cache.clone(),
+            fs.clone(),
+            config.clone(),
+            log.clone(),
+        );
+
+        // Initialize the review store, kept apart from the feed cache so it survives invalidation
+        let review_cache = ReviewCache::spawn(fs.clone(), config.clone(), log.clone()).await?;
+
+        // Initialize the store of opened patches, kept apart from the feed cache as well
+        let seen_cache = SeenCache::spawn(fs.clone(), config.clone(), log.clone()).await?;
+
         // Load existing cache data
         if let Err(e) = mailing_list_cache.load().await {
             log.warn(SCOPE, &format!("Failed to load mailing list cache: {}", e));
@@ -143,6 +214,13 @@ impl Core {
             mailing_list_cache,
             feed_cache,
             patch_cache,
+            apply,
+            review_cache,
+            seen_cache,
+            reply,
+            mail,
+            maintainers,
+            watch,
         })
     }
 
@@ -158,6 +236,13 @@ impl Core {
             self.feed_cache.clone(),
             self.patch_cache.clone(),
             self.render.clone(),
+            self.apply.clone(),
+            self.review_cache.clone(),
+            self.seen_cache.clone(),
+            self.reply.clone(),
+            self.mail.clone(),
+            self.maintainers.clone(),
+            self.watch.clone(),
         );
 
         let (tx, mut rx) = mpsc::channel(BUFFER_SIZE);
@@ -169,6 +254,16 @@ impl Core {
             // Start with lists view
             let _ = ui.show_lists(0).await;
 
+            // Check the watched feeds in the background, unless the interval is 0; the
+            // channel is closed then, so its branch below never fires
+            let interval = core.config.usize(USizeOpt::WatchInterval).await;
+            let bell = core.config.bool(BoolOpt::WatchBell).await;
+            let mut watch_updates = if interval > 0 && !core.watch.targets().await.is_empty() {
+                core.watch.subscribe(Duration::from_secs(interval as u64)).await
+            } else {
+                mpsc::channel(1).1
+            };
+
             loop {
                 tokio::select! {
                     Some(message) = rx.recv() => {
@@ -191,6 +286,9 @@ impl Core {
                         // Forward UI events to key event handler
                         core.handle_key_event(&ui, ui_event).await;
                     }
+                    Some(update) = watch_updates.recv() => {
+                        let _ = ui.notify(update, bell).await;
+                    }
                     _ = &mut ui_exit => {
                         // UI exited, shutdown
                         let _ = core.handle_shutdown().await;
@@ -208,14 +306,53 @@ impl Core {
 
         match command {
             Command::Lists { page, count } => self.handle_lists_command(page, count).await,
-            Command::Feed { list, page, count } => {
-                self.handle_feed_command(list, page, count).await
-            }
+            Command::Feed {
+                list,
+                page,
+                count,
+                review,
+            } => self.handle_feed_command(list, page, count, review).await,
+            Command::Search {
+                search,
+                page,
+                count,
+            } => self.handle_search_command(search, page, count).await,
+            Command::Inbox { page, count } => self.handle_inbox_command(page, count).await,
+            Command::Maintainers { filter } => self.handle_maintainers_command(filter).await,
+            Command::Watch { once, interval } => self.handle_watch_command(once, interval).await,
             Command::Patch {
                 list,
                 message_id,
                 html,
             } => self.handle_patch_command(list, message_id, html).await,
+            Command::Apply {
+                list,
+                message_id,
+                series,
+                options,
+            } => {
+                self.handle_apply_command(list, message_id, series, options)
+                    .await
+            }
+            Command::Review {
+                message_id,
+                state,
+                clear,
+                note,
+            } => {
+                self.handle_review_command(message_id, state, clear, note)
+                    .await
+            }
+            Command::Reply {
+                list,
+                message_id,
+                trailers,
+                edit,
+            } => {
+                self.handle_reply_command(list, message_id, trailers, edit)
+                    .await
+            }
+            Command::Send { path } => self.handle_send_command(path).await,
         }
     }
 
@@ -253,6 +390,42 @@ impl Core {
             UiEvent::Esc => {
                 let _ = ui.navigate_back().await;
             }
+            UiEvent::ToggleSeries => {
+                let _ = ui.toggle_series().await;
+            }
+            UiEvent::ShowVersions => {
+                let _ = ui.show_versions().await;
+            }
+            UiEvent::MarkVersion => {
+                let _ = ui.mark_version().await;
+            }
+            UiEvent::Interdiff => {
+                let _ = ui.show_interdiff().await;
+            }
+            UiEvent::Apply => {
+                let _ = ui.apply().await;
+            }
+            UiEvent::CycleReview => {
+                let _ = ui.cycle_review().await;
+            }
+            UiEvent::Reply(trailer) => {
+                let _ = ui.reply(trailer).await;
+            }
+            UiEvent::SendReply => {
+                let _ = ui.send_reply().await;
+            }
+            UiEvent::Search(query) => {
+                let _ = ui.search(query).await;
+            }
+            UiEvent::Inbox => {
+                let _ = ui.inbox().await;
+            }
+            UiEvent::NextUnread => {
+                let _ = ui.next_unread().await;
+            }
+            UiEvent::MarkAllRead => {
+                let _ = ui.mark_all_read().await;
+            }
         }
     }
 
@@ -273,6 +446,14 @@ impl Core {
                 &format!("Failed to persist patch metadata cache: {}", e),
             );
         }
+        if let Err(e) = self.review_cache.persist().await {
+            self.log
+                .warn(SCOPE, format!("Failed to persist review store: {}", e));
+        }
+        if let Err(e) = self.seen_cache.persist().await {
+            self.log
+                .warn(SCOPE, format!("Failed to persist seen store: {}", e));
+        }
 
         self.log.info(SCOPE, "Application shutdown complete");
         Ok(())
@@ -304,6 +485,7 @@ impl Core {
         for (i, list) in lists.iter().enumerate() {
             let global_index = start_index + i + 1;
             println!("{}. {} - {}", global_index, list.name, list.description);
+            println!("   Archive: {}", list.archive);
             println!(
                 "   Last update: {}",
                 list.last_update.format("%Y-%m-%d %H:%M:%S UTC")
@@ -315,48 +497,351 @@ impl Core {
     }
 
     /// Handle the feed command to display patch feed for a mailing list using cache
-    pub async fn handle_feed_command(&self, list: ArcStr, page: usize, count: usize) -> Result<()> {
+    pub async fn handle_feed_command(
+        &self,
+        list: ArcStr,
+        page: usize,
+        count: usize,
+        review: Option<ReviewFilter>,
+    ) -> Result<()> {
         println!(
             "Fetching patch feed for '{}' (page {}, count {})...",
             list, page, count
         );
+        self.print_feed_page(list.clone(), &list, page, count, review)
+            .await
+    }
 
-        let start_index = page * count;
-        let end_index = start_index + count;
-        let range = start_index..end_index;
+    /// Handle the search command to display the patches matching a query using cache
+    pub async fn handle_search_command(
+        &self,
+        search: LoreSearch,
+        page: usize,
+        count: usize,
+    ) -> Result<()> {
+        println!(
+            "Searching '{}' in '{}' (page {}, count {})...",
+            search.query, search.list, page, count
+        );
+        let label = search.describe();
+        let key = self.feed_cache.search(search).await;
+        self.print_feed_page(key, &label, page, count, None).await
+    }
 
-        let patches = self.feed_cache.get_slice(list.clone(), range).await?;
+    /// Prints a page of a feed cache entry, which is a mailing list or a search
+    async fn print_feed_page(
+        &self,
+        list: ArcStr,
+        label: &str,
+        page: usize,
+        count: usize,
+        review: Option<ReviewFilter>,
+    ) -> Result<()> {
+        let start_index = page * count;
+        let (patches, reviews) = match review {
+            Some(filter) => {
+                self.review_page(list.clone(), filter, start_index, count)
+                    .await?
+            }
+            None => {
+                let patches = self
+                    .feed_cache
+                    .get_slice(list.clone(), start_index..start_index + count)
+                    .await?;
+                let reviews = self
+                    .review_cache
+                    .get_many(patches.iter().map(|p| p.message_id.clone()).collect())
+                    .await;
+                let patches = patches
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, p)| (start_index + i, p))
+                    .collect();
+                (patches, reviews)
+            }
+        };
 
         if patches.is_empty() {
-            println!("No patch feed found for '{}' on page {}", list, page);
+            println!("No patch feed found for '{}' on page {}", label, page);
             return Ok(());
         }
 
         println!(
-            "Patch Feed for '{}' (Page {}, showing items {} to {}):",
-            list,
+            "Patch Feed for '{}' (Page {}, showing {} {} to {}):",
+            label,
             page,
+            if review.is_some() { "matches" } else { "items" },
             start_index + 1,
             start_index + patches.len()
         );
+        if let Some(filter) = review {
+            println!("Review filter: {}", filter);
+        }
+        let filter = self.feed_cache.path_filter(list.clone()).await;
+        if !filter.is_empty() {
+            let paths: Vec<&str> = filter.iter().map(|path| path.as_ref()).collect();
+            println!(
+                "Path filter: only patches touching {} are shown",
+                paths.join(", ")
+            );
+        }
+        let skipped = self.feed_cache.skipped(list.clone()).await;
+        if skipped > 0 {
+            println!(
+                "Skipped {} feed entries that could not be parsed (see the log for details)",
+                skipped
+            );
+        }
         println!();
 
-        for (i, patch) in patches.iter().enumerate() {
-            let global_index = start_index + i + 1;
-            println!("{}. {}", global_index, patch.title);
-            println!("   Author: {} <{}>", patch.author, patch.email);
-            println!(
-                "   Date: {}",
-                patch.last_update.format("%Y-%m-%d %H:%M:%S UTC")
+        for (i, patch) in patches.iter() {
+            print_patch(i + 1, patch, &list);
+            if let Some(status) = reviews.get(&patch.message_id) {
+                print_review_status(status);
+            }
+            println!();
+        }
+
+        Ok(())
+    }
+
+    /// Collects a page of the patches of a feed whose review state matches a filter
+    ///
+    /// Pages count matching patches only, so the feed is walked from its start until
+    /// the page is filled or the feed runs out.
+    ///
+    /// # Returns
+    /// The matching patches with their index in the feed, and their review statuses.
+    async fn review_page(
+        &self,
+        list: ArcStr,
+        filter: ReviewFilter,
+        start_index: usize,
+        count: usize,
+    ) -> Result<(
+        Vec<(usize, LorePatchMetadata)>,
+        HashMap<ArcStr, ReviewStatus>,
+    )> {
+        let wanted = start_index + count;
+        let chunk = count.max(REVIEW_SCAN_CHUNK);
+        let mut matches = Vec::new();
+        let mut reviews = HashMap::new();
+        let mut scanned = 0;
+        while matches.len() < wanted {
+            let patches = self
+                .feed_cache
+                .get_slice(list.clone(), scanned..scanned + chunk)
+                .await?;
+            let found = self
+                .review_cache
+                .get_many(patches.iter().map(|p| p.message_id.clone()).collect())
+                .await;
+            let fetched = patches.len();
+            for (i, patch) in patches.into_iter().enumerate() {
+                let state = found.get(&patch.message_id).and_then(|s| s.state);
+                if matches.len() < wanted && filter.matches(state) {
+                    matches.push((scanned + i, patch));
+                }
+            }
+            reviews.extend(found);
+            if fetched < chunk {
+                break;
+            }
+            scanned += fetched;
+        }
+        let matches = matches.split_off(start_index.min(matches.len()));
+        Ok((matches, reviews))
+    }
+
+    /// Handle the inbox command to display the patches touching the declared subsystems
+    ///
+    /// Patches come from a search of every list, and each one is then checked against
+    /// the `MAINTAINERS` entries, so the ones the search matched too broadly are left out.
+    /// Pages count the patches that are shown only, and each one keeps its index in the
+    /// search feed.
+    pub async fn handle_inbox_command(&self, page: usize, count: usize) -> Result<()> {
+        let declared = self.maintainers.declared().await?;
+        if declared.is_empty() {
+            anyhow::bail!(
+                "No subsystem declared: add the names of your MAINTAINERS entries to \
+                 `subsystems` in the configuration (see `patch-hub maintainers`)"
             );
-            println!("   Message ID: {}", patch.message_id);
-            println!("   Link: {}", patch.link);
+        }
+        let Some(search) = inbox_search(&declared) else {
+            anyhow::bail!("The declared subsystems have no file pattern that can be searched");
+        };
+        let names: Vec<&str> = declared.iter().map(|s| s.name.as_ref()).collect();
+        let label = names.join(", ");
+        println!(
+            "Fetching the inbox of {} (page {}, count {})...",
+            label, page, count
+        );
+
+        let key = self.feed_cache.search(search).await;
+        let start_index = page * count;
+        let patches = inbox_slice(
+            &self.maintainers,
+            &self.feed_cache,
+            key.clone(),
+            start_index..start_index + count,
+        )
+        .await?;
+        if patches.is_empty() {
+            println!("No patches found in the inbox on page {}", page);
+            return Ok(());
+        }
+        let reviews = self
+            .review_cache
+            .get_many(patches.iter().map(|p| p.patch.message_id.clone()).collect())
+            .await;
+
+        println!(
+            "Inbox of {} (Page {}, showing matches {} to {}):",
+            label,
+            page,
+            start_index + 1,
+            start_index + patches.len()
+        );
+        println!();
+
+        for inbox_patch in &patches {
+            let patch = &inbox_patch.patch;
+            let subsystems = match &inbox_patch.subsystems {
+                Ok(subsystems) => {
+                    let names: Vec<&str> = subsystems.iter().map(|s| s.as_ref()).collect();
+                    names.join(", ")
+                }
+                Err(e) => {
+                    self.log.warn(
+                        SCOPE,
+                        format!(
+                            "Failed to check {} against MAINTAINERS: {}",
+                            patch.message_id, e
+                        ),
+                    );
+                    String::from("unknown (the patch could not be checked)")
+                }
+            };
+            print_patch(inbox_patch.index + 1, patch, &key);
+            println!("   Subsystems: {}", subsystems);
+            if let Some(status) = reviews.get(&patch.message_id) {
+                print_review_status(status);
+            }
             println!();
         }
 
         Ok(())
     }
 
+    /// Handle the maintainers command to display the entries of the MAINTAINERS file
+    pub async fn handle_maintainers_command(&self, filter: Option<ArcStr>) -> Result<()> {
+        let subsystems = self.maintainers.subsystems().await?;
+        let declared = self.config.list(ListOpt::Subsystems).await;
+        let filter = filter.map(|filter| filter.to_lowercase());
+        let matches: Vec<_> = subsystems
+            .iter()
+            .filter(|s| {
+                filter
+                    .as_ref()
+                    .is_none_or(|filter| s.name.to_lowercase().contains(filter))
+            })
+            .collect();
+        if matches.is_empty() {
+            println!("No MAINTAINERS entry matches '{}'", filter.unwrap_or_default());
+            return Ok(());
+        }
+
+        println!(
+            "MAINTAINERS entries ({} of {}, * marks the ones declared in `subsystems`):",
+            matches.len(),
+            subsystems.len()
+        );
+        println!();
+        for subsystem in matches {
+            let mark = if declared
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&subsystem.name))
+            {
+                "*"
+            } else {
+                " "
+            };
+            println!("{} {}", mark, subsystem.name);
+            for maintainer in &subsystem.maintainers {
+                println!("   Maintainer: {}", maintainer);
+            }
+            for reviewer in &subsystem.reviewers {
+                println!("   Reviewer: {}", reviewer);
+            }
+            for list in &subsystem.lists {
+                println!("   List: {}", list);
+            }
+            if !subsystem.files.is_empty() {
+                let files: Vec<&str> = subsystem.files.iter().map(|f| f.as_ref()).collect();
+                println!("   Files: {}", files.join(", "));
+            }
+            println!();
+        }
+
+        Ok(())
+    }
+
+    /// Handle the watch command to print the patches arriving in the watched feeds
+    ///
+    /// Feeds are checked right away and then every `interval` seconds (the configured
+    /// `watch_interval` by default) until Ctrl-C, or only once with `once`. The first
+    /// check of a feed only records where it stands, so patches show up from then on.
+    pub async fn handle_watch_command(&self, once: bool, interval: Option<usize>) -> Result<()> {
+        let targets = self.watch.targets().await;
+        if targets.is_empty() {
+            anyhow::bail!(
+                "Nothing to watch: add lists to `watched_lists` or searches to \
+                 `watched_searches` in the configuration"
+            );
+        }
+        let names: Vec<&str> = targets.iter().map(|t| t.name.as_ref()).collect();
+        println!("Watching {}...", names.join(", "));
+
+        if once {
+            let updates = self.watch.check().await?;
+            if updates.is_empty() {
+                println!("No new patches since the last check");
+            }
+            for update in &updates {
+                print_watch_update(update);
+            }
+            return Ok(());
+        }
+
+        let interval = match interval {
+            Some(interval) => interval,
+            None => self.config.usize(USizeOpt::WatchInterval).await,
+        };
+        if interval == 0 {
+            anyhow::bail!("The watch interval is 0, give one with --interval");
+        }
+        println!(
+            "Checking every {} seconds, press Ctrl-C to stop",
+            interval
+        );
+        println!();
+        let mut updates = self
+            .watch
+            .subscribe(Duration::from_secs(interval as u64))
+            .await;
+        loop {
+            tokio::select! {
+                update = updates.recv() => match update {
+                    Some(update) => print_watch_update(&update),
+                    None => break,
+                },
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+
+        Ok(())
+    }
+
     /// Handle the patch command to display patch content
     pub async fn handle_patch_command(
         &self,
@@ -388,4 +873,163 @@ impl Core {
 
         Ok(())
     }
+
+    /// Handle the apply command to apply a patch or series to the configured repository
+    pub async fn handle_apply_command(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+        series: bool,
+        options: ApplyOptions,
+    ) -> Result<()> {
+        let message_ids = if series {
+            println!(
+                "Looking up the series of '{}' in the feed of '{}'...",
+                message_id, list
+            );
+            let revisions = self
+                .feed_cache
+                .get_revisions(list.clone(), message_id.clone())
+                .await?;
+            let Some(series) = revisions.iter().find(|s| s.contains(&message_id)) else {
+                anyhow::bail!(
+                    "Message '{}' not found in the feed of '{}'",
+                    message_id,
+                    list
+                );
+            };
+            if !series.is_complete() {
+                anyhow::bail!(
+                    "Series '{}' is missing patches {:?}",
+                    series.title,
+                    series.missing()
+                );
+            }
+            series.patch_ids()
+        } else {
+            vec![message_id]
+        };
+
+        println!(
+            "Applying {} patch(es) from '{}'...",
+            message_ids.len(),
+            list
+        );
+        let report = self.apply.apply(list, message_ids, options).await?;
+        println!("{}", report);
+
+        match report.status {
+            ApplyStatus::Applied => Ok(()),
+            ApplyStatus::Conflict(conflict) => Err(anyhow::anyhow!(
+                "Patch {} '{}' did not apply",
+                conflict.index,
+                conflict.subject
+            )),
+        }
+    }
+
+    /// Handle the review command to show or update the review status of a patch
+    pub async fn handle_review_command(
+        &self,
+        message_id: ArcStr,
+        state: Option<ReviewState>,
+        clear: bool,
+        note: Option<ArcStr>,
+    ) -> Result<()> {
+        if clear && state.is_some() {
+            anyhow::bail!("Cannot set and clear the review state at the same time");
+        }
+
+        if clear || state.is_some() {
+            self.review_cache
+                .set_state(message_id.clone(), state)
+                .await?;
+        }
+        if let Some(note) = note {
+            self.review_cache.set_note(message_id.clone(), note).await?;
+        }
+
+        match self.review_cache.get(message_id.clone()).await {
+            Some(status) => {
+                println!("Message ID: {}", message_id);
+                print_review_status(&status);
+            }
+            None => println!("No review status recorded for '{}'", message_id),
+        }
+
+        Ok(())
+    }
+
+    /// Handle the reply command to compose a reply to a patch
+    pub async fn handle_reply_command(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+        trailers: Vec<Trailer>,
+        edit: bool,
+    ) -> Result<()> {
+        println!(
+            "Composing a reply to '{}' from '{}'...",
+            message_id, list
+        );
+        let draft = self
+            .reply
+            .compose(list, message_id, ReplyOptions { trailers, edit })
+            .await?;
+        println!("{}", draft);
+        Ok(())
+    }
+
+    /// Handle the send command to send a composed reply
+    pub async fn handle_send_command(&self, path: ArcPath) -> Result<()> {
+        println!("Sending '{}'...", path.display());
+        let report = self.mail.send(path).await?;
+        println!("{}", report);
+        Ok(())
+    }
+}
+
+/// Print a patch of a feed in the CLI listing format
+fn print_patch(index: usize, patch: &LorePatchMetadata, list: &ArcStr) {
+    println!("{}. {}", index, patch.title);
+    println!("   Author: {} <{}>", patch.author, patch.email);
+    println!(
+        "   Date: {}",
+        patch.last_update.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("   Message ID: {}", patch.message_id);
+    // Search results span lists, and `patch`/`apply` need to know which one
+    if patch.list != *list {
+        println!("   List: {}", patch.list);
+    }
+    println!("   Link: {}", patch.link);
+}
+
+/// Print the patches that arrived in a watched feed in the CLI listing format
+fn print_watch_update(update: &WatchUpdate) {
+    println!(
+        "{} new patch(es) in {} ({}):",
+        update.patches.len(),
+        update.target.name,
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!();
+    for (i, patch) in update.patches.iter().enumerate() {
+        print_patch(i + 1, patch, &update.target.key());
+        println!();
+    }
+}
+
+/// Print the review state and note of a patch in the CLI listing format
+fn print_review_status(status: &ReviewStatus) {
+    if let Some(state) = status.state {
+        println!(
+            "   Review: {} ({})",
+            state,
+            status.updated.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    if !status.note.is_empty() {
+        println!("   Note: {}", status.note);
+    }
 }
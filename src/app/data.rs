@@ -1,4 +1,8 @@
-use crate::ArcStr;
+use crate::{ArcPath, ArcStr};
+use crate::api::lore::LoreSearch;
+use crate::app::apply::ApplyOptions;
+use crate::app::cache::{ReviewFilter, ReviewState};
+use crate::app::reply::Trailer;
 
 /// Commands that can be executed by the App actor
 #[derive(Debug, Clone)]
@@ -10,6 +14,26 @@ pub enum Command {
         list: ArcStr,
         page: usize,
         count: usize,
+        /// Only show patches whose review status matches
+        review: Option<ReviewFilter>,
+    },
+    /// Search the archive and show the matching patches like a feed
+    Search {
+        search: LoreSearch,
+        page: usize,
+        count: usize,
+    },
+    /// Show the patches of every list that touch the subsystems the user declared
+    Inbox { page: usize, count: usize },
+    /// Show the entries of the `MAINTAINERS` file, optionally only those whose name
+    /// contains a filter
+    Maintainers { filter: Option<ArcStr> },
+    /// Print the patches arriving in the watched lists and searches
+    Watch {
+        /// Check once and exit instead of checking periodically
+        once: bool,
+        /// Seconds between two checks, the configured interval if `None`
+        interval: Option<usize>,
     },
     /// Get the content of a patch from the feed
     Patch {
@@ -17,6 +41,34 @@ pub enum Command {
         message_id: ArcStr,
         html: bool,
     },
+    /// Apply a patch, or the whole series it belongs to, to the configured repository
+    Apply {
+        list: ArcStr,
+        message_id: ArcStr,
+        series: bool,
+        options: ApplyOptions,
+    },
+    /// Show or update the review status of a patch
+    Review {
+        message_id: ArcStr,
+        /// New review state, if any
+        state: Option<ReviewState>,
+        /// Clear the review state
+        clear: bool,
+        /// New review note, if any
+        note: Option<ArcStr>,
+    },
+    /// Compose a reply to a patch and save it as an mbox
+    Reply {
+        list: ArcStr,
+        message_id: ArcStr,
+        /// Trailers to add after the quoted patch
+        trailers: Vec<Trailer>,
+        /// Whether to open the reply in `$EDITOR`
+        edit: bool,
+    },
+    /// Send a composed message through the configured mail backend
+    Send { path: ArcPath },
 }
 
 /// Application state managed by the App actor
@@ -0,0 +1,110 @@
+mod core;
+mod data;
+mod message;
+mod mock;
+mod smtp;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Context;
+use tokio::sync::mpsc::Sender;
+
+use crate::ArcPath;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+
+pub use data::{Outgoing, SendReport, outbox_file_name, prepare_message};
+pub use smtp::{deliver, encode_data};
+
+/// The mail actor that sends composed replies.
+///
+/// A message saved by the [`Reply`](crate::app::reply::Reply) actor is read back,
+/// given a `Date` and `Message-ID` if it lacks them, and handed to the backend
+/// selected by `MailBackendOpt::SendBackend`:
+///
+/// - `DryRun` writes it under `PathOpt::OutboxDir` and sends nothing
+/// - `Sendmail` pipes it to `StrOpt::SendmailCommand`
+/// - `GitSendEmail` hands it to `git send-email` in the configured repository
+/// - `Smtp` delivers it to `StrOpt::SmtpServer` over plain SMTP
+///
+/// # Examples
+/// ```ignore
+/// let mail = Mail::spawn(fs, shell, config, log);
+/// let report = mail.send(draft.path).await?;
+/// println!("{report}");
+/// ```
+///
+/// # Thread Safety
+/// This type is designed to be safely shared between threads. Cloning is cheap as it only
+/// copies the channel sender or mock reference.
+#[derive(Debug, Clone)]
+pub enum Mail {
+    /// A real mail actor that sends through the configured backend
+    Actual(Sender<message::Message>),
+    /// A mock implementation for testing that records sent paths in memory
+    Mock(mock::Mock),
+}
+
+impl Mail {
+    /// Creates a new mail instance and spawns its actor.
+    ///
+    /// # Arguments
+    /// * `fs` - The filesystem actor for reading messages and writing the outbox
+    /// * `shell` - The shell actor for running sendmail and git
+    /// * `config` - The configuration actor for the backend settings
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new mail instance with a spawned actor.
+    pub fn spawn(fs: Fs, shell: Shell, config: Config, log: Log) -> Self {
+        let (mail, _) = core::Core::new(fs, shell, config, log).spawn();
+        mail
+    }
+
+    /// Creates a new mock mail instance for testing.
+    ///
+    /// # Returns
+    /// A new mock mail instance that records sent paths in memory.
+    pub fn mock() -> Self {
+        Self::Mock(mock::Mock::new())
+    }
+
+    /// Sends the message stored in a file through the configured backend.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the message, such as a reply draft
+    ///
+    /// # Returns
+    /// A report of what was sent, or an error if the message cannot be read, lacks a
+    /// sender or recipients, or the backend rejects it.
+    pub async fn send(&self, path: ArcPath) -> anyhow::Result<SendReport> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Send { path, tx })
+                    .await
+                    .context("Sending message with Mail actor")
+                    .expect("mail actor died");
+                rx.await
+                    .context("Awaiting response for message sending with Mail actor")
+                    .expect("mail actor died")
+            }
+            Self::Mock(mock) => mock.send(path).await,
+        }
+    }
+
+    /// Gets the paths of all messages sent through the mock implementation.
+    /// This method is only available for mock instances and is useful for testing.
+    ///
+    /// # Returns
+    /// A vector of the sent paths, or None if this is not a mock instance.
+    pub async fn get_sent(&self) -> Option<Vec<ArcPath>> {
+        match self {
+            Self::Mock(mock) => Some(mock.get_sent().await),
+            Self::Actual(_) => None,
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::Context;
+use chrono::Utc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::JoinHandle;
+
+use crate::app::config::{Config, MailBackend, MailBackendOpt, PathOpt, StrOpt, USizeOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+use crate::{ArcPath, ArcSlice, ArcStr};
+
+use super::data::{Outgoing, SendReport, outbox_file_name, prepare_message};
+use super::message::Message;
+use super::smtp;
+
+const SCOPE: &str = "app.mail";
+
+/// The core implementation of the mail actor.
+///
+/// Messages are read from disk, prepared once and handed to the backend
+/// selected in the configuration.
+pub struct Core {
+    /// The filesystem actor for reading messages and writing the outbox
+    fs: Fs,
+    /// The shell actor for running sendmail and git
+    shell: Shell,
+    /// The configuration actor for the backend settings
+    config: Config,
+    /// The logging actor
+    log: Log,
+}
+
+impl Core {
+    /// Creates a new mail actor core.
+    ///
+    /// # Arguments
+    /// * `fs` - The filesystem actor for reading messages and writing the outbox
+    /// * `shell` - The shell actor for running sendmail and git
+    /// * `config` - The configuration actor for the backend settings
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new mail actor core instance.
+    pub fn new(fs: Fs, shell: Shell, config: Config, log: Log) -> Self {
+        Self {
+            fs,
+            shell,
+            config,
+            log,
+        }
+    }
+
+    /// Spawns the mail actor and returns the handle and join handle.
+    ///
+    /// # Returns
+    /// A tuple containing the mail actor handle and the join handle for the spawned task.
+    pub fn spawn(self) -> (super::Mail, JoinHandle<anyhow::Result<()>>) {
+        let (tx, rx) = mpsc::channel(32);
+        let handle = super::Mail::Actual(tx);
+        let join_handle = tokio::spawn(self.run(rx));
+        (handle, join_handle)
+    }
+
+    /// Runs the mail actor event loop.
+    ///
+    /// # Arguments
+    /// * `rx` - The message receiver
+    ///
+    /// # Returns
+    /// Result indicating success or failure of the actor
+    async fn run(self, mut rx: Receiver<Message>) -> anyhow::Result<()> {
+        while let Some(message) = rx.recv().await {
+            match message {
+                Message::Send { path, tx } => {
+                    let result = self.handle_send(path).await;
+                    let _ = tx.send(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the message stored at `path` through the configured backend.
+    async fn handle_send(&self, path: ArcPath) -> anyhow::Result<SendReport> {
+        let raw = self.read(&path).await?;
+        let now = Utc::now();
+        let outgoing =
+            prepare_message(&raw, now).with_context(|| format!("Preparing {}", path.display()))?;
+
+        let backend = self.config.mail_backend(MailBackendOpt::SendBackend).await;
+        self.log.info(
+            SCOPE,
+            format!(
+                "Sending {} to {} recipient(s) with {:?}",
+                outgoing.message_id,
+                outgoing.recipients.len(),
+                backend
+            ),
+        );
+
+        let mut report = SendReport {
+            backend,
+            message_id: outgoing.message_id.clone(),
+            subject: outgoing.subject.clone(),
+            recipients: outgoing.recipients.clone(),
+            path: None,
+            detail: ArcStr::default(),
+        };
+        let result = match backend {
+            MailBackend::DryRun => {
+                let dir = self.config.path(PathOpt::OutboxDir).await;
+                self.mkdir(&dir).await?;
+                let out = ArcPath::from(&dir.join(outbox_file_name(now, &outgoing.message_id)));
+                self.write(&out, &outgoing.message).await?;
+                report.path = Some(out);
+                Ok(())
+            }
+            MailBackend::Sendmail => self.sendmail(&outgoing).await,
+            MailBackend::GitSendEmail => self.git_send_email(&outgoing, now).await,
+            MailBackend::Smtp => self.smtp(&outgoing).await.map(|reply| {
+                report.detail = reply;
+            }),
+        };
+
+        match result {
+            Ok(()) => {
+                self.log
+                    .info(SCOPE, format!("Sent {}", outgoing.message_id));
+                Ok(report)
+            }
+            Err(e) => {
+                self.log.error(
+                    SCOPE,
+                    format!("Failed to send {}: {}", outgoing.message_id, e),
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Pipes the message to the configured sendmail-compatible command.
+    async fn sendmail(&self, outgoing: &Outgoing) -> anyhow::Result<()> {
+        let command = self.config.str(StrOpt::SendmailCommand).await;
+        let mut words = command.split_whitespace().map(ArcStr::from);
+        let Some(program) = words.next() else {
+            anyhow::bail!("No sendmail command configured");
+        };
+        let args: Vec<ArcStr> = words.collect();
+
+        let result = self
+            .shell
+            .execute(
+                program,
+                ArcSlice::from(args),
+                Some(outgoing.message.clone()),
+            )
+            .await?;
+        if result.is_failure() {
+            anyhow::bail!(
+                "'{}' exited with {}: {}",
+                command,
+                result.status,
+                result.stderr.trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Hands the message to `git send-email`, which uses its own `sendemail.*` settings.
+    ///
+    /// The recipients are given explicitly and automatic Cc harvesting is turned off,
+    /// so the trailers in the reply do not add anyone to the thread.
+    async fn git_send_email(
+        &self,
+        outgoing: &Outgoing,
+        now: chrono::DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let dir = self.config.path(PathOpt::OutboxDir).await;
+        self.mkdir(&dir).await?;
+        let file = ArcPath::from(&dir.join(format!(
+            ".send-email-{}",
+            outbox_file_name(now, &outgoing.message_id)
+        )));
+        self.write(&file, &outgoing.message).await?;
+
+        let repo = self.config.path(PathOpt::RepoPath).await;
+        let mut args = vec![
+            ArcStr::from("-C"),
+            ArcStr::from(&repo.to_string_lossy()),
+            ArcStr::from("send-email"),
+            ArcStr::from("--confirm=never"),
+            ArcStr::from("--quiet"),
+            ArcStr::from("--suppress-cc=all"),
+        ];
+        for recipient in &outgoing.recipients {
+            args.push(ArcStr::from(format!("--to={}", recipient)));
+        }
+        args.push(ArcStr::from(&file.to_string_lossy()));
+
+        let result = self
+            .shell
+            .execute(ArcStr::from("git"), ArcSlice::from(args), None)
+            .await;
+        if let Err(e) = self.fs.remove_file(file.clone()).await {
+            self.log
+                .warn(SCOPE, format!("Failed to remove {}: {}", file.display(), e));
+        }
+
+        let result = result?;
+        if result.is_failure() {
+            anyhow::bail!("git send-email failed: {}", result.stderr.trim());
+        }
+        Ok(())
+    }
+
+    /// Delivers the message to the configured SMTP server.
+    async fn smtp(&self, outgoing: &Outgoing) -> anyhow::Result<ArcStr> {
+        let server = self.config.str(StrOpt::SmtpServer).await;
+        let address = if server.contains(':') {
+            server.to_string()
+        } else {
+            format!("{}:25", server)
+        };
+        let timeout = Duration::from_secs(self.config.usize(USizeOpt::Timeout).await as u64);
+        let helo = outgoing
+            .from
+            .rsplit_once('@')
+            .map_or("localhost", |(_, domain)| domain);
+
+        let exchange = async {
+            let stream = TcpStream::connect(&address)
+                .await
+                .with_context(|| format!("Connecting to SMTP server {}", address))?;
+            smtp::deliver(
+                stream,
+                helo,
+                &outgoing.from,
+                &outgoing.recipients,
+                &outgoing.message,
+            )
+            .await
+        };
+        tokio::time::timeout(timeout, exchange)
+            .await
+            .with_context(|| format!("Timed out talking to SMTP server {}", address))?
+    }
+
+    /// Creates `dir` if needed.
+    async fn mkdir(&self, dir: &ArcPath) -> anyhow::Result<()> {
+        self.fs
+            .mkdir(dir.clone())
+            .await
+            .with_context(|| format!("Creating directory {}", dir.display()))
+    }
+
+    /// Writes `content` to `path`, replacing what was there.
+    async fn write(&self, path: &ArcPath, content: &str) -> anyhow::Result<()> {
+        let mut file = self
+            .fs
+            .write_file(path.clone())
+            .await
+            .with_context(|| format!("Opening {} for writing", path.display()))?;
+        file.write_all(content.as_bytes())
+            .await
+            .with_context(|| format!("Writing {}", path.display()))?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Reads the whole content of `path`.
+    async fn read(&self, path: &ArcPath) -> anyhow::Result<String> {
+        let mut file = self
+            .fs
+            .read_file(path.clone())
+            .await
+            .with_context(|| format!("Opening {} for reading", path.display()))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .await
+            .with_context(|| format!("Reading {}", path.display()))?;
+        Ok(content)
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+use crate::api::lore::parse_patch_message;
+use crate::app::config::MailBackend;
+use crate::app::reply::{address_email, split_addresses};
+use crate::{ArcPath, ArcStr};
+
+/// A message ready to be handed to a mail backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outgoing {
+    /// Envelope sender address
+    pub from: ArcStr,
+    /// Envelope recipient addresses, from `To` and `Cc`
+    pub recipients: Vec<ArcStr>,
+    /// The `Message-ID` of the message
+    pub message_id: ArcStr,
+    /// The `Subject` of the message
+    pub subject: ArcStr,
+    /// The full message, headers and body, without the mbox `From_` line
+    pub message: ArcStr,
+}
+
+/// The outcome of sending a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendReport {
+    /// The backend the message went through
+    pub backend: MailBackend,
+    /// The `Message-ID` of the message
+    pub message_id: ArcStr,
+    /// The `Subject` of the message
+    pub subject: ArcStr,
+    /// The envelope recipients
+    pub recipients: Vec<ArcStr>,
+    /// Where the message was written, for the dry-run backend
+    pub path: Option<ArcPath>,
+    /// Backend output worth showing, such as the SMTP server's final reply
+    pub detail: ArcStr,
+}
+
+impl fmt::Display for SendReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => writeln!(f, "Dry run, message written to {}", path.display())?,
+            None => writeln!(f, "Sent with {:?}", self.backend)?,
+        }
+        writeln!(f, "Subject: {}", self.subject)?;
+        writeln!(f, "Message-ID: {}", self.message_id)?;
+        let recipients: Vec<&str> = self.recipients.iter().map(|r| &**r).collect();
+        write!(f, "Recipients: {}", recipients.join(", "))?;
+        if !self.detail.is_empty() {
+            write!(f, "\n{}", self.detail.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Prepares a composed message for sending.
+///
+/// The mbox `From_` line is dropped and missing `Date` and `Message-ID` headers are
+/// added, so every backend sends the same bytes. The envelope is taken from the
+/// `From`, `To` and `Cc` headers.
+///
+/// # Arguments
+/// * `raw` - The message as written by the reply composer
+/// * `now` - The time used for the `Date` and `Message-ID` headers
+///
+/// # Returns
+/// The prepared message, or an error if it has no sender or no recipients.
+pub fn prepare_message(raw: &str, now: DateTime<Utc>) -> anyhow::Result<Outgoing> {
+    let raw = match raw.strip_prefix("From ") {
+        Some(rest) => rest.split_once('\n').map_or("", |(_, rest)| rest),
+        None => raw,
+    };
+    let parsed = parse_patch_message(raw).unwrap_or_default();
+    let headers = &parsed.headers;
+
+    let Some(from) = headers
+        .get("From")
+        .map(|from| address_email(from))
+        .filter(|f| !f.is_empty())
+    else {
+        anyhow::bail!("The message has no From header");
+    };
+    let mut recipients: Vec<ArcStr> = Vec::new();
+    for value in headers.get_all("To").chain(headers.get_all("Cc")) {
+        for address in split_addresses(value) {
+            let email = ArcStr::from(address_email(&address));
+            if !recipients.contains(&email) {
+                recipients.push(email);
+            }
+        }
+    }
+    if recipients.is_empty() {
+        anyhow::bail!("The message has no recipients");
+    }
+
+    let mut extra = String::new();
+    if headers.get("Date").is_none() {
+        extra.push_str(&format!("Date: {}\n", now.to_rfc2822()));
+    }
+    let message_id = match headers.get("Message-ID") {
+        Some(id) => id.to_string(),
+        None => {
+            let domain = from.rsplit_once('@').map_or("localhost", |(_, d)| d);
+            let id = format!("<{}.patch-hub@{}>", now.format("%Y%m%d%H%M%S%.9f"), domain);
+            extra.push_str(&format!("Message-ID: {}\n", id));
+            id
+        }
+    };
+
+    let mut message = String::with_capacity(raw.len() + extra.len());
+    message.push_str(&extra);
+    message.push_str(raw);
+    if !message.ends_with('\n') {
+        message.push('\n');
+    }
+
+    Ok(Outgoing {
+        from: ArcStr::from(from),
+        recipients,
+        message_id: ArcStr::from(message_id),
+        subject: parsed.subject.clone(),
+        message: ArcStr::from(message),
+    })
+}
+
+/// Builds the name of the file the dry-run backend writes a message to.
+pub fn outbox_file_name(now: DateTime<Utc>, message_id: &str) -> String {
+    let id: String = message_id
+        .trim_matches(['<', '>'])
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}-{}.eml", now.format("%Y%m%d-%H%M%S"), id)
+}
@@ -0,0 +1,17 @@
+use tokio::sync::oneshot::Sender;
+
+use crate::ArcPath;
+
+use super::data::SendReport;
+
+/// Messages that can be sent to the mail actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Send the message stored in a file through the configured backend
+    Send {
+        /// Path of the message, as written by the reply composer
+        path: ArcPath,
+        /// Response channel for the send report
+        tx: Sender<anyhow::Result<SendReport>>,
+    },
+}
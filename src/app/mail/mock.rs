@@ -0,0 +1,53 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::app::config::MailBackend;
+use crate::{ArcPath, ArcStr};
+
+use super::data::SendReport;
+
+/// Mock implementation of the Mail actor for testing purposes.
+///
+/// This struct records the paths it is asked to send and reports them as sent,
+/// allowing tests to run without a mail setup.
+#[derive(Debug, Clone, Default)]
+pub struct Mock {
+    sent: Arc<Mutex<Vec<ArcPath>>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with an empty record of sent messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends the message stored in a file.
+    /// Mock implementation records the path and reports a dry run.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the message
+    ///
+    /// # Returns
+    /// A report pointing back to the given path.
+    pub async fn send(&self, path: ArcPath) -> anyhow::Result<SendReport> {
+        let mut lock = self.sent.lock().await;
+        lock.push(path.clone());
+        Ok(SendReport {
+            backend: MailBackend::DryRun,
+            message_id: ArcStr::default(),
+            subject: ArcStr::default(),
+            recipients: Vec::new(),
+            path: Some(path),
+            detail: ArcStr::default(),
+        })
+    }
+
+    /// Gets the paths of all messages sent so far.
+    ///
+    /// # Returns
+    /// A vector of the sent paths, in order.
+    pub async fn get_sent(&self) -> Vec<ArcPath> {
+        let lock = self.sent.lock().await;
+        lock.clone()
+    }
+}
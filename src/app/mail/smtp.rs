@@ -0,0 +1,100 @@
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufStream};
+
+use crate::ArcStr;
+
+/// Reads one (possibly multiline) SMTP reply and checks its code.
+///
+/// # Returns
+/// The text of the reply, or an error if its code is not the expected one.
+async fn expect<S>(stream: &mut BufStream<S>, code: &str) -> anyhow::Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            anyhow::bail!("SMTP server closed the connection");
+        }
+        let done = line.as_bytes().get(3) != Some(&b'-');
+        reply.push_str(&line);
+        if done {
+            break;
+        }
+    }
+    if !reply.starts_with(code) {
+        anyhow::bail!(
+            "Unexpected SMTP reply (wanted {}): {}",
+            code,
+            reply.trim_end()
+        );
+    }
+    Ok(reply)
+}
+
+/// Sends one SMTP command line and checks the reply.
+async fn command<S>(stream: &mut BufStream<S>, line: &str, code: &str) -> anyhow::Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(line.as_bytes()).await?;
+    stream.write_all(b"\r\n").await?;
+    stream.flush().await?;
+    expect(stream, code).await
+}
+
+/// Converts a message to CRLF line endings and dot-stuffs it for the `DATA` command.
+pub fn encode_data(message: &str) -> String {
+    let mut data = String::with_capacity(message.len() + message.len() / 32);
+    for line in message.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push_str(".\r\n");
+    data
+}
+
+/// Delivers a message over an established SMTP connection.
+///
+/// This is a minimal client: it speaks plain SMTP (`EHLO`, `MAIL`, `RCPT`, `DATA`)
+/// without STARTTLS or authentication, which is what local relays and test
+/// stand-ins expect. Servers needing either are reached through the sendmail or
+/// `git send-email` backends instead.
+///
+/// # Arguments
+/// * `stream` - The connection to the server
+/// * `helo` - The name we introduce ourselves with
+/// * `from` - The envelope sender
+/// * `recipients` - The envelope recipients
+/// * `message` - The message to deliver
+///
+/// # Returns
+/// The server's reply to the end of the data, which usually carries a queue ID.
+pub async fn deliver<S>(
+    stream: S,
+    helo: &str,
+    from: &str,
+    recipients: &[ArcStr],
+    message: &str,
+) -> anyhow::Result<ArcStr>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufStream::new(stream);
+    expect(&mut stream, "220").await?;
+    command(&mut stream, &format!("EHLO {}", helo), "250").await?;
+    command(&mut stream, &format!("MAIL FROM:<{}>", from), "250").await?;
+    for recipient in recipients {
+        command(&mut stream, &format!("RCPT TO:<{}>", recipient), "250").await?;
+    }
+    command(&mut stream, "DATA", "354").await?;
+    stream.write_all(encode_data(message).as_bytes()).await?;
+    stream.flush().await?;
+    let accepted = expect(&mut stream, "250").await?;
+    // The message is queued at this point, a failed QUIT does not matter
+    let _ = command(&mut stream, "QUIT", "221").await;
+    Ok(ArcStr::from(accepted.trim_end()))
+}
@@ -0,0 +1,236 @@
+use chrono::{TimeZone, Utc};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use super::{Mail, deliver, encode_data, outbox_file_name, prepare_message};
+use crate::app::config::{Config, Data, MailBackend, MailBackendOpt, PathOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+use crate::{ArcPath, ArcStr};
+
+const REPLY: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Me <me@example.com>
+To: Jane Doe <jane@example.com>
+Cc: netdev@vger.kernel.org,
+    \"Smith, John\" <John@Example.com>, jane@example.com
+Subject: Re: [PATCH] net: do the thing
+In-Reply-To: <1-jane@example.com>
+References: <1-jane@example.com>
+
+> Commit message.
+.
+Reviewed-by: Me <me@example.com>
+";
+
+fn now() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 6, 10, 0, 0).unwrap()
+}
+
+#[test]
+fn test_prepare_message_adds_missing_headers() {
+    let outgoing = prepare_message(REPLY, now()).unwrap();
+    assert_eq!(outgoing.from, ArcStr::from("me@example.com"));
+    assert_eq!(
+        outgoing.recipients,
+        vec![
+            ArcStr::from("jane@example.com"),
+            ArcStr::from("netdev@vger.kernel.org"),
+            ArcStr::from("john@example.com"),
+        ]
+    );
+    assert_eq!(
+        outgoing.message_id,
+        ArcStr::from("<20250106100000.000000000.patch-hub@example.com>")
+    );
+    assert_eq!(
+        outgoing.subject,
+        ArcStr::from("Re: [PATCH] net: do the thing")
+    );
+    assert!(outgoing.message.starts_with(
+        "Date: Mon, 6 Jan 2025 10:00:00 +0000\nMessage-ID: <20250106100000.000000000.patch-hub@example.com>\nFrom: Me"
+    ));
+}
+
+#[test]
+fn test_prepare_message_keeps_existing_headers() {
+    let raw =
+        "Date: Sun, 5 Jan 2025 09:00:00 +0000\nMessage-ID: <x@y>\nFrom: a@b.c\nTo: d@e.f\n\nbody";
+    let outgoing = prepare_message(raw, now()).unwrap();
+    assert_eq!(outgoing.message_id, ArcStr::from("<x@y>"));
+    assert_eq!(&*outgoing.message, &format!("{}\n", raw));
+}
+
+#[test]
+fn test_prepare_message_requires_envelope() {
+    assert!(prepare_message("To: d@e.f\n\nbody\n", now()).is_err());
+    assert!(prepare_message("From: a@b.c\n\nbody\n", now()).is_err());
+}
+
+#[test]
+fn test_outbox_file_name() {
+    assert_eq!(
+        outbox_file_name(now(), "<1/2@example.com>"),
+        "20250106-100000-1_2@example.com.eml"
+    );
+}
+
+#[test]
+fn test_encode_data_dot_stuffs() {
+    assert_eq!(encode_data("a\n.\n..b\nc"), "a\r\n..\r\n...b\r\nc\r\n.\r\n");
+}
+
+#[tokio::test]
+async fn test_deliver_talks_smtp() {
+    let (client, server) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        let mut server = BufReader::new(server);
+        let mut transcript = Vec::new();
+        server.write_all(b"220 mx ready\r\n").await.unwrap();
+        loop {
+            let mut line = String::new();
+            if server.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end().to_string();
+            let reply: &[u8] = if line.starts_with("EHLO") {
+                b"250-mx\r\n250 8BITMIME\r\n"
+            } else if line == "DATA" {
+                b"354 go ahead\r\n"
+            } else if line == "." {
+                b"250 2.0.0 queued as 42\r\n"
+            } else if line == "QUIT" {
+                b"221 bye\r\n"
+            } else if line.starts_with("MAIL") || line.starts_with("RCPT") {
+                b"250 ok\r\n"
+            } else {
+                b""
+            };
+            server.write_all(reply).await.unwrap();
+            let quit = line == "QUIT";
+            transcript.push(line);
+            if quit {
+                break;
+            }
+        }
+        transcript
+    });
+
+    let recipients = [ArcStr::from("a@b.c"), ArcStr::from("d@e.f")];
+    let reply = deliver(
+        client,
+        "example.com",
+        "me@example.com",
+        &recipients,
+        ".x\ny\n",
+    )
+    .await
+    .unwrap();
+    assert_eq!(reply, ArcStr::from("250 2.0.0 queued as 42"));
+    assert_eq!(
+        server.await.unwrap(),
+        vec![
+            "EHLO example.com",
+            "MAIL FROM:<me@example.com>",
+            "RCPT TO:<a@b.c>",
+            "RCPT TO:<d@e.f>",
+            "DATA",
+            "..x",
+            "y",
+            ".",
+            "QUIT",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_deliver_fails_on_rejected_recipient() {
+    let (client, mut server) = tokio::io::duplex(4096);
+    server
+        .write_all(b"220 mx\r\n250 mx\r\n250 ok\r\n550 no such user\r\n")
+        .await
+        .unwrap();
+    let recipients = [ArcStr::from("nobody@b.c")];
+    let result = deliver(client, "example.com", "me@example.com", &recipients, "x\n").await;
+    assert!(result.unwrap_err().to_string().contains("550 no such user"));
+}
+
+async fn spawn_mail(shell: Shell, backend: MailBackend) -> (Mail, Fs) {
+    let fs = Fs::mock();
+    let mut file = fs.write_file(ArcPath::from("re.mbox")).await.unwrap();
+    file.write_all(REPLY.as_bytes()).await.unwrap();
+    file.flush().await.unwrap();
+
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::OutboxDir, ArcPath::from("outbox"))
+        .await;
+    config
+        .set_mail_backend(MailBackendOpt::SendBackend, backend)
+        .await;
+    (Mail::spawn(fs.clone(), shell, config, Log::mock()), fs)
+}
+
+#[tokio::test]
+async fn test_send_dry_run_writes_outbox() {
+    let shell = Shell::mock();
+    let (mail, fs) = spawn_mail(shell.clone(), MailBackend::DryRun).await;
+
+    let report = mail.send(ArcPath::from("re.mbox")).await.unwrap();
+    let path = report.path.unwrap();
+    assert!(path.starts_with("outbox"));
+    assert_eq!(report.recipients.len(), 3);
+
+    let entries = fs.read_dir(ArcPath::from("outbox")).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(shell.get_commands().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_send_with_sendmail_pipes_message() {
+    let shell = Shell::mock();
+    let (mail, _fs) = spawn_mail(shell.clone(), MailBackend::Sendmail).await;
+
+    let report = mail.send(ArcPath::from("re.mbox")).await.unwrap();
+    assert!(report.path.is_none());
+
+    let commands = shell.get_commands().await.unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(&*commands[0].program, "/usr/sbin/sendmail");
+    assert_eq!(
+        commands[0].args.iter().map(|a| &**a).collect::<Vec<_>>(),
+        vec!["-t", "-i"]
+    );
+    let stdin = commands[0].stdin.clone().unwrap();
+    assert!(stdin.starts_with("Date: "));
+    assert!(stdin.contains("\nSubject: Re: [PATCH] net: do the thing\n"));
+    assert!(!stdin.contains("From mboxrd@z"));
+}
+
+#[tokio::test]
+async fn test_send_with_git_send_email_cleans_up() {
+    let shell = Shell::mock();
+    let (mail, fs) = spawn_mail(shell.clone(), MailBackend::GitSendEmail).await;
+
+    mail.send(ArcPath::from("re.mbox")).await.unwrap();
+
+    let commands = shell.get_commands().await.unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(&*commands[0].program, "git");
+    let args: Vec<&str> = commands[0].args.iter().map(|a| &**a).collect();
+    assert!(args.contains(&"send-email"));
+    assert!(args.contains(&"--to=jane@example.com"));
+    assert!(args.contains(&"--suppress-cc=all"));
+    // The temporary message is removed once git is done with it
+    assert!(
+        fs.read_dir(ArcPath::from("outbox"))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_send_fails_on_missing_file() {
+    let (mail, _fs) = spawn_mail(Shell::mock(), MailBackend::DryRun).await;
+    assert!(mail.send(ArcPath::from("missing.mbox")).await.is_err());
+}
@@ -0,0 +1,222 @@
+mod core;
+mod data;
+mod message;
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Context;
+use std::collections::HashMap;
+use std::ops::Range;
+use tokio::sync::mpsc::Sender;
+
+use crate::ArcStr;
+use crate::api::lore::LorePatchMetadata;
+use crate::app::cache::{FeedCache, PatchCache};
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+
+pub use data::{Subsystem, file_matches, inbox_search, parse_maintainers};
+
+/// Number of search results checked against `MAINTAINERS` at a time when filling a page
+const INBOX_SCAN_CHUNK: usize = 50;
+
+/// The maintainers actor that knows who looks after which files of the kernel tree.
+///
+/// It parses the `MAINTAINERS` file of the repository configured by
+/// `PathOpt::RepoPath` and resolves the entries the user declared in
+/// `ListOpt::Subsystems`. Patches are pulled from the [`PatchCache`] to tell which of
+/// those entries they touch, which is what the cross-list inbox is filtered with.
+///
+/// # Examples
+/// ```ignore
+/// let maintainers = Maintainers::spawn(fs, patch_cache, config, log);
+/// let search = inbox_search(&maintainers.declared().await?);
+/// let names = maintainers.matching(list, message_id).await?;
+/// ```
+///
+/// # Thread Safety
+/// This type is designed to be safely shared between threads. Cloning is cheap as it only
+/// copies the channel sender or mock reference.
+#[derive(Debug, Clone)]
+pub enum Maintainers {
+    /// A real maintainers actor reading the kernel tree
+    Actual(Sender<message::Message>),
+    /// A mock implementation for testing that serves entries from memory
+    Mock(mock::Mock),
+}
+
+/// A patch of the inbox along with the declared entries it touches.
+#[derive(Debug, Clone)]
+pub struct InboxPatch {
+    /// Position of the patch in the feed of the inbox search
+    pub index: usize,
+    /// The patch
+    pub patch: LorePatchMetadata,
+    /// Names of the declared entries the patch touches, or why it could not be checked
+    pub subsystems: Result<Vec<ArcStr>, ArcStr>,
+}
+
+/// Data served by the mock maintainers actor.
+#[derive(Debug, Clone, Default)]
+pub struct MockData {
+    /// Every entry of the `MAINTAINERS` file
+    pub subsystems: Vec<Subsystem>,
+    /// Names of the entries declared by the user
+    pub declared: Vec<ArcStr>,
+    /// Files touched by each patch, by message ID
+    pub files: HashMap<String, Vec<ArcStr>>,
+}
+
+impl Maintainers {
+    /// Creates a new maintainers instance and spawns its actor.
+    ///
+    /// # Arguments
+    /// * `fs` - The filesystem actor for reading the `MAINTAINERS` file
+    /// * `patch_cache` - The patch cache for fetching the patches whose files are matched
+    /// * `config` - The configuration actor for the repository path and the declared entries
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new maintainers instance with a spawned actor.
+    pub fn spawn(fs: Fs, patch_cache: PatchCache, config: Config, log: Log) -> Self {
+        let (maintainers, _) = core::Core::new(fs, patch_cache, config, log).spawn();
+        maintainers
+    }
+
+    /// Creates a new mock maintainers instance for testing.
+    ///
+    /// # Arguments
+    /// * `data` - The entries, the declared names and the files touched by each patch
+    pub fn mock(data: MockData) -> Self {
+        Self::Mock(mock::Mock::new(data))
+    }
+
+    /// Gets every entry of the `MAINTAINERS` file.
+    ///
+    /// # Returns
+    /// The entries in file order, or an error if the file cannot be read.
+    pub async fn subsystems(&self) -> anyhow::Result<Vec<Subsystem>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Subsystems { tx })
+                    .await
+                    .context("Sending message to Maintainers actor")
+                    .expect("Maintainers actor died");
+                rx.await
+                    .context("Awaiting response from Maintainers actor")
+                    .expect("Maintainers actor died")
+            }
+            Self::Mock(mock) => mock.subsystems().await,
+        }
+    }
+
+    /// Gets the entries the user maintains or reviews, as declared in the configuration.
+    ///
+    /// # Returns
+    /// The declared entries found in the file, in declaration order.
+    pub async fn declared(&self) -> anyhow::Result<Vec<Subsystem>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Declared { tx })
+                    .await
+                    .context("Sending message to Maintainers actor")
+                    .expect("Maintainers actor died");
+                rx.await
+                    .context("Awaiting response from Maintainers actor")
+                    .expect("Maintainers actor died")
+            }
+            Self::Mock(mock) => mock.declared().await,
+        }
+    }
+
+    /// Tells which declared entries a patch touches files of.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list the patch belongs to
+    /// * `message_id` - The message ID of the patch
+    ///
+    /// # Returns
+    /// The names of the entries, empty if the patch touches none of their files.
+    pub async fn matching(&self, list: ArcStr, message_id: ArcStr) -> anyhow::Result<Vec<ArcStr>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Matching {
+                        list,
+                        message_id,
+                        tx,
+                    })
+                    .await
+                    .context("Sending message to Maintainers actor")
+                    .expect("Maintainers actor died");
+                rx.await
+                    .context("Awaiting response from Maintainers actor")
+                    .expect("Maintainers actor died")
+            }
+            Self::Mock(mock) => mock.matching(list, message_id).await,
+        }
+    }
+}
+
+/// Collects a range of the inbox, that is of the results of the inbox search that
+/// touch files of a declared entry.
+///
+/// The search matches more broadly than the entries do, so its feed is walked from
+/// the start and each patch is checked with [`Maintainers::matching`] until the range
+/// is filled or the feed runs out.
+/// Patches that cannot be checked are kept rather than hidden.
+///
+/// # Arguments
+/// * `maintainers` - The maintainers actor checking the patches
+/// * `feed_cache` - The feed cache holding the inbox search
+/// * `key` - The feed cache key of the inbox search
+/// * `range` - The range to collect, counting matching patches only
+///
+/// # Returns
+/// The matching patches of the range, in feed order.
+pub async fn inbox_slice(
+    maintainers: &Maintainers,
+    feed_cache: &FeedCache,
+    key: ArcStr,
+    range: Range<usize>,
+) -> anyhow::Result<Vec<InboxPatch>> {
+    let chunk = range.len().max(INBOX_SCAN_CHUNK);
+    let mut matches = Vec::new();
+    let mut scanned = 0;
+    while matches.len() < range.end {
+        let patches = feed_cache
+            .get_slice(key.clone(), scanned..scanned + chunk)
+            .await?;
+        let fetched = patches.len();
+        for (i, patch) in patches.into_iter().enumerate() {
+            if matches.len() == range.end {
+                break;
+            }
+            let subsystems = match maintainers
+                .matching(patch.list.clone(), patch.message_id.clone())
+                .await
+            {
+                Ok(subsystems) if subsystems.is_empty() => continue,
+                Ok(subsystems) => Ok(subsystems),
+                Err(e) => Err(ArcStr::from(format!("{:#}", e))),
+            };
+            matches.push(InboxPatch {
+                index: scanned + i,
+                patch,
+                subsystems,
+            });
+        }
+        if fetched < chunk {
+            break;
+        }
+        scanned += fetched;
+    }
+    Ok(matches.split_off(range.start.min(matches.len())))
+}
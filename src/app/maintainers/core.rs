@@ -0,0 +1,181 @@
+use anyhow::Context;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::JoinHandle;
+
+use crate::api::lore::parse_patch_message;
+use crate::app::cache::PatchCache;
+use crate::app::config::{Config, ListOpt, PathOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::{ArcPath, ArcStr};
+
+use super::data::{Subsystem, parse_maintainers};
+use super::message::Message;
+
+const SCOPE: &str = "app.maintainers";
+
+/// The core implementation of the maintainers actor.
+///
+/// The `MAINTAINERS` file of the configured repository is parsed on first use and
+/// kept in memory until the repository path changes.
+pub struct Core {
+    /// The filesystem actor for reading the `MAINTAINERS` file
+    fs: Fs,
+    /// The patch cache for fetching the patches whose files are matched
+    patch_cache: PatchCache,
+    /// The configuration actor for the repository path and the declared entries
+    config: Config,
+    /// The logging actor
+    log: Log,
+    /// The path of the parsed file along with its entries
+    parsed: Option<(ArcPath, Vec<Subsystem>)>,
+}
+
+impl Core {
+    /// Creates a new maintainers actor core.
+    ///
+    /// # Arguments
+    /// * `fs` - The filesystem actor for reading the `MAINTAINERS` file
+    /// * `patch_cache` - The patch cache for fetching patches
+    /// * `config` - The configuration actor for the repository path and the declared entries
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new maintainers actor core instance.
+    pub fn new(fs: Fs, patch_cache: PatchCache, config: Config, log: Log) -> Self {
+        Self {
+            fs,
+            patch_cache,
+            config,
+            log,
+            parsed: None,
+        }
+    }
+
+    /// Spawns the maintainers actor and returns the handle and join handle.
+    ///
+    /// # Returns
+    /// A tuple containing the maintainers actor handle and the join handle for the spawned task.
+    pub fn spawn(self) -> (super::Maintainers, JoinHandle<anyhow::Result<()>>) {
+        let (tx, rx) = mpsc::channel(32);
+        let handle = super::Maintainers::Actual(tx);
+        let join_handle = tokio::spawn(self.run(rx));
+        (handle, join_handle)
+    }
+
+    /// Runs the maintainers actor event loop.
+    ///
+    /// # Arguments
+    /// * `rx` - The message receiver
+    ///
+    /// # Returns
+    /// Result indicating success or failure of the actor
+    async fn run(mut self, mut rx: Receiver<Message>) -> anyhow::Result<()> {
+        while let Some(message) = rx.recv().await {
+            match message {
+                Message::Subsystems { tx } => {
+                    let result = self.subsystems().await.map(<[Subsystem]>::to_vec);
+                    let _ = tx.send(result);
+                }
+                Message::Declared { tx } => {
+                    let result = self.handle_declared().await;
+                    let _ = tx.send(result);
+                }
+                Message::Matching {
+                    list,
+                    message_id,
+                    tx,
+                } => {
+                    let result = self.handle_matching(list, message_id).await;
+                    let _ = tx.send(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the entries declared in the configuration, in the order they are declared.
+    ///
+    /// Names are compared without regard to case, and names missing from the file are
+    /// logged and skipped.
+    async fn handle_declared(&mut self) -> anyhow::Result<Vec<Subsystem>> {
+        let names = self.config.list(ListOpt::Subsystems).await;
+        let subsystems = self.subsystems().await?;
+        let mut declared = Vec::with_capacity(names.len());
+        let mut missing = Vec::new();
+        for name in names {
+            match subsystems
+                .iter()
+                .find(|subsystem| subsystem.name.eq_ignore_ascii_case(&name))
+            {
+                Some(subsystem) => declared.push(subsystem.clone()),
+                None => missing.push(name),
+            }
+        }
+        for name in missing {
+            self.log.warn(
+                SCOPE,
+                format!("Subsystem '{}' is not in the MAINTAINERS file", name),
+            );
+        }
+        Ok(declared)
+    }
+
+    /// Returns the names of the declared entries the files touched by a patch belong to.
+    async fn handle_matching(
+        &mut self,
+        list: ArcStr,
+        message_id: ArcStr,
+    ) -> anyhow::Result<Vec<ArcStr>> {
+        let declared = self.handle_declared().await?;
+        let raw = self
+            .patch_cache
+            .get(list.clone(), message_id.clone())
+            .await
+            .with_context(|| format!("Fetching patch {message_id} from {list}"))?;
+        let diff = parse_patch_message(&raw)?.parse_diff();
+        let paths = diff.paths();
+        Ok(declared
+            .into_iter()
+            .filter(|subsystem| paths.iter().any(|path| subsystem.matches(path)))
+            .map(|subsystem| subsystem.name)
+            .collect())
+    }
+
+    /// Returns the entries of the `MAINTAINERS` file of the configured repository,
+    /// parsing it if the repository changed since it was last read.
+    async fn subsystems(&mut self) -> anyhow::Result<&[Subsystem]> {
+        let repo = self.config.path(PathOpt::RepoPath).await;
+        let path = ArcPath::from(&repo.join("MAINTAINERS"));
+        if self
+            .parsed
+            .as_ref()
+            .is_none_or(|(parsed, _)| *parsed != path)
+        {
+            let mut file = self
+                .fs
+                .read_file(path.clone())
+                .await
+                .with_context(|| format!("Opening {}", path.display()))?;
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .await
+                .with_context(|| format!("Reading {}", path.display()))?;
+            let subsystems = parse_maintainers(&content);
+            self.log.info(
+                SCOPE,
+                format!(
+                    "Parsed {} entries from {}",
+                    subsystems.len(),
+                    path.display()
+                ),
+            );
+            self.parsed = Some((path, subsystems));
+        }
+        Ok(self
+            .parsed
+            .as_ref()
+            .map_or(&[], |(_, subsystems)| subsystems.as_slice()))
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+use crate::ArcStr;
+use crate::api::lore::{ALL_LISTS, LoreQuery, LoreSearch};
+
+/// An entry of the kernel `MAINTAINERS` file.
+///
+/// Only the tags describing who looks after which files are kept; `S:`, `W:`, `T:`
+/// and the other informational tags are dropped while parsing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subsystem {
+    /// The name of the entry, e.g. `MEMORY MANAGEMENT - CORE`
+    pub name: ArcStr,
+    /// Maintainers (`M:`), as `Name <address>`
+    pub maintainers: Vec<ArcStr>,
+    /// Designated reviewers (`R:`), as `Name <address>`
+    pub reviewers: Vec<ArcStr>,
+    /// Addresses of the mailing lists patches are sent to (`L:`)
+    pub lists: Vec<ArcStr>,
+    /// File patterns (`F:`)
+    pub files: Vec<ArcStr>,
+    /// File patterns left out of the entry (`X:`)
+    pub excluded: Vec<ArcStr>,
+    /// Regular expressions matched against file paths (`N:`)
+    pub file_regexes: Vec<ArcStr>,
+}
+
+impl Subsystem {
+    /// Returns true if `path` belongs to the entry.
+    ///
+    /// The path must match an `F:` pattern or an `N:` regex, and no `X:` pattern,
+    /// with the same rules as `scripts/get_maintainer.pl`.
+    pub fn matches(&self, path: &str) -> bool {
+        let included = self.files.iter().any(|pattern| file_matches(pattern, path))
+            || self
+                .file_regexes
+                .iter()
+                .any(|regex| Regex::new(regex).is_ok_and(|regex| regex.is_match(path)));
+        let excluded = self
+            .excluded
+            .iter()
+            .any(|pattern| file_matches(pattern, path));
+        included && !excluded
+    }
+
+    /// Returns the paths whose patches the archive is searched for.
+    ///
+    /// Wildcards cannot be searched, so patterns are cut back to the directory holding
+    /// their first wildcard (`drivers/net/*/phy.c` becomes `drivers/net/`). The search
+    /// thus finds a superset of the patches [`Subsystem::matches`] accepts, and
+    /// patterns starting with a wildcard are left out.
+    pub fn search_paths(&self) -> Vec<ArcStr> {
+        self.files
+            .iter()
+            .filter_map(|pattern| {
+                let pattern: &str = pattern;
+                let path = match pattern.find(['*', '?']) {
+                    Some(idx) => &pattern[..pattern[..idx].rfind('/').map_or(0, |end| end + 1)],
+                    None => pattern,
+                };
+                (!path.is_empty()).then(|| ArcStr::from(path))
+            })
+            .collect()
+    }
+}
+
+/// Parses the content of a kernel `MAINTAINERS` file.
+///
+/// Entries are blocks of lines separated by blank lines: a name followed by `X: value`
+/// tag lines. Blocks without tags, such as the introduction at the top of the file,
+/// are skipped.
+///
+/// # Arguments
+/// * `content` - The content of the file
+///
+/// # Returns
+/// The entries in the order they appear in the file.
+pub fn parse_maintainers(content: &str) -> Vec<Subsystem> {
+    let mut subsystems = Vec::new();
+    let mut current: Option<Subsystem> = None;
+    let mut tagged = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if let Some(subsystem) = current.take().filter(|_| tagged) {
+                subsystems.push(subsystem);
+            }
+            tagged = false;
+            continue;
+        }
+
+        let bytes = line.as_bytes();
+        let is_tag = bytes.len() > 2
+            && bytes[0].is_ascii_uppercase()
+            && bytes[1] == b':'
+            && bytes[2].is_ascii_whitespace();
+        if !is_tag {
+            // The name is the last line before the first tag
+            if !tagged {
+                current = Some(Subsystem {
+                    name: ArcStr::from(line.trim()),
+                    ..Default::default()
+                });
+            }
+            continue;
+        }
+
+        let Some(subsystem) = current.as_mut() else {
+            continue;
+        };
+        tagged = true;
+        let value = line[2..].trim();
+        let entries = match bytes[0] {
+            b'M' => &mut subsystem.maintainers,
+            b'R' => &mut subsystem.reviewers,
+            b'F' => &mut subsystem.files,
+            b'X' => &mut subsystem.excluded,
+            b'N' => &mut subsystem.file_regexes,
+            b'L' => {
+                // Drop remarks such as `(moderated for non-subscribers)`
+                let address = value.split_whitespace().next().unwrap_or(value);
+                subsystem.lists.push(ArcStr::from(address));
+                continue;
+            }
+            _ => continue,
+        };
+        entries.push(ArcStr::from(value));
+    }
+    if let Some(subsystem) = current.filter(|_| tagged) {
+        subsystems.push(subsystem);
+    }
+    subsystems
+}
+
+/// Builds the search behind the inbox of the given entries.
+///
+/// The search runs against all lists, since entries send their patches to several
+/// of them, and matches the patches touching the [`Subsystem::search_paths`] of any
+/// entry.
+///
+/// # Returns
+/// The search, or `None` if the entries have no searchable path.
+pub fn inbox_search(subsystems: &[Subsystem]) -> Option<LoreSearch> {
+    let paths: BTreeSet<ArcStr> = subsystems
+        .iter()
+        .flat_map(|subsystem| subsystem.search_paths())
+        .collect();
+    // Paths under a directory already searched add nothing but length to the URL
+    let mut kept: Vec<ArcStr> = Vec::new();
+    for path in paths {
+        let covered = kept
+            .iter()
+            .any(|dir| dir.ends_with('/') && path.starts_with(&**dir));
+        if !covered {
+            kept.push(path);
+        }
+    }
+    if kept.is_empty() {
+        return None;
+    }
+    Some(LoreSearch::new(
+        ArcStr::from(ALL_LISTS),
+        LoreQuery::patches().touches_any(&kept),
+    ))
+}
+
+/// Returns true if `path` matches an `F:` or `X:` pattern.
+///
+/// A pattern ending in `/` matches everything below that directory, and so does a
+/// pattern without wildcards naming a directory, as `get_maintainer.pl` checks the
+/// tree for those. Any other pattern with wildcards must match a prefix of the path
+/// with the same number of `/`, so `drivers/net/*` leaves out the subdirectories of
+/// `drivers/net`.
+pub fn file_matches(pattern: &str, path: &str) -> bool {
+    if pattern.ends_with('/') {
+        return glob_prefix(pattern.as_bytes(), path.as_bytes());
+    }
+    if !pattern.contains(['*', '?']) {
+        return path
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+    }
+    glob_prefix(pattern.as_bytes(), path.as_bytes())
+        && pattern.matches('/').count() == path.matches('/').count()
+}
+
+/// Returns true if `pattern` matches a prefix of `text`, `*` standing for any run of
+/// characters and `?` for a single one.
+fn glob_prefix(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_prefix(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_prefix(rest, &text[1..]),
+        Some((byte, rest)) => text.first() == Some(byte) && glob_prefix(rest, &text[1..]),
+    }
+}
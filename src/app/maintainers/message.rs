@@ -0,0 +1,29 @@
+use tokio::sync::oneshot::Sender;
+
+use crate::ArcStr;
+
+use super::data::Subsystem;
+
+/// Messages that can be sent to the maintainers actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Get every entry of the `MAINTAINERS` file
+    Subsystems {
+        /// Response channel for the entries
+        tx: Sender<anyhow::Result<Vec<Subsystem>>>,
+    },
+    /// Get the entries the user declared in the configuration
+    Declared {
+        /// Response channel for the entries
+        tx: Sender<anyhow::Result<Vec<Subsystem>>>,
+    },
+    /// Get the declared entries a patch touches files of
+    Matching {
+        /// The mailing list the patch belongs to
+        list: ArcStr,
+        /// The message ID of the patch
+        message_id: ArcStr,
+        /// Response channel for the names of the entries
+        tx: Sender<anyhow::Result<Vec<ArcStr>>>,
+    },
+}
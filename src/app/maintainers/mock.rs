@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::ArcStr;
+
+use super::MockData;
+use super::data::Subsystem;
+
+/// Mock implementation of the Maintainers actor for testing purposes.
+///
+/// This struct serves the entries and the files touched by each patch from memory,
+/// allowing tests to run without a kernel tree or the patch cache.
+#[derive(Debug, Clone)]
+pub struct Mock {
+    data: Arc<Mutex<MockData>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with the provided data.
+    ///
+    /// # Arguments
+    /// * `data` - The entries, the declared names and the files touched by each patch
+    pub fn new(data: MockData) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+        }
+    }
+
+    /// Gets every entry.
+    /// Mock implementation returns the stored entries.
+    pub async fn subsystems(&self) -> anyhow::Result<Vec<Subsystem>> {
+        let data = self.data.lock().await;
+        Ok(data.subsystems.clone())
+    }
+
+    /// Gets the declared entries.
+    /// Mock implementation looks the declared names up in the stored entries.
+    pub async fn declared(&self) -> anyhow::Result<Vec<Subsystem>> {
+        let data = self.data.lock().await;
+        Ok(data
+            .declared
+            .iter()
+            .filter_map(|name| {
+                data.subsystems
+                    .iter()
+                    .find(|subsystem| subsystem.name.eq_ignore_ascii_case(name))
+                    .cloned()
+            })
+            .collect())
+    }
+
+    /// Gets the names of the declared entries a patch touches files of.
+    /// Mock implementation matches the stored files of the patch.
+    ///
+    /// # Arguments
+    /// * `_list` - The mailing list the patch belongs to (ignored in mock)
+    /// * `message_id` - The message ID of the patch
+    pub async fn matching(&self, _list: ArcStr, message_id: ArcStr) -> anyhow::Result<Vec<ArcStr>> {
+        let files = {
+            let data = self.data.lock().await;
+            data.files.get(message_id.as_ref() as &str).cloned()
+        };
+        let Some(files) = files else {
+            anyhow::bail!("Patch {} not found in mock data", message_id);
+        };
+        Ok(self
+            .declared()
+            .await?
+            .into_iter()
+            .filter(|subsystem| files.iter().any(|file| subsystem.matches(file)))
+            .map(|subsystem| subsystem.name)
+            .collect())
+    }
+}
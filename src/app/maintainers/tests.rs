@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use tokio::io::AsyncWriteExt;
+
+use chrono::DateTime;
+
+use super::{
+    Maintainers, MockData, Subsystem, file_matches, inbox_search, inbox_slice, parse_maintainers,
+};
+use crate::api::lore::LorePatchMetadata;
+use crate::app::cache::{FeedCache, FeedMockData, PatchCache, PatchMockData};
+use crate::app::config::{Config, Data, ListOpt, PathOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::{ArcPath, ArcStr};
+
+const MAINTAINERS: &str = "List of maintainers
+===================
+
+Descriptions of section entries and preferred order
+---------------------------------------------------
+
+\tM: *Mail* patches to: FullName <address@domain>
+\tF: *Files* and directories wildcard patterns.
+
+Maintainers List
+----------------
+
+MEMORY MANAGEMENT - CORE
+M:\tAndrew Morton <akpm@linux-foundation.org>
+R:\tJane Doe <jane@example.com>
+L:\tlinux-mm@kvack.org
+S:\tMaintained
+W:\thttp://www.linux-mm.org
+F:\tinclude/linux/mm.h
+F:\tmm/
+X:\tmm/kasan/
+
+NETWORKING DRIVERS
+M:\tJohn Roe <john@example.com>
+L:\tnetdev@vger.kernel.org (moderated for non-subscribers)
+F:\tdrivers/net/*
+F:\t*/net/phy/
+N:\t^drivers/.*/phy_[a-z]+\\.c$
+
+THE REST
+M:\tLinus Torvalds <torvalds@linux-foundation.org>
+F:\t*
+";
+
+const PATCH: &str = "From: Jane Doe <jane@example.com>
+Subject: [PATCH] mm: fix the thing
+
+---
+ mm/kasan/common.c | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/mm/kasan/common.c b/mm/kasan/common.c
+--- a/mm/kasan/common.c
++++ b/mm/kasan/common.c
+@@ -1 +1 @@
+-old
++new
+diff --git a/drivers/net/dummy.c b/drivers/net/dummy.c
+--- a/drivers/net/dummy.c
++++ b/drivers/net/dummy.c
+@@ -1 +1 @@
+-old
++new
+";
+
+#[test]
+fn test_parse_maintainers() {
+    let subsystems = parse_maintainers(MAINTAINERS);
+    let names: Vec<&str> = subsystems.iter().map(|s| s.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec!["MEMORY MANAGEMENT - CORE", "NETWORKING DRIVERS", "THE REST"]
+    );
+
+    let mm = &subsystems[0];
+    assert_eq!(
+        mm.maintainers,
+        vec![ArcStr::from("Andrew Morton <akpm@linux-foundation.org>")]
+    );
+    assert_eq!(
+        mm.reviewers,
+        vec![ArcStr::from("Jane Doe <jane@example.com>")]
+    );
+    assert_eq!(mm.lists, vec![ArcStr::from("linux-mm@kvack.org")]);
+    assert_eq!(
+        mm.files,
+        vec![ArcStr::from("include/linux/mm.h"), ArcStr::from("mm/")]
+    );
+    assert_eq!(mm.excluded, vec![ArcStr::from("mm/kasan/")]);
+
+    let net = &subsystems[1];
+    assert_eq!(net.lists, vec![ArcStr::from("netdev@vger.kernel.org")]);
+    assert_eq!(net.file_regexes.len(), 1);
+}
+
+#[test]
+fn test_file_matches() {
+    assert!(file_matches("mm/", "mm/page_alloc.c"));
+    assert!(file_matches("mm/", "mm/kasan/common.c"));
+    assert!(file_matches("mm", "mm/page_alloc.c"));
+    assert!(!file_matches("mm", "mmap.c"));
+    assert!(file_matches("include/linux/mm.h", "include/linux/mm.h"));
+    assert!(file_matches("drivers/net/*", "drivers/net/dummy.c"));
+    assert!(!file_matches("drivers/net/*", "drivers/net/phy/phy.c"));
+    assert!(file_matches("*/net/phy/", "drivers/net/phy/phy.c"));
+    assert!(file_matches(
+        "include/linux/mm*.h",
+        "include/linux/mm_types.h"
+    ));
+    assert!(!file_matches("include/linux/mm?.h", "include/linux/mm.h"));
+}
+
+#[test]
+fn test_subsystem_matches() {
+    let subsystems = parse_maintainers(MAINTAINERS);
+    let (mm, net) = (&subsystems[0], &subsystems[1]);
+    assert!(mm.matches("mm/page_alloc.c"));
+    assert!(!mm.matches("mm/kasan/common.c"));
+    assert!(!mm.matches("drivers/net/dummy.c"));
+    assert!(net.matches("drivers/net/dummy.c"));
+    assert!(net.matches("drivers/usb/phy_generic.c"));
+    assert!(!net.matches("drivers/usb/core.c"));
+}
+
+#[test]
+fn test_inbox_search() {
+    let subsystems = parse_maintainers(MAINTAINERS);
+    let search = inbox_search(&subsystems[..2]).unwrap();
+    assert_eq!(search.list, ArcStr::from("all"));
+    assert_eq!(
+        search.query.terms(),
+        &[ArcStr::from(
+            "(dfn:drivers/net/ OR dfn:include/linux/mm.h OR dfn:mm/)"
+        )]
+    );
+    assert!(search.query.is_patches());
+
+    // Patterns starting with a wildcard cannot be searched
+    assert!(inbox_search(&subsystems[2..]).is_none());
+    assert!(inbox_search(&[]).is_none());
+    let subsystem = Subsystem {
+        files: vec![ArcStr::from("drivers/net/*/phy.c")],
+        ..Default::default()
+    };
+    assert_eq!(subsystem.search_paths(), vec![ArcStr::from("drivers/net/")]);
+}
+
+#[tokio::test]
+async fn test_declared_and_matching() {
+    let fs = Fs::mock();
+    fs.mkdir(ArcPath::from("linux")).await.unwrap();
+    let mut file = fs
+        .write_file(ArcPath::from("linux/MAINTAINERS"))
+        .await
+        .unwrap();
+    file.write_all(MAINTAINERS.as_bytes()).await.unwrap();
+    file.flush().await.unwrap();
+
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::RepoPath, ArcPath::from("linux"))
+        .await;
+    config
+        .set_list(
+            ListOpt::Subsystems,
+            vec![
+                ArcStr::from("networking drivers"),
+                ArcStr::from("MEMORY MANAGEMENT - CORE"),
+                ArcStr::from("NO SUCH SUBSYSTEM"),
+            ],
+        )
+        .await;
+    let patch_cache = PatchCache::mock(PatchMockData {
+        patches: HashMap::from([("linux-mm:msg@x".to_string(), PATCH.to_string())]),
+    });
+    let maintainers = Maintainers::spawn(fs, patch_cache, config, Log::mock());
+
+    assert_eq!(maintainers.subsystems().await.unwrap().len(), 3);
+    let declared = maintainers.declared().await.unwrap();
+    let names: Vec<&str> = declared.iter().map(|s| s.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec!["NETWORKING DRIVERS", "MEMORY MANAGEMENT - CORE"]
+    );
+
+    // mm/kasan/ is excluded from memory management
+    let matching = maintainers
+        .matching(ArcStr::from("linux-mm"), ArcStr::from("msg@x"))
+        .await
+        .unwrap();
+    assert_eq!(matching, vec![ArcStr::from("NETWORKING DRIVERS")]);
+    assert!(
+        maintainers
+            .matching(ArcStr::from("linux-mm"), ArcStr::from("missing@x"))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_missing_maintainers_file() {
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::RepoPath, ArcPath::from("nowhere"))
+        .await;
+    let maintainers = Maintainers::spawn(
+        Fs::mock(),
+        PatchCache::mock(PatchMockData::default()),
+        config,
+        Log::mock(),
+    );
+    let err = maintainers.declared().await.unwrap_err();
+    assert!(format!("{:#}", err).contains("MAINTAINERS"));
+}
+
+#[tokio::test]
+async fn test_mock_matching() {
+    let maintainers = Maintainers::mock(MockData {
+        subsystems: parse_maintainers(MAINTAINERS),
+        declared: vec![ArcStr::from("MEMORY MANAGEMENT - CORE")],
+        files: HashMap::from([("msg@x".to_string(), vec![ArcStr::from("mm/page_alloc.c")])]),
+    });
+    assert_eq!(maintainers.declared().await.unwrap().len(), 1);
+    assert_eq!(
+        maintainers
+            .matching(ArcStr::from("linux-mm"), ArcStr::from("msg@x"))
+            .await
+            .unwrap(),
+        vec![ArcStr::from("MEMORY MANAGEMENT - CORE")]
+    );
+}
+
+fn patch(id: &str) -> LorePatchMetadata {
+    LorePatchMetadata {
+        author: ArcStr::from("Jane Doe"),
+        email: ArcStr::from("jane@example.com"),
+        last_update: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        title: ArcStr::from(format!("[PATCH] {id}")),
+        version: 1,
+        sequence: None,
+        link: ArcStr::from(format!("https://lore.kernel.org/all/{id}/")),
+        list: ArcStr::from("linux-mm"),
+        message_id: ArcStr::from(id),
+        in_reply_to: None,
+        rfc: false,
+        resend: false,
+        prefixes: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_inbox_slice_counts_matching_patches() {
+    let maintainers = Maintainers::mock(MockData {
+        subsystems: parse_maintainers(MAINTAINERS),
+        declared: vec![ArcStr::from("MEMORY MANAGEMENT - CORE")],
+        files: HashMap::from([
+            ("a".to_string(), vec![ArcStr::from("mm/page_alloc.c")]),
+            ("b".to_string(), vec![ArcStr::from("net/core/dev.c")]),
+            ("c".to_string(), vec![ArcStr::from("include/linux/mm.h")]),
+            ("e".to_string(), vec![ArcStr::from("mm/slub.c")]),
+        ]),
+    });
+    let feed_cache = FeedCache::mock(FeedMockData {
+        feeds: HashMap::from([(
+            ArcStr::from("inbox"),
+            ["a", "b", "c", "d", "e"].map(patch).to_vec(),
+        )]),
+        ..Default::default()
+    });
+
+    let page = inbox_slice(&maintainers, &feed_cache, ArcStr::from("inbox"), 1..3)
+        .await
+        .unwrap();
+    let shown: Vec<(usize, &str)> = page
+        .iter()
+        .map(|p| (p.index, p.patch.message_id.as_ref()))
+        .collect();
+    // "b" touches no declared entry, "d" cannot be checked and is kept
+    assert_eq!(shown, vec![(2, "c"), (3, "d")]);
+    assert!(page[0].subsystems.is_ok());
+    assert!(page[1].subsystems.is_err());
+
+    let all = inbox_slice(&maintainers, &feed_cache, ArcStr::from("inbox"), 0..20)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 4);
+    assert_eq!(all.last().unwrap().index, 4);
+}
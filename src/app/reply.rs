@@ -0,0 +1,135 @@
+mod core;
+mod data;
+mod message;
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Context;
+use tokio::sync::mpsc::Sender;
+
+use crate::ArcStr;
+use crate::app::cache::PatchCache;
+use crate::app::config::Config;
+use crate::env::Env;
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+
+pub use data::{
+    Identity, ReplyDraft, ReplyOptions, Trailer, address_email, compose_reply, reply_file_name,
+    reply_subject, split_addresses,
+};
+pub use mock::Request as MockRequest;
+
+/// The reply actor that composes review replies to patches.
+///
+/// The raw patch is pulled from the [`PatchCache`] and quoted in a reply whose
+/// `To`/`Cc`/`Subject`/`In-Reply-To`/`References` headers keep it in the original
+/// thread. The reply is written as an mbox under `PathOpt::ReplyDir`, optionally
+/// after a round trip through `$EDITOR`, ready to be sent.
+///
+/// # Examples
+/// ```ignore
+/// let reply = Reply::spawn(env, fs, shell, patch_cache, config, log);
+/// let options = ReplyOptions { trailers: vec![Trailer::ReviewedBy], edit: false };
+/// let draft = reply.compose(list, message_id, options).await?;
+/// ```
+///
+/// # Thread Safety
+/// This type is designed to be safely shared between threads. Cloning is cheap as it only
+/// copies the channel sender or mock reference.
+#[derive(Debug, Clone)]
+pub enum Reply {
+    /// A real reply actor that writes drafts to disk
+    Actual(Sender<message::Message>),
+    /// A mock implementation for testing that records requests in memory
+    Mock(mock::Mock),
+}
+
+impl Reply {
+    /// Creates a new reply instance and spawns its actor.
+    ///
+    /// # Arguments
+    /// * `env` - The environment actor for resolving `$EDITOR`
+    /// * `fs` - The filesystem actor for writing drafts
+    /// * `shell` - The shell actor for running the editor and git
+    /// * `patch_cache` - The patch cache for fetching raw patches
+    /// * `config` - The configuration actor for the identity and reply directory
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new reply instance with a spawned actor.
+    pub fn spawn(
+        env: Env,
+        fs: Fs,
+        shell: Shell,
+        patch_cache: PatchCache,
+        config: Config,
+        log: Log,
+    ) -> Self {
+        let (reply, _) = core::Core::new(env, fs, shell, patch_cache, config, log).spawn();
+        reply
+    }
+
+    /// Creates a new mock reply instance for testing.
+    ///
+    /// # Returns
+    /// A new mock reply instance that records requests in memory.
+    pub fn mock() -> Self {
+        Self::Mock(mock::Mock::new())
+    }
+
+    /// Composes a reply to a message and saves it as an mbox.
+    ///
+    /// With `options.edit` the draft is opened in `$EDITOR` (falling back to `vi`)
+    /// and read back once the editor exits. The editor runs on the current terminal,
+    /// so a TUI must suspend itself around this call.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list the message belongs to
+    /// * `message_id` - The message ID of the message being replied to
+    /// * `options` - Trailers to add and whether to open the editor
+    ///
+    /// # Returns
+    /// The saved reply, or an error if the patch cannot be fetched, no identity is
+    /// configured, the draft cannot be written or the editor fails.
+    pub async fn compose(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+        options: ReplyOptions,
+    ) -> anyhow::Result<ReplyDraft> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Compose {
+                        list,
+                        message_id,
+                        options,
+                        tx,
+                    })
+                    .await
+                    .context("Composing reply with Reply actor")
+                    .expect("reply actor died");
+                rx.await
+                    .context("Awaiting response for reply composition with Reply actor")
+                    .expect("reply actor died")
+            }
+            Self::Mock(mock) => mock.compose(list, message_id, options).await,
+        }
+    }
+
+    /// Gets all compose requests from the mock implementation.
+    /// This method is only available for mock instances and is useful for testing.
+    ///
+    /// # Returns
+    /// A vector of all compose requests, or None if this is not a mock instance.
+    pub async fn get_requests(&self) -> Option<Vec<MockRequest>> {
+        match self {
+            Self::Mock(mock) => Some(mock.get_requests().await),
+            Self::Actual(_) => None,
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::Context;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::JoinHandle;
+
+use crate::app::cache::PatchCache;
+use crate::app::config::{Config, PathOpt, StrOpt};
+use crate::env::Env;
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+use crate::{ArcOsStr, ArcPath, ArcSlice, ArcStr};
+
+use super::data::{Identity, ReplyDraft, ReplyOptions, compose_reply, reply_file_name};
+use super::message::Message;
+
+const SCOPE: &str = "app.reply";
+
+/// Editor used when `$EDITOR` is not set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The core implementation of the reply actor.
+///
+/// Replies are composed from the raw patch in the patch cache, written to the
+/// configured reply directory and optionally handed to `$EDITOR` before being
+/// read back.
+pub struct Core {
+    /// The environment actor for resolving `$EDITOR`
+    env: Env,
+    /// The filesystem actor for writing drafts
+    fs: Fs,
+    /// The shell actor for running the editor and git
+    shell: Shell,
+    /// The patch cache for fetching raw patches
+    patch_cache: PatchCache,
+    /// The configuration actor for the identity and reply directory
+    config: Config,
+    /// The logging actor
+    log: Log,
+}
+
+impl Core {
+    /// Creates a new reply actor core.
+    ///
+    /// # Arguments
+    /// * `env` - The environment actor for resolving `$EDITOR`
+    /// * `fs` - The filesystem actor for writing drafts
+    /// * `shell` - The shell actor for running the editor and git
+    /// * `patch_cache` - The patch cache for fetching raw patches
+    /// * `config` - The configuration actor for the identity and reply directory
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new reply actor core instance.
+    pub fn new(
+        env: Env,
+        fs: Fs,
+        shell: Shell,
+        patch_cache: PatchCache,
+        config: Config,
+        log: Log,
+    ) -> Self {
+        Self {
+            env,
+            fs,
+            shell,
+            patch_cache,
+            config,
+            log,
+        }
+    }
+
+    /// Spawns the reply actor and returns the handle and join handle.
+    ///
+    /// # Returns
+    /// A tuple containing the reply actor handle and the join handle for the spawned task.
+    pub fn spawn(self) -> (super::Reply, JoinHandle<anyhow::Result<()>>) {
+        let (tx, rx) = mpsc::channel(32);
+        let handle = super::Reply::Actual(tx);
+        let join_handle = tokio::spawn(self.run(rx));
+        (handle, join_handle)
+    }
+
+    /// Runs the reply actor event loop.
+    ///
+    /// # Arguments
+    /// * `rx` - The message receiver
+    ///
+    /// # Returns
+    /// Result indicating success or failure of the actor
+    async fn run(self, mut rx: Receiver<Message>) -> anyhow::Result<()> {
+        while let Some(message) = rx.recv().await {
+            match message {
+                Message::Compose {
+                    list,
+                    message_id,
+                    options,
+                    tx,
+                } => {
+                    let result = self.handle_compose(list, message_id, options).await;
+                    let _ = tx.send(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Composes a reply, saves it and optionally lets the user edit it.
+    async fn handle_compose(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+        options: ReplyOptions,
+    ) -> anyhow::Result<ReplyDraft> {
+        let raw = self
+            .patch_cache
+            .get(list.clone(), message_id.clone())
+            .await
+            .with_context(|| format!("Fetching patch {message_id} from {list}"))?;
+        let identity = self.identity().await?;
+
+        let dir = self.config.path(PathOpt::ReplyDir).await;
+        self.fs
+            .mkdir(dir.clone())
+            .await
+            .with_context(|| format!("Creating reply directory {}", dir.display()))?;
+        let path = ArcPath::from(&dir.join(reply_file_name(&message_id)));
+
+        let mut draft = compose_reply(&raw, &message_id, &identity, &options.trailers, path);
+        self.write(&draft.path, &draft.mbox).await?;
+        self.log.info(
+            SCOPE,
+            format!(
+                "Composed reply to {} in {}",
+                message_id,
+                draft.path.display()
+            ),
+        );
+
+        if options.edit {
+            self.edit(&draft.path).await?;
+            draft.mbox = self.read(&draft.path).await?;
+        }
+        Ok(draft)
+    }
+
+    /// Resolves the identity replies are sent from.
+    ///
+    /// The configured name and email take precedence, falling back to `user.name`
+    /// and `user.email` from git.
+    async fn identity(&self) -> anyhow::Result<Identity> {
+        let mut name = self.config.str(StrOpt::UserName).await;
+        if name.is_empty() {
+            name = self.git_config("user.name").await;
+        }
+        let mut email = self.config.str(StrOpt::UserEmail).await;
+        if email.is_empty() {
+            email = self.git_config("user.email").await;
+        }
+        if email.is_empty() {
+            anyhow::bail!(
+                "No identity configured: set `user_email` in the configuration or `user.email` in git"
+            );
+        }
+        Ok(Identity { name, email })
+    }
+
+    /// Reads a git configuration value from the configured repository, empty if unset.
+    async fn git_config(&self, key: &str) -> ArcStr {
+        let repo = self.config.path(PathOpt::RepoPath).await;
+        let args = ArcSlice::from([
+            ArcStr::from("-C"),
+            ArcStr::from(&repo.to_string_lossy()),
+            ArcStr::from("config"),
+            ArcStr::from(key),
+        ]);
+        match self.shell.execute(ArcStr::from("git"), args, None).await {
+            Ok(result) if result.is_success() => ArcStr::from(result.stdout.trim()),
+            _ => ArcStr::default(),
+        }
+    }
+
+    /// Opens a file in the user's editor and waits for it to exit.
+    async fn edit(&self, path: &ArcPath) -> anyhow::Result<()> {
+        let editor = self
+            .env
+            .env(ArcOsStr::from("EDITOR"))
+            .await
+            .ok()
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or_else(|| ArcStr::from(DEFAULT_EDITOR));
+
+        // `$EDITOR` may carry arguments, as in `code --wait`
+        let mut words = editor.split_whitespace().map(ArcStr::from);
+        let program = words.next().unwrap_or_else(|| ArcStr::from(DEFAULT_EDITOR));
+        let mut args: Vec<ArcStr> = words.collect();
+        args.push(ArcStr::from(&path.to_string_lossy()));
+
+        let result = self
+            .shell
+            .interactive(program, ArcSlice::from(args))
+            .await?;
+        if result.is_failure() {
+            anyhow::bail!("Editor '{}' exited with {}", editor, result.status);
+        }
+        Ok(())
+    }
+
+    /// Writes `content` to `path`, replacing what was there.
+    async fn write(&self, path: &ArcPath, content: &str) -> anyhow::Result<()> {
+        let mut file = self
+            .fs
+            .write_file(path.clone())
+            .await
+            .with_context(|| format!("Opening {} for writing", path.display()))?;
+        file.write_all(content.as_bytes())
+            .await
+            .with_context(|| format!("Writing {}", path.display()))?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Reads the whole content of `path`.
+    async fn read(&self, path: &ArcPath) -> anyhow::Result<ArcStr> {
+        let mut file = self
+            .fs
+            .read_file(path.clone())
+            .await
+            .with_context(|| format!("Opening {} for reading", path.display()))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .await
+            .with_context(|| format!("Reading {}", path.display()))?;
+        Ok(ArcStr::from(content))
+    }
+}
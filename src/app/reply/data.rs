@@ -0,0 +1,309 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::api::lore::mime::encode_encoded_words;
+use crate::api::lore::{LorePatchMessage, parse_patch_message};
+use crate::{ArcPath, ArcStr};
+
+/// The `From_` line that opens every message of the mbox files we write.
+const MBOX_FROM_LINE: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970";
+
+/// Trailers a reviewer can add to a reply with a single action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trailer {
+    /// `Reviewed-by:`
+    ReviewedBy,
+    /// `Acked-by:`
+    AckedBy,
+    /// `Tested-by:`
+    TestedBy,
+}
+
+impl Trailer {
+    /// Gets the trailer key as written in the message.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Trailer::ReviewedBy => "Reviewed-by",
+            Trailer::AckedBy => "Acked-by",
+            Trailer::TestedBy => "Tested-by",
+        }
+    }
+
+    /// Formats the trailer line for the given identity.
+    pub fn line(&self, identity: &Identity) -> String {
+        format!("{}: {}", self.key(), identity)
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+impl FromStr for Trailer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().trim_end_matches("-by") {
+            "reviewed" => Ok(Trailer::ReviewedBy),
+            "acked" => Ok(Trailer::AckedBy),
+            "tested" => Ok(Trailer::TestedBy),
+            _ => Err(anyhow::anyhow!(
+                "Invalid trailer '{}', expected reviewed-by, acked-by or tested-by",
+                s
+            )),
+        }
+    }
+}
+
+/// The name and address replies are sent from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// Display name, may be empty
+    pub name: ArcStr,
+    /// Email address
+    pub email: ArcStr,
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "<{}>", self.email)
+        } else {
+            write!(f, "{} <{}>", self.name, self.email)
+        }
+    }
+}
+
+/// Options for composing a reply.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplyOptions {
+    /// Trailers appended after the quoted patch
+    pub trailers: Vec<Trailer>,
+    /// Whether to open the draft in `$EDITOR` before saving it
+    pub edit: bool,
+}
+
+/// A reply saved to disk, ready to be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplyDraft {
+    /// Path of the mbox file holding the reply
+    pub path: ArcPath,
+    /// Subject of the reply
+    pub subject: ArcStr,
+    /// Main recipient of the reply
+    pub to: ArcStr,
+    /// Other recipients of the reply
+    pub cc: Vec<ArcStr>,
+    /// The whole mbox content
+    pub mbox: ArcStr,
+}
+
+impl fmt::Display for ReplyDraft {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Subject: {}", self.subject)?;
+        writeln!(f, "To: {}", self.to)?;
+        if !self.cc.is_empty() {
+            let cc: Vec<&str> = self.cc.iter().map(|address| &**address).collect();
+            writeln!(f, "Cc: {}", cc.join(", "))?;
+        }
+        write!(f, "Saved to {}", self.path.display())
+    }
+}
+
+/// Splits an address list header on the commas that separate addresses.
+///
+/// Commas inside quoted display names or angle brackets do not split, and neither do
+/// the ones before any address, as in the decoded `Smith, John <john@example.com>`.
+pub fn split_addresses(value: &str) -> Vec<String> {
+    let mut addresses = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut angle = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => angle = true,
+            '>' if !quoted => angle = false,
+            ',' if !quoted && !angle && (current.contains('@') || current.trim().is_empty()) => {
+                addresses.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    addresses.push(current);
+    addresses
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Extracts the lowercase email address of an address such as `Jane <jane@example.com>`.
+pub fn address_email(address: &str) -> String {
+    let email = match (address.rfind('<'), address.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &address[start + 1..end],
+        _ => address,
+    };
+    email.trim().to_ascii_lowercase()
+}
+
+/// Encodes the display name of a decoded address for a header, if it needs it.
+///
+/// Names that are not plain ASCII become encoded-words, and plain ones with a comma
+/// are quoted so the address is not split on it.
+fn encode_address(address: &str) -> String {
+    let Some(start) = address.rfind('<').filter(|start| *start > 0) else {
+        return address.to_string();
+    };
+    let (name, email) = address.split_at(start);
+    let name = name.trim();
+    if !name.is_ascii() {
+        format!("{} {}", encode_encoded_words(name.trim_matches('"')), email)
+    } else if name.contains(',') && !name.starts_with('"') {
+        format!("\"{}\" {}", name, email)
+    } else {
+        address.to_string()
+    }
+}
+
+/// Builds the subject of a reply, adding `Re: ` unless it is already there.
+pub fn reply_subject(subject: &str) -> String {
+    let subject = subject.trim();
+    if subject
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+    {
+        subject.to_string()
+    } else {
+        format!("Re: {}", subject)
+    }
+}
+
+/// Builds the file name of the reply to a message, keeping it filesystem safe.
+pub fn reply_file_name(message_id: &str) -> String {
+    let name: String = message_id
+        .trim_matches(['<', '>'])
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("re-{}.mbox", name)
+}
+
+/// Composes a reply to a raw patch email.
+///
+/// The reply goes to the author (or `Reply-To`) with everyone else on `To`/`Cc` copied,
+/// minus the replying identity. `In-Reply-To` and `References` keep it in the thread,
+/// the patch body is quoted up to its signature, and the trailers follow the quote.
+///
+/// # Arguments
+/// * `raw` - The raw email being replied to
+/// * `message_id` - The message ID of the email, used if it has no `Message-ID` header
+/// * `identity` - Who the reply is from
+/// * `trailers` - Trailers to append after the quote
+/// * `path` - Where the reply is going to be saved
+///
+/// # Returns
+/// The reply, with its content as a single-message mbox.
+pub fn compose_reply(
+    raw: &str,
+    message_id: &str,
+    identity: &Identity,
+    trailers: &[Trailer],
+    path: ArcPath,
+) -> ReplyDraft {
+    let message = parse_patch_message(raw).unwrap_or_else(|_| LorePatchMessage {
+        body: ArcStr::from(raw),
+        ..Default::default()
+    });
+    let headers = &message.headers;
+
+    let to = headers
+        .get("Reply-To")
+        .or(headers.get("From"))
+        .map(|to| to.to_string())
+        .unwrap_or_default();
+
+    let own = identity.email.to_ascii_lowercase();
+    let mut seen = vec![own, address_email(&to)];
+    let mut cc = Vec::new();
+    for value in headers.get_all("To").chain(headers.get_all("Cc")) {
+        for address in split_addresses(value) {
+            let email = address_email(&address);
+            if !seen.contains(&email) {
+                seen.push(email);
+                cc.push(ArcStr::from(address));
+            }
+        }
+    }
+
+    let subject = reply_subject(&message.subject);
+    let in_reply_to = headers
+        .get("Message-ID")
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| format!("<{}>", message_id.trim_matches(['<', '>'])));
+    let references = match headers.get("References").or(headers.get("In-Reply-To")) {
+        Some(parent) => format!("{} {}", parent, in_reply_to),
+        None => in_reply_to.clone(),
+    };
+
+    let mut mbox = format!("{}\n", MBOX_FROM_LINE);
+    mbox.push_str(&format!("From: {}\n", identity));
+    mbox.push_str(&format!("To: {}\n", encode_address(&to)));
+    if !cc.is_empty() {
+        let cc: Vec<String> = cc.iter().map(|address| encode_address(address)).collect();
+        mbox.push_str(&format!("Cc: {}\n", cc.join(",\n    ")));
+    }
+    mbox.push_str(&format!("Subject: {}\n", encode_encoded_words(&subject)));
+    mbox.push_str(&format!("In-Reply-To: {}\n", in_reply_to));
+    mbox.push_str(&format!("References: {}\n", references));
+
+    // Quote the decoded text, not its quoted-printable or base64 transfer form
+    let body = &message.body;
+    let mut text = String::with_capacity(body.len() + body.len() / 16);
+    match headers.get("Date") {
+        Some(date) => text.push_str(&format!("On {}, {} wrote:\n", date, message.author)),
+        None => text.push_str(&format!("{} wrote:\n", message.author)),
+    }
+    for line in body.lines() {
+        if line == "-- " {
+            break;
+        }
+        if line.is_empty() {
+            text.push_str(">\n");
+        } else {
+            text.push_str(&format!("> {}\n", line));
+        }
+    }
+    text.push('\n');
+    for trailer in trailers {
+        text.push_str(&trailer.line(identity));
+        text.push('\n');
+    }
+
+    // Decoded text may no longer be ASCII, so say how it is encoded
+    if !text.is_ascii() {
+        mbox.push_str("MIME-Version: 1.0\n");
+        mbox.push_str("Content-Type: text/plain; charset=UTF-8\n");
+        mbox.push_str("Content-Transfer-Encoding: 8bit\n");
+    }
+    mbox.push('\n');
+    mbox.push_str(&text);
+
+    ReplyDraft {
+        path,
+        subject: ArcStr::from(subject),
+        to: ArcStr::from(to),
+        cc,
+        mbox: ArcStr::from(mbox),
+    }
+}
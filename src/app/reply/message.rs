@@ -0,0 +1,21 @@
+use tokio::sync::oneshot::Sender;
+
+use crate::ArcStr;
+
+use super::data::{ReplyDraft, ReplyOptions};
+
+/// Messages that can be sent to the reply actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Compose a reply to a message and save it as an mbox
+    Compose {
+        /// The mailing list the message belongs to
+        list: ArcStr,
+        /// The message ID of the message being replied to
+        message_id: ArcStr,
+        /// Trailers to add and whether to open the editor
+        options: ReplyOptions,
+        /// Response channel for the saved reply
+        tx: Sender<anyhow::Result<ReplyDraft>>,
+    },
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::{ArcPath, ArcStr};
+
+use super::data::{ReplyDraft, ReplyOptions, reply_file_name};
+
+/// A request recorded by the mock reply actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The mailing list the message belongs to
+    pub list: ArcStr,
+    /// The message ID of the message being replied to
+    pub message_id: ArcStr,
+    /// Trailers to add and whether to open the editor
+    pub options: ReplyOptions,
+}
+
+/// Mock implementation of the Reply actor for testing purposes.
+///
+/// This struct stores compose requests in memory and returns empty drafts,
+/// allowing tests to run without an editor or the patch cache.
+#[derive(Debug, Clone, Default)]
+pub struct Mock {
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with an empty request store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Composes a reply to a message.
+    /// Mock implementation stores the request and returns an empty draft.
+    ///
+    /// # Arguments
+    /// * `list` - The mailing list the message belongs to
+    /// * `message_id` - The message ID of the message being replied to
+    /// * `options` - Trailers to add and whether to open the editor
+    ///
+    /// # Returns
+    /// A draft named after the message ID.
+    pub async fn compose(
+        &self,
+        list: ArcStr,
+        message_id: ArcStr,
+        options: ReplyOptions,
+    ) -> anyhow::Result<ReplyDraft> {
+        let mut lock = self.requests.lock().await;
+        let path = ArcPath::from(reply_file_name(&message_id).as_str());
+        lock.push(Request {
+            list,
+            message_id,
+            options,
+        });
+        Ok(ReplyDraft {
+            path,
+            subject: ArcStr::default(),
+            to: ArcStr::default(),
+            cc: Vec::new(),
+            mbox: ArcStr::default(),
+        })
+    }
+
+    /// Gets all recorded compose requests.
+    ///
+    /// # Returns
+    /// A vector of all compose requests.
+    pub async fn get_requests(&self) -> Vec<Request> {
+        let lock = self.requests.lock().await;
+        lock.clone()
+    }
+}
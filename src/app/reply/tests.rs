@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use super::{
+    Identity, Reply, ReplyDraft, ReplyOptions, Trailer, address_email, compose_reply,
+    reply_file_name, reply_subject, split_addresses,
+};
+use crate::app::cache::{PatchCache, PatchMockData};
+use crate::app::config::{Config, Data, PathOpt, StrOpt};
+use crate::env::Env;
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::shell::Shell;
+use crate::{ArcOsStr, ArcPath, ArcStr};
+
+const PATCH: &str = "From mboxrd@z Thu Jan  1 00:00:00 1970
+From: Jane Doe <jane@example.com>
+To: netdev@vger.kernel.org
+Cc: \"Smith, John\" <john@example.com>, Me <me@example.com>,
+ jane@example.com
+Subject: [PATCH v2 1/2] net: do
+ the thing
+Date: Mon, 6 Jan 2025 10:00:00 +0000
+Message-ID: <20250106.1-jane@example.com>
+In-Reply-To: <20250106.0-jane@example.com>
+References: <20250106.0-jane@example.com>
+
+Commit message.
+
+---
+diff --git a/file.c b/file.c
+-- \n\
+2.43.0
+";
+
+fn compose(raw: &str, message_id: &str, trailers: &[Trailer]) -> ReplyDraft {
+    compose_reply(raw, message_id, &me(), trailers, ArcPath::from("re.mbox"))
+}
+
+fn me() -> Identity {
+    Identity {
+        name: ArcStr::from("Me"),
+        email: ArcStr::from("Me@Example.com"),
+    }
+}
+
+#[test]
+fn test_split_addresses() {
+    assert_eq!(
+        split_addresses("\"Smith, John\" <john@example.com>, a@b.c ,, <x,y@z>"),
+        vec!["\"Smith, John\" <john@example.com>", "a@b.c", "<x,y@z>"]
+    );
+    assert_eq!(
+        split_addresses("Smith, John <john@example.com>, a@b.c"),
+        vec!["Smith, John <john@example.com>", "a@b.c"]
+    );
+    assert_eq!(address_email("Jane <Jane@Example.com>"), "jane@example.com");
+    assert_eq!(address_email(" jane@example.com "), "jane@example.com");
+}
+
+#[test]
+fn test_reply_subject_and_file_name() {
+    assert_eq!(reply_subject("[PATCH] x"), "Re: [PATCH] x");
+    assert_eq!(reply_subject("RE: [PATCH] x"), "RE: [PATCH] x");
+    assert_eq!(
+        reply_file_name("<20250106.1-jane@example.com>"),
+        "re-20250106.1-jane@example.com.mbox"
+    );
+    assert_eq!(reply_file_name("a/b c"), "re-a_b_c.mbox");
+}
+
+#[test]
+fn test_trailer_from_str() {
+    assert_eq!(
+        "Reviewed-by".parse::<Trailer>().unwrap(),
+        Trailer::ReviewedBy
+    );
+    assert_eq!("acked".parse::<Trailer>().unwrap(), Trailer::AckedBy);
+    assert!("signed-off-by".parse::<Trailer>().is_err());
+    assert_eq!(
+        Trailer::TestedBy.line(&me()),
+        "Tested-by: Me <Me@Example.com>"
+    );
+}
+
+#[test]
+fn test_compose_reply_headers() {
+    let reply = compose(PATCH, "ignored", &[]);
+    assert_eq!(
+        reply.subject,
+        ArcStr::from("Re: [PATCH v2 1/2] net: do the thing")
+    );
+    assert_eq!(reply.to, ArcStr::from("Jane Doe <jane@example.com>"));
+    assert_eq!(
+        reply.cc,
+        vec![
+            ArcStr::from("netdev@vger.kernel.org"),
+            ArcStr::from("\"Smith, John\" <john@example.com>"),
+        ]
+    );
+    let mbox = reply.mbox;
+    assert!(mbox.starts_with(
+        "From mboxrd@z Thu Jan  1 00:00:00 1970\nFrom: Me <Me@Example.com>\nTo: Jane"
+    ));
+    assert!(mbox.contains("\nIn-Reply-To: <20250106.1-jane@example.com>\n"));
+    assert!(
+        mbox.contains(
+            "\nReferences: <20250106.0-jane@example.com> <20250106.1-jane@example.com>\n\n"
+        )
+    );
+}
+
+#[test]
+fn test_compose_reply_body() {
+    let reply = compose(PATCH, "ignored", &[Trailer::ReviewedBy]);
+    let body = reply.mbox.split_once("\n\n").unwrap().1;
+    assert_eq!(
+        body,
+        "On Mon, 6 Jan 2025 10:00:00 +0000, Jane Doe wrote:
+> Commit message.
+>
+> ---
+> diff --git a/file.c b/file.c
+
+Reviewed-by: Me <Me@Example.com>
+"
+    );
+}
+
+#[test]
+fn test_compose_reply_without_message_id_header() {
+    let raw = "From: jane@example.com\nSubject: Re: [PATCH] x\n\nbody\n";
+    let reply = compose(raw, "1234@example.com", &[]);
+    assert_eq!(reply.to, ArcStr::from("jane@example.com"));
+    assert!(reply.cc.is_empty());
+    assert!(reply.mbox.contains(
+        "Subject: Re: [PATCH] x\nIn-Reply-To: <1234@example.com>\nReferences: <1234@example.com>\n"
+    ));
+    assert!(reply.mbox.contains("\n\njane@example.com wrote:\n> body\n"));
+}
+
+#[test]
+fn test_compose_reply_decodes_body() {
+    let raw = "From: =?utf-8?q?Andr=C3=A9?= <andre@example.com>
+Cc: =?utf-8?q?M=C3=BCller=2C_J=C3=B6rg?= <jorg@example.com>
+Subject: [PATCH] =?utf-8?q?caf=C3=A9?=
+Content-Type: text/plain; charset=utf-8
+Content-Transfer-Encoding: quoted-printable
+
+Caf=C3=A9 fix.
+";
+    let reply = compose(raw, "1@example.com", &[]);
+    assert_eq!(reply.to, ArcStr::from("André <andre@example.com>"));
+    assert_eq!(
+        reply.cc,
+        vec![ArcStr::from("Müller, Jörg <jorg@example.com>")]
+    );
+    assert_eq!(reply.subject, ArcStr::from("Re: [PATCH] café"));
+    let (headers, body) = reply.mbox.split_once("\n\n").unwrap();
+    // Headers are encoded again, and decode to the same values
+    assert!(headers.contains("\nTo: =?UTF-8?B?QW5kcsOp?= <andre@example.com>\n"));
+    let parsed = crate::api::lore::mime::parse_headers(&reply.mbox);
+    assert_eq!(
+        parsed.get("Cc").map(|cc| cc.to_string()),
+        Some("Müller, Jörg <jorg@example.com>".to_string())
+    );
+    assert_eq!(
+        parsed.get("Subject").map(|subject| subject.to_string()),
+        Some("Re: [PATCH] café".to_string())
+    );
+    assert!(
+        headers.ends_with(
+            "\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit"
+        )
+    );
+    assert_eq!(body, "André wrote:\n> Café fix.\n\n");
+}
+
+async fn spawn_reply(shell: Shell, env: Env) -> Reply {
+    let patch_cache = PatchCache::mock(PatchMockData {
+        patches: HashMap::from([("netdev:p1".to_string(), PATCH.to_string())]),
+    });
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::ReplyDir, ArcPath::from("replies"))
+        .await;
+    config.set_str(StrOpt::UserName, ArcStr::from("Me")).await;
+    config
+        .set_str(StrOpt::UserEmail, ArcStr::from("me@example.com"))
+        .await;
+    Reply::spawn(env, Fs::mock(), shell, patch_cache, config, Log::mock())
+}
+
+#[tokio::test]
+async fn test_compose_writes_draft_with_trailers() {
+    let shell = Shell::mock();
+    let reply = spawn_reply(shell.clone(), Env::mock()).await;
+
+    let draft = reply
+        .compose(
+            ArcStr::from("netdev"),
+            ArcStr::from("p1"),
+            ReplyOptions {
+                trailers: vec![Trailer::ReviewedBy, Trailer::TestedBy],
+                edit: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(draft.path, ArcPath::from("replies/re-p1.mbox"));
+    assert!(
+        draft
+            .mbox
+            .ends_with("\nReviewed-by: Me <me@example.com>\nTested-by: Me <me@example.com>\n")
+    );
+    // The configured identity is used, git is not consulted and no editor is opened
+    assert!(shell.get_commands().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_compose_opens_editor_from_env() {
+    let shell = Shell::mock();
+    let env = Env::mock();
+    env.set_env(ArcOsStr::from("EDITOR"), "nvim -c 'set tw=72'")
+        .await;
+    let reply = spawn_reply(shell.clone(), env).await;
+
+    let draft = reply
+        .compose(
+            ArcStr::from("netdev"),
+            ArcStr::from("p1"),
+            ReplyOptions {
+                edit: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let commands = shell.get_commands().await.unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(&*commands[0].program, "nvim");
+    assert_eq!(
+        commands[0].args.last().unwrap(),
+        &ArcStr::from("replies/re-p1.mbox")
+    );
+    // The mock editor leaves the draft untouched
+    assert!(
+        draft
+            .mbox
+            .contains("Subject: Re: [PATCH v2 1/2] net: do the thing\n")
+    );
+}
+
+#[tokio::test]
+async fn test_compose_fails_on_missing_patch() {
+    let reply = spawn_reply(Shell::mock(), Env::mock()).await;
+    let result = reply
+        .compose(
+            ArcStr::from("netdev"),
+            ArcStr::from("missing"),
+            ReplyOptions::default(),
+        )
+        .await;
+    assert!(result.is_err());
+}
@@ -2,7 +2,12 @@ use anyhow::{Context, Result};
 use tokio::sync::{mpsc::Sender, oneshot};
 
 use crate::ArcStr;
-use crate::app::cache::{FeedCache, MailingListCache, PatchCache};
+use crate::app::apply::Apply;
+use crate::app::mail::Mail;
+use crate::app::maintainers::Maintainers;
+use crate::app::reply::{Reply, Trailer};
+use crate::app::cache::{FeedCache, MailingListCache, PatchCache, ReviewCache, SeenCache};
+use crate::app::watch::{Watch, WatchUpdate};
 use crate::log::Log;
 use crate::render::Render;
 use crate::terminal::Terminal;
@@ -29,6 +34,7 @@ pub enum Ui {
 
 impl Ui {
     /// Create a new UI actor
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         log: Log,
         terminal: Terminal,
@@ -36,6 +42,13 @@ impl Ui {
         feed_cache: FeedCache,
         patch_cache: PatchCache,
         render: Render,
+        apply: Apply,
+        review_cache: ReviewCache,
+        seen_cache: SeenCache,
+        reply: Reply,
+        mail: Mail,
+        maintainers: Maintainers,
+        watch: Watch,
     ) -> (Self, tokio::task::JoinHandle<()>) {
         let core = core::Core::new(
             log,
@@ -44,6 +57,13 @@ impl Ui {
             feed_cache,
             patch_cache,
             render,
+            apply,
+            review_cache,
+            seen_cache,
+            reply,
+            mail,
+            maintainers,
+            watch,
         );
         core.spawn()
     }
@@ -93,6 +113,73 @@ impl Ui {
         }
     }
 
+    /// Search the archive and show the matching patches as a feed.
+    ///
+    /// The query uses public-inbox syntax (e.g. `f:jane dfn:mm/`) and runs against the
+    /// list whose feed is open, or against all lists from the lists view.
+    pub async fn search(&self, query: ArcStr) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::Search { query, tx })
+                    .await
+                    .context("Sending search message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for search from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.search(query).await
+            }
+        }
+    }
+
+    /// Show the inbox: the patches of every list touching the subsystems the user
+    /// declared in the configuration, as a feed.
+    pub async fn inbox(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::Inbox { tx })
+                    .await
+                    .context("Sending inbox message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for inbox from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.inbox().await
+            }
+        }
+    }
+
+    /// Tell the user new patches arrived in a watched list or search.
+    ///
+    /// The status line names the feed, and the unread counts of the lists view are
+    /// redrawn if it is open.
+    pub async fn notify(&self, update: WatchUpdate, bell: bool) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::Notify { update, bell, tx })
+                    .await
+                    .context("Sending notify message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for notify from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.notify(update).await
+            }
+        }
+    }
+
     /// Show a specific patch content
     pub async fn show_patch(&self, list: ArcStr, message_id: ArcStr, title: ArcStr) -> Result<()> {
         match self {
@@ -118,6 +205,213 @@ impl Ui {
         }
     }
 
+    /// Switch the feed between the flat and the series-grouped view
+    pub async fn toggle_series(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::ToggleSeries { tx })
+                    .await
+                    .context("Sending toggle series message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for toggle series from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.toggle_series().await
+            }
+        }
+    }
+
+    /// Show the known revisions of the selected series
+    pub async fn show_versions(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::ShowVersions { tx })
+                    .await
+                    .context("Sending show versions message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for show versions from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.show_versions().await
+            }
+        }
+    }
+
+    /// Mark the selected revision as the base of the next interdiff
+    pub async fn mark_version(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::MarkVersion { tx })
+                    .await
+                    .context("Sending mark version message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for mark version from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.mark_version().await
+            }
+        }
+    }
+
+    /// Show the interdiff between the marked (or previous) and the selected revision
+    pub async fn show_interdiff(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::ShowInterdiff { tx })
+                    .await
+                    .context("Sending show interdiff message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for show interdiff from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.show_interdiff().await
+            }
+        }
+    }
+
+    /// Apply the selected (or open) patch or series to the local repository
+    pub async fn apply(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::Apply { tx })
+                    .await
+                    .context("Sending apply message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for apply from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.apply().await
+            }
+        }
+    }
+
+    /// Compose a reply to the selected (or open) patch.
+    ///
+    /// Without a trailer the reply is opened in `$EDITOR`; with one, the trailer is
+    /// added and the reply saved right away.
+    pub async fn reply(&self, trailer: Option<Trailer>) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::Reply { trailer, tx })
+                    .await
+                    .context("Sending reply message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for reply from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.reply(trailer).await
+            }
+        }
+    }
+
+    /// Send the last reply composed in this session through the mail backend
+    pub async fn send_reply(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::SendReply { tx })
+                    .await
+                    .context("Sending send reply message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for send reply from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.send_reply().await
+            }
+        }
+    }
+
+    /// Cycle the review state of the selected (or open) patch
+    pub async fn cycle_review(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::CycleReview { tx })
+                    .await
+                    .context("Sending cycle review message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for cycle review from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.cycle_review().await
+            }
+        }
+    }
+
+    /// Move to the next patch of the feed that was not opened yet.
+    ///
+    /// From the feed the selection moves to it, from a patch opened from the feed the
+    /// patch is opened.
+    pub async fn next_unread(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::NextUnread { tx })
+                    .await
+                    .context("Sending next unread message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for next unread from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.next_unread().await
+            }
+        }
+    }
+
+    /// Mark every cached patch of the open list, or of the list selected in the lists
+    /// view, as read
+    pub async fn mark_all_read(&self) -> Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = oneshot::channel();
+                sender
+                    .send(Message::MarkAllRead { tx })
+                    .await
+                    .context("Sending mark all read message to UI actor")
+                    .expect("UI actor died");
+                rx.await
+                    .context("Awaiting response for mark all read from UI actor")
+                    .expect("UI actor died")
+            }
+            Self::Mock(mock) => {
+                mock.mark_all_read().await
+            }
+        }
+    }
+
     /// Update the current selection index
     pub async fn update_selection(&self, index: usize) {
         match self {
@@ -1,9 +1,19 @@
 use anyhow::Result;
+use std::collections::HashSet;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use crate::ArcStr;
-use crate::app::cache::{FeedCache, MailingListCache, PatchCache};
+use crate::api::lore::{ALL_LISTS, LorePatchMetadata, LoreQuery, LoreSearch, parse_patch_message};
+use crate::app::apply::{Apply, ApplyOptions};
+use crate::app::cache::patch::interdiff;
+use crate::app::cache::{
+    FeedCache, MailingListCache, PatchCache, PatchSeries, ReviewCache, ReviewState, SeenCache,
+};
+use crate::app::mail::Mail;
+use crate::app::maintainers::{Maintainers, inbox_search, inbox_slice};
+use crate::app::reply::{Reply, ReplyOptions, Trailer};
+use crate::app::watch::{Watch, WatchUpdate};
 use crate::log::Log;
 use crate::render::Render;
 use crate::terminal::{Screen, Terminal};
@@ -30,10 +40,25 @@ pub struct Core {
     patch_cache: PatchCache,
     /// Render actor
     render: Render,
+    /// Apply actor
+    apply: Apply,
+    /// Review status store
+    review_cache: ReviewCache,
+    /// Store of the patches already opened
+    seen_cache: SeenCache,
+    /// Reply actor
+    reply: Reply,
+    /// Mail actor
+    mail: Mail,
+    /// Maintainers actor
+    maintainers: Maintainers,
+    /// Watch actor
+    watch: Watch,
 }
 
 impl Core {
     /// Create a new UI actor core
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         log: Log,
         terminal: Terminal,
@@ -41,6 +66,13 @@ impl Core {
         feed_cache: FeedCache,
         patch_cache: PatchCache,
         render: Render,
+        apply: Apply,
+        review_cache: ReviewCache,
+        seen_cache: SeenCache,
+        reply: Reply,
+        mail: Mail,
+        maintainers: Maintainers,
+        watch: Watch,
     ) -> Self {
         Self {
             state: UiState::default(),
@@ -50,6 +82,13 @@ impl Core {
             feed_cache,
             patch_cache,
             render,
+            apply,
+            review_cache,
+            seen_cache,
+            reply,
+            mail,
+            maintainers,
+            watch,
         }
     }
 
@@ -77,6 +116,14 @@ impl Core {
                         let result = core.handle_show_feed(list, page).await;
                         let _ = tx.send(result);
                     }
+                    Message::Search { query, tx } => {
+                        let result = core.handle_search(query).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Inbox { tx } => {
+                        let result = core.handle_inbox().await;
+                        let _ = tx.send(result);
+                    }
                     Message::ShowPatch {
                         list,
                         message_id,
@@ -86,6 +133,50 @@ impl Core {
                         let result = core.handle_show_patch(list, message_id, title).await;
                         let _ = tx.send(result);
                     }
+                    Message::ToggleSeries { tx } => {
+                        let result = core.handle_toggle_series().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::ShowVersions { tx } => {
+                        let result = core.handle_show_versions().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::MarkVersion { tx } => {
+                        let result = core.handle_mark_version().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::ShowInterdiff { tx } => {
+                        let result = core.handle_show_interdiff().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::CycleReview { tx } => {
+                        let result = core.handle_cycle_review().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::NextUnread { tx } => {
+                        let result = core.handle_next_unread().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::MarkAllRead { tx } => {
+                        let result = core.handle_mark_all_read().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Notify { update, bell, tx } => {
+                        let result = core.handle_notify(update, bell).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Apply { tx } => {
+                        let result = core.handle_apply().await;
+                        let _ = tx.send(result);
+                    }
+                    Message::Reply { trailer, tx } => {
+                        let result = core.handle_reply(trailer).await;
+                        let _ = tx.send(result);
+                    }
+                    Message::SendReply { tx } => {
+                        let result = core.handle_send_reply().await;
+                        let _ = tx.send(result);
+                    }
                     Message::UpdateSelection { index } => {
                         core.handle_update_selection(index);
                     }
@@ -124,10 +215,175 @@ impl Core {
 
     /// Handle showing feed view
     async fn handle_show_feed(&mut self, list: ArcStr, page: usize) -> Result<()> {
+        self.state.feed_search = None;
+        self.state.feed_inbox.clear();
+        self.open_feed(list, page).await
+    }
+
+    /// Handle searching the archive, showing the results as a feed
+    ///
+    /// The search runs against the list whose feed is open (or that the open search
+    /// ran against), and against all lists from the lists view.
+    async fn handle_search(&mut self, query: ArcStr) -> Result<()> {
+        let query = LoreQuery::patches().raw(&query);
+        if query.terms().is_empty() {
+            return Ok(());
+        }
+        let list = match self.state.view {
+            ViewKind::Lists => None,
+            _ => self.lore_list(),
+        };
+        let search = LoreSearch::new(list.unwrap_or_else(|| ArcStr::from(ALL_LISTS)), query);
+        self.log.info(
+            SCOPE,
+            format!("{:?} -> Search '{}'", self.state.view, search.describe()),
+        );
+        let key = self.feed_cache.search(search.clone()).await;
+        self.state.feed_search = Some(search);
+        self.state.feed_inbox.clear();
+        self.open_feed(key, 0).await
+    }
+
+    /// Handle showing the inbox: the patches of every list touching the declared subsystems
+    ///
+    /// The inbox is the search built by [`inbox_search`], so it may hold patches that only
+    /// touch files excluded from the subsystems. Failures are shown in the patch view, so
+    /// leaving it returns to where the inbox was opened from.
+    async fn handle_inbox(&mut self) -> Result<()> {
+        let search = match self.maintainers.declared().await {
+            Ok(declared) => match inbox_search(&declared) {
+                Some(search) => Ok((search, declared)),
+                None => Err(String::from(
+                    "No subsystem with searchable files is declared.\n\n\
+                     Add the names of your MAINTAINERS entries to `subsystems` in the \
+                     configuration (`patch-hub maintainers` lists them).",
+                )),
+            },
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        let (search, declared) = match search {
+            Ok(search) => search,
+            Err(message) => {
+                self.log.error(SCOPE, format!("Inbox: {}", message));
+                if self.state.view != ViewKind::Patch {
+                    self.state.patch_origin = self.state.view;
+                }
+                self.state.view = ViewKind::Patch;
+                self.state.patch_message_id = None;
+                return self
+                    .terminal
+                    .show(Screen::Patch {
+                        title: ArcStr::from("Inbox unavailable"),
+                        content: ArcStr::from(message),
+                    })
+                    .await;
+            }
+        };
+
+        self.log.info(
+            SCOPE,
+            format!(
+                "{:?} -> Inbox of {} subsystem(s)",
+                self.state.view,
+                declared.len()
+            ),
+        );
+        let key = self.feed_cache.search(search.clone()).await;
+        self.state.feed_search = Some(search);
+        self.state.feed_inbox = declared.into_iter().map(|s| s.name).collect();
+        self.open_feed(key, 0).await
+    }
+
+    /// Handle new patches arriving in a watched list or search
+    ///
+    /// The status line names the feed and its newest patch, and the lists view is
+    /// redrawn if it is open so its unread counts take the new patches in.
+    async fn handle_notify(&mut self, update: WatchUpdate, bell: bool) -> Result<()> {
+        let Some(newest) = update.patches.first() else {
+            return Ok(());
+        };
+        let text = format!(
+            "{} new patch(es) in {}, latest: {}",
+            update.patches.len(),
+            update.target.name,
+            newest.title
+        );
+        self.log.info(SCOPE, format!("Notify: {}", text));
+        self.terminal.notify(ArcStr::from(text), bell).await?;
+        if self.state.view == ViewKind::Lists {
+            self.render_lists().await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the list to fetch the messages of the feed from.
+    ///
+    /// That is the open list, or the list a search ran against, since search results
+    /// are cached under a key that is no list name.
+    fn lore_list(&self) -> Option<ArcStr> {
+        match &self.state.feed_search {
+            Some(search) => Some(search.list.clone()),
+            None => self.state.feed_list.clone(),
+        }
+    }
+
+    /// Returns a range of the patches of the open feed.
+    ///
+    /// In the inbox, the range counts the patches that touch files of a declared entry
+    /// only, so it shows the same patches as the `inbox` command.
+    async fn feed_slice(
+        &self,
+        list: ArcStr,
+        range: std::ops::Range<usize>,
+    ) -> Result<Vec<LorePatchMetadata>> {
+        if self.state.feed_inbox.is_empty() {
+            return self.feed_cache.get_slice(list, range).await;
+        }
+        let patches = inbox_slice(&self.maintainers, &self.feed_cache, list, range).await?;
+        for patch in &patches {
+            if let Err(e) = &patch.subsystems {
+                self.log.warn(
+                    SCOPE,
+                    format!(
+                        "Inbox: failed to check {} against MAINTAINERS: {}",
+                        patch.patch.message_id, e
+                    ),
+                );
+            }
+        }
+        Ok(patches.into_iter().map(|p| p.patch).collect())
+    }
+
+    /// Returns how the open feed is named in titles.
+    fn feed_label(&self, list: &ArcStr) -> ArcStr {
+        if !self.state.feed_inbox.is_empty() {
+            let names: Vec<&str> = self.state.feed_inbox.iter().map(|n| n.as_ref()).collect();
+            return ArcStr::from(format!("inbox of {}", names.join(", ")));
+        }
+        match &self.state.feed_search {
+            Some(search) => ArcStr::from(format!("search {}", search.describe())),
+            None => list.clone(),
+        }
+    }
+
+    /// Switch to the feed view of a mailing list or search and render its first page
+    async fn open_feed(&mut self, list: ArcStr, page: usize) -> Result<()> {
         self.state.view = ViewKind::Feed;
         self.state.feed_list = Some(list.clone());
         self.state.feed_page = page;
         self.state.feed_selected = 0;
+        self.state.feed_grouped = false;
+
+        // Opening a watched feed is what reading its new patches means
+        if let Err(e) = self.watch.mark_seen(list.clone()).await {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Failed to mark the new patches of '{}' as seen: {}",
+                    list, e
+                ),
+            );
+        }
 
         // Show loading screen immediately when transitioning to feed view
         self.terminal
@@ -162,15 +418,620 @@ impl Core {
         message_id: ArcStr,
         title: ArcStr,
     ) -> Result<()> {
+        if self.state.view != ViewKind::Patch {
+            self.state.patch_origin = self.state.view;
+        }
         self.state.view = ViewKind::Patch;
+        self.state.patch_message_id = Some(message_id.clone());
         self.render_patch(list, message_id, title).await
     }
 
+    /// Handle switching the feed between the flat and the series-grouped view
+    async fn handle_toggle_series(&mut self) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        match self.state.view {
+            ViewKind::Feed => {
+                self.log.info(SCOPE, "Feed -> Series");
+                self.state.view = ViewKind::Series;
+                self.state.series_page = 0;
+                self.state.series_selected = 0;
+                self.state.feed_grouped = true;
+                self.terminal
+                    .show(Screen::Loading(ArcStr::from("Grouping series...")))
+                    .await?;
+                self.render_series(list).await
+            }
+            ViewKind::Series => {
+                self.log.info(SCOPE, "Series -> Feed");
+                self.state.view = ViewKind::Feed;
+                self.state.feed_grouped = false;
+                self.render_feed(list).await
+            }
+            ViewKind::Lists | ViewKind::Versions | ViewKind::Patch => Ok(()),
+        }
+    }
+
+    /// Handle showing the known revisions of the selected feed item or series
+    async fn handle_show_versions(&mut self) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let message_id = match self.state.view {
+            ViewKind::Feed => {
+                let start = self.state.feed_page * 20;
+                let end = start + 20;
+                let items = self.feed_slice(list.clone(), start..end).await?;
+                items
+                    .get(self.state.feed_selected)
+                    .map(|item| item.message_id.clone())
+            }
+            ViewKind::Series => {
+                let start = self.state.series_page * 20;
+                let end = start + 20;
+                let items = self.feed_cache.get_series(list.clone(), start..end).await?;
+                items
+                    .get(self.state.series_selected)
+                    .map(|series| series.root.clone())
+            }
+            ViewKind::Lists | ViewKind::Versions | ViewKind::Patch => None,
+        };
+        let Some(message_id) = message_id else {
+            return Ok(());
+        };
+
+        self.log.info(
+            SCOPE,
+            format!(
+                "{:?} -> Versions list={} msg_id={}",
+                self.state.view, list, message_id
+            ),
+        );
+        let revisions = self
+            .feed_cache
+            .get_revisions(list.clone(), message_id.clone())
+            .await?;
+        self.state.view = ViewKind::Versions;
+        self.state.versions_of = Some(message_id);
+        self.state.versions_selected = revisions.len().saturating_sub(1);
+        self.state.versions_marked = None;
+        self.render_versions(list).await
+    }
+
+    /// Handle toggling the interdiff base mark on the selected revision
+    async fn handle_mark_version(&mut self) -> Result<()> {
+        if self.state.view != ViewKind::Versions {
+            return Ok(());
+        }
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let selected = self.state.versions_selected;
+        self.state.versions_marked = match self.state.versions_marked {
+            Some(marked) if marked == selected => None,
+            _ => Some(selected),
+        };
+        self.render_versions(list).await
+    }
+
+    /// Handle showing the interdiff between the marked (or previous) and the selected revision
+    async fn handle_show_interdiff(&mut self) -> Result<()> {
+        if self.state.view != ViewKind::Versions {
+            return Ok(());
+        }
+        let (Some(list), Some(message_id)) =
+            (self.state.feed_list.clone(), self.state.versions_of.clone())
+        else {
+            return Ok(());
+        };
+
+        let selected = self.state.versions_selected;
+        let base = match self.state.versions_marked {
+            Some(marked) => marked,
+            None if selected > 0 => selected - 1,
+            None => return Ok(()),
+        };
+        if base == selected {
+            return Ok(());
+        }
+
+        let revisions = self
+            .feed_cache
+            .get_revisions(list.clone(), message_id)
+            .await?;
+        let (Some(old), Some(new)) = (
+            revisions.get(base.min(selected)),
+            revisions.get(base.max(selected)),
+        ) else {
+            return Ok(());
+        };
+
+        let old_label = format!("v{}", old.version);
+        let new_label = format!("v{}", new.version);
+        let title = ArcStr::from(format!(
+            "Interdiff {} → {}: {}",
+            old_label, new_label, new.title
+        ));
+        self.log
+            .info(SCOPE, format!("Versions -> Interdiff {}", title));
+
+        self.terminal
+            .show(Screen::Loading(ArcStr::from("Computing interdiff...")))
+            .await?;
+        let (old_patches, new_patches) = match (
+            self.fetch_series_patches(old).await,
+            self.fetch_series_patches(new).await,
+        ) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(e), _) | (_, Err(e)) => {
+                self.log
+                    .error(SCOPE, format!("Interdiff: fetch error: {}", e));
+                return self
+                    .terminal
+                    .show(Screen::Error(ArcStr::from("Failed to load revisions")))
+                    .await;
+            }
+        };
+
+        let diff = interdiff::interdiff(&old_patches, &new_patches, &old_label, &new_label);
+        let content = if diff.is_empty() {
+            ArcStr::from(format!(
+                "No differences between {} and {}",
+                old_label, new_label
+            ))
+        } else {
+            match self.render.render_patch(ArcStr::from(diff.clone())).await {
+                Ok(rendered) => rendered,
+                Err(e) => {
+                    self.log
+                        .warn(SCOPE, format!("Interdiff: render error: {}", e));
+                    ArcStr::from(diff)
+                }
+            }
+        };
+
+        self.state.view = ViewKind::Patch;
+        self.state.patch_origin = ViewKind::Versions;
+        self.state.patch_message_id = None;
+        self.terminal.show(Screen::Patch { title, content }).await
+    }
+
+    /// Handle applying the selected patch or series, or the open patch, with `git am`
+    ///
+    /// A cover letter stands for its whole series. Like the `apply` command, a series
+    /// with missing patches is not applied, and the missing ones are reported instead.
+    /// The outcome is shown in the patch view so leaving it returns to where the apply
+    /// was triggered.
+    async fn handle_apply(&mut self) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let target = match self.state.view {
+            ViewKind::Feed => {
+                let start = self.state.feed_page * 20;
+                let end = start + 20;
+                let items = self.feed_slice(list.clone(), start..end).await?;
+                match items.get(self.state.feed_selected) {
+                    Some(item) => Some(self.apply_targets(&list, &item.message_id).await?),
+                    None => None,
+                }
+            }
+            ViewKind::Series => {
+                let start = self.state.series_page * 20;
+                let end = start + 20;
+                let items = self.feed_cache.get_series(list.clone(), start..end).await?;
+                items
+                    .get(self.state.series_selected)
+                    .map(|series| (series.title.clone(), series.patch_ids(), series.missing()))
+            }
+            ViewKind::Versions => match self.state.versions_of.clone() {
+                Some(message_id) => self
+                    .feed_cache
+                    .get_revisions(list.clone(), message_id)
+                    .await?
+                    .get(self.state.versions_selected)
+                    .map(|series| (series.title.clone(), series.patch_ids(), series.missing())),
+                None => None,
+            },
+            ViewKind::Patch => match self.state.patch_message_id.clone() {
+                Some(message_id) => Some(self.apply_targets(&list, &message_id).await?),
+                None => None,
+            },
+            ViewKind::Lists => None,
+        };
+        let Some((title, message_ids, missing)) = target else {
+            return Ok(());
+        };
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+
+        let (title, content) = if !missing.is_empty() {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "{:?} -> Apply refused title='{}' list={} missing={:?}",
+                    self.state.view, title, list, missing
+                ),
+            );
+            (
+                format!("Apply failed: {}", title),
+                format!("Series '{}' is missing patches {:?}", title, missing),
+            )
+        } else {
+            self.log.info(
+                SCOPE,
+                format!(
+                    "{:?} -> Apply title='{}' list={} patches={}",
+                    self.state.view,
+                    title,
+                    list,
+                    message_ids.len()
+                ),
+            );
+            self.terminal
+                .show(Screen::Loading(ArcStr::from(format!(
+                    "Applying {} patch(es)...",
+                    message_ids.len()
+                ))))
+                .await?;
+
+            let lore_list = self.lore_list().unwrap_or(list);
+            match self
+                .apply
+                .apply(lore_list, message_ids, ApplyOptions::default())
+                .await
+            {
+                Ok(report) if report.is_success() => {
+                    (format!("Applied: {}", title), report.to_string())
+                }
+                Ok(report) => (format!("Apply failed: {}", title), report.to_string()),
+                Err(e) => {
+                    self.log.error(SCOPE, format!("Apply: {}", e));
+                    (format!("Apply failed: {}", title), format!("{:#}", e))
+                }
+            }
+        };
+
+        if self.state.view != ViewKind::Patch {
+            self.state.patch_origin = self.state.view;
+        }
+        self.state.view = ViewKind::Patch;
+        self.state.patch_message_id = None;
+        self.terminal
+            .show(Screen::Patch {
+                title: ArcStr::from(title),
+                content: ArcStr::from(content),
+            })
+            .await
+    }
+
+    /// Handle cycling the review state of the selected feed item or the open patch
+    async fn handle_cycle_review(&mut self) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let Some(message_id) = self.selected_message_id(&list).await? else {
+            return Ok(());
+        };
+
+        let current = self
+            .review_cache
+            .get(message_id.clone())
+            .await
+            .and_then(|status| status.state);
+        let next = ReviewState::next(current);
+        self.log.info(
+            SCOPE,
+            format!(
+                "Review: msg_id={} {} -> {}",
+                message_id,
+                current.map_or_else(|| "none".to_string(), |s| s.to_string()),
+                next.map_or_else(|| "none".to_string(), |s| s.to_string())
+            ),
+        );
+        self.review_cache.set_state(message_id, next).await?;
+
+        // Only the feed shows markers, the patch view is left as is
+        if self.state.view == ViewKind::Feed {
+            self.render_feed(list).await?;
+        }
+        Ok(())
+    }
+
+    /// Handle moving to the next patch of the feed that was not opened yet
+    ///
+    /// Only the patches already in the feed cache are searched, so no page is fetched on
+    /// the way, except in the inbox where filling its pages may fetch more search results.
+    /// From a patch opened from the feed, the next unread patch is opened.
+    async fn handle_next_unread(&mut self) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let open_patch = match self.state.view {
+            ViewKind::Feed => false,
+            ViewKind::Patch if self.state.patch_origin == ViewKind::Feed => true,
+            ViewKind::Lists | ViewKind::Series | ViewKind::Versions | ViewKind::Patch => {
+                return Ok(());
+            }
+        };
+
+        let len = self.feed_cache.len(list.clone()).await;
+        let mut start = self.state.feed_page * 20 + self.state.feed_selected + 1;
+        let mut next = None;
+        while next.is_none() && start < len {
+            let end = (start + 20).min(len);
+            let items = self.feed_slice(list.clone(), start..end).await?;
+            if items.is_empty() {
+                // The inbox holds fewer patches than the feed it is filtered from
+                break;
+            }
+            let seen = self
+                .seen_cache
+                .get_many(items.iter().map(|item| item.message_id.clone()).collect())
+                .await;
+            next = items
+                .into_iter()
+                .enumerate()
+                .find(|(_, item)| !seen.contains(&item.message_id))
+                .map(|(offset, item)| (start + offset, item));
+            start = end;
+        }
+
+        let Some((index, item)) = next else {
+            let text = format!("No unread patches left in {}", self.feed_label(&list));
+            self.log.info(SCOPE, format!("NextUnread: {}", text));
+            return self.terminal.notify(ArcStr::from(text), false).await;
+        };
+        self.log.info(
+            SCOPE,
+            format!(
+                "{:?} -> NextUnread index={} msg_id={}",
+                self.state.view, index, item.message_id
+            ),
+        );
+        self.state.feed_page = index / 20;
+        self.state.feed_selected = index % 20;
+        if open_patch {
+            let lore_list = self.lore_list().unwrap_or(list);
+            self.handle_show_patch(lore_list, item.message_id, item.title)
+                .await
+        } else {
+            self.render_feed(list).await
+        }
+    }
+
+    /// Handle marking every cached patch of a list as read
+    ///
+    /// That is the open feed, or the list selected in the lists view. The new patches
+    /// the watch counted in it are cleared as well.
+    async fn handle_mark_all_read(&mut self) -> Result<()> {
+        let list = match self.state.view {
+            ViewKind::Lists => {
+                let start = self.state.list_page * 20;
+                let end = start + 20;
+                let items = self.mailing_list_cache.get_slice(start..end).await?;
+                match items.get(self.state.list_selected) {
+                    Some(selected) => selected.name.clone(),
+                    None => return Ok(()),
+                }
+            }
+            ViewKind::Feed | ViewKind::Series => match self.state.feed_list.clone() {
+                Some(list) => list,
+                None => return Ok(()),
+            },
+            ViewKind::Versions | ViewKind::Patch => return Ok(()),
+        };
+        let label = match self.state.view {
+            ViewKind::Lists => list.clone(),
+            _ => self.feed_label(&list),
+        };
+
+        self.feed_cache.ensure_loaded(list.clone()).await?;
+        let len = self.feed_cache.len(list.clone()).await;
+        let items = if len > 0 {
+            self.feed_cache.get_slice(list.clone(), 0..len).await?
+        } else {
+            Vec::new()
+        };
+        let marked = self
+            .seen_cache
+            .mark_seen(items.into_iter().map(|item| item.message_id).collect())
+            .await?;
+        if let Err(e) = self.watch.mark_seen(list.clone()).await {
+            self.log.warn(
+                SCOPE,
+                format!(
+                    "Failed to mark the new patches of '{}' as seen: {}",
+                    list, e
+                ),
+            );
+        }
+
+        let text = format!("Marked {} patch(es) in {} as read", marked, label);
+        self.log.info(
+            SCOPE,
+            format!("{:?} -> MarkAllRead: {}", self.state.view, text),
+        );
+        self.terminal.notify(ArcStr::from(text), false).await?;
+        match self.state.view {
+            ViewKind::Lists => self.render_lists().await,
+            ViewKind::Feed => self.render_feed(list).await,
+            ViewKind::Series | ViewKind::Versions | ViewKind::Patch => Ok(()),
+        }
+    }
+
+    /// Handle composing a reply to the selected feed item or the open patch.
+    ///
+    /// Without a trailer the draft is opened in the editor, with the terminal
+    /// suspended meanwhile; quick trailer replies are saved right away.
+    async fn handle_reply(&mut self, trailer: Option<Trailer>) -> Result<()> {
+        let Some(list) = self.state.feed_list.clone() else {
+            return Ok(());
+        };
+        let Some(message_id) = self.selected_message_id(&list).await? else {
+            return Ok(());
+        };
+
+        let options = ReplyOptions {
+            trailers: trailer.into_iter().collect(),
+            edit: trailer.is_none(),
+        };
+        self.log.info(
+            SCOPE,
+            format!(
+                "{:?} -> Reply msg_id={} trailers={:?} edit={}",
+                self.state.view, message_id, options.trailers, options.edit
+            ),
+        );
+
+        let edit = options.edit;
+        if edit {
+            self.terminal.suspend().await?;
+        }
+        let lore_list = self.lore_list().unwrap_or(list);
+        let result = self.reply.compose(lore_list, message_id, options).await;
+        if edit {
+            self.terminal.resume().await?;
+        }
+
+        let (title, content) = match result {
+            Ok(draft) => {
+                self.state.reply_draft = Some(draft.path.clone());
+                (
+                    format!("Reply saved: {} (press S to send)", draft.path.display()),
+                    draft.mbox.to_string(),
+                )
+            }
+            Err(e) => {
+                self.log.error(SCOPE, format!("Reply: {}", e));
+                ("Reply failed".to_string(), format!("{:#}", e))
+            }
+        };
+
+        if self.state.view != ViewKind::Patch {
+            self.state.patch_origin = self.state.view;
+        }
+        self.state.view = ViewKind::Patch;
+        self.state.patch_message_id = None;
+        self.terminal
+            .show(Screen::Patch {
+                title: ArcStr::from(title),
+                content: ArcStr::from(content),
+            })
+            .await
+    }
+
+    /// Handle sending the last reply composed in this session.
+    ///
+    /// The outcome replaces the patch view, which already shows the draft.
+    async fn handle_send_reply(&mut self) -> Result<()> {
+        let Some(path) = self.state.reply_draft.clone() else {
+            return Ok(());
+        };
+        self.log.info(
+            SCOPE,
+            format!("{:?} -> SendReply path={}", self.state.view, path.display()),
+        );
+
+        let (title, content) = match self.mail.send(path).await {
+            Ok(report) => {
+                // Sending twice would duplicate the message on the list
+                self.state.reply_draft = None;
+                (format!("Sent: {}", report.subject), report.to_string())
+            }
+            Err(e) => {
+                self.log.error(SCOPE, format!("SendReply: {}", e));
+                ("Send failed".to_string(), format!("{:#}", e))
+            }
+        };
+
+        if self.state.view != ViewKind::Patch {
+            self.state.patch_origin = self.state.view;
+        }
+        self.state.view = ViewKind::Patch;
+        self.state.patch_message_id = None;
+        self.terminal
+            .show(Screen::Patch {
+                title: ArcStr::from(title),
+                content: ArcStr::from(content),
+            })
+            .await
+    }
+
+    /// Get the message ID of the selected feed item, or of the open patch
+    async fn selected_message_id(&self, list: &ArcStr) -> Result<Option<ArcStr>> {
+        let message_id = match self.state.view {
+            ViewKind::Feed => {
+                let start = self.state.feed_page * 20;
+                let end = start + 20;
+                let items = self.feed_slice(list.clone(), start..end).await?;
+                items
+                    .get(self.state.feed_selected)
+                    .map(|item| item.message_id.clone())
+            }
+            ViewKind::Patch => self.state.patch_message_id.clone(),
+            ViewKind::Lists | ViewKind::Series | ViewKind::Versions => None,
+        };
+        Ok(message_id)
+    }
+
+    /// Resolve what applying a message means: the whole series for a cover letter,
+    /// along with the sequence numbers of its missing patches, the message alone otherwise
+    async fn apply_targets(
+        &self,
+        list: &ArcStr,
+        message_id: &ArcStr,
+    ) -> Result<(ArcStr, Vec<ArcStr>, Vec<usize>)> {
+        let revisions = self
+            .feed_cache
+            .get_revisions(list.clone(), message_id.clone())
+            .await?;
+        let series = revisions
+            .into_iter()
+            .find(|series| series.contains(message_id));
+        Ok(match series {
+            Some(series)
+                if series
+                    .cover
+                    .as_ref()
+                    .is_some_and(|cover| &cover.message_id == message_id) =>
+            {
+                (series.title.clone(), series.patch_ids(), series.missing())
+            }
+            Some(series) => {
+                let title = series
+                    .patches
+                    .iter()
+                    .find(|patch| &patch.message_id == message_id)
+                    .map_or_else(|| series.title.clone(), |patch| patch.title.clone());
+                (title, vec![message_id.clone()], Vec::new())
+            }
+            None => (message_id.clone(), vec![message_id.clone()], Vec::new()),
+        })
+    }
+
+    /// Fetch the raw patches of a series revision, in series order, skipping the cover letter
+    async fn fetch_series_patches(&self, series: &PatchSeries) -> Result<Vec<String>> {
+        let mut patches = Vec::with_capacity(series.patches.len());
+        for patch in &series.patches {
+            let raw = self
+                .patch_cache
+                .get(series.list.clone(), patch.message_id.clone())
+                .await?;
+            patches.push(raw.to_string());
+        }
+        Ok(patches)
+    }
+
     /// Handle selection update
     fn handle_update_selection(&mut self, index: usize) {
         match self.state.view {
             ViewKind::Lists => self.state.list_selected = index,
             ViewKind::Feed => self.state.feed_selected = index,
+            ViewKind::Series => self.state.series_selected = index,
+            ViewKind::Versions => self.state.versions_selected = index,
             ViewKind::Patch => {} // No selection in patch view
         }
     }
@@ -234,23 +1095,31 @@ impl Core {
                             .await?;
 
                         // Fetch the data (this will trigger on-demand fetching)
-                        let items = self.feed_cache.get_slice(list.clone(), start..end).await?;
+                        let items = self.feed_slice(list.clone(), start..end).await?;
 
                         // Show the feed with fetched data
-                        self.terminal
-                            .show(Screen::Feed {
-                                list,
-                                items,
-                                page: self.state.feed_page,
-                                selected: self.state.feed_selected,
-                            })
-                            .await
+                        self.show_feed_screen(list, items).await
                     }
                 } else {
                     Ok(())
                 }
             }
-            ViewKind::Patch => Ok(()), // No pagination in patch view
+            ViewKind::Series => {
+                let new_page = self.state.series_page.saturating_sub(1);
+                if new_page == self.state.series_page {
+                    // Already at page 0, can't go back
+                    return Ok(());
+                }
+
+                if let Some(list) = self.state.feed_list.clone() {
+                    self.state.series_page = new_page;
+                    self.state.series_selected = 0;
+                    self.render_series(list).await
+                } else {
+                    Ok(())
+                }
+            }
+            ViewKind::Versions | ViewKind::Patch => Ok(()), // No pagination
         }
     }
 
@@ -305,23 +1174,35 @@ impl Core {
                             .await?;
 
                         // Fetch the data (this will trigger on-demand fetching)
-                        let items = self.feed_cache.get_slice(list.clone(), start..end).await?;
+                        let items = self.feed_slice(list.clone(), start..end).await?;
 
                         // Show the feed with fetched data
-                        self.terminal
-                            .show(Screen::Feed {
-                                list,
-                                items,
-                                page: self.state.feed_page,
-                                selected: self.state.feed_selected,
-                            })
-                            .await
+                        self.show_feed_screen(list, items).await
                     }
                 } else {
                     Ok(())
                 }
             }
-            ViewKind::Patch => Ok(()), // No pagination in patch view
+            ViewKind::Series => {
+                let Some(list) = self.state.feed_list.clone() else {
+                    return Ok(());
+                };
+
+                // Check if the next page would have data
+                let new_page = self.state.series_page.saturating_add(1);
+                let start = new_page * 20;
+                let end = start + 20;
+                self.terminal
+                    .show(Screen::Loading(ArcStr::from("Loading page...")))
+                    .await?;
+                let items = self.feed_cache.get_series(list.clone(), start..end).await?;
+                if !items.is_empty() {
+                    self.state.series_page = new_page;
+                    self.state.series_selected = 0;
+                }
+                self.render_series(list).await
+            }
+            ViewKind::Versions | ViewKind::Patch => Ok(()), // No pagination
         }
     }
 
@@ -338,15 +1219,46 @@ impl Core {
                 self.state.view = ViewKind::Lists;
                 self.render_lists().await
             }
+            ViewKind::Series => {
+                // From series back to lists
+                self.log.info(SCOPE, "Series -> Lists");
+                self.state.view = ViewKind::Lists;
+                self.render_lists().await
+            }
+            ViewKind::Versions => {
+                // From versions back to the feed or series it was opened from
+                let origin = if self.state.feed_grouped {
+                    ViewKind::Series
+                } else {
+                    ViewKind::Feed
+                };
+                self.log.info(SCOPE, format!("Versions -> {:?}", origin));
+                self.return_to(origin).await
+            }
             ViewKind::Patch => {
-                // From patch back to feed
-                self.log.info(SCOPE, "Patch -> Feed");
+                // From patch back to the view it was opened from
+                let origin = self.state.patch_origin;
+                self.log.info(SCOPE, format!("Patch -> {:?}", origin));
+                self.return_to(origin).await
+            }
+        }
+    }
+
+    /// Switch back to a list-backed view and render it
+    async fn return_to(&mut self, view: ViewKind) -> Result<()> {
+        self.state.view = view;
+        let Some(list) = self.state.feed_list.clone() else {
+            // Nothing but the lists was open yet
+            self.state.view = ViewKind::Lists;
+            return self.render_lists().await;
+        };
+        match view {
+            ViewKind::Series => self.render_series(list).await,
+            ViewKind::Versions => self.render_versions(list).await,
+            ViewKind::Lists => self.render_lists().await,
+            ViewKind::Feed | ViewKind::Patch => {
                 self.state.view = ViewKind::Feed;
-                if let Some(list) = self.state.feed_list.clone() {
-                    self.render_feed(list).await
-                } else {
-                    Ok(())
-                }
+                self.render_feed(list).await
             }
         }
     }
@@ -374,7 +1286,7 @@ impl Core {
                 if let Some(list) = self.state.feed_list.clone() {
                     let start = self.state.feed_page * 20;
                     let end = start + 20;
-                    let items = self.feed_cache.get_slice(list.clone(), start..end).await?;
+                    let items = self.feed_slice(list.clone(), start..end).await?;
                     if let Some(selected) = items.get(self.state.feed_selected) {
                         self.log.info(
                             SCOPE,
@@ -384,7 +1296,7 @@ impl Core {
                             ),
                         );
                         Ok(Some(NavigationAction::OpenPatch {
-                            list,
+                            list: self.lore_list().unwrap_or(list),
                             message_id: ArcStr::from(selected.message_id.clone()),
                             title: ArcStr::from(selected.title.clone()),
                         }))
@@ -395,6 +1307,61 @@ impl Core {
                     Ok(None)
                 }
             }
+            ViewKind::Series => {
+                let Some(list) = self.state.feed_list.clone() else {
+                    return Ok(None);
+                };
+                let start = self.state.series_page * 20;
+                let end = start + 20;
+                let items = self.feed_cache.get_series(list.clone(), start..end).await?;
+                let Some(head) = items
+                    .get(self.state.series_selected)
+                    .and_then(|series| series.head())
+                else {
+                    return Ok(None);
+                };
+                self.log.info(
+                    SCOPE,
+                    format!(
+                        "Series -> Patch title='{}' list={} msg_id={}",
+                        head.title, list, head.message_id
+                    ),
+                );
+                Ok(Some(NavigationAction::OpenPatch {
+                    list: self.lore_list().unwrap_or(list),
+                    message_id: head.message_id.clone(),
+                    title: head.title.clone(),
+                }))
+            }
+            ViewKind::Versions => {
+                let (Some(list), Some(message_id)) =
+                    (self.state.feed_list.clone(), self.state.versions_of.clone())
+                else {
+                    return Ok(None);
+                };
+                let revisions = self
+                    .feed_cache
+                    .get_revisions(list.clone(), message_id)
+                    .await?;
+                let Some(head) = revisions
+                    .get(self.state.versions_selected)
+                    .and_then(|series| series.head())
+                else {
+                    return Ok(None);
+                };
+                self.log.info(
+                    SCOPE,
+                    format!(
+                        "Versions -> Patch title='{}' list={} msg_id={}",
+                        head.title, list, head.message_id
+                    ),
+                );
+                Ok(Some(NavigationAction::OpenPatch {
+                    list: self.lore_list().unwrap_or(list),
+                    message_id: head.message_id.clone(),
+                    title: head.title.clone(),
+                }))
+            }
             ViewKind::Patch => Ok(None), // No submission in patch view
         }
     }
@@ -409,6 +1376,7 @@ impl Core {
         );
 
         let items = self.mailing_list_cache.get_slice(start..end).await?;
+        let unread = self.watch.unread().await;
 
         if items.is_empty() {
             self.log.warn(
@@ -455,6 +1423,7 @@ impl Core {
                                 items: refreshed_items,
                                 page: self.state.list_page,
                                 selected: self.state.list_selected,
+                                unread,
                             })
                             .await
                     }
@@ -483,6 +1452,7 @@ impl Core {
                         items,
                         page: self.state.list_page,
                         selected: self.state.list_selected,
+                        unread,
                     })
                     .await
             }
@@ -494,6 +1464,7 @@ impl Core {
                     items,
                     page: self.state.list_page,
                     selected: self.state.list_selected,
+                    unread,
                 })
                 .await
         }
@@ -509,7 +1480,7 @@ impl Core {
         );
 
         // Try to get items from cache
-        let items = self.feed_cache.get_slice(list.clone(), start..end).await?;
+        let items = self.feed_slice(list.clone(), start..end).await?;
 
         if items.is_empty() {
             self.log.warn(
@@ -539,8 +1510,7 @@ impl Core {
                 match refresh_result {
                     Ok(Ok(())) => {
                         // Refresh succeeded, try again
-                        let refreshed_items =
-                            self.feed_cache.get_slice(list.clone(), start..end).await?;
+                        let refreshed_items = self.feed_slice(list.clone(), start..end).await?;
                         if refreshed_items.is_empty() {
                             self.log.warn(
                                 SCOPE,
@@ -566,14 +1536,7 @@ impl Core {
                             }
                         }
 
-                        self.terminal
-                            .show(Screen::Feed {
-                                list,
-                                items: refreshed_items,
-                                page: self.state.feed_page,
-                                selected: self.state.feed_selected,
-                            })
-                            .await
+                        self.show_feed_screen(list, refreshed_items).await
                     }
                     Ok(Err(e)) => {
                         // Refresh failed
@@ -596,29 +1559,139 @@ impl Core {
                 }
             } else {
                 // Cache has data but this page is empty (e.g., page beyond available data)
+                self.show_feed_screen(list, items).await
+            }
+        } else {
+            self.log
+                .info(SCOPE, &format!("Feed: fetched {} items", items.len()));
+            self.show_feed_screen(list, items).await
+        }
+    }
+
+    /// Show a page of the feed along with the review markers and read state of its items
+    async fn show_feed_screen(
+        &self,
+        list: ArcStr,
+        items: Vec<crate::api::lore::LorePatchMetadata>,
+    ) -> Result<()> {
+        let reviews = self
+            .review_cache
+            .get_many(items.iter().map(|item| item.message_id.clone()).collect())
+            .await
+            .into_iter()
+            .filter_map(|(id, status)| Some((id, status.state?)))
+            .collect();
+        let seen = self
+            .seen_cache
+            .get_many(items.iter().map(|item| item.message_id.clone()).collect())
+            .await;
+        let unread: HashSet<ArcStr> = items
+            .iter()
+            .map(|item| item.message_id.clone())
+            .filter(|id| !seen.contains(id))
+            .collect();
+        let skipped = self.feed_cache.skipped(list.clone()).await;
+        let filter = self.feed_cache.path_filter(list.clone()).await;
+        self.terminal
+            .show(Screen::Feed {
+                list: self.feed_label(&list),
+                items,
+                page: self.state.feed_page,
+                selected: self.state.feed_selected,
+                reviews,
+                unread,
+                skipped,
+                filter,
+            })
+            .await
+    }
+
+    /// Render the series view
+    async fn render_series(&self, list: ArcStr) -> Result<()> {
+        let start = self.state.series_page * 20;
+        let end = start + 20;
+        self.log.info(
+            SCOPE,
+            format!("Series: list={} range {}..{}", list, start, end),
+        );
+
+        // Grouping fetches more feed pages on demand, bound it like a refresh
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            self.feed_cache.get_series(list.clone(), start..end),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(items)) => {
+                let incomplete = items.iter().filter(|series| !series.is_complete()).count();
+                self.log.info(
+                    SCOPE,
+                    format!(
+                        "Series: grouped {} series, {} with missing patches",
+                        items.len(),
+                        incomplete
+                    ),
+                );
                 self.terminal
-                    .show(Screen::Feed {
-                        list,
+                    .show(Screen::Series {
+                        list: self.feed_label(&list),
                         items,
-                        page: self.state.feed_page,
-                        selected: self.state.feed_selected,
+                        page: self.state.series_page,
+                        selected: self.state.series_selected,
                     })
                     .await
             }
-        } else {
-            self.log
-                .info(SCOPE, &format!("Feed: fetched {} items", items.len()));
-            self.terminal
-                .show(Screen::Feed {
-                    list,
-                    items,
-                    page: self.state.feed_page,
-                    selected: self.state.feed_selected,
-                })
-                .await
+            Ok(Err(e)) => {
+                self.log.error(
+                    SCOPE,
+                    format!("Series: grouping failed for '{}': {}", list, e),
+                );
+                self.terminal
+                    .show(Screen::Error(ArcStr::from("Failed to load series")))
+                    .await
+            }
+            Err(_) => {
+                self.log
+                    .error(SCOPE, format!("Series: loading timed out for '{}'", list));
+                self.terminal
+                    .show(Screen::Error(ArcStr::from("Series loading timed out")))
+                    .await
+            }
         }
     }
 
+    /// Render the versions view
+    async fn render_versions(&self, list: ArcStr) -> Result<()> {
+        let Some(message_id) = self.state.versions_of.clone() else {
+            return Ok(());
+        };
+        let items = self
+            .feed_cache
+            .get_revisions(list.clone(), message_id.clone())
+            .await?;
+        self.log.info(
+            SCOPE,
+            format!(
+                "Versions: list={} msg_id={} found {} revisions",
+                list,
+                message_id,
+                items.len()
+            ),
+        );
+        let title = items
+            .last()
+            .map_or_else(|| message_id.clone(), |series| series.title.clone());
+        self.terminal
+            .show(Screen::Versions {
+                title,
+                items,
+                selected: self.state.versions_selected,
+                marked: self.state.versions_marked,
+            })
+            .await
+    }
+
     /// Render the patch view
     async fn render_patch(&self, list: ArcStr, message_id: ArcStr, title: ArcStr) -> Result<()> {
         self.log.info(
@@ -635,7 +1708,12 @@ impl Core {
             Ok(raw) => {
                 self.log
                     .info(SCOPE, &format!("Patch: raw chars={}", raw.len()));
-                match self.render.render_patch(ArcStr::from(raw)).await {
+                // Show the decoded message rather than its transfer-encoded form
+                let text = match parse_patch_message(&raw) {
+                    Ok(message) => message.to_text(),
+                    Err(_) => raw.to_string(),
+                };
+                match self.render.render_patch(ArcStr::from(text)).await {
                     Ok(rendered) => {
                         if rendered.is_empty() {
                             self.log.warn(
@@ -650,6 +1728,11 @@ impl Core {
                         } else {
                             self.log
                                 .info(SCOPE, &format!("Patch: rendered chars={}", rendered.len()));
+                            // Only patches that could be shown count as read
+                            if let Err(e) = self.seen_cache.mark_seen(vec![message_id]).await {
+                                self.log
+                                    .warn(SCOPE, format!("Patch: failed to mark as seen: {}", e));
+                            }
                         }
                         self.terminal
                             .show(Screen::Patch {
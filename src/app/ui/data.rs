@@ -1,4 +1,5 @@
-use crate::ArcStr;
+use crate::api::lore::LoreSearch;
+use crate::{ArcPath, ArcStr};
 
 /// Different view types in the TUI
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -7,6 +8,10 @@ pub enum ViewKind {
     Lists,
     /// Patch feed view for a specific mailing list
     Feed,
+    /// Patch feed view grouped by series for a specific mailing list
+    Series,
+    /// Known revisions of a single series
+    Versions,
     /// Individual patch content view
     Patch,
 }
@@ -20,12 +25,34 @@ pub struct UiState {
     pub list_page: usize,
     /// Currently selected item in lists view
     pub list_selected: usize,
-    /// Current mailing list name (when in Feed view)
+    /// Current mailing list name, or search cache key (when in Feed view)
     pub feed_list: Option<ArcStr>,
+    /// The search whose results the feed shows, if it is not a mailing list feed
+    pub feed_search: Option<LoreSearch>,
+    /// Names of the subsystems whose inbox the feed shows, empty for any other feed
+    pub feed_inbox: Vec<ArcStr>,
     /// Current page in feed view
     pub feed_page: usize,
     /// Currently selected item in feed view
     pub feed_selected: usize,
+    /// Current page in series view
+    pub series_page: usize,
+    /// Currently selected item in series view
+    pub series_selected: usize,
+    /// Whether the feed is shown grouped by series, so leaving a patch returns there
+    pub feed_grouped: bool,
+    /// Message ID of the series whose revisions are listed (when in Versions view)
+    pub versions_of: Option<ArcStr>,
+    /// Currently selected revision in versions view
+    pub versions_selected: usize,
+    /// Revision marked as the base of the next interdiff
+    pub versions_marked: Option<usize>,
+    /// View to return to when leaving the patch view
+    pub patch_origin: ViewKind,
+    /// Message ID of the patch shown in the patch view, if it is a patch
+    pub patch_message_id: Option<ArcStr>,
+    /// Reply composed in this session that has not been sent yet
+    pub reply_draft: Option<ArcPath>,
 }
 
 impl Default for UiState {
@@ -35,8 +62,19 @@ impl Default for UiState {
             list_page: 0,
             list_selected: 0,
             feed_list: None,
+            feed_search: None,
+            feed_inbox: Vec::new(),
             feed_page: 0,
             feed_selected: 0,
+            series_page: 0,
+            series_selected: 0,
+            feed_grouped: false,
+            versions_of: None,
+            versions_selected: 0,
+            versions_marked: None,
+            patch_origin: ViewKind::Feed,
+            patch_message_id: None,
+            reply_draft: None,
         }
     }
 }
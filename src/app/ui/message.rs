@@ -2,6 +2,8 @@ use anyhow::Result;
 use tokio::sync::oneshot;
 
 use crate::ArcStr;
+use crate::app::reply::Trailer;
+use crate::app::watch::WatchUpdate;
 
 /// Messages for communicating with the UI actor
 #[derive(Debug)]
@@ -17,6 +19,13 @@ pub enum Message {
         page: usize,
         tx: oneshot::Sender<Result<()>>,
     },
+    /// Search the archive and show the results as a feed
+    Search {
+        query: ArcStr,
+        tx: oneshot::Sender<Result<()>>,
+    },
+    /// Show the patches of every list touching the declared subsystems as a feed
+    Inbox { tx: oneshot::Sender<Result<()>> },
     /// Show a specific patch content
     ShowPatch {
         list: ArcStr,
@@ -24,6 +33,36 @@ pub enum Message {
         title: ArcStr,
         tx: oneshot::Sender<Result<()>>,
     },
+    /// Switch the feed between the flat and the series-grouped view
+    ToggleSeries { tx: oneshot::Sender<Result<()>> },
+    /// Show the known revisions of the selected series
+    ShowVersions { tx: oneshot::Sender<Result<()>> },
+    /// Mark the selected revision as the base of the next interdiff
+    MarkVersion { tx: oneshot::Sender<Result<()>> },
+    /// Show the interdiff between the marked (or previous) and the selected revision
+    ShowInterdiff { tx: oneshot::Sender<Result<()>> },
+    /// Apply the selected (or open) patch or series to the local repository
+    Apply { tx: oneshot::Sender<Result<()>> },
+    /// Compose a reply to the selected (or open) patch, with an optional quick trailer
+    Reply {
+        trailer: Option<Trailer>,
+        tx: oneshot::Sender<Result<()>>,
+    },
+    /// Send the last composed reply through the mail backend
+    SendReply { tx: oneshot::Sender<Result<()>> },
+    /// Cycle the review state of the selected (or open) patch
+    CycleReview { tx: oneshot::Sender<Result<()>> },
+    /// Move to the next patch of the feed that was not opened yet
+    NextUnread { tx: oneshot::Sender<Result<()>> },
+    /// Mark every patch of the open (or selected) list as read
+    MarkAllRead { tx: oneshot::Sender<Result<()>> },
+    /// Tell the user new patches arrived in a watched list or search
+    Notify {
+        update: WatchUpdate,
+        /// Whether to ring the terminal bell
+        bell: bool,
+        tx: oneshot::Sender<Result<()>>,
+    },
     /// Update the current selection index
     UpdateSelection { index: usize },
     /// Navigate to the previous page
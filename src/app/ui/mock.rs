@@ -2,6 +2,9 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::ArcStr;
+use crate::api::lore::{ALL_LISTS, LoreQuery, LoreSearch};
+use crate::app::reply::Trailer;
+use crate::app::watch::WatchUpdate;
 use crate::app::ui::{MockData, NavigationAction, UiState, ViewKind};
 
 /// Mock implementation of the UI actor for testing purposes.
@@ -56,14 +59,83 @@ impl Mock {
         let mut mock_data = self.data.lock().await;
         mock_data.state.view = ViewKind::Feed;
         mock_data.state.feed_list = Some(list.clone());
+        mock_data.state.feed_search = None;
+        mock_data.state.feed_inbox.clear();
         mock_data.state.feed_page = page;
         mock_data.state.feed_selected = 0;
+        mock_data.state.feed_grouped = false;
         mock_data
             .rendered_screens
             .push(format!("Feed(list={}, page={})", list, page));
         Ok(())
     }
 
+    /// Search the archive and show the results as a feed.
+    /// Mock implementation updates the UI state and records rendered screens.
+    ///
+    /// # Arguments
+    /// * `query` - The query, in public-inbox syntax
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn search(&self, query: ArcStr) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        let state = &mock_data.state;
+        let list = match (state.view, &state.feed_search, &state.feed_list) {
+            (ViewKind::Lists, _, _) => ArcStr::from(ALL_LISTS),
+            (_, Some(search), _) => search.list.clone(),
+            (_, None, Some(list)) => list.clone(),
+            _ => ArcStr::from(ALL_LISTS),
+        };
+        let search = LoreSearch::new(list, LoreQuery::patches().raw(&query));
+        let key = search.cache_key();
+        mock_data.state.view = ViewKind::Feed;
+        mock_data.state.feed_list = Some(key.clone());
+        mock_data.state.feed_search = Some(search);
+        mock_data.state.feed_inbox.clear();
+        mock_data.state.feed_page = 0;
+        mock_data.state.feed_selected = 0;
+        mock_data.state.feed_grouped = false;
+        mock_data
+            .rendered_screens
+            .push(format!("Feed(list={}, page=0)", key));
+        Ok(())
+    }
+
+    /// Show the inbox of the declared subsystems.
+    /// Mock implementation switches to the feed view and records the rendered screen,
+    /// leaving the feed list and search as they were since it knows no subsystem.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn inbox(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data.state.view = ViewKind::Feed;
+        mock_data.state.feed_page = 0;
+        mock_data.state.feed_selected = 0;
+        mock_data.state.feed_grouped = false;
+        mock_data.rendered_screens.push("Feed(inbox, page=0)".to_string());
+        Ok(())
+    }
+
+    /// Tell the user new patches arrived in a watched feed.
+    /// Mock implementation records the notification in the navigation actions.
+    ///
+    /// # Arguments
+    /// * `update` - The feed and its new patches
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn notify(&self, update: WatchUpdate) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data.navigation_actions.push(format!(
+            "Notify({}, {})",
+            update.target.name,
+            update.patches.len()
+        ));
+        Ok(())
+    }
+
     /// Show a specific patch content.
     /// Mock implementation updates the UI state and records rendered screens.
     ///
@@ -76,6 +148,9 @@ impl Mock {
     /// Ok(()) if successful
     pub async fn show_patch(&self, list: ArcStr, message_id: ArcStr, title: ArcStr) -> anyhow::Result<()> {
         let mut mock_data = self.data.lock().await;
+        if mock_data.state.view != ViewKind::Patch {
+            mock_data.state.patch_origin = mock_data.state.view;
+        }
         mock_data.state.view = ViewKind::Patch;
         mock_data.rendered_screens.push(format!(
             "Patch(list={}, msg_id={}, title={})",
@@ -84,6 +159,191 @@ impl Mock {
         Ok(())
     }
 
+    /// Switch the feed between the flat and the series-grouped view.
+    /// Mock implementation flips the view kind and records rendered screens.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn toggle_series(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        let list = mock_data.state.feed_list.clone().unwrap_or_default();
+        match mock_data.state.view {
+            ViewKind::Feed => {
+                mock_data.state.view = ViewKind::Series;
+                mock_data.state.series_page = 0;
+                mock_data.state.series_selected = 0;
+                mock_data.state.feed_grouped = true;
+                mock_data
+                    .rendered_screens
+                    .push(format!("Series(list={}, page=0)", list));
+            }
+            ViewKind::Series => {
+                mock_data.state.view = ViewKind::Feed;
+                mock_data.state.feed_grouped = false;
+                let page = mock_data.state.feed_page;
+                mock_data
+                    .rendered_screens
+                    .push(format!("Feed(list={}, page={})", list, page));
+            }
+            ViewKind::Lists | ViewKind::Versions | ViewKind::Patch => {}
+        }
+        Ok(())
+    }
+
+    /// Show the known revisions of the selected series.
+    /// Mock implementation switches to the versions view and records rendered screens.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn show_versions(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if !matches!(mock_data.state.view, ViewKind::Feed | ViewKind::Series) {
+            return Ok(());
+        }
+        mock_data.state.view = ViewKind::Versions;
+        mock_data.state.versions_selected = 0;
+        mock_data.state.versions_marked = None;
+        mock_data.rendered_screens.push("Versions".to_string());
+        Ok(())
+    }
+
+    /// Mark the selected revision as the base of the next interdiff.
+    /// Mock implementation toggles the mark on the selected revision.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn mark_version(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if mock_data.state.view != ViewKind::Versions {
+            return Ok(());
+        }
+        let selected = mock_data.state.versions_selected;
+        mock_data.state.versions_marked = match mock_data.state.versions_marked {
+            Some(marked) if marked == selected => None,
+            _ => Some(selected),
+        };
+        Ok(())
+    }
+
+    /// Show the interdiff between the marked (or previous) and the selected revision.
+    /// Mock implementation switches to the patch view and records rendered screens.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn show_interdiff(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if mock_data.state.view != ViewKind::Versions {
+            return Ok(());
+        }
+        mock_data.state.view = ViewKind::Patch;
+        mock_data.state.patch_origin = ViewKind::Versions;
+        mock_data.rendered_screens.push("Interdiff".to_string());
+        Ok(())
+    }
+
+    /// Apply the selected (or open) patch or series to the local repository.
+    /// Mock implementation switches to the patch view and records rendered screens.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn apply(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if mock_data.state.view == ViewKind::Lists {
+            return Ok(());
+        }
+        if mock_data.state.view != ViewKind::Patch {
+            mock_data.state.patch_origin = mock_data.state.view;
+        }
+        mock_data.state.view = ViewKind::Patch;
+        mock_data.state.patch_message_id = None;
+        mock_data.rendered_screens.push("Apply".to_string());
+        Ok(())
+    }
+
+    /// Compose a reply to the selected (or open) patch.
+    /// Mock implementation switches to the patch view and records rendered screens.
+    ///
+    /// # Arguments
+    /// * `trailer` - The quick trailer to add, if any
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn reply(&self, trailer: Option<Trailer>) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if !matches!(mock_data.state.view, ViewKind::Feed | ViewKind::Patch) {
+            return Ok(());
+        }
+        if mock_data.state.view != ViewKind::Patch {
+            mock_data.state.patch_origin = mock_data.state.view;
+        }
+        mock_data.state.view = ViewKind::Patch;
+        mock_data.state.patch_message_id = None;
+        mock_data.rendered_screens.push(match trailer {
+            Some(trailer) => format!("Reply({})", trailer),
+            None => "Reply".to_string(),
+        });
+        Ok(())
+    }
+
+    /// Send the last composed reply.
+    /// Mock implementation shows the outcome in the patch view and records it.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn send_reply(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if mock_data.state.view != ViewKind::Patch {
+            mock_data.state.patch_origin = mock_data.state.view;
+        }
+        mock_data.state.view = ViewKind::Patch;
+        mock_data.state.patch_message_id = None;
+        mock_data.rendered_screens.push("SendReply".to_string());
+        Ok(())
+    }
+
+    /// Cycle the review state of the selected (or open) patch.
+    /// Mock implementation records the action in the navigation actions.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn cycle_review(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if matches!(mock_data.state.view, ViewKind::Feed | ViewKind::Patch) {
+            mock_data
+                .navigation_actions
+                .push("CycleReview".to_string());
+        }
+        Ok(())
+    }
+
+    /// Move to the next patch of the feed that was not opened yet.
+    /// Mock implementation records the action in the navigation actions.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn next_unread(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        if matches!(mock_data.state.view, ViewKind::Feed | ViewKind::Patch) {
+            mock_data
+                .navigation_actions
+                .push("NextUnread".to_string());
+        }
+        Ok(())
+    }
+
+    /// Mark every patch of the open (or selected) list as read.
+    /// Mock implementation records the action in the navigation actions.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn mark_all_read(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data
+            .navigation_actions
+            .push("MarkAllRead".to_string());
+        Ok(())
+    }
+
     /// Update the current selection index.
     /// Mock implementation updates the selection based on the current view.
     ///
@@ -94,6 +354,8 @@ impl Mock {
         match mock_data.state.view {
             ViewKind::Lists => mock_data.state.list_selected = index,
             ViewKind::Feed => mock_data.state.feed_selected = index,
+            ViewKind::Series => mock_data.state.series_selected = index,
+            ViewKind::Versions => mock_data.state.versions_selected = index,
             ViewKind::Patch => {} // No selection in patch view
         }
     }
@@ -117,7 +379,11 @@ impl Mock {
                 mock_data.state.feed_page = mock_data.state.feed_page.saturating_sub(1);
                 mock_data.state.feed_selected = 0;
             }
-            ViewKind::Patch => {} // No pagination in patch view
+            ViewKind::Series => {
+                mock_data.state.series_page = mock_data.state.series_page.saturating_sub(1);
+                mock_data.state.series_selected = 0;
+            }
+            ViewKind::Versions | ViewKind::Patch => {} // No pagination
         }
         Ok(())
     }
@@ -139,7 +405,11 @@ impl Mock {
                 mock_data.state.feed_page = mock_data.state.feed_page.saturating_add(1);
                 mock_data.state.feed_selected = 0;
             }
-            ViewKind::Patch => {} // No pagination in patch view
+            ViewKind::Series => {
+                mock_data.state.series_page = mock_data.state.series_page.saturating_add(1);
+                mock_data.state.series_selected = 0;
+            }
+            ViewKind::Versions | ViewKind::Patch => {} // No pagination
         }
         Ok(())
     }
@@ -156,8 +426,12 @@ impl Mock {
             .push("NavigateBack".to_string());
         match mock_data.state.view {
             ViewKind::Lists => {} // From lists, we quit (handled elsewhere)
-            ViewKind::Feed => mock_data.state.view = ViewKind::Lists,
-            ViewKind::Patch => mock_data.state.view = ViewKind::Feed,
+            ViewKind::Feed | ViewKind::Series => mock_data.state.view = ViewKind::Lists,
+            ViewKind::Versions if mock_data.state.feed_grouped => {
+                mock_data.state.view = ViewKind::Series
+            }
+            ViewKind::Versions => mock_data.state.view = ViewKind::Feed,
+            ViewKind::Patch => mock_data.state.view = mock_data.state.patch_origin,
         }
         Ok(())
     }
@@ -173,11 +447,13 @@ impl Mock {
             ViewKind::Lists => Ok(Some(NavigationAction::OpenFeed {
                 list: ArcStr::from("test-list"),
             })),
-            ViewKind::Feed => Ok(Some(NavigationAction::OpenPatch {
-                list: ArcStr::from("test-list"),
-                message_id: ArcStr::from("test-msg-id"),
-                title: ArcStr::from("test-title"),
-            })),
+            ViewKind::Feed | ViewKind::Series | ViewKind::Versions => {
+                Ok(Some(NavigationAction::OpenPatch {
+                    list: ArcStr::from("test-list"),
+                    message_id: ArcStr::from("test-msg-id"),
+                    title: ArcStr::from("test-title"),
+                }))
+            }
             ViewKind::Patch => Ok(None),
         }
     }
@@ -0,0 +1,197 @@
+mod core;
+mod data;
+mod message;
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Context;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, Sender};
+
+use crate::ArcStr;
+use crate::app::cache::FeedCache;
+use crate::app::config::Config;
+use crate::fs::Fs;
+use crate::log::Log;
+
+pub use data::{WatchMarker, WatchTarget, WatchUpdate, watch_targets};
+
+/// The watch actor that tells when new patches arrive in the lists and searches the
+/// user follows.
+///
+/// The lists of `ListOpt::WatchedLists` and the searches of `MapOpt::WatchedSearches`
+/// are refreshed through the [`FeedCache`], and the patches newer than the marker left
+/// by the previous check are recorded as unread until the feed is opened. Markers are
+/// persisted in `watch.toml` under the cache directory, so patches that arrive while
+/// the application is closed are reported by the first check of the next run.
+///
+/// # Examples
+/// ```ignore
+/// let watch = Watch::spawn(feed_cache, fs, config, log);
+/// let mut updates = watch.subscribe(Duration::from_secs(300)).await;
+/// while let Some(update) = updates.recv().await {
+///     println!("{} new patches in {}", update.patches.len(), update.target.name);
+/// }
+/// ```
+///
+/// # Thread Safety
+/// This type is designed to be safely shared between threads. Cloning is cheap as it only
+/// copies the channel sender or mock reference.
+#[derive(Debug, Clone)]
+pub enum Watch {
+    /// A real watch actor checking the archive
+    Actual(Sender<message::Message>),
+    /// A mock implementation for testing that serves updates from memory
+    Mock(mock::Mock),
+}
+
+/// Data served by the mock watch actor.
+#[derive(Debug, Clone, Default)]
+pub struct MockData {
+    /// The watched lists and searches
+    pub targets: Vec<WatchTarget>,
+    /// The updates returned by the next check
+    pub updates: Vec<WatchUpdate>,
+    /// Unread counts by feed cache key
+    pub unread: HashMap<ArcStr, usize>,
+}
+
+impl Watch {
+    /// Creates a new watch instance and spawns its actor.
+    ///
+    /// # Arguments
+    /// * `feed_cache` - The feed cache the watched feeds are refreshed through
+    /// * `fs` - The filesystem actor for persisting the markers
+    /// * `config` - The configuration actor for the watched lists and searches
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new watch instance with a spawned actor.
+    pub fn spawn(feed_cache: FeedCache, fs: Fs, config: Config, log: Log) -> Self {
+        let (watch, _) = core::Core::new(feed_cache, fs, config, log).spawn();
+        watch
+    }
+
+    /// Creates a new mock watch instance for testing.
+    ///
+    /// # Arguments
+    /// * `data` - The targets, the updates of the next check and the unread counts
+    pub fn mock(data: MockData) -> Self {
+        Self::Mock(mock::Mock::new(data))
+    }
+
+    /// Gets the watched lists and searches, as currently configured.
+    ///
+    /// # Returns
+    /// The lists in configuration order, then the searches sorted by name.
+    pub async fn targets(&self) -> Vec<WatchTarget> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Targets { tx })
+                    .await
+                    .context("Sending message to Watch actor")
+                    .expect("Watch actor died");
+                rx.await
+                    .context("Awaiting response from Watch actor")
+                    .expect("Watch actor died")
+            }
+            Self::Mock(mock) => mock.targets().await,
+        }
+    }
+
+    /// Refreshes every watched feed now and records the patches that arrived.
+    ///
+    /// Feeds that cannot be refreshed are logged and skipped. The first check of a feed
+    /// only sets its marker.
+    ///
+    /// # Returns
+    /// The feeds that got new patches, or an error if the markers cannot be persisted.
+    pub async fn check(&self) -> anyhow::Result<Vec<WatchUpdate>> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Check { tx })
+                    .await
+                    .context("Sending message to Watch actor")
+                    .expect("Watch actor died");
+                rx.await
+                    .context("Awaiting response from Watch actor")
+                    .expect("Watch actor died")
+            }
+            Self::Mock(mock) => mock.check().await,
+        }
+    }
+
+    /// Gets how many patches arrived in each watched feed since it was last opened.
+    ///
+    /// # Returns
+    /// The counts by feed cache key (see [`WatchTarget::key`]), leaving out feeds
+    /// without unread patches.
+    pub async fn unread(&self) -> HashMap<ArcStr, usize> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Unread { tx })
+                    .await
+                    .context("Sending message to Watch actor")
+                    .expect("Watch actor died");
+                rx.await
+                    .context("Awaiting response from Watch actor")
+                    .expect("Watch actor died")
+            }
+            Self::Mock(mock) => mock.unread().await,
+        }
+    }
+
+    /// Forgets the unread patches of a feed, as it was opened.
+    ///
+    /// # Arguments
+    /// * `key` - The feed cache key of the feed; unwatched feeds are ignored
+    pub async fn mark_seen(&self, key: ArcStr) -> anyhow::Result<()> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::MarkSeen { key, tx })
+                    .await
+                    .context("Sending message to Watch actor")
+                    .expect("Watch actor died");
+                rx.await
+                    .context("Awaiting response from Watch actor")
+                    .expect("Watch actor died")
+            }
+            Self::Mock(mock) => mock.mark_seen(key).await,
+        }
+    }
+
+    /// Checks the watched feeds periodically, starting right away.
+    ///
+    /// Checks keep going while a subscriber is listening, at the shortest period asked
+    /// for. Only the feeds that got new patches are sent.
+    ///
+    /// # Arguments
+    /// * `every` - Time between two checks
+    ///
+    /// # Returns
+    /// The channel the updates are sent to.
+    pub async fn subscribe(&self, every: Duration) -> mpsc::Receiver<WatchUpdate> {
+        match self {
+            Self::Actual(sender) => {
+                let (updates, rx) = mpsc::channel(crate::BUFFER_SIZE);
+                sender
+                    .send(message::Message::Subscribe { every, updates })
+                    .await
+                    .context("Sending message to Watch actor")
+                    .expect("Watch actor died");
+                rx
+            }
+            Self::Mock(mock) => mock.subscribe().await,
+        }
+    }
+}
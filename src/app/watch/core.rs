@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::JoinHandle;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::api::lore::LorePatchMetadata;
+use crate::app::cache::FeedCache;
+use crate::app::config::{Config, ListOpt, MapOpt, PathOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::{ArcPath, ArcStr};
+
+use super::data::{WatchData, WatchTarget, WatchUpdate, watch_targets};
+use super::message::Message;
+
+const SCOPE: &str = "app.watch";
+
+/// How many of the newest patches of a feed are compared against its marker
+const WINDOW: usize = 50;
+
+/// The newest patches of every target, or why they could not be fetched
+type Fetched = Vec<(WatchTarget, anyhow::Result<Vec<LorePatchMetadata>>)>;
+
+/// The core implementation of the watch actor.
+///
+/// Periodic checks fetch the feeds in a task of their own, so the unread counts can be
+/// served while the archive is queried.
+pub struct Core {
+    /// The feed cache the watched feeds are refreshed through
+    feed_cache: FeedCache,
+    /// The filesystem actor for persisting the markers
+    fs: Fs,
+    /// The configuration actor for the watched lists and searches
+    config: Config,
+    /// The logging actor
+    log: Log,
+    /// The markers of the watched feeds
+    data: WatchData,
+    /// Channels the updates found by periodic checks are sent to
+    subscribers: Vec<mpsc::Sender<WatchUpdate>>,
+}
+
+impl Core {
+    /// Creates a new watch actor core.
+    ///
+    /// # Arguments
+    /// * `feed_cache` - The feed cache the watched feeds are refreshed through
+    /// * `fs` - The filesystem actor for persisting the markers
+    /// * `config` - The configuration actor for the watched lists and searches
+    /// * `log` - The logging actor
+    ///
+    /// # Returns
+    /// A new watch actor core instance.
+    pub fn new(feed_cache: FeedCache, fs: Fs, config: Config, log: Log) -> Self {
+        Self {
+            feed_cache,
+            fs,
+            config,
+            log,
+            data: WatchData::default(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Spawns the watch actor and returns the handle and join handle.
+    ///
+    /// # Returns
+    /// A tuple containing the watch actor handle and the join handle for the spawned task.
+    pub fn spawn(self) -> (super::Watch, JoinHandle<anyhow::Result<()>>) {
+        let (tx, rx) = mpsc::channel(32);
+        let handle = super::Watch::Actual(tx);
+        let join_handle = tokio::spawn(self.run(rx));
+        (handle, join_handle)
+    }
+
+    /// Runs the watch actor event loop.
+    ///
+    /// # Arguments
+    /// * `rx` - The message receiver
+    ///
+    /// # Returns
+    /// Result indicating success or failure of the actor
+    async fn run(mut self, mut rx: Receiver<Message>) -> anyhow::Result<()> {
+        if let Err(e) = self.load().await {
+            self.log
+                .error(SCOPE, format!("Failed to load watch markers: {:#}", e));
+        }
+
+        // Periodic checks only run while someone subscribed to their updates
+        let mut ticker: Option<Interval> = None;
+        let (fetched_tx, mut fetched_rx) = mpsc::channel::<Fetched>(1);
+        let mut checking = false;
+
+        loop {
+            tokio::select! {
+                message = rx.recv() => {
+                    let Some(message) = message else {
+                        break;
+                    };
+                    match message {
+                        Message::Targets { tx } => {
+                            let _ = tx.send(self.targets().await);
+                        }
+                        Message::Check { tx } => {
+                            let targets = self.targets().await;
+                            let fetched = fetch(&self.feed_cache, targets).await;
+                            let _ = tx.send(self.record(fetched).await);
+                        }
+                        Message::Unread { tx } => {
+                            let _ = tx.send(self.handle_unread());
+                        }
+                        Message::MarkSeen { key, tx } => {
+                            let _ = tx.send(self.handle_mark_seen(key).await);
+                        }
+                        Message::Subscribe { every, updates } => {
+                            self.subscribers.push(updates);
+                            // Intervals cannot be empty
+                            let every = every.max(Duration::from_secs(1));
+                            // The first tick fires right away
+                            if ticker.as_ref().is_none_or(|ticker| ticker.period() > every) {
+                                let mut interval = tokio::time::interval(every);
+                                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                                ticker = Some(interval);
+                            }
+                        }
+                    }
+                }
+                _ = tick(&mut ticker) => {
+                    // A slow archive must not pile checks up
+                    if !checking {
+                        checking = true;
+                        let targets = self.targets().await;
+                        let feed_cache = self.feed_cache.clone();
+                        let fetched_tx = fetched_tx.clone();
+                        tokio::spawn(async move {
+                            let _ = fetched_tx.send(fetch(&feed_cache, targets).await).await;
+                        });
+                    }
+                }
+                Some(fetched) = fetched_rx.recv() => {
+                    checking = false;
+                    match self.record(fetched).await {
+                        Ok(updates) => self.notify(updates).await,
+                        Err(e) => self.log.error(SCOPE, format!("Watch check failed: {:#}", e)),
+                    }
+                    if self.subscribers.is_empty() {
+                        ticker = None;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the watched lists and searches, as currently configured.
+    async fn targets(&self) -> Vec<WatchTarget> {
+        watch_targets(
+            self.config.list(ListOpt::WatchedLists).await,
+            self.config.map(MapOpt::WatchedSearches).await,
+        )
+    }
+
+    /// Updates the markers with the newest patches of the targets and persists them.
+    ///
+    /// Markers of feeds that are no longer watched are dropped, and so are the targets
+    /// that could not be fetched after logging why.
+    ///
+    /// # Returns
+    /// The targets that got new patches.
+    async fn record(&mut self, fetched: Fetched) -> anyhow::Result<Vec<WatchUpdate>> {
+        let keys: Vec<String> = fetched
+            .iter()
+            .map(|(target, _)| target.key().to_string())
+            .collect();
+        self.data.markers.retain(|key, _| keys.contains(key));
+
+        let mut updates = Vec::new();
+        for (target, newest) in fetched {
+            let newest = match newest {
+                Ok(newest) => newest,
+                Err(e) => {
+                    self.log
+                        .warn(SCOPE, format!("Failed to check '{}': {:#}", target.name, e));
+                    continue;
+                }
+            };
+            let patches = self
+                .data
+                .markers
+                .entry(target.key().to_string())
+                .or_default()
+                .record(&newest);
+            if !patches.is_empty() {
+                self.log.info(
+                    SCOPE,
+                    format!("{} new patch(es) in '{}'", patches.len(), target.name),
+                );
+                updates.push(WatchUpdate { target, patches });
+            }
+        }
+        self.persist().await?;
+        Ok(updates)
+    }
+
+    /// Sends the updates to the subscribers, dropping the ones that went away.
+    async fn notify(&mut self, updates: Vec<WatchUpdate>) {
+        for update in updates {
+            let mut open = Vec::with_capacity(self.subscribers.len());
+            for subscriber in self.subscribers.drain(..) {
+                if subscriber.send(update.clone()).await.is_ok() {
+                    open.push(subscriber);
+                }
+            }
+            self.subscribers = open;
+        }
+        self.subscribers
+            .retain(|subscriber| !subscriber.is_closed());
+    }
+
+    /// Returns how many patches arrived in each feed since it was last opened.
+    fn handle_unread(&self) -> HashMap<ArcStr, usize> {
+        self.data
+            .markers
+            .iter()
+            .filter(|(_, marker)| !marker.unread.is_empty())
+            .map(|(key, marker)| (ArcStr::from(key), marker.unread.len()))
+            .collect()
+    }
+
+    /// Forgets the unread patches of a feed.
+    async fn handle_mark_seen(&mut self, key: ArcStr) -> anyhow::Result<()> {
+        match self.data.markers.get_mut(key.as_ref() as &str) {
+            Some(marker) if !marker.unread.is_empty() => {
+                marker.unread.clear();
+                self.persist().await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the path of the file the markers are kept in.
+    async fn path(&self) -> ArcPath {
+        let cache_dir = self.config.path(PathOpt::CachePath).await;
+        ArcPath::from(&cache_dir.join("watch.toml"))
+    }
+
+    /// Persists the markers to the filesystem.
+    async fn persist(&self) -> anyhow::Result<()> {
+        let content =
+            toml::to_string_pretty(&self.data).context("Failed to serialize watch markers")?;
+        self.fs
+            .write_atomic(self.path().await, content.into_bytes())
+            .await
+            .context("Failed to write watch markers")?;
+        Ok(())
+    }
+
+    /// Loads the markers from the filesystem, if they were ever persisted.
+    async fn load(&mut self) -> anyhow::Result<()> {
+        let Ok(mut file) = self.fs.read_file(self.path().await).await else {
+            return Ok(());
+        };
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .await
+            .context("Failed to read watch markers")?;
+        self.data = toml::from_str(&content).context("Failed to deserialize watch markers")?;
+        Ok(())
+    }
+}
+
+/// Refreshes the feed of every target and returns its newest patches.
+async fn fetch(feed_cache: &FeedCache, targets: Vec<WatchTarget>) -> Fetched {
+    let mut fetched = Vec::with_capacity(targets.len());
+    for target in targets {
+        let newest = newest(feed_cache, &target).await;
+        fetched.push((target, newest));
+    }
+    fetched
+}
+
+/// Refreshes the feed of a target and returns its newest patches, newest first.
+async fn newest(
+    feed_cache: &FeedCache,
+    target: &WatchTarget,
+) -> anyhow::Result<Vec<LorePatchMetadata>> {
+    let key = match &target.search {
+        Some(search) => feed_cache.search(search.clone()).await,
+        None => target.name.clone(),
+    };
+    feed_cache.ensure_loaded(key.clone()).await?;
+    feed_cache.refresh(key.clone()).await?;
+    let len = feed_cache.len(key.clone()).await.min(WINDOW);
+    feed_cache.get_slice(key, 0..len).await
+}
+
+/// Waits for the next tick, or forever if periodic checks are off.
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
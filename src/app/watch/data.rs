@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ArcStr;
+use crate::api::lore::{ALL_LISTS, LorePatchMetadata, LoreQuery, LoreSearch};
+
+/// A feed checked for new patches: a watched mailing list or saved search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchTarget {
+    /// The name of the list, or the name the search is saved under
+    pub name: ArcStr,
+    /// The saved search, `None` for a mailing list
+    pub search: Option<LoreSearch>,
+}
+
+impl WatchTarget {
+    /// Returns the key the feed is cached under in the feed cache: the list name, or
+    /// the [`LoreSearch::cache_key`] of the search.
+    pub fn key(&self) -> ArcStr {
+        match &self.search {
+            Some(search) => search.cache_key(),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Patches that arrived in a watched feed since it was last checked.
+#[derive(Debug, Clone)]
+pub struct WatchUpdate {
+    /// The feed the patches arrived in
+    pub target: WatchTarget,
+    /// The new patches, newest first
+    pub patches: Vec<LorePatchMetadata>,
+}
+
+/// What is known about a watched feed, kept between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchMarker {
+    /// Message ID of the newest patch of the feed when it was last checked
+    #[serde(default)]
+    pub last_seen: Option<ArcStr>,
+    /// Message IDs of the patches that arrived since the feed was last opened, newest first
+    #[serde(default)]
+    pub unread: Vec<ArcStr>,
+}
+
+impl WatchMarker {
+    /// Records the newest patches of a feed and returns the ones that arrived since the
+    /// last check.
+    ///
+    /// The first check only sets the marker, so the patches already in the feed are
+    /// not reported. If the marker fell out of `newest`, all of `newest` is reported.
+    ///
+    /// # Arguments
+    /// * `newest` - The newest patches of the feed, newest first
+    ///
+    /// # Returns
+    /// The new patches, newest first.
+    pub fn record(&mut self, newest: &[LorePatchMetadata]) -> Vec<LorePatchMetadata> {
+        let Some(head) = newest.first() else {
+            return Vec::new();
+        };
+        let Some(last_seen) = self.last_seen.replace(head.message_id.clone()) else {
+            return Vec::new();
+        };
+        let arrived: Vec<LorePatchMetadata> = newest
+            .iter()
+            .take_while(|patch| patch.message_id != last_seen)
+            .filter(|patch| !self.unread.contains(&patch.message_id))
+            .cloned()
+            .collect();
+        self.unread
+            .splice(0..0, arrived.iter().map(|patch| patch.message_id.clone()));
+        arrived
+    }
+}
+
+/// The markers of the watched feeds, as persisted on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchData {
+    /// Markers by feed cache key (see [`WatchTarget::key`])
+    #[serde(default)]
+    pub markers: HashMap<String, WatchMarker>,
+}
+
+/// Builds the targets watched according to the configuration.
+///
+/// Lists come first, in the configured order, followed by the saved searches sorted by
+/// name. Searches run against all lists and match patches only; the ones without
+/// terms are left out, as they would match every patch of the archive.
+///
+/// # Arguments
+/// * `lists` - The watched mailing lists (`ListOpt::WatchedLists`)
+/// * `searches` - The public-inbox terms of the saved searches by name
+///   (`MapOpt::WatchedSearches`)
+pub fn watch_targets(
+    lists: Vec<ArcStr>,
+    searches: HashMap<String, Vec<ArcStr>>,
+) -> Vec<WatchTarget> {
+    let mut targets: Vec<WatchTarget> = lists
+        .into_iter()
+        .map(|name| WatchTarget { name, search: None })
+        .collect();
+    let mut searches: Vec<_> = searches.into_iter().collect();
+    searches.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, terms) in searches {
+        let query = terms
+            .iter()
+            .fold(LoreQuery::patches(), |query, term| query.raw(term));
+        if query.terms().is_empty() {
+            continue;
+        }
+        targets.push(WatchTarget {
+            name: ArcStr::from(name),
+            search: Some(LoreSearch::new(ArcStr::from(ALL_LISTS), query)),
+        });
+    }
+    targets
+}
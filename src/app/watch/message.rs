@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::sync::oneshot::Sender;
+
+use crate::ArcStr;
+
+use super::data::{WatchTarget, WatchUpdate};
+
+/// Messages that can be sent to the watch actor.
+#[derive(Debug)]
+pub enum Message {
+    /// Get the watched lists and searches
+    Targets {
+        /// Response channel for the targets
+        tx: Sender<Vec<WatchTarget>>,
+    },
+    /// Refresh every watched feed now and record the patches that arrived
+    Check {
+        /// Response channel for the feeds that got new patches
+        tx: Sender<anyhow::Result<Vec<WatchUpdate>>>,
+    },
+    /// Get how many patches arrived in each watched feed since it was last opened
+    Unread {
+        /// Response channel for the counts, by feed cache key
+        tx: Sender<HashMap<ArcStr, usize>>,
+    },
+    /// Forget the unread patches of a feed, as it was opened
+    MarkSeen {
+        /// The feed cache key of the feed
+        key: ArcStr,
+        /// Response channel for the result of the operation
+        tx: Sender<anyhow::Result<()>>,
+    },
+    /// Check the watched feeds periodically and send the updates to a channel
+    Subscribe {
+        /// Time between two checks
+        every: Duration,
+        /// Channel the feeds that got new patches are sent to
+        updates: mpsc::Sender<WatchUpdate>,
+    },
+}
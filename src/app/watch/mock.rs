@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, mpsc};
+
+use crate::ArcStr;
+
+use super::MockData;
+use super::data::{WatchTarget, WatchUpdate};
+
+/// Mock implementation of the Watch actor for testing purposes.
+///
+/// This struct serves the targets, updates and unread counts from memory, allowing
+/// tests to run without the feed cache or the filesystem.
+#[derive(Debug, Clone)]
+pub struct Mock {
+    data: Arc<Mutex<MockData>>,
+    /// Kept so the channels handed out by `subscribe` stay open
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<WatchUpdate>>>>,
+}
+
+impl Mock {
+    /// Creates a new mock instance with the provided data.
+    ///
+    /// # Arguments
+    /// * `data` - The targets, the updates of the next check and the unread counts
+    pub fn new(data: MockData) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Gets the watched targets.
+    /// Mock implementation returns the stored targets.
+    pub async fn targets(&self) -> Vec<WatchTarget> {
+        let data = self.data.lock().await;
+        data.targets.clone()
+    }
+
+    /// Checks the watched feeds.
+    /// Mock implementation hands the stored updates out once, counting them as unread.
+    pub async fn check(&self) -> anyhow::Result<Vec<WatchUpdate>> {
+        let mut data = self.data.lock().await;
+        let updates = std::mem::take(&mut data.updates);
+        for update in &updates {
+            *data.unread.entry(update.target.key()).or_default() += update.patches.len();
+        }
+        Ok(updates)
+    }
+
+    /// Gets the unread counts.
+    /// Mock implementation returns the stored counts.
+    pub async fn unread(&self) -> HashMap<ArcStr, usize> {
+        let data = self.data.lock().await;
+        data.unread.clone()
+    }
+
+    /// Forgets the unread patches of a feed.
+    /// Mock implementation removes its stored count.
+    ///
+    /// # Arguments
+    /// * `key` - The feed cache key of the feed
+    pub async fn mark_seen(&self, key: ArcStr) -> anyhow::Result<()> {
+        let mut data = self.data.lock().await;
+        data.unread.remove(&key);
+        Ok(())
+    }
+
+    /// Subscribes to the periodic checks.
+    /// Mock implementation returns a channel that never gets an update.
+    pub async fn subscribe(&self) -> mpsc::Receiver<WatchUpdate> {
+        let (tx, rx) = mpsc::channel(crate::BUFFER_SIZE);
+        self.subscribers.lock().await.push(tx);
+        rx
+    }
+}
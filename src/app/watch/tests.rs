@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::DateTime;
+
+use super::{MockData, Watch, WatchMarker, WatchTarget, WatchUpdate, watch_targets};
+use crate::api::lore::LorePatchMetadata;
+use crate::app::cache::{FeedCache, FeedMockData};
+use crate::app::config::{Config, Data, ListOpt, MapOpt, PathOpt};
+use crate::fs::Fs;
+use crate::log::Log;
+use crate::{ArcPath, ArcStr};
+
+fn patch(id: &str) -> LorePatchMetadata {
+    LorePatchMetadata {
+        author: ArcStr::from("Jane Doe"),
+        email: ArcStr::from("jane@example.com"),
+        last_update: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        title: ArcStr::from(format!("[PATCH] {id}")),
+        version: 1,
+        sequence: None,
+        link: ArcStr::from(format!("https://lore.kernel.org/linux-mm/{id}/")),
+        list: ArcStr::from("linux-mm"),
+        message_id: ArcStr::from(id),
+        in_reply_to: None,
+        rfc: false,
+        resend: false,
+        prefixes: Vec::new(),
+    }
+}
+
+fn feed(ids: &[&str]) -> FeedCache {
+    FeedCache::mock(FeedMockData {
+        feeds: HashMap::from([(
+            ArcStr::from("linux-mm"),
+            ids.iter().map(|id| patch(id)).collect(),
+        )]),
+        ..Default::default()
+    })
+}
+
+async fn config() -> Config {
+    let config = Config::mock(Data::default());
+    config
+        .set_path(PathOpt::CachePath, ArcPath::from("cache"))
+        .await;
+    config
+        .set_list(ListOpt::WatchedLists, vec![ArcStr::from("linux-mm")])
+        .await;
+    config
+}
+
+fn ids(patches: &[LorePatchMetadata]) -> Vec<&str> {
+    patches.iter().map(|p| p.message_id.as_ref()).collect()
+}
+
+#[test]
+fn test_marker_record() {
+    let mut marker = WatchMarker::default();
+    assert!(marker.record(&[]).is_empty());
+    assert!(marker.last_seen.is_none());
+
+    // The first check only sets the marker
+    assert!(marker.record(&[patch("b"), patch("a")]).is_empty());
+    assert_eq!(marker.last_seen, Some(ArcStr::from("b")));
+
+    let arrived = marker.record(&[patch("d"), patch("c"), patch("b"), patch("a")]);
+    assert_eq!(ids(&arrived), vec!["d", "c"]);
+    assert_eq!(marker.unread, vec![ArcStr::from("d"), ArcStr::from("c")]);
+
+    // Once the marker falls out of the window, the whole window is new
+    let arrived = marker.record(&[patch("f"), patch("e"), patch("d")]);
+    assert_eq!(ids(&arrived), vec!["f", "e"]);
+    assert_eq!(marker.unread.len(), 4);
+    assert_eq!(marker.last_seen, Some(ArcStr::from("f")));
+}
+
+#[test]
+fn test_watch_targets() {
+    let targets = watch_targets(
+        vec![ArcStr::from("linux-mm"), ArcStr::from("amd-gfx")],
+        HashMap::from([
+            ("mine".to_string(), vec![ArcStr::from("f:jane")]),
+            ("empty".to_string(), vec![ArcStr::from(" ")]),
+            (
+                "amd display".to_string(),
+                vec![ArcStr::from("dfn:drivers/gpu/drm/amd/display/")],
+            ),
+        ]),
+    );
+    let names: Vec<&str> = targets.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, vec!["linux-mm", "amd-gfx", "amd display", "mine"]);
+    assert_eq!(targets[0].key(), ArcStr::from("linux-mm"));
+
+    let search = targets[3].search.as_ref().unwrap();
+    assert_eq!(search.list, ArcStr::from("all"));
+    assert!(search.query.is_patches());
+    assert_eq!(search.query.terms(), &[ArcStr::from("f:jane")]);
+    assert_eq!(targets[3].key(), search.cache_key());
+}
+
+#[tokio::test]
+async fn test_check_records_new_patches_across_runs() {
+    let fs = Fs::mock();
+    let config = config().await;
+
+    let watch = Watch::spawn(feed(&["b", "a"]), fs.clone(), config.clone(), Log::mock());
+    assert_eq!(watch.targets().await.len(), 1);
+    assert!(watch.check().await.unwrap().is_empty());
+    assert!(watch.unread().await.is_empty());
+
+    // The markers are persisted, so a later run reports what arrived in between
+    let watch = Watch::spawn(feed(&["d", "c", "b", "a"]), fs, config, Log::mock());
+    let updates = watch.check().await.unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].target.name, ArcStr::from("linux-mm"));
+    assert_eq!(ids(&updates[0].patches), vec!["d", "c"]);
+    assert_eq!(
+        watch.unread().await,
+        HashMap::from([(ArcStr::from("linux-mm"), 2)])
+    );
+    assert!(watch.check().await.unwrap().is_empty());
+
+    watch.mark_seen(ArcStr::from("linux-mm")).await.unwrap();
+    assert!(watch.unread().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_subscribe_checks_right_away() {
+    let fs = Fs::mock();
+    let config = config().await;
+    config
+        .set_map(
+            MapOpt::WatchedSearches,
+            HashMap::from([("mine".to_string(), vec![ArcStr::from("f:jane")])]),
+        )
+        .await;
+    let watch = Watch::spawn(feed(&["a"]), fs.clone(), config.clone(), Log::mock());
+    watch.check().await.unwrap();
+
+    let watch = Watch::spawn(feed(&["b", "a"]), fs, config, Log::mock());
+    let mut updates = watch.subscribe(Duration::from_secs(3600)).await;
+    let update = tokio::time::timeout(Duration::from_secs(5), updates.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.target.name, ArcStr::from("linux-mm"));
+    assert_eq!(ids(&update.patches), vec!["b"]);
+}
+
+#[tokio::test]
+async fn test_mock_check() {
+    let target = WatchTarget {
+        name: ArcStr::from("linux-mm"),
+        search: None,
+    };
+    let watch = Watch::mock(MockData {
+        targets: vec![target.clone()],
+        updates: vec![WatchUpdate {
+            target,
+            patches: vec![patch("a")],
+        }],
+        ..Default::default()
+    });
+    assert_eq!(watch.check().await.unwrap().len(), 1);
+    assert!(watch.check().await.unwrap().is_empty());
+    assert_eq!(watch.unread().await[&ArcStr::from("linux-mm")], 1);
+    watch.mark_seen(ArcStr::from("linux-mm")).await.unwrap();
+    assert!(watch.unread().await.is_empty());
+}
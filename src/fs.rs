@@ -193,4 +193,107 @@ impl Fs {
             }
         }
     }
+
+    /// Renames a file, replacing the destination if it exists
+    pub async fn rename(&self, from: ArcPath, to: ArcPath) -> Result<(), io::Error> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Rename { tx, from, to })
+                    .await
+                    .context("Renaming file with Fs")
+                    .expect("fs actor died");
+                rx.await
+                    .context("Awaiting response for file rename with Fs")
+                    .expect("fs actor died")
+            }
+            Self::Mock(mock) => {
+                mock.rename(from, to).await
+            }
+        }
+    }
+
+    /// Replaces the content of a file atomically (writes a temporary file next to it,
+    /// syncs it and renames it over the file), creating the file and its parents if needed.
+    ///
+    /// A crash mid-write leaves either the old or the new content, never a truncated file.
+    pub async fn write_atomic(&self, path: ArcPath, content: Vec<u8>) -> Result<(), io::Error> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::WriteAtomic { tx, path, content })
+                    .await
+                    .context("Writing file atomically with Fs")
+                    .expect("fs actor died");
+                rx.await
+                    .context("Awaiting response for atomic file write with Fs")
+                    .expect("fs actor died")
+            }
+            Self::Mock(mock) => {
+                mock.write_atomic(path, content).await
+            }
+        }
+    }
+
+    /// Checks whether a path exists
+    pub async fn exists(&self, path: ArcPath) -> Result<bool, io::Error> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Exists { tx, path })
+                    .await
+                    .context("Checking path with Fs")
+                    .expect("fs actor died");
+                rx.await
+                    .context("Awaiting response for path check with Fs")
+                    .expect("fs actor died")
+            }
+            Self::Mock(mock) => {
+                mock.exists(path).await
+            }
+        }
+    }
+
+    /// Reads the metadata of a file or directory
+    pub async fn metadata(&self, path: ArcPath) -> Result<std::fs::Metadata, io::Error> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Metadata { tx, path })
+                    .await
+                    .context("Reading metadata with Fs")
+                    .expect("fs actor died");
+                rx.await
+                    .context("Awaiting response for metadata read with Fs")
+                    .expect("fs actor died")
+            }
+            Self::Mock(mock) => {
+                mock.metadata(path).await
+            }
+        }
+    }
+
+    /// Copies a file, replacing the destination if it exists, and returns the number of bytes copied
+    pub async fn copy(&self, from: ArcPath, to: ArcPath) -> Result<u64, io::Error> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(message::Message::Copy { tx, from, to })
+                    .await
+                    .context("Copying file with Fs")
+                    .expect("fs actor died");
+                rx.await
+                    .context("Awaiting response for file copy with Fs")
+                    .expect("fs actor died")
+            }
+            Self::Mock(mock) => {
+                mock.copy(from, to).await
+            }
+        }
+    }
 }
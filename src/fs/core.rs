@@ -1,6 +1,6 @@
-use std::{collections::LinkedList, io};
+use std::{collections::LinkedList, io, path::Path};
 
-use tokio::{fs::OpenOptions, sync::mpsc};
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::mpsc};
 
 use crate::ArcPath;
 
@@ -31,6 +31,13 @@ impl Core {
                     ReadDir { tx, path } => Self::read_dir(tx, path).await,
                     MkDir { tx, path } => Self::mkdir(tx, path).await,
                     RmDir { tx, path } => Self::rmdir(tx, path).await,
+                    Rename { tx, from, to } => Self::rename(tx, from, to).await,
+                    WriteAtomic { tx, path, content } => {
+                        Self::write_atomic(tx, path, content).await
+                    }
+                    Exists { tx, path } => Self::exists(tx, path).await,
+                    Metadata { tx, path } => Self::metadata(tx, path).await,
+                    Copy { tx, from, to } => Self::copy(tx, from, to).await,
                 }
             }
         });
@@ -55,7 +62,7 @@ impl Core {
         if let Some(parent) = path.parent() {
             let _ = tokio::fs::create_dir_all(parent).await;
         }
-        
+
         let res = OpenOptions::new()
             .write(true)
             .create(true)
@@ -74,7 +81,7 @@ impl Core {
         if let Some(parent) = path.parent() {
             let _ = tokio::fs::create_dir_all(parent).await;
         }
-        
+
         let res = OpenOptions::new()
             .write(true)
             .create(true)
@@ -123,4 +130,85 @@ impl Core {
         let res = tokio::fs::remove_dir_all(&path).await;
         let _ = tx.send(res);
     }
+
+    async fn rename(
+        tx: tokio::sync::oneshot::Sender<Result<(), io::Error>>,
+        from: ArcPath,
+        to: ArcPath,
+    ) {
+        let res = tokio::fs::rename(&from, &to).await;
+        let _ = tx.send(res);
+    }
+
+    /// Replaces the content of a file atomically (see [`write_atomic`]).
+    async fn write_atomic(
+        tx: tokio::sync::oneshot::Sender<Result<(), io::Error>>,
+        path: ArcPath,
+        content: Vec<u8>,
+    ) {
+        let res = write_atomic(&path, &content).await;
+        let _ = tx.send(res);
+    }
+
+    async fn exists(tx: tokio::sync::oneshot::Sender<Result<bool, io::Error>>, path: ArcPath) {
+        let res = tokio::fs::try_exists(&path).await;
+        let _ = tx.send(res);
+    }
+
+    async fn metadata(
+        tx: tokio::sync::oneshot::Sender<Result<std::fs::Metadata, io::Error>>,
+        path: ArcPath,
+    ) {
+        let res = tokio::fs::metadata(&path).await;
+        let _ = tx.send(res);
+    }
+
+    async fn copy(
+        tx: tokio::sync::oneshot::Sender<Result<u64, io::Error>>,
+        from: ArcPath,
+        to: ArcPath,
+    ) {
+        let res = tokio::fs::copy(&from, &to).await;
+        let _ = tx.send(res);
+    }
+}
+
+/// Replaces the content of a file without ever leaving it half-written.
+///
+/// The content goes to a temporary file next to the target, which is synced to disk
+/// and then renamed over the target, so the file holds either the old or the new
+/// content even if the process dies halfway. Parent directories are created if needed.
+pub(super) async fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty());
+    if let Some(parent) = parent {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut temp_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_owned();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let written = async {
+        let mut file = tokio::fs::File::create(&temp_path).await?;
+        file.write_all(content).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temp_path, path).await
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
+
+    // Make the rename itself durable
+    if let Some(parent) = parent
+        && let Ok(dir) = tokio::fs::File::open(parent).await
+    {
+        let _ = dir.sync_all().await;
+    }
+    Ok(())
 }
@@ -59,4 +59,45 @@ pub enum Message {
         /// The path of the directory to remove
         path: ArcPath,
     },
+    /// Renames a file, replacing the destination if it exists
+    Rename {
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<Result<(), std::io::Error>>,
+        /// The current path of the file
+        from: ArcPath,
+        /// The new path of the file
+        to: ArcPath,
+    },
+    /// Replaces the content of a file atomically, creating it if needed
+    WriteAtomic {
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<Result<(), std::io::Error>>,
+        /// The path of the file to write
+        path: ArcPath,
+        /// The new content of the file
+        content: Vec<u8>,
+    },
+    /// Checks whether a path exists
+    Exists {
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<Result<bool, std::io::Error>>,
+        /// The path to check
+        path: ArcPath,
+    },
+    /// Reads the metadata of a file or directory
+    Metadata {
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<Result<std::fs::Metadata, std::io::Error>>,
+        /// The path of the file or directory
+        path: ArcPath,
+    },
+    /// Copies a file, replacing the destination if it exists
+    Copy {
+        /// Channel to send the number of bytes copied back to the caller
+        tx: oneshot::Sender<Result<u64, std::io::Error>>,
+        /// The path of the file to copy
+        from: ArcPath,
+        /// The path of the copy
+        to: ArcPath,
+    },
 }
@@ -1,6 +1,6 @@
 use std::{collections::LinkedList, io, sync::Arc};
-use tokio::sync::Mutex;
 use tempfile::TempDir;
+use tokio::sync::Mutex;
 
 use crate::ArcPath;
 
@@ -138,4 +138,69 @@ impl Mock {
         let real_path = self.mock_path(&path).await;
         tokio::fs::remove_dir_all(real_path).await
     }
+
+    /// Renames a file, replacing the destination if it exists.
+    ///
+    /// # Arguments
+    /// * `from` - The current path of the file
+    /// * `to` - The new path of the file
+    ///
+    /// # Returns
+    /// Ok(()) if successful, or an error if the file cannot be renamed.
+    pub async fn rename(&self, from: ArcPath, to: ArcPath) -> Result<(), io::Error> {
+        let from = self.mock_path(&from).await;
+        let to = self.mock_path(&to).await;
+        tokio::fs::rename(from, to).await
+    }
+
+    /// Replaces the content of a file atomically, creating it if needed.
+    ///
+    /// # Arguments
+    /// * `path` - The file path to write
+    /// * `content` - The new content of the file
+    ///
+    /// # Returns
+    /// Ok(()) if successful, or an error if the file cannot be written.
+    pub async fn write_atomic(&self, path: ArcPath, content: Vec<u8>) -> Result<(), io::Error> {
+        let real_path = self.mock_path(&path).await;
+        super::core::write_atomic(&real_path, &content).await
+    }
+
+    /// Checks whether a path exists.
+    ///
+    /// # Arguments
+    /// * `path` - The path to check
+    ///
+    /// # Returns
+    /// Whether the path exists, or an error if it cannot be checked.
+    pub async fn exists(&self, path: ArcPath) -> Result<bool, io::Error> {
+        let real_path = self.mock_path(&path).await;
+        tokio::fs::try_exists(real_path).await
+    }
+
+    /// Reads the metadata of a file or directory.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file or directory
+    ///
+    /// # Returns
+    /// The metadata, or an error if the path does not exist.
+    pub async fn metadata(&self, path: ArcPath) -> Result<std::fs::Metadata, io::Error> {
+        let real_path = self.mock_path(&path).await;
+        tokio::fs::metadata(real_path).await
+    }
+
+    /// Copies a file, replacing the destination if it exists.
+    ///
+    /// # Arguments
+    /// * `from` - The path of the file to copy
+    /// * `to` - The path of the copy
+    ///
+    /// # Returns
+    /// The number of bytes copied, or an error if the file cannot be copied.
+    pub async fn copy(&self, from: ArcPath, to: ArcPath) -> Result<u64, io::Error> {
+        let from = self.mock_path(&from).await;
+        let to = self.mock_path(&to).await;
+        tokio::fs::copy(from, to).await
+    }
 }
@@ -111,3 +111,50 @@ async fn test_fs_file_operations() {
     // Cleanup
     fs.remove_file(path).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_fs_write_atomic() {
+    let fs = Fs::mock();
+    let path = ArcPath::from("cache/data.toml");
+    assert!(!fs.exists(path.clone()).await.unwrap());
+
+    // Parents are created and the temporary file does not stay around
+    fs.write_atomic(path.clone(), b"first".to_vec())
+        .await
+        .unwrap();
+    fs.write_atomic(path.clone(), b"second".to_vec())
+        .await
+        .unwrap();
+    assert!(fs.exists(path.clone()).await.unwrap());
+    assert_eq!(fs.metadata(path.clone()).await.unwrap().len(), 6);
+    let entries = fs.read_dir(ArcPath::from("cache")).await.unwrap();
+    assert_eq!(entries.len(), 1);
+
+    let mut content = String::new();
+    use tokio::io::AsyncReadExt;
+    fs.read_file(path)
+        .await
+        .unwrap()
+        .read_to_string(&mut content)
+        .await
+        .unwrap();
+    assert_eq!(content, "second");
+}
+
+#[tokio::test]
+async fn test_fs_copy_rename() {
+    let fs = Fs::mock();
+    let original = ArcPath::from("original.txt");
+    let copy = ArcPath::from("copy.txt");
+    let renamed = ArcPath::from("renamed.txt");
+
+    fs.write_atomic(original.clone(), b"content".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(fs.copy(original.clone(), copy.clone()).await.unwrap(), 7);
+    fs.rename(copy.clone(), renamed.clone()).await.unwrap();
+
+    assert!(fs.exists(original).await.unwrap());
+    assert!(!fs.exists(copy).await.unwrap());
+    assert!(fs.metadata(renamed).await.unwrap().is_file());
+}
@@ -1,5 +1,9 @@
 use clap::{Parser, Subcommand};
-use ph::ArcStr;
+use ph::{ArcPath, ArcStr};
+use ph::api::lore::{ALL_LISTS, LoreQuery, LoreSearch};
+use ph::app::apply::ApplyOptions;
+use ph::app::cache::{ReviewFilter, ReviewState};
+use ph::app::reply::Trailer;
 use ph::app::{App, Command};
 use ph::utils::install_panic_hook;
 
@@ -33,6 +37,68 @@ enum Commands {
         /// Number of items per page
         #[arg(short, long, default_value = "10")]
         count: usize,
+        /// Only show patches with this review state (reviewed, needs-work, ignored or unreviewed);
+        /// pages then hold `count` matching patches each
+        #[arg(short, long)]
+        review: Option<ReviewFilter>,
+    },
+    /// Search the archive for patches, showing the results like a feed
+    Search {
+        /// Extra query terms in public-inbox syntax (e.g. 's:"mm: fix" OR s:mm/')
+        terms: Vec<String>,
+        /// Only search this mailing list instead of all of them
+        #[arg(short, long)]
+        list: Option<String>,
+        /// Author name or address (f:)
+        #[arg(short, long)]
+        from: Option<String>,
+        /// Words or phrase in the subject (s:)
+        #[arg(short, long)]
+        subject: Option<String>,
+        /// File touched by the patch (dfn:)
+        #[arg(long)]
+        file: Option<String>,
+        /// Words or phrase in the body (b:)
+        #[arg(short, long)]
+        body: Option<String>,
+        /// Recipient address, in To or Cc (tc:)
+        #[arg(long)]
+        to: Option<String>,
+        /// Only patches sent since this date (e.g. 2024-01-31 or 2.weeks.ago)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only patches sent until this date
+        #[arg(long)]
+        until: Option<String>,
+        /// Page number (0-based)
+        #[arg(short, long, default_value = "0")]
+        page: usize,
+        /// Number of items per page
+        #[arg(short, long, default_value = "10")]
+        count: usize,
+    },
+    /// Show the patches of every list touching the subsystems declared in the configuration
+    Inbox {
+        /// Page number (0-based)
+        #[arg(short, long, default_value = "0")]
+        page: usize,
+        /// Number of items per page
+        #[arg(short, long, default_value = "10")]
+        count: usize,
+    },
+    /// Show the entries of the MAINTAINERS file of the configured repository
+    Maintainers {
+        /// Only show the entries whose name contains this text (e.g. "memory management")
+        filter: Option<String>,
+    },
+    /// Print the patches arriving in the lists and searches watched in the configuration
+    Watch {
+        /// Check once, print what arrived since the last check and exit
+        #[arg(long)]
+        once: bool,
+        /// Seconds between two checks (defaults to watch_interval from the configuration)
+        #[arg(short, long, conflicts_with = "once")]
+        interval: Option<usize>,
     },
     /// Get the content of a patch from the feed
     Patch {
@@ -46,6 +112,63 @@ enum Commands {
         #[arg(long)]
         html: bool,
     },
+    /// Apply a patch or series to the configured local git repository
+    Apply {
+        /// The mailing list name
+        #[arg(required = true)]
+        list: String,
+        /// The message ID of the patch (or of any message of the series)
+        #[arg(required = true)]
+        message_id: String,
+        /// Apply the whole series the message belongs to
+        #[arg(long)]
+        series: bool,
+        /// Fall back to a 3-way merge if a patch does not apply cleanly
+        #[arg(short = '3', long)]
+        three_way: bool,
+        /// Add a Signed-off-by trailer to the applied commits
+        #[arg(short, long)]
+        signoff: bool,
+        /// Branch to check out (created if missing) before applying
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+    /// Show or update the review status of a patch
+    Review {
+        /// The message ID of the patch
+        #[arg(required = true)]
+        message_id: String,
+        /// Set the review state (reviewed, needs-work or ignored)
+        #[arg(short, long, conflicts_with = "clear")]
+        state: Option<ReviewState>,
+        /// Clear the review state
+        #[arg(long)]
+        clear: bool,
+        /// Set the review note (an empty note removes it)
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+    /// Compose a reply to a patch and save it as an mbox ready to send
+    Reply {
+        /// The mailing list name
+        #[arg(required = true)]
+        list: String,
+        /// The message ID of the patch
+        #[arg(required = true)]
+        message_id: String,
+        /// Add a trailer from your identity (reviewed-by, acked-by or tested-by), may be repeated
+        #[arg(short, long)]
+        trailer: Vec<Trailer>,
+        /// Save the reply without opening $EDITOR
+        #[arg(long)]
+        no_edit: bool,
+    },
+    /// Send a composed reply through the configured mail backend
+    Send {
+        /// Path of the message, as printed by `reply`
+        #[arg(required = true)]
+        path: String,
+    },
 }
 
 #[tokio::main]
@@ -63,14 +186,77 @@ async fn main() -> anyhow::Result<()> {
             let command = Command::Lists { page, count };
             app.resolve(command).await?;
         }
-        Some(Commands::Feed { list, page, count }) => {
+        Some(Commands::Feed {
+            list,
+            page,
+            count,
+            review,
+        }) => {
             let command = Command::Feed {
                 list: ArcStr::from(list),
                 page,
                 count,
+                review,
             };
             app.resolve(command).await?;
         }
+        Some(Commands::Search {
+            terms,
+            list,
+            from,
+            subject,
+            file,
+            body,
+            to,
+            since,
+            until,
+            page,
+            count,
+        }) => {
+            let mut query = LoreQuery::patches();
+            if let Some(from) = from {
+                query = query.author(&from);
+            }
+            if let Some(subject) = subject {
+                query = query.subject(&subject);
+            }
+            if let Some(file) = file {
+                query = query.touches(&file);
+            }
+            if let Some(body) = body {
+                query = query.body(&body);
+            }
+            if let Some(to) = to {
+                query = query.addressed_to(&to);
+            }
+            query = query.date(since.as_deref(), until.as_deref());
+            query = query.raw(&terms.join(" "));
+            if query.terms().is_empty() {
+                anyhow::bail!("Nothing to search for, give some terms or filters");
+            }
+
+            let list = list.unwrap_or_else(|| ALL_LISTS.to_string());
+            let command = Command::Search {
+                search: LoreSearch::new(ArcStr::from(list), query),
+                page,
+                count,
+            };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Inbox { page, count }) => {
+            let command = Command::Inbox { page, count };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Maintainers { filter }) => {
+            let command = Command::Maintainers {
+                filter: filter.map(ArcStr::from),
+            };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Watch { once, interval }) => {
+            let command = Command::Watch { once, interval };
+            app.resolve(command).await?;
+        }
         Some(Commands::Patch {
             list,
             message_id,
@@ -83,6 +269,60 @@ async fn main() -> anyhow::Result<()> {
             };
             app.resolve(command).await?;
         }
+        Some(Commands::Apply {
+            list,
+            message_id,
+            series,
+            three_way,
+            signoff,
+            branch,
+        }) => {
+            let command = Command::Apply {
+                list: ArcStr::from(list),
+                message_id: ArcStr::from(message_id),
+                series,
+                options: ApplyOptions {
+                    three_way,
+                    signoff,
+                    branch: branch.map(ArcStr::from),
+                },
+            };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Review {
+            message_id,
+            state,
+            clear,
+            note,
+        }) => {
+            let command = Command::Review {
+                message_id: ArcStr::from(message_id),
+                state,
+                clear,
+                note: note.map(ArcStr::from),
+            };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Reply {
+            list,
+            message_id,
+            trailer,
+            no_edit,
+        }) => {
+            let command = Command::Reply {
+                list: ArcStr::from(list),
+                message_id: ArcStr::from(message_id),
+                trailers: trailer,
+                edit: !no_edit,
+            };
+            app.resolve(command).await?;
+        }
+        Some(Commands::Send { path }) => {
+            let command = Command::Send {
+                path: ArcPath::from(&path),
+            };
+            app.resolve(command).await?;
+        }
         None => {
             // Interactive mode - spawn the app and enter key event loop
             let (_handle, join_handle) = app.spawn()?;
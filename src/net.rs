@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use tokio::sync::mpsc::Sender;
 
 use crate::{
-    ArcStr,
+    ArcSlice, ArcStr,
     app::config::Config,
     net::{
         core::Core,
@@ -102,6 +102,39 @@ impl Net {
         }
     }
 
+    /// Performs an HTTP GET request and returns the response body as raw bytes.
+    ///
+    /// Use this instead of [`Net::get`] for binary payloads such as compressed archives.
+    ///
+    /// # Arguments
+    /// * `url` - The URL to send the GET request to
+    /// * `headers` - Optional headers to include in the request
+    ///
+    /// # Returns
+    /// The response body as bytes, or an error if the request fails.
+    pub async fn get_bytes(
+        &self,
+        url: ArcStr,
+        headers: Option<HashMap<ArcStr, ArcStr>>,
+    ) -> Result<ArcSlice<u8>, anyhow::Error> {
+        match self {
+            Net::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                sender
+                    .send(Message::GetBytes { url, headers, tx })
+                    .await
+                    .context("Sending message to Net actor")
+                    .expect("Net actor died");
+                rx.await
+                    .context("Awaiting response from Net actor")
+                    .expect("Net actor died")
+            }
+            Net::Mock(mock) => {
+                mock.get_bytes(url, headers).await
+            }
+        }
+    }
+
     /// Performs an HTTP POST request to the specified URL.
     ///
     /// # Arguments
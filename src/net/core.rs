@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use tokio::task::JoinHandle;
 
 use crate::{
-    ArcStr,
+    ArcSlice, ArcStr,
     app::config::{Config, USizeOpt},
     log::Log,
     net::{Net, message::Message},
@@ -99,6 +99,13 @@ impl Core {
                             .with_context(|| format!("GET request failed for URL: {url}"));
                         let _ = tx.send(response);
                     }
+                    Message::GetBytes { url, headers, tx } => {
+                        let response = self
+                            .handle_get_bytes_request(url.clone(), headers)
+                            .await
+                            .with_context(|| format!("GET request failed for URL: {url}"));
+                        let _ = tx.send(response);
+                    }
                     Message::Post {
                         url,
                         headers,
@@ -171,6 +178,28 @@ impl Core {
         Ok(ArcStr::from(&text))
     }
 
+    /// Handles GET requests whose body must be kept as raw bytes
+    async fn handle_get_bytes_request(
+        &self,
+        url: ArcStr,
+        headers: Option<HashMap<ArcStr, ArcStr>>,
+    ) -> anyhow::Result<ArcSlice<u8>> {
+        let mut request = self.client.get::<&str>(url.as_ref());
+
+        if let Some(headers) = headers {
+            for (key, value) in headers {
+                request = request.header(
+                    <ArcStr as AsRef<str>>::as_ref(&key),
+                    <ArcStr as AsRef<str>>::as_ref(&value),
+                );
+            }
+        }
+
+        let response = request.send().await.context("Sending GET request")?;
+        let bytes = response.bytes().await.context("Reading response body")?;
+        Ok(ArcSlice::from(&bytes[..]))
+    }
+
     /// Handles POST requests with optional headers and body
     async fn handle_post_request(
         &self,
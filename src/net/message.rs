@@ -3,7 +3,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 use tokio::sync::oneshot::Sender;
 
-use crate::ArcStr;
+use crate::{ArcSlice, ArcStr};
 
 /// Represents HTTP methods supported by the networking actor.
 ///
@@ -153,6 +153,12 @@ pub enum Message {
         headers: Option<HashMap<ArcStr, ArcStr>>,
         tx: Sender<anyhow::Result<ArcStr>>,
     },
+    /// Performs an HTTP GET request and returns the raw response body
+    GetBytes {
+        url: ArcStr,
+        headers: Option<HashMap<ArcStr, ArcStr>>,
+        tx: Sender<anyhow::Result<ArcSlice<u8>>>,
+    },
     /// Performs an HTTP POST request to the specified URL
     Post {
         url: ArcStr,
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::ArcStr;
+use crate::{ArcSlice, ArcStr};
 use crate::net::message::MockRequestKey;
 
 /// Mock implementation of the Net actor for testing purposes.
@@ -52,6 +52,25 @@ impl Mock {
         })
     }
 
+    /// Performs an HTTP GET request using mock responses, returning raw bytes.
+    ///
+    /// Shares the GET response cache, so the body is the bytes of the mocked string.
+    ///
+    /// # Arguments
+    /// * `url` - The URL to send the GET request to
+    /// * `headers` - Optional headers to include in the request (ignored in mock)
+    ///
+    /// # Returns
+    /// The response body as bytes, or an error if not found in mock responses.
+    pub async fn get_bytes(
+        &self,
+        url: ArcStr,
+        headers: Option<HashMap<ArcStr, ArcStr>>,
+    ) -> Result<ArcSlice<u8>, anyhow::Error> {
+        let text = self.get(url, headers).await?;
+        Ok(ArcSlice::from(text.as_bytes()))
+    }
+
     /// Performs an HTTP POST request using mock responses.
     ///
     /// # Arguments
@@ -1,6 +1,7 @@
 mod core;
 mod mock;
 mod message;
+mod native;
 #[cfg(test)]
 mod tests;
 
@@ -14,7 +15,7 @@ use crate::ArcStr;
 ///
 /// This enum represents either a real render actor or a mock implementation
 /// for testing purposes. It provides a unified interface for rendering patch content
-/// using external programs like `bat` or `delta`.
+/// using external programs like `bat` or `delta`, or the built-in highlighter.
 ///
 /// # Examples
 /// ```ignore
@@ -35,6 +36,7 @@ pub enum Render {
 
 /// Re-export the renderer type from config for convenience
 pub use crate::app::config::Renderer;
+pub use native::highlight_patch;
 
 impl Render {
     /// Creates a new render instance and spawns its actor.
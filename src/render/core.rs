@@ -4,6 +4,7 @@ use tokio::task::JoinHandle;
 use crate::{ArcSlice, ArcStr};
 
 use super::message::Message;
+use super::native::highlight_patch;
 
 /// The core implementation of the render actor.
 ///
@@ -74,9 +75,14 @@ impl Core {
             .renderer(crate::app::config::RendererOpt::PatchRenderer)
             .await;
 
-        if matches!(renderer, crate::app::config::Renderer::None) {
-            // No external renderer: return raw content
-            return Ok(content);
+        match renderer {
+            // No renderer: return raw content
+            crate::app::config::Renderer::None => return Ok(content),
+            // Built-in highlighting, no external program involved
+            crate::app::config::Renderer::Native => {
+                return Ok(ArcStr::from(highlight_patch(&content)));
+            }
+            _ => {}
         }
 
         // Get the program name and default arguments
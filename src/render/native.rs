@@ -0,0 +1,223 @@
+use crate::api::lore::{Diff, LineKind, parse_diff, parse_patch_message};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const RED_BACKGROUND: &str = "\x1b[41m";
+
+/// What a line of a patch email is, as far as highlighting goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// Anything without a style of its own
+    Plain,
+    /// An email header line
+    Header,
+    /// A trailer of the commit message
+    Trailer,
+    /// The `---` separator or the `-- ` signature
+    Dim,
+    /// A line of the diffstat
+    Diffstat,
+    /// A line of the header of a file diff
+    FileHeader,
+    /// The `@@` line that starts a hunk
+    HunkHeader,
+    /// A line within a hunk
+    Hunk(LineKind),
+}
+
+/// Wraps `text` in an SGR style.
+fn paint(out: &mut String, style: &str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    out.push_str(style);
+    out.push_str(text);
+    out.push_str(RESET);
+}
+
+/// Paints a diffstat line, coloring the `+`/`-` histogram.
+fn paint_diffstat(out: &mut String, line: &str) {
+    match line.rsplit_once(" | ") {
+        Some((path, stat)) => {
+            out.push_str(path);
+            out.push_str(" | ");
+            let bar = stat.trim_end_matches(['+', '-']);
+            out.push_str(bar);
+            let histogram = &stat[bar.len()..];
+            let plus = histogram.chars().take_while(|c| *c == '+').count();
+            paint(out, GREEN, &histogram[..plus]);
+            paint(out, RED, &histogram[plus..]);
+        }
+        None if line.contains(" changed") => paint(out, DIM, line),
+        None => out.push_str(line),
+    }
+}
+
+/// Paints an added or removed line, flagging trailing whitespace on added lines.
+fn paint_change(out: &mut String, style: &str, line: &str) {
+    if style != GREEN {
+        paint(out, style, line);
+        return;
+    }
+    let trimmed = line.trim_end();
+    paint(out, style, trimmed);
+    paint(out, RED_BACKGROUND, &line[trimmed.len()..]);
+}
+
+/// Marks the lines of `diff` within `lines`, returning the index past its last line.
+///
+/// The parsed model only keeps the lines it understood, so each file header line and
+/// hunk header is looked up in order from where the previous one was found.
+fn mark_diff(lines: &[&str], diff: &Diff, roles: &mut [Role]) -> usize {
+    let find = |cursor: &mut usize, text: &str| {
+        let idx = (*cursor..lines.len()).find(|&idx| lines[idx] == text)?;
+        *cursor = idx + 1;
+        Some(idx)
+    };
+
+    let mut cursor = 0;
+
+    for file in &diff.files {
+        cursor = cursor.max(file.start_line);
+        for header in &file.header {
+            if let Some(idx) = find(&mut cursor, header) {
+                roles[idx] = Role::FileHeader;
+            }
+        }
+        for hunk in &file.hunks {
+            let Some(idx) = find(&mut cursor, &hunk.header) else {
+                continue;
+            };
+            roles[idx] = Role::HunkHeader;
+            for line in &hunk.lines {
+                if cursor < roles.len() {
+                    roles[cursor] = Role::Hunk(line.kind);
+                    cursor += 1;
+                }
+            }
+        }
+    }
+    cursor
+}
+
+/// Works out the role of every line of a patch email.
+///
+/// The email is parsed with [`parse_patch_message`] for its headers, trailers and
+/// diffstat and with [`parse_diff`] for its files, hunks and line kinds.
+fn roles(lines: &[&str], content: &str) -> Vec<Role> {
+    let mut roles = vec![Role::Plain; lines.len()];
+    let message = parse_patch_message(content).ok();
+
+    let body_start = match &message {
+        Some(_) => {
+            let end = lines
+                .iter()
+                .position(|line| line.is_empty())
+                .unwrap_or(lines.len());
+            roles[..end].fill(Role::Header);
+            end
+        }
+        None => 0,
+    };
+
+    let diff = parse_diff(content);
+    let diff_start = diff
+        .files
+        .first()
+        .map_or(lines.len(), |file| file.start_line);
+    let diff_end = mark_diff(lines, &diff, &mut roles).max(body_start);
+
+    let signature = (diff_end..lines.len()).rfind(|&idx| lines[idx] == "-- ");
+    if let Some(signature) = signature {
+        roles[signature..].fill(Role::Dim);
+    }
+
+    let Some(message) = message else {
+        return roles;
+    };
+    let message_end = diff_start.min(signature.unwrap_or(lines.len()));
+    let separator = (body_start..message_end).find(|&idx| lines[idx] == "---");
+    for idx in body_start..separator.unwrap_or(message_end) {
+        let is_trailer = lines[idx].split_once(':').is_some_and(|(key, value)| {
+            message
+                .trailers
+                .iter()
+                .any(|trailer| *trailer.key == *key && trailer.value.starts_with(value.trim()))
+        });
+        if is_trailer {
+            roles[idx] = Role::Trailer;
+        }
+    }
+    if let Some(separator) = separator {
+        roles[separator] = Role::Dim;
+        for idx in separator + 1..message_end {
+            if message.diffstat.lines().any(|stat| stat == lines[idx]) {
+                roles[idx] = Role::Diffstat;
+            }
+        }
+    }
+    roles
+}
+
+/// Highlights a patch email with ANSI colors, without any external program.
+///
+/// The email headers, trailers, diffstat, file headers, hunk headers and the added
+/// and removed lines each get their own style. Lines are classified by the same
+/// parsers that build [`LorePatchMessage`](crate::api::lore::LorePatchMessage) and
+/// [`Diff`], so removed lines that look like `--- ` file headers are still painted
+/// as removals.
+///
+/// # Arguments
+/// * `content` - The patch, raw or as shown by the patch view
+///
+/// # Returns
+/// The same text with SGR escape sequences added.
+pub fn highlight_patch(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let roles = roles(&lines, content);
+    let mut out = String::with_capacity(content.len() + content.len() / 4);
+
+    for (line, role) in lines.iter().zip(roles) {
+        match role {
+            Role::Plain | Role::Hunk(LineKind::Context) => out.push_str(line),
+            Role::Header => {
+                if line.starts_with("From ") {
+                    paint(&mut out, DIM, line);
+                } else if line.starts_with([' ', '\t']) {
+                    out.push_str(line);
+                } else if let Some((name, value)) = line.split_once(':') {
+                    paint(&mut out, BOLD, &format!("{}:", name));
+                    out.push_str(value);
+                } else {
+                    paint(&mut out, DIM, line);
+                }
+            }
+            Role::Trailer => {
+                let (key, value) = line.split_once(':').unwrap_or((line, ""));
+                paint(&mut out, YELLOW, &format!("{}:", key));
+                out.push_str(value);
+            }
+            Role::Dim | Role::Hunk(LineKind::NoNewline) => paint(&mut out, DIM, line),
+            Role::Diffstat => paint_diffstat(&mut out, line),
+            Role::FileHeader => paint(&mut out, BOLD, line),
+            Role::HunkHeader => {
+                let end = line[2..].find("@@").map_or(line.len(), |end| end + 4);
+                paint(&mut out, CYAN, &line[..end]);
+                out.push_str(&line[end..]);
+            }
+            Role::Hunk(LineKind::Added) => paint_change(&mut out, GREEN, line),
+            Role::Hunk(LineKind::Removed) => paint_change(&mut out, RED, line),
+        }
+        out.push('\n');
+    }
+
+    if !content.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
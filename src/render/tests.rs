@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use super::Render;
+use super::{Render, highlight_patch};
+use crate::app::config::{Config, Data, RendererOpt};
+use crate::shell::Shell;
 use crate::{ArcStr, app::config::Renderer};
 
 #[tokio::test]
@@ -34,3 +36,87 @@ async fn test_mock_render() {
         .unwrap();
     assert_eq!(result, ArcStr::from("rendered content"));
 }
+
+const PATCH: &str = "From: Jane Doe <jane@example.com>
+Subject: [PATCH] net: do the thing
+
+Commit message.
+
+Signed-off-by: Jane Doe <jane@example.com>
+---
+ net/core.c | 3 ++-
+ 1 file changed, 2 insertions(+), 1 deletion(-)
+
+diff --git a/net/core.c b/net/core.c
+--- a/net/core.c
++++ b/net/core.c
+@@ -1,2 +1,3 @@ int setup(void)
+--- removed line that looks like a header
++++ added line that looks like a header
++trailing space 
+
+-- 
+2.43.0
+";
+
+#[test]
+fn test_highlight_patch() {
+    let out = highlight_patch(PATCH);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "\x1b[1mFrom:\x1b[0m Jane Doe <jane@example.com>");
+    assert_eq!(lines[3], "Commit message.");
+    assert_eq!(lines[5], "\x1b[33mSigned-off-by:\x1b[0m Jane Doe <jane@example.com>");
+    assert_eq!(lines[7], " net/core.c | 3 \x1b[32m++\x1b[0m\x1b[31m-\x1b[0m");
+    assert_eq!(lines[10], "\x1b[1mdiff --git a/net/core.c b/net/core.c\x1b[0m");
+    assert_eq!(lines[13], "\x1b[36m@@ -1,2 +1,3 @@\x1b[0m int setup(void)");
+    // Hunk counts win over look-alike file headers
+    assert!(lines[14].starts_with("\x1b[31m--- removed"));
+    assert!(lines[15].starts_with("\x1b[32m+++ added"));
+    assert_eq!(lines[16], "\x1b[32m+trailing space\x1b[0m\x1b[41m \x1b[0m");
+    assert_eq!(lines[17], "");
+    assert_eq!(lines[18], "\x1b[2m-- \x1b[0m");
+    assert!(out.ends_with("\x1b[2m2.43.0\x1b[0m\n"));
+}
+
+#[test]
+fn test_highlight_patch_keeps_text() {
+    let strip = |s: &str| {
+        let mut out = String::new();
+        let mut escape = false;
+        for c in s.chars() {
+            match c {
+                '\x1b' => escape = true,
+                'm' if escape => escape = false,
+                _ if escape => {}
+                c => out.push(c),
+            }
+        }
+        out
+    };
+    assert_eq!(strip(&highlight_patch(PATCH)), PATCH);
+    assert_eq!(highlight_patch("no newline"), "no newline");
+}
+
+#[test]
+fn test_highlight_bare_diff() {
+    let out = highlight_patch("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n+new\n");
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "\x1b[1m--- a/x\x1b[0m");
+    assert_eq!(lines[2], "\x1b[36m@@ -1 +1 @@\x1b[0m");
+    assert_eq!(lines[3], "\x1b[31m-old\x1b[0m");
+    assert_eq!(lines[4], "\x1b[32m+new\x1b[0m");
+}
+
+#[tokio::test]
+async fn test_native_renderer_runs_no_program() {
+    let shell = Shell::mock();
+    let config = Config::mock(Data::default());
+    config
+        .set_renderer(RendererOpt::PatchRenderer, Renderer::Native)
+        .await;
+    let render = Render::spawn(shell.clone(), config).await.unwrap();
+
+    let rendered = render.render_patch(ArcStr::from(PATCH)).await.unwrap();
+    assert!(rendered.contains("\x1b[32m"));
+    assert!(shell.get_commands().await.unwrap().is_empty());
+}
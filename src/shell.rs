@@ -5,11 +5,15 @@ mod message;
 #[cfg(test)]
 mod tests;
 
+use std::collections::HashMap;
+
 use anyhow::Context;
 use tokio::sync::mpsc::Sender;
 
 use crate::{ArcSlice, ArcStr};
 
+pub use data::{Command, Result as Output, Status};
+
 /// The shell actor that provides a thread-safe interface for executing external programs.
 ///
 /// This enum represents either a real shell actor or a mock implementation
@@ -54,6 +58,17 @@ impl Shell {
         Self::Mock(mock::Mock::new())
     }
 
+    /// Creates a new mock shell instance that answers some command lines with a given output.
+    ///
+    /// # Arguments
+    /// * `outputs` - Exit code, stdout and stderr by command line, as in `git -C . am`
+    ///
+    /// # Returns
+    /// A new mock shell instance; other commands succeed as with [`Shell::mock`].
+    pub fn mock_with_outputs(outputs: HashMap<ArcStr, (i32, ArcStr, ArcStr)>) -> Self {
+        Self::Mock(mock::Mock::with_outputs(outputs))
+    }
+
     /// Executes an external program with the given arguments and optional stdin.
     ///
     /// # Arguments
@@ -92,6 +107,45 @@ impl Shell {
         }
     }
 
+    /// Runs an interactive program, such as a text editor, attached to the current terminal.
+    ///
+    /// The program inherits stdin, stdout and stderr, so nothing is captured: the result
+    /// only carries the exit status. Whoever owns the terminal must release it first.
+    ///
+    /// # Arguments
+    /// * `program` - The name or path of the program to run (ArcStr)
+    /// * `args` - Command line arguments to pass to the program (ArcSlice<ArcStr>)
+    ///
+    /// # Returns
+    /// A structured result with empty output and the exit status.
+    pub async fn interactive(
+        &self,
+        program: ArcStr,
+        args: ArcSlice<ArcStr>,
+    ) -> anyhow::Result<data::Result> {
+        match self {
+            Self::Actual(sender) => {
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let command = data::Command {
+                    program,
+                    args,
+                    stdin: None,
+                };
+                sender
+                    .send(message::Message::Interactive { tx, command })
+                    .await
+                    .context("Running interactive command with Shell")
+                    .expect("shell actor died");
+                rx.await
+                    .context("Awaiting response for interactive command with Shell")
+                    .expect("shell actor died")
+            }
+            Self::Mock(mock) => {
+                mock.execute(program, args, None).await
+            }
+        }
+    }
+
     /// Gets all executed commands from the mock implementation.
     /// This method is only available for mock instances and is useful for testing.
     ///
@@ -49,6 +49,7 @@ impl Core {
                 use Message::*;
                 match msg {
                     Execute { tx, command } => self.execute(tx, command).await,
+                    Interactive { tx, command } => self.interactive(tx, command).await,
                 }
             }
         });
@@ -97,12 +98,14 @@ impl Core {
                 // Write to stdin if provided
                 if let Some(stdin_data) = &command.stdin {
                     if let Some(stdin) = child.stdin.take() {
-                        if let Err(e) = tokio::io::AsyncWriteExt::write_all(
-                            &mut tokio::io::BufWriter::new(stdin),
-                            stdin_data.as_bytes(),
-                        )
-                        .await
-                        {
+                        // Flush explicitly: a dropped BufWriter discards buffered input
+                        let mut writer = tokio::io::BufWriter::new(stdin);
+                        let written = async {
+                            tokio::io::AsyncWriteExt::write_all(&mut writer, stdin_data.as_bytes())
+                                .await?;
+                            tokio::io::AsyncWriteExt::shutdown(&mut writer).await
+                        };
+                        if let Err(e) = written.await {
                             self.log
                                 .error(SCOPE, format!("Failed to write to stdin: {e}"));
                             let _ =
@@ -174,4 +177,59 @@ impl Core {
 
         let _ = tx.send(result);
     }
+
+    /// Runs an interactive program attached to the current terminal.
+    ///
+    /// The child inherits the standard streams and the actor waits for it to exit.
+    ///
+    /// # Arguments
+    /// * `tx` - A oneshot channel sender to receive the result
+    /// * `command` - The command to run
+    async fn interactive(
+        &mut self,
+        tx: tokio::sync::oneshot::Sender<anyhow::Result<Result>>,
+        command: ShellCommand,
+    ) {
+        let command_str = command.to_string();
+        self.log
+            .info(SCOPE, format!("Running interactive command: {command_str}"));
+
+        let mut cmd = Command::new(&command.program);
+        for arg in command.args.iter() {
+            cmd.arg(arg);
+        }
+        cmd.stdin(std::process::Stdio::inherit());
+        cmd.stdout(std::process::Stdio::inherit());
+        cmd.stderr(std::process::Stdio::inherit());
+
+        let result = match cmd.status().await {
+            Ok(status) => {
+                let result = Result::new(
+                    ArcStr::default(),
+                    ArcStr::default(),
+                    Status::from(status),
+                    command,
+                );
+                if result.is_failure() {
+                    self.log.warn(
+                        SCOPE,
+                        format!(
+                            "Interactive command exited with {}: {command_str}",
+                            result.status
+                        ),
+                    );
+                }
+                Ok(result)
+            }
+            Err(e) => {
+                self.log.error(
+                    SCOPE,
+                    format!("Failed to run interactive command: {command_str} - {e}"),
+                );
+                Err(anyhow::anyhow!("Failed to run interactive command: {}", e))
+            }
+        };
+
+        let _ = tx.send(result);
+    }
 }
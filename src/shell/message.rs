@@ -15,4 +15,11 @@ pub enum Message {
         /// The command to execute
         command: Command,
     },
+    /// Runs an interactive program attached to the current terminal
+    Interactive {
+        /// Channel to send the result back to the caller
+        tx: oneshot::Sender<anyhow::Result<super::data::Result>>,
+        /// The command to run
+        command: Command,
+    },
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -11,18 +12,30 @@ use crate::shell::data::{Command, Result, Status};
 #[derive(Debug, Clone)]
 pub struct Mock {
     commands: Arc<Mutex<Vec<Command>>>,
+    /// Exit code, stdout and stderr to return, by command line
+    outputs: Arc<HashMap<ArcStr, (i32, ArcStr, ArcStr)>>,
 }
 
 impl Mock {
     /// Creates a new mock instance with an empty command store.
     pub fn new() -> Self {
+        Self::with_outputs(HashMap::new())
+    }
+
+    /// Creates a new mock instance that answers some command lines with the given output.
+    ///
+    /// # Arguments
+    /// * `outputs` - Exit code, stdout and stderr by command line, as in `git -C . am`
+    pub fn with_outputs(outputs: HashMap<ArcStr, (i32, ArcStr, ArcStr)>) -> Self {
         Self {
             commands: Arc::new(Mutex::new(Vec::new())),
+            outputs: Arc::new(outputs),
         }
     }
 
     /// Executes an external program with the given arguments and optional stdin.
-    /// Mock implementation stores the command and returns a success result.
+    /// Mock implementation stores the command and returns its configured output, or a
+    /// success result if it has none.
     ///
     /// # Arguments
     /// * `program` - The name or path of the program to execute (ArcStr)
@@ -45,6 +58,16 @@ impl Mock {
         };
         lock.push(command.clone());
 
+        let line = ArcStr::from(command.to_string());
+        if let Some((code, stdout, stderr)) = self.outputs.get(&line) {
+            return Ok(Result {
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+                status: Status::Success(*code),
+                command,
+            });
+        }
+
         // Mock implementation returns a success result
         Ok(Result {
             stdout: ArcStr::from(format!("Mock output for: {}", command.program).as_str()),
@@ -143,6 +143,24 @@ async fn test_shell_result_display() {
     assert!(display.contains("hello"));
 }
 
+#[tokio::test]
+async fn test_shell_interactive_exit_status() {
+    let shell = Shell::spawn(crate::log::Log::mock()).await.unwrap();
+
+    let result = shell
+        .interactive(ArcStr::from("true"), ArcSlice::from([]))
+        .await
+        .unwrap();
+    assert!(result.is_success());
+    assert!(result.stdout.is_empty());
+
+    let result = shell
+        .interactive(ArcStr::from("false"), ArcSlice::from([]))
+        .await
+        .unwrap();
+    assert!(result.is_failure());
+}
+
 #[tokio::test]
 async fn test_shell_actual_integration() {
     // This test requires a real shell actor with logging
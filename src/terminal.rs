@@ -1,9 +1,11 @@
 use anyhow::Context;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
+use crate::ArcStr;
 use crate::log::Log;
 
+mod ansi;
 mod core;
 mod data;
 mod mock;
@@ -66,4 +68,61 @@ impl Terminal {
             }
         }
     }
+
+    /// Releases the terminal so an external program (e.g. an editor) can use it.
+    ///
+    /// Returns once the screen has been restored to its normal mode. The UI stays frozen
+    /// until [`Terminal::resume`] is called.
+    pub async fn suspend(&self) -> anyhow::Result<()> {
+        match self {
+            Terminal::Actual(tx) => {
+                let (ack_tx, ack_rx) = oneshot::channel();
+                tx.send(Message::Suspend(ack_tx))
+                    .await
+                    .context("Sending Suspend message to terminal")
+                    .expect("Terminal actor died");
+                ack_rx
+                    .await
+                    .context("Awaiting terminal suspension")
+            }
+            Terminal::Mock(mock) => {
+                mock.suspend().await
+            }
+        }
+    }
+
+    /// Takes the terminal back after [`Terminal::suspend`] and redraws the UI.
+    pub async fn resume(&self) -> anyhow::Result<()> {
+        match self {
+            Terminal::Actual(tx) => {
+                tx.send(Message::Resume)
+                    .await
+                    .context("Sending Resume message to terminal")
+                    .expect("Terminal actor died");
+                Ok(())
+            }
+            Terminal::Mock(mock) => {
+                mock.resume().await
+            }
+        }
+    }
+
+    /// Shows a notification in the status line under the lists and feeds.
+    ///
+    /// The notification stays until the next one replaces it. With `bell`, the terminal
+    /// bell rings as well.
+    pub async fn notify(&self, text: ArcStr, bell: bool) -> anyhow::Result<()> {
+        match self {
+            Terminal::Actual(tx) => {
+                tx.send(Message::Notify { text, bell })
+                    .await
+                    .context("Sending Notify message to terminal")
+                    .expect("Terminal actor died");
+                Ok(())
+            }
+            Terminal::Mock(mock) => {
+                mock.notify(text).await
+            }
+        }
+    }
 }
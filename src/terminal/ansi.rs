@@ -0,0 +1,239 @@
+use cursive::style::{BaseColor, Color, ColorType, Effect, Style};
+use cursive::utils::markup::StyledString;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Converts text carrying ANSI escape sequences into a Cursive styled string.
+///
+/// SGR sequences (`ESC [ ... m`) become span styles: the 16 basic colors, the
+/// 256-color palette and truecolor for both foreground and background, along
+/// with bold, dim, italic, underline, blink, reverse and strikethrough. Any other
+/// control sequence (cursor movement, OSC hyperlinks, ...) is dropped so it
+/// does not show up as garbage in a `TextView`.
+///
+/// # Arguments
+/// * `text` - The output of a renderer such as `bat --color=always`
+///
+/// # Returns
+/// The text without escape sequences, styled as the terminal would show it.
+pub fn parse_ansi(text: &str) -> StyledString {
+    let mut styled = StyledString::new();
+    let mut style = Style::none();
+    let mut segment = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            segment.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, intermediates and a final byte in `@`..=`~`
+            Some('[') => {
+                let mut params = String::new();
+                let mut command = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        command = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if command == Some('m') {
+                    let mut next = style;
+                    apply_sgr(&mut next, &params);
+                    if next != style {
+                        flush(&mut styled, &mut segment, style);
+                        style = next;
+                    }
+                }
+            }
+            // OSC, DCS and friends: skip up to BEL or the `ESC \` terminator
+            Some(']' | 'P' | '^' | '_') => {
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-byte sequences such as `ESC =`
+            Some(_) | None => {}
+        }
+    }
+    flush(&mut styled, &mut segment, style);
+    styled
+}
+
+/// Appends the pending text with the style it was written in.
+fn flush(styled: &mut StyledString, segment: &mut String, style: Style) {
+    if !segment.is_empty() {
+        styled.append_styled(std::mem::take(segment), style);
+    }
+}
+
+/// Applies the parameters of one SGR sequence to `style`.
+fn apply_sgr(style: &mut Style, params: &str) {
+    let mut codes = params.split(';');
+    while let Some(code) = codes.next() {
+        // ITU T.416 form: `38:2::r:g:b`, `38:5:n` or `4:3`
+        if code.contains(':') {
+            let mut parts: Vec<&str> = code.split(':').collect();
+            match parts[0] {
+                "38" | "48" => {
+                    // Drop the color space ID of the truecolor form
+                    if parts.len() == 6 && parts[1] == "2" {
+                        parts.remove(2);
+                    }
+                    let color = extended_color(&mut parts[1..].iter().copied());
+                    set_color(style, parts[0] == "48", color);
+                }
+                "4" if parts[1] == "0" => style.effects.remove(Effect::Underline),
+                "4" => style.effects.insert(Effect::Underline),
+                _ => {}
+            }
+            continue;
+        }
+
+        // An empty parameter counts as 0, so `ESC [ m` resets too
+        let code = if code.is_empty() { "0" } else { code };
+        let Ok(n) = code.parse::<u8>() else {
+            continue;
+        };
+        match n {
+            0 => *style = Style::none(),
+            1 => style.effects.insert(Effect::Bold),
+            2 => style.effects.insert(Effect::Dim),
+            3 => style.effects.insert(Effect::Italic),
+            4 => style.effects.insert(Effect::Underline),
+            5 | 6 => style.effects.insert(Effect::Blink),
+            7 => style.effects.insert(Effect::Reverse),
+            9 => style.effects.insert(Effect::Strikethrough),
+            21 => style.effects.insert(Effect::Underline),
+            22 => {
+                style.effects.remove(Effect::Bold);
+                style.effects.remove(Effect::Dim);
+            }
+            23 => style.effects.remove(Effect::Italic),
+            24 => style.effects.remove(Effect::Underline),
+            25 => style.effects.remove(Effect::Blink),
+            27 => style.effects.remove(Effect::Reverse),
+            29 => style.effects.remove(Effect::Strikethrough),
+            30..=37 => set_color(style, false, Some(Color::Dark(BaseColor::from_u8(n - 30)))),
+            38 => set_color(style, false, extended_color(&mut codes)),
+            39 => style.color.front = ColorType::InheritParent,
+            40..=47 => set_color(style, true, Some(Color::Dark(BaseColor::from_u8(n - 40)))),
+            48 => set_color(style, true, extended_color(&mut codes)),
+            49 => style.color.back = ColorType::InheritParent,
+            90..=97 => set_color(style, false, Some(Color::Light(BaseColor::from_u8(n - 90)))),
+            100..=107 => set_color(style, true, Some(Color::Light(BaseColor::from_u8(n - 100)))),
+            _ => {}
+        }
+    }
+}
+
+/// Reads the rest of a `38`/`48` color: `5;n` or `2;r;g;b`.
+///
+/// # Returns
+/// The color, or `None` if the parameters are malformed.
+fn extended_color<'a>(params: &mut impl Iterator<Item = &'a str>) -> Option<Color> {
+    let mut next = || params.next().and_then(|p| p.parse::<u8>().ok());
+    match next()? {
+        5 => Some(Color::from_256colors(next()?)),
+        2 => Some(Color::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+/// Sets the foreground or background color, ignoring malformed colors.
+fn set_color(style: &mut Style, background: bool, color: Option<Color>) {
+    let Some(color) = color else {
+        return;
+    };
+    if background {
+        style.color.back = ColorType::Color(color);
+    } else {
+        style.color.front = ColorType::Color(color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cursive::style::ColorStyle;
+
+    /// Collects the spans as `(text, style)` pairs.
+    fn spans(styled: &StyledString) -> Vec<(String, Style)> {
+        styled
+            .spans()
+            .map(|span| (span.content.to_string(), *span.attr))
+            .collect()
+    }
+
+    fn front(color: Color) -> Style {
+        Style::from(ColorStyle::front(color))
+    }
+
+    #[test]
+    fn test_parse_ansi_plain_text() {
+        let styled = parse_ansi("no escapes\nhere");
+        assert_eq!(styled.source(), "no escapes\nhere");
+        assert_eq!(
+            spans(&styled),
+            vec![("no escapes\nhere".to_string(), Style::none())]
+        );
+    }
+
+    #[test]
+    fn test_parse_ansi_colors() {
+        let styled = parse_ansi(
+            "\x1b[31mred\x1b[0m \x1b[38;5;208morange\x1b[39m \x1b[38;2;1;2;3mrgb\x1b[m \x1b[92mlight",
+        );
+        assert_eq!(
+            spans(&styled),
+            vec![
+                ("red".to_string(), front(Color::Dark(BaseColor::Red))),
+                (" ".to_string(), Style::none()),
+                ("orange".to_string(), front(Color::from_256colors(208))),
+                (" ".to_string(), Style::none()),
+                ("rgb".to_string(), front(Color::Rgb(1, 2, 3))),
+                (" ".to_string(), Style::none()),
+                ("light".to_string(), front(Color::Light(BaseColor::Green))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ansi_effects_and_background() {
+        let styled = parse_ansi("\x1b[1;4;48:2::10:20:30mboth\x1b[22mline\x1b[24;49mnone");
+        let mut both = Style::from(ColorStyle::back(Color::Rgb(10, 20, 30)));
+        both.effects.insert(Effect::Bold);
+        both.effects.insert(Effect::Underline);
+        let mut line = both;
+        line.effects.remove(Effect::Bold);
+
+        assert_eq!(
+            spans(&styled),
+            vec![
+                ("both".to_string(), both),
+                ("line".to_string(), line),
+                ("none".to_string(), Style::none()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ansi_drops_other_sequences() {
+        let styled = parse_ansi(
+            "\x1b[2K\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07\x1b[1A\x1b[38;5mtext",
+        );
+        assert_eq!(
+            spans(&styled),
+            vec![("linktext".to_string(), Style::none())]
+        );
+    }
+}
@@ -1,18 +1,33 @@
 use cursive::Cursive;
-use cursive::event::{Event, Key};
+use cursive::event::{Event, EventResult, Key};
+use cursive::style::Effect;
 use cursive::traits::*;
-use cursive::views::{Dialog, SelectView, TextView};
+use cursive::utils::markup::StyledString;
+use cursive::views::{
+    Dialog, EditView, LinearLayout, OnEventView, ScrollView, SelectView, TextView,
+};
+use std::io::Write;
+use std::sync::Arc;
 use std::thread;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use super::Terminal;
+use super::ansi::parse_ansi;
 use super::data::{Screen, UiEvent};
 use super::message::Message;
+use crate::api::lore::{Diff, parse_diff};
+use crate::app::reply::Trailer;
 use crate::log::Log;
 
 const SCOPE: &str = "terminal";
 
+/// Name of the view holding the status line
+const STATUS: &str = "status";
+
+/// The last notification, kept in the Cursive user data so screens drawn later show it
+struct Status(String);
+
 /// Core implementation of the terminal actor that manages the Cursive UI.
 pub struct Core {
     log: Log,
@@ -51,13 +66,31 @@ impl Core {
                 let fwd = |ev: UiEvent| {
                     let tx = ui_events.clone();
                     move |_s: &mut Cursive| {
-                        let _ = tx.try_send(ev);
+                        let _ = tx.try_send(ev.clone());
                     }
                 };
 
                 siv.add_global_callback(Event::Key(Key::Left), fwd(UiEvent::Left));
                 siv.add_global_callback(Event::Key(Key::Right), fwd(UiEvent::Right));
                 siv.add_global_callback(Event::Key(Key::Esc), fwd(UiEvent::Esc));
+                siv.add_global_callback('s', fwd(UiEvent::ToggleSeries));
+                siv.add_global_callback('v', fwd(UiEvent::ShowVersions));
+                siv.add_global_callback('m', fwd(UiEvent::MarkVersion));
+                siv.add_global_callback('d', fwd(UiEvent::Interdiff));
+                siv.add_global_callback('a', fwd(UiEvent::Apply));
+                siv.add_global_callback('r', fwd(UiEvent::CycleReview));
+                siv.add_global_callback('R', fwd(UiEvent::Reply(None)));
+                siv.add_global_callback('B', fwd(UiEvent::Reply(Some(Trailer::ReviewedBy))));
+                siv.add_global_callback('K', fwd(UiEvent::Reply(Some(Trailer::AckedBy))));
+                siv.add_global_callback('T', fwd(UiEvent::Reply(Some(Trailer::TestedBy))));
+                siv.add_global_callback('S', fwd(UiEvent::SendReply));
+                siv.add_global_callback('i', fwd(UiEvent::Inbox));
+                siv.add_global_callback('n', fwd(UiEvent::NextUnread));
+                siv.add_global_callback('N', fwd(UiEvent::MarkAllRead));
+                let tx_search = ui_events.clone();
+                siv.add_global_callback('/', move |s: &mut Cursive| {
+                    Self::show_search_prompt(s, tx_search.clone());
+                });
 
                 let cb_sink = siv.cb_sink().clone();
                 let _ = sink_tx.send(cb_sink);
@@ -74,6 +107,9 @@ impl Core {
 
             self.log.info(SCOPE, "Terminal actor spawned");
 
+            // Wakes the UI thread up while the terminal is suspended
+            let mut resume: Option<std::sync::mpsc::Sender<()>> = None;
+
             // Message handling loop - this is the actual actor behavior
             while let Some(msg) = rx.recv().await {
                 match msg {
@@ -84,6 +120,20 @@ impl Core {
                         let _ = cb_sink.send(Box::new(|s: &mut Cursive| s.quit()));
                         break;
                     }
+                    Message::Suspend(ack) => {
+                        let (resume_tx, resume_rx) = std::sync::mpsc::channel();
+                        resume = Some(resume_tx);
+                        Self::handle_suspend(&cb_sink, ack, resume_rx);
+                    }
+                    Message::Resume => {
+                        // Dropping the sender wakes the UI thread up as well
+                        if let Some(resume_tx) = resume.take() {
+                            let _ = resume_tx.send(());
+                        }
+                    }
+                    Message::Notify { text, bell } => {
+                        Self::handle_notify(&cb_sink, text.to_string(), bell);
+                    }
                 }
             }
         });
@@ -91,6 +141,126 @@ impl Core {
         (Terminal::Actual(tx), handle)
     }
 
+    /// Handles the Suspend message by leaving the alternate screen and raw mode.
+    ///
+    /// The Cursive thread blocks inside the callback until `resume` fires, so it neither
+    /// draws nor reads input while an external program owns the terminal.
+    fn handle_suspend(
+        cb_sink: &cursive::CbSink,
+        ack: tokio::sync::oneshot::Sender<()>,
+        resume: std::sync::mpsc::Receiver<()>,
+    ) {
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            use cursive::backends::crossterm::crossterm::{cursor, event, execute, terminal};
+
+            let mut stdout = std::io::stdout();
+            let _ = execute!(
+                stdout,
+                terminal::LeaveAlternateScreen,
+                event::DisableMouseCapture,
+                cursor::Show
+            );
+            let _ = terminal::disable_raw_mode();
+            let _ = ack.send(());
+
+            let _ = resume.recv();
+
+            let _ = terminal::enable_raw_mode();
+            let _ = execute!(
+                stdout,
+                terminal::EnterAlternateScreen,
+                event::EnableMouseCapture,
+                cursor::Hide
+            );
+            s.clear();
+        }));
+    }
+
+    /// Handles the Notify message by updating the status line and ringing the bell.
+    fn handle_notify(cb_sink: &cursive::CbSink, text: String, bell: bool) {
+        let _ = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            s.call_on_name(STATUS, |view: &mut TextView| view.set_content(text.clone()));
+            s.set_user_data(Status(text));
+            if bell {
+                // BEL goes straight to the terminal, between two frames
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(b"\x07");
+                let _ = stdout.flush();
+            }
+        }));
+    }
+
+    /// Stacks the status line under the content of a screen.
+    fn with_status(s: &mut Cursive, content: impl View) -> LinearLayout {
+        let status = s
+            .user_data::<Status>()
+            .map(|status| status.0.clone())
+            .unwrap_or_default();
+        LinearLayout::vertical()
+            .child(content)
+            .child(TextView::new(status).with_name(STATUS))
+    }
+
+    /// Opens a prompt on top of the current screen asking for a search query.
+    ///
+    /// Submitting a non-blank query sends it as a [`UiEvent::Search`], while Esc or
+    /// Cancel close the prompt without leaving the screen below.
+    fn show_search_prompt(s: &mut Cursive, ui_events: mpsc::Sender<UiEvent>) {
+        let submit = move |s: &mut Cursive, query: &str| {
+            s.pop_layer();
+            if !query.trim().is_empty() {
+                let _ = ui_events.try_send(UiEvent::Search(query.trim().into()));
+            }
+        };
+        let submit_button = submit.clone();
+        let input = EditView::new()
+            .on_submit(submit)
+            .with_name("search")
+            .min_width(50);
+        let dialog = Dialog::new()
+            .title("Search (e.g. f:jane s:\"mm: fix\" dfn:mm/ d:2.weeks.ago..)")
+            .content(input)
+            .button("Search", move |s| {
+                let query = s
+                    .call_on_name("search", |view: &mut EditView| view.get_content())
+                    .unwrap_or_default();
+                submit_button(s, &query);
+            })
+            .button("Cancel", |s| {
+                s.pop_layer();
+            });
+        s.add_layer(OnEventView::new(dialog).on_event(Key::Esc, |s| {
+            s.pop_layer();
+        }));
+    }
+
+    /// Scrolls the patch view to the start of the next or previous file of its diff.
+    ///
+    /// Moving back from within a file goes to the start of that file first. Patches
+    /// without a diff leave the key to the rest of the UI.
+    fn scroll_to_file(
+        view: &mut ScrollView<TextView>,
+        diff: &Diff,
+        forward: bool,
+    ) -> Option<EventResult> {
+        if diff.is_empty() {
+            return None;
+        }
+        let viewport = view.content_viewport();
+        let top = viewport.top();
+        let target = match (diff.file_at_line(top), forward) {
+            (None, true) => Some(0),
+            (Some(current), true) => Some(current + 1),
+            (None, false) => None,
+            (Some(current), false) if diff.files[current].start_line < top => Some(current),
+            (Some(current), false) => current.checked_sub(1),
+        };
+        if let Some(file) = target.and_then(|target| diff.files.get(target)) {
+            view.set_offset((viewport.left(), file.start_line));
+        }
+        Some(EventResult::Consumed(None))
+    }
+
     /// Handles the Show message by updating the UI with the given screen.
     fn handle_show_screen(
         screen: Screen,
@@ -112,11 +282,15 @@ impl Core {
                 items,
                 page,
                 selected,
+                unread,
             } => {
                 s.pop_layer();
                 let mut list = SelectView::<usize>::new();
                 for (i, it) in items.into_iter().enumerate() {
-                    let label = format!("{} - {}", it.name, it.description);
+                    let mut label = format!("{} ({}) - {}", it.name, it.instance(), it.description);
+                    if let Some(count) = unread.get(&it.name) {
+                        label.insert_str(0, &format!("[{} new] ", count));
+                    }
                     list.add_item(label, i);
                 }
                 let tx_sel = ui_events.clone();
@@ -130,8 +304,9 @@ impl Core {
                 let len = list.len();
                 let idx = selected.min(len.saturating_sub(1));
                 let _ = list.set_selection(idx);
+                let content = Self::with_status(s, list);
                 s.add_layer(
-                    Dialog::around(list).title(format!("Mailing Lists - Page {}", page + 1)),
+                    Dialog::around(content).title(format!("Mailing Lists - Page {}", page + 1)),
                 );
             }
             Screen::Feed {
@@ -139,11 +314,69 @@ impl Core {
                 items,
                 page,
                 selected,
+                reviews,
+                unread,
+                skipped,
+                filter,
             } => {
                 s.pop_layer();
                 let mut listv = SelectView::<usize>::new();
                 for (i, p) in items.into_iter().enumerate() {
-                    let label = format!("{} — {} <{}>", p.title, p.author, p.email);
+                    let marker = reviews
+                        .get(&p.message_id)
+                        .map_or(" ", |state| state.marker());
+                    let label = format!("{} {} — {} <{}>", marker, p.title, p.author, p.email);
+                    if unread.contains(&p.message_id) {
+                        listv.add_item(StyledString::styled(label, Effect::Bold), i);
+                    } else {
+                        listv.add_item(label, i);
+                    }
+                }
+                let tx_sel = ui_events.clone();
+                listv.set_on_select(move |_siv, idx| {
+                    let _ = tx_sel.try_send(UiEvent::SelectionChange(*idx));
+                });
+                let tx_submit = ui_events.clone();
+                listv.set_on_submit(move |_siv, idx| {
+                    let _ = tx_submit.try_send(UiEvent::SelectionSubmit(*idx));
+                });
+                let len = listv.len();
+                let idx = selected.min(len.saturating_sub(1));
+                let _ = listv.set_selection(idx);
+                let mut title = format!("Feed: {} — Page {}", list.to_string(), page + 1);
+                if !filter.is_empty() {
+                    let paths: Vec<&str> = filter.iter().map(|path| path.as_ref()).collect();
+                    title.push_str(&format!(" — only {}", paths.join(", ")));
+                }
+                if skipped > 0 {
+                    title.push_str(&format!(" — {} skipped", skipped));
+                }
+                let content = Self::with_status(s, listv);
+                s.add_layer(Dialog::around(content).title(title));
+            }
+            Screen::Series {
+                list,
+                items,
+                page,
+                selected,
+            } => {
+                s.pop_layer();
+                let mut listv = SelectView::<usize>::new();
+                for (i, series) in items.into_iter().enumerate() {
+                    let have = series.patches.len();
+                    let mut label = format!(
+                        "{} — {} <{}> [{}/{}]",
+                        series.title, series.author, series.email, have, series.total
+                    );
+                    let missing = series.missing();
+                    if !missing.is_empty() {
+                        let missing = missing
+                            .iter()
+                            .map(|n| n.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        label.push_str(&format!(" (missing: {missing})"));
+                    }
                     listv.add_item(label, i);
                 }
                 let tx_sel = ui_events.clone();
@@ -157,16 +390,63 @@ impl Core {
                 let len = listv.len();
                 let idx = selected.min(len.saturating_sub(1));
                 let _ = listv.set_selection(idx);
-                s.add_layer(Dialog::around(listv).title(format!(
-                    "Feed: {} — Page {}",
-                    list.to_string(),
+                let content = Self::with_status(s, listv);
+                s.add_layer(Dialog::around(content).title(format!(
+                    "Series: {} — Page {}",
+                    list,
                     page + 1
                 )));
             }
+            Screen::Versions {
+                title,
+                items,
+                selected,
+                marked,
+            } => {
+                s.pop_layer();
+                let mut listv = SelectView::<usize>::new();
+                for (i, series) in items.into_iter().enumerate() {
+                    let base = if marked == Some(i) { "*" } else { " " };
+                    let label = format!(
+                        "{} v{} — {} — {} [{}/{}]",
+                        base,
+                        series.version,
+                        series.last_update.format("%Y-%m-%d %H:%M"),
+                        series.title,
+                        series.patches.len(),
+                        series.total
+                    );
+                    listv.add_item(label, i);
+                }
+                let tx_sel = ui_events.clone();
+                listv.set_on_select(move |_siv, idx| {
+                    let _ = tx_sel.try_send(UiEvent::SelectionChange(*idx));
+                });
+                let tx_submit = ui_events.clone();
+                listv.set_on_submit(move |_siv, idx| {
+                    let _ = tx_submit.try_send(UiEvent::SelectionSubmit(*idx));
+                });
+                let len = listv.len();
+                let idx = selected.min(len.saturating_sub(1));
+                let _ = listv.set_selection(idx);
+                s.add_layer(
+                    Dialog::around(listv)
+                        .title(format!("Versions: {} — m: mark base, d: interdiff", title)),
+                );
+            }
             Screen::Patch { title, content } => {
                 s.pop_layer();
-                let text = TextView::new(content.to_string()).scrollable();
-                s.add_layer(Dialog::around(text).title(format!("Patch: {}", title.to_string())));
+                let text = parse_ansi(&content);
+                // Lines are not wrapped, so the line numbers of the diff are rows of the view
+                let diff = Arc::new(parse_diff(text.source()));
+                let previous = diff.clone();
+                let view = TextView::new(text).no_wrap().scrollable().scroll_x(true);
+                let view = OnEventView::new(view)
+                    .on_event_inner('f', move |view, _| Self::scroll_to_file(view, &diff, true))
+                    .on_event_inner('F', move |view, _| {
+                        Self::scroll_to_file(view, &previous, false)
+                    });
+                s.add_layer(Dialog::around(view).title(format!("Patch: {}", title.to_string())));
             }
         }));
     }
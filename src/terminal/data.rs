@@ -1,14 +1,42 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::ArcStr;
 use crate::api::lore::{LoreMailingList, LorePatchMetadata};
+use crate::app::cache::{PatchSeries, ReviewState};
+use crate::app::reply::Trailer;
 
 /// UI key events emitted by the terminal.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum UiEvent {
     Left,
     Right,
     Esc,
     SelectionChange(usize),
     SelectionSubmit(usize),
+    /// Switch the feed between one row per patch and one row per series
+    ToggleSeries,
+    /// Open the list of known versions of the selected series
+    ShowVersions,
+    /// Mark the selected version as the base of the next interdiff
+    MarkVersion,
+    /// Show the interdiff between the marked (or previous) and the selected version
+    Interdiff,
+    /// Apply the selected (or open) patch or series to the local repository
+    Apply,
+    /// Cycle the review state of the selected (or open) patch
+    CycleReview,
+    /// Reply to the selected (or open) patch, in the editor or with a quick trailer
+    Reply(Option<Trailer>),
+    /// Send the last composed reply through the configured mail backend
+    SendReply,
+    /// Search the archive with a query typed in the search prompt
+    Search(ArcStr),
+    /// Open the inbox of the subsystems the user maintains or reviews
+    Inbox,
+    /// Move to the next patch of the feed that was not opened yet
+    NextUnread,
+    /// Mark every patch of the open (or selected) list as read
+    MarkAllRead,
 }
 
 /// A high-level description of the screen to render.
@@ -19,13 +47,39 @@ pub enum Screen {
         items: Vec<LoreMailingList>,
         page: usize,
         selected: usize,
+        /// Number of new patches in the watched lists that have some, by list name
+        unread: HashMap<ArcStr, usize>,
     },
-    /// Feed screen: shows patches for a mailing list
+    /// Feed screen: shows patches for a mailing list or the results of a search
     Feed {
+        /// The name of the list, or a description of the search
         list: ArcStr,
         items: Vec<LorePatchMetadata>,
         page: usize,
         selected: usize,
+        /// Review state of the items that have one, by message ID
+        reviews: HashMap<ArcStr, ReviewState>,
+        /// Message IDs of the items that were never opened
+        unread: HashSet<ArcStr>,
+        /// Number of feed entries left out because they could not be parsed
+        skipped: usize,
+        /// Files and directories the shown patches touch, empty if the feed is not filtered
+        filter: Vec<ArcStr>,
+    },
+    /// Series screen: shows the patches of a mailing list grouped by submission
+    Series {
+        list: ArcStr,
+        items: Vec<PatchSeries>,
+        page: usize,
+        selected: usize,
+    },
+    /// Versions screen: shows every known revision of a series
+    Versions {
+        title: ArcStr,
+        items: Vec<PatchSeries>,
+        selected: usize,
+        /// The revision marked as the base for an interdiff, if any
+        marked: Option<usize>,
     },
     /// Patch screen: shows rendered patch content
     Patch { title: ArcStr, content: ArcStr },
@@ -42,4 +96,8 @@ pub struct MockData {
     pub last_screen: Option<Screen>,
     /// Whether quit was called
     pub quit_called: bool,
+    /// Whether the terminal is currently suspended
+    pub suspended: bool,
+    /// Notifications shown in the status line, oldest first
+    pub notifications: Vec<ArcStr>,
 }
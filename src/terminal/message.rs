@@ -1,3 +1,7 @@
+use tokio::sync::oneshot;
+
+use crate::ArcStr;
+
 use super::data::Screen;
 
 /// Messages that can be sent to the terminal actor.
@@ -7,4 +11,10 @@ pub enum Message {
     Show(Screen),
     /// Quit the UI
     Quit,
+    /// Release the terminal so an external program can use it, acknowledging once done
+    Suspend(oneshot::Sender<()>),
+    /// Take the terminal back after a suspend and redraw
+    Resume,
+    /// Show a notification in the status line, ringing the bell if asked to
+    Notify { text: ArcStr, bell: bool },
 }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::ArcStr;
 use crate::terminal::data::{MockData, Screen};
 
 /// Mock implementation of the Terminal actor for testing purposes.
@@ -48,6 +49,42 @@ impl Mock {
         Ok(())
     }
 
+    /// Releases the terminal for an external program.
+    /// Mock implementation sets the suspended flag in mock data.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn suspend(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data.suspended = true;
+        Ok(())
+    }
+
+    /// Takes the terminal back after a suspend.
+    /// Mock implementation clears the suspended flag in mock data.
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn resume(&self) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data.suspended = false;
+        Ok(())
+    }
+
+    /// Shows a notification in the status line.
+    /// Mock implementation records the notification in mock data.
+    ///
+    /// # Arguments
+    /// * `text` - The notification
+    ///
+    /// # Returns
+    /// Ok(()) if successful
+    pub async fn notify(&self, text: ArcStr) -> anyhow::Result<()> {
+        let mut mock_data = self.data.lock().await;
+        mock_data.notifications.push(text);
+        Ok(())
+    }
+
     /// Gets the mock data for inspection in tests.
     ///
     /// # Returns